@@ -0,0 +1,200 @@
+use std::{
+    io::{BufRead, BufReader, Write},
+    os::unix::net::UnixStream,
+    path::PathBuf,
+    time::Duration,
+};
+
+use lazaro_core::timer::BreakKind;
+use tauri::{AppHandle, Manager};
+use tokio::{
+    io::{AsyncBufReadExt, AsyncWriteExt},
+    net::UnixListener,
+};
+
+use crate::{
+    AppError, BackendState, default_data_dir, get_runtime_status, i18n, send_notification,
+    settings_to_core, start_guest_session, start_pending_break, summary, trigger_break,
+};
+
+const SOCKET_NAME: &str = "lazaro-actions.sock";
+const PAUSE_SECONDS: u64 = 3600;
+const FORWARD_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Quick actions for desktops without a tray. The desktop file lists them,
+/// each running `lazaro --action <name>`, which hands the name to the
+/// running instance over a local socket.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum QuickAction {
+    StartBreak,
+    Pause,
+    Status,
+}
+
+impl QuickAction {
+    fn parse(value: &str) -> Option<Self> {
+        match value.trim() {
+            "start-break" => Some(Self::StartBreak),
+            "pause" => Some(Self::Pause),
+            "status" => Some(Self::Status),
+            _ => None,
+        }
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            Self::StartBreak => "start-break",
+            Self::Pause => "pause",
+            Self::Status => "status",
+        }
+    }
+}
+
+/// The action asked for with `--action <name>` or `--action=<name>`.
+pub fn requested() -> Option<QuickAction> {
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        if arg == "--action" {
+            return args.next().as_deref().and_then(QuickAction::parse);
+        }
+        if let Some(value) = arg.strip_prefix("--action=") {
+            return QuickAction::parse(value);
+        }
+    }
+    None
+}
+
+fn socket_path() -> PathBuf {
+    std::env::var("XDG_RUNTIME_DIR")
+        .ok()
+        .filter(|value| !value.is_empty())
+        .map_or_else(default_data_dir, PathBuf::from)
+        .join(SOCKET_NAME)
+}
+
+/// Runs `action` in the instance already running and returns its reply;
+/// fails when there is none.
+pub fn forward(action: QuickAction) -> Result<String, String> {
+    let mut stream = UnixStream::connect(socket_path()).map_err(|e| e.to_string())?;
+    stream
+        .set_read_timeout(Some(FORWARD_TIMEOUT))
+        .map_err(|e| e.to_string())?;
+    writeln!(stream, "{}", action.name()).map_err(|e| e.to_string())?;
+    let mut reply = String::new();
+    BufReader::new(stream)
+        .read_line(&mut reply)
+        .map_err(|e| e.to_string())?;
+    Ok(reply.trim().to_string())
+}
+
+/// Listens for forwarded actions, after running `pending`: the action this
+/// process was started with when no instance was running yet.
+pub fn spawn_listener(app: AppHandle, pending: Option<QuickAction>) {
+    if let Some(action) = pending {
+        tracing::info!(action = action.name(), "running quick action");
+        perform(&app, action);
+    }
+
+    let path = socket_path();
+    if UnixStream::connect(&path).is_ok() {
+        tracing::warn!(path = %path.display(), "another instance handles quick actions");
+        return;
+    }
+    let _ = std::fs::remove_file(&path);
+
+    tauri::async_runtime::spawn(async move {
+        let listener = match UnixListener::bind(&path) {
+            Ok(listener) => listener,
+            Err(error) => {
+                tracing::warn!(%error, "failed to listen for quick actions");
+                return;
+            }
+        };
+        loop {
+            let Ok((stream, _)) = listener.accept().await else {
+                continue;
+            };
+            let (reader, mut writer) = stream.into_split();
+            let mut line = String::new();
+            if tokio::io::BufReader::new(reader)
+                .read_line(&mut line)
+                .await
+                .is_err()
+            {
+                continue;
+            }
+            let reply = match QuickAction::parse(&line) {
+                Some(action) => {
+                    tracing::info!(action = action.name(), "running quick action");
+                    perform(&app, action)
+                }
+                None => format!("unknown action: {}", line.trim()),
+            };
+            let _ = writer.write_all(format!("{reply}\n").as_bytes()).await;
+        }
+    });
+}
+
+fn perform(app: &AppHandle, action: QuickAction) -> String {
+    let result = match action {
+        QuickAction::StartBreak => start_break(app),
+        QuickAction::Pause => {
+            start_guest_session(PAUSE_SECONDS, app.state()).map(|_| "paused".to_string())
+        }
+        QuickAction::Status => Ok(announce_status(app)),
+    };
+    result.unwrap_or_else(|error| {
+        tracing::warn!(%error, action = action.name(), "quick action failed");
+        error.to_string()
+    })
+}
+
+// The due break if one is waiting, otherwise the next one early.
+fn start_break(app: &AppHandle) -> Result<String, AppError> {
+    let status = get_runtime_status(app.state())?;
+    if status.pending_break.is_some() {
+        return start_pending_break(app.state()).map(|()| "break started".to_string());
+    }
+    let kind = status.next_break_kind.unwrap_or(BreakKind::Micro);
+    trigger_break(kind, app.state())
+}
+
+/// Shows the status as a notification, since the desktop action that asks
+/// for it has no terminal.
+fn announce_status(app: &AppHandle) -> String {
+    let state = app.state::<BackendState>();
+    let Ok(settings) = state
+        .persistent
+        .data
+        .lock()
+        .map(|guard| guard.effective.clone())
+    else {
+        return String::new();
+    };
+    let core = settings_to_core(&settings).unwrap_or_default();
+    let messages = i18n::Messages::new(i18n::resolve(&settings.language), &core);
+    let message = match get_runtime_status(app.state()) {
+        Ok(status) if status.running => messages.format(
+            "actions.status",
+            &[
+                (
+                    "kind",
+                    status
+                        .next_break_kind
+                        .map_or("", |kind| messages.kind_label(kind)),
+                ),
+                (
+                    "eta",
+                    &summary::format_duration(status.next_break_eta_seconds.unwrap_or(0)),
+                ),
+                (
+                    "active",
+                    &summary::format_duration(status.daily_active_seconds),
+                ),
+            ],
+        ),
+        _ => messages.text("actions.not_running").to_string(),
+    };
+    send_notification(&settings, false, messages.text("app.title"), &message);
+    message
+}
@@ -0,0 +1,53 @@
+use crate::inhibit;
+
+/// The window in the foreground, as far as usage tracking is concerned.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ActiveWindow {
+    /// The class part of `WM_CLASS`, which names the application rather
+    /// than one of its windows.
+    pub class: String,
+    pub title: Option<String>,
+}
+
+/// Reads the focused window through `xprop`. The title is only read when
+/// asked for, so it never leaves the X server otherwise.
+pub fn active_window(with_title: bool) -> Option<ActiveWindow> {
+    let window_id = inhibit::active_window_id()?;
+    let mut args = vec!["-id", window_id.as_str(), "WM_CLASS"];
+    if with_title {
+        args.push("_NET_WM_NAME");
+    }
+    let raw = inhibit::xprop(&args)?;
+    let mut class = None;
+    let mut title = None;
+    for line in raw.lines() {
+        if let Some(value) = line.strip_prefix("WM_CLASS(STRING) = ") {
+            // Instance first, then class.
+            class = quoted(value).pop();
+        } else if let Some(value) = line.strip_prefix("_NET_WM_NAME(UTF8_STRING) = ") {
+            title = quoted(value).into_iter().next();
+        }
+    }
+    Some(ActiveWindow {
+        class: class.filter(|class| !class.is_empty())?,
+        title: title.filter(|_| with_title),
+    })
+}
+
+/// The double-quoted strings in an `xprop` value, unescaped.
+fn quoted(value: &str) -> Vec<String> {
+    let mut strings = Vec::new();
+    let mut chars = value.chars();
+    while chars.any(|c| c == '"') {
+        let mut current = String::new();
+        while let Some(c) = chars.next() {
+            match c {
+                '\\' => current.extend(chars.next()),
+                '"' => break,
+                c => current.push(c),
+            }
+        }
+        strings.push(current);
+    }
+    strings
+}
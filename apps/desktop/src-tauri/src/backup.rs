@@ -0,0 +1,171 @@
+use std::{collections::BTreeSet, fs::File, io::Read, path::Path};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    AppError, AppState, AppStateOnDisk, diagnostics, effective_settings, storage, undo, unix_now,
+    vault,
+};
+
+const FORMAT: &str = "lazaro-backup";
+/// Bumped when the archive layout changes; older versions stay readable.
+const FORMAT_VERSION: u32 = 1;
+const MANIFEST_ENTRY: &str = "manifest.json";
+const STATE_ENTRY: &str = "state.json";
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+struct ManifestDto {
+    format: String,
+    format_version: u32,
+    app_version: String,
+    created_at: u64,
+}
+
+#[derive(Clone, Copy, Debug, Serialize)]
+pub struct CountChangeDto {
+    pub current: usize,
+    pub backup: usize,
+}
+
+/// What restoring a backup replaces; returned as is for a dry run.
+#[derive(Clone, Debug, Serialize)]
+pub struct RestoreReportDto {
+    pub dry_run: bool,
+    pub app_version: String,
+    pub created_at: u64,
+    pub changed_settings: Vec<String>,
+    pub profiles_added: Vec<String>,
+    pub profiles_removed: Vec<String>,
+    pub profiles_changed: Vec<String>,
+    pub daily_stats_days: CountChangeDto,
+    pub break_sessions: CountChangeDto,
+    pub focus_sessions: CountChangeDto,
+    pub runtime_restarted: bool,
+}
+
+/// Writes settings, profiles, stats and history to one zip archive. The
+/// state is stored unencrypted so the backup opens on any machine.
+pub fn create(state: &AppState, path: &Path) -> Result<(), AppError> {
+    let data = state.data.lock()?.clone();
+    let manifest = ManifestDto {
+        format: FORMAT.into(),
+        format_version: FORMAT_VERSION,
+        app_version: env!("CARGO_PKG_VERSION").into(),
+        created_at: unix_now(),
+    };
+    diagnostics::write_bundle(
+        path,
+        &[
+            (MANIFEST_ENTRY, pretty(&manifest)?),
+            (STATE_ENTRY, pretty(&data)?),
+        ],
+    )
+    .map_err(AppError::Io)
+}
+
+fn pretty<T: Serialize>(value: &T) -> Result<String, AppError> {
+    Ok(serde_json::to_string_pretty(value)?)
+}
+
+/// A validated backup, ready to compare against or replace the current
+/// state.
+pub struct Backup {
+    manifest: ManifestDto,
+    state: AppStateOnDisk,
+}
+
+pub fn read(path: &Path) -> Result<Backup, AppError> {
+    let invalid = |reason: String| AppError::InvalidBackup(reason);
+    let file = File::open(path)?;
+    let mut archive = zip::ZipArchive::new(file).map_err(|e| invalid(e.to_string()))?;
+    let mut entry = |name: &str| {
+        let mut raw = String::new();
+        archive
+            .by_name(name)
+            .map_err(|e| invalid(format!("{name}: {e}")))?
+            .read_to_string(&mut raw)
+            .map_err(|e| invalid(format!("{name}: {e}")))?;
+        Ok::<_, AppError>(raw)
+    };
+
+    let manifest: ManifestDto = serde_json::from_str(&entry(MANIFEST_ENTRY)?)
+        .map_err(|e| invalid(format!("{MANIFEST_ENTRY}: {e}")))?;
+    if manifest.format != FORMAT {
+        return Err(invalid(format!("not a Lazaro backup: {}", manifest.format)));
+    }
+    if manifest.format_version > FORMAT_VERSION {
+        return Err(invalid(format!(
+            "format version {} is newer than this app supports ({FORMAT_VERSION})",
+            manifest.format_version
+        )));
+    }
+    let state: AppStateOnDisk = serde_json::from_str(&entry(STATE_ENTRY)?)
+        .map_err(|e| invalid(format!("{STATE_ENTRY}: {e}")))?;
+    effective_settings(state.settings.clone())?;
+    for profile in state.profiles.values() {
+        effective_settings(profile.settings.clone())
+            .map_err(|e| invalid(format!("profile {}: {e}", profile.id)))?;
+    }
+    Ok(Backup { manifest, state })
+}
+
+impl Backup {
+    pub fn report(&self, current: &AppStateOnDisk, dry_run: bool) -> RestoreReportDto {
+        let backup = &self.state;
+        let current_ids: BTreeSet<&String> = current.profiles.keys().collect();
+        let backup_ids: BTreeSet<&String> = backup.profiles.keys().collect();
+        let profiles_changed = current_ids
+            .intersection(&backup_ids)
+            .filter(|id| {
+                serde_json::to_value(&current.profiles[**id]).ok()
+                    != serde_json::to_value(&backup.profiles[**id]).ok()
+            })
+            .map(|id| (*id).clone())
+            .collect();
+        let count = |current: usize, backup: usize| CountChangeDto { current, backup };
+
+        RestoreReportDto {
+            dry_run,
+            app_version: self.manifest.app_version.clone(),
+            created_at: self.manifest.created_at,
+            changed_settings: undo::changed_fields(&current.settings, &backup.settings),
+            profiles_added: backup_ids
+                .difference(&current_ids)
+                .map(|id| (*id).clone())
+                .collect(),
+            profiles_removed: current_ids
+                .difference(&backup_ids)
+                .map(|id| (*id).clone())
+                .collect(),
+            profiles_changed,
+            daily_stats_days: count(current.daily_stats.len(), backup.daily_stats.len()),
+            break_sessions: count(current.break_history.len(), backup.break_history.len()),
+            focus_sessions: count(current.focus_sessions.len(), backup.focus_sessions.len()),
+            runtime_restarted: false,
+        }
+    }
+
+    /// Replaces the in-memory state with the backup and saves it. This
+    /// device keeps its own sync identity, and timer counters start fresh.
+    /// The runtime must be stopped first so it does not write the old state
+    /// back.
+    pub fn apply(self, state: &AppState) -> Result<(), AppError> {
+        let mut restored = self.state;
+        restored.resolve_effective();
+        restored.engine = None;
+        if restored.effective.encrypt_state {
+            vault::ensure_key().map_err(AppError::Io)?;
+        }
+        {
+            let mut guard = state.data.lock()?;
+            restored.sync.device_id = std::mem::take(&mut guard.sync.device_id);
+            *guard = restored;
+        }
+        if let Ok(mut synced) = state.synced_days.lock() {
+            synced.clear();
+        }
+        state.save(storage::Sections::ALL)?;
+        tracing::info!(created_at = self.manifest.created_at, "backup restored");
+        Ok(())
+    }
+}
@@ -0,0 +1,110 @@
+use std::{fs, io, path::Path, process::Command};
+
+use lazaro_core::{
+    config::{AppBlockAction, BlockingSettings},
+    hosts,
+};
+
+const ENFORCE_INTERVAL_TICKS: u64 = 3;
+
+/// Applies the `blocking` settings for as long as a strict break runs.
+#[derive(Default)]
+pub struct BlockingController {
+    hosts_file: Option<String>,
+}
+
+impl BlockingController {
+    pub fn update(&mut self, settings: &BlockingSettings, active: bool, tick: u64) {
+        if !active || !settings.enabled {
+            self.clear();
+            return;
+        }
+
+        if self.hosts_file.is_none() && !settings.hosts_file_path.is_empty() {
+            let hosts = settings.host_list().collect::<Vec<_>>();
+            write_hosts(&settings.hosts_file_path, &hosts);
+            tracing::info!(count = hosts.len(), "host blocklist enabled");
+            self.hosts_file = Some(settings.hosts_file_path.clone());
+        }
+
+        // Re-run every few seconds to catch apps reopened mid-break.
+        if tick.is_multiple_of(ENFORCE_INTERVAL_TICKS) {
+            let classes = settings
+                .app_class_list()
+                .map(str::to_string)
+                .collect::<Vec<_>>();
+            enforce_in_background(classes, settings.app_action);
+        }
+    }
+
+    pub fn clear(&mut self) {
+        if let Some(path) = self.hosts_file.take() {
+            write_hosts(&path, &[]);
+            tracing::info!("host blocklist cleared");
+        }
+    }
+}
+
+/// Empties a blocklist left behind by a run that did not shut down cleanly.
+pub fn release_stale(settings: &BlockingSettings) {
+    let path = settings.hosts_file_path.as_str();
+    if path.is_empty() {
+        return;
+    }
+    if fs::read_to_string(path).is_ok_and(|contents| hosts::has_block(&contents)) {
+        write_hosts(path, &[]);
+    }
+}
+
+/// Rewrites only the Lázaro block of the file, which may be shared with
+/// other entries or be the system hosts file.
+fn write_hosts(path: &str, hosts: &[&str]) {
+    let path = Path::new(path);
+    let current = match fs::read_to_string(path) {
+        Ok(contents) => contents,
+        Err(error) if error.kind() == io::ErrorKind::NotFound => String::new(),
+        Err(error) => {
+            tracing::warn!(%error, path = %path.display(), "failed to read hosts file");
+            return;
+        }
+    };
+    let contents = match hosts::with_block(&current, hosts) {
+        Ok(contents) => contents,
+        Err(error) => {
+            tracing::warn!(%error, path = %path.display(), "leaving hosts file untouched");
+            return;
+        }
+    };
+    if contents == current {
+        return;
+    }
+    if let Some(parent) = path.parent() {
+        let _ = fs::create_dir_all(parent);
+    }
+    if let Err(error) = fs::write(path, contents) {
+        tracing::warn!(%error, path = %path.display(), "failed to write host blocklist");
+    }
+}
+
+/// Applies `action` to the windows or processes of `classes` off the
+/// runtime thread.
+pub fn enforce_in_background(classes: Vec<String>, action: AppBlockAction) {
+    if !classes.is_empty() {
+        tokio::task::spawn_blocking(move || enforce_apps(&classes, action));
+    }
+}
+
+// Minimizing goes through xdotool, which only sees X11 and XWayland windows;
+// killing matches process names, so it also works on native Wayland.
+fn enforce_apps(classes: &[String], action: AppBlockAction) {
+    for class in classes {
+        let _ = match action {
+            AppBlockAction::Minimize => Command::new("xdotool")
+                .args(["search", "--class", class.as_str(), "windowminimize", "%@"])
+                .output(),
+            AppBlockAction::Kill => Command::new("pkill")
+                .args(["--ignore-case", "--exact", class.as_str()])
+                .output(),
+        };
+    }
+}
@@ -0,0 +1,82 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use chrono::Local;
+use lazaro_core::calendar::{self, BusyWindow};
+use serde::Serialize;
+
+pub const DEFAULT_HORIZON_HOURS: u64 = 24;
+
+// Evolution keeps the local "Personal" calendar here; remote calendars are
+// cached as vdir-like directories that can be configured explicitly.
+const EVOLUTION_CALENDAR: &str = ".local/share/evolution/calendar/system/calendar.ics";
+
+#[derive(Clone, Debug, Serialize)]
+pub struct BusyWindowDto {
+    pub start: u64,
+    pub end: u64,
+    pub summary: String,
+}
+
+impl From<BusyWindow> for BusyWindowDto {
+    fn from(value: BusyWindow) -> Self {
+        Self {
+            start: value.start,
+            end: value.end,
+            summary: value.summary,
+        }
+    }
+}
+
+pub fn source_path(configured: &str) -> PathBuf {
+    let home = std::env::var("HOME").unwrap_or_else(|_| ".".into());
+    match configured.trim() {
+        "" => Path::new(&home).join(EVOLUTION_CALENDAR),
+        path => match path.strip_prefix("~/") {
+            Some(relative) => Path::new(&home).join(relative),
+            None => PathBuf::from(path),
+        },
+    }
+}
+
+pub fn load_busy_windows(configured: &str) -> Vec<BusyWindow> {
+    let offset = i64::from(Local::now().offset().local_minus_utc());
+    let mut windows: Vec<BusyWindow> = ics_files(&source_path(configured))
+        .iter()
+        .filter_map(|file| fs::read_to_string(file).ok())
+        .flat_map(|source| calendar::parse_ics(&source, offset))
+        .collect();
+    windows.sort_by_key(|window| (window.start, window.end));
+    windows
+}
+
+// khal and vdirsyncer store one event per file, with one directory per
+// calendar, so a configured directory is searched one level deep.
+fn ics_files(path: &Path) -> Vec<PathBuf> {
+    if path.is_file() {
+        return vec![path.to_path_buf()];
+    }
+
+    let mut files = Vec::new();
+    for entry in read_dir(path) {
+        if entry.is_dir() {
+            files.extend(read_dir(&entry).into_iter().filter(|file| is_ics(file)));
+        } else if is_ics(&entry) {
+            files.push(entry);
+        }
+    }
+    files
+}
+
+fn read_dir(path: &Path) -> Vec<PathBuf> {
+    fs::read_dir(path)
+        .map(|entries| entries.flatten().map(|entry| entry.path()).collect())
+        .unwrap_or_default()
+}
+
+fn is_ics(path: &Path) -> bool {
+    path.extension()
+        .is_some_and(|extension| extension.eq_ignore_ascii_case("ics"))
+}
@@ -0,0 +1,215 @@
+use std::{
+    collections::BTreeMap,
+    fs,
+    path::{Path, PathBuf},
+    sync::OnceLock,
+    time::{Duration, SystemTime},
+};
+
+use lazaro_core::{
+    config::{ConfigSource, ENV_PREFIX, LayeredConfig},
+    event::RuntimeEvent,
+};
+use serde::Serialize;
+use tauri::{AppHandle, Manager};
+
+use crate::{
+    BackendState, RuntimeEventDto, SettingsDto, commit_settings, emit_runtime_event, i18n,
+    settings_to_core,
+};
+
+const WATCH_INTERVAL: Duration = Duration::from_secs(2);
+
+#[derive(Clone, Debug, Serialize)]
+pub struct ConfigOverrideDto {
+    pub field: String,
+    pub source: String,
+}
+
+#[derive(Clone, Debug, Default, Serialize)]
+pub struct ConfigFileInfoDto {
+    pub path: String,
+    pub exists: bool,
+    pub overrides: Vec<ConfigOverrideDto>,
+    pub errors: Vec<String>,
+}
+
+pub fn config_path() -> PathBuf {
+    if let Ok(xdg) = std::env::var("XDG_CONFIG_HOME")
+        && !xdg.is_empty()
+    {
+        return PathBuf::from(xdg).join("lazaro/config.toml");
+    }
+
+    let home = std::env::var("HOME").unwrap_or_else(|_| ".".to_string());
+    Path::new(&home).join(".config/lazaro/config.toml")
+}
+
+pub fn load() -> Result<Option<toml::Table>, String> {
+    let path = config_path();
+    if !path.exists() {
+        return Ok(None);
+    }
+
+    let raw = fs::read_to_string(&path).map_err(|e| e.to_string())?;
+    raw.parse::<toml::Table>()
+        .map(Some)
+        .map_err(|e| e.to_string())
+}
+
+pub fn value_to_string(value: &toml::Value) -> String {
+    match value {
+        toml::Value::String(text) => text.clone(),
+        other => other.to_string(),
+    }
+}
+
+fn file_entries(table: &toml::Table) -> Vec<(String, String)> {
+    table
+        .iter()
+        .map(|(key, value)| (key.clone(), value_to_string(value)))
+        .collect()
+}
+
+fn source_to_string(source: ConfigSource) -> String {
+    match source {
+        ConfigSource::File => "file",
+        ConfigSource::Env => "env",
+        ConfigSource::Cli => "cli",
+    }
+    .to_string()
+}
+
+/// `LAZARO_*` variables and command-line flags.
+type RuntimeOverrides = (Vec<(String, String)>, Vec<String>);
+
+/// Read once at startup. They override settings for this run only and are
+/// never saved.
+fn runtime_overrides() -> &'static RuntimeOverrides {
+    static OVERRIDES: OnceLock<RuntimeOverrides> = OnceLock::new();
+    OVERRIDES.get_or_init(|| {
+        let vars = std::env::vars()
+            .filter(|(name, _)| name.starts_with(ENV_PREFIX))
+            .collect();
+        (vars, std::env::args().skip(1).collect())
+    })
+}
+
+fn resolve(settings: &mut SettingsDto) -> (BTreeMap<String, ConfigSource>, Vec<String>) {
+    let mut errors = Vec::new();
+    let mut layers = LayeredConfig::new();
+    match load() {
+        Ok(Some(table)) => layers = layers.with_file(file_entries(&table)),
+        Ok(None) => {}
+        Err(error) => errors.push(format!("config.toml: {error}")),
+    }
+    let (vars, args) = runtime_overrides();
+    layers = layers
+        .with_env(vars.iter().cloned())
+        .with_args(args.iter().cloned());
+
+    if layers.is_empty() {
+        return (BTreeMap::new(), errors);
+    }
+    let core = match settings_to_core(settings) {
+        Ok(core) => core,
+        Err(error) => {
+            errors.push(error.to_string());
+            return (BTreeMap::new(), errors);
+        }
+    };
+
+    let resolved = layers.resolve(core);
+    errors.extend(resolved.errors.iter().map(|error| error.to_string()));
+    *settings = SettingsDto::from(resolved.settings);
+    (resolved.sources, errors)
+}
+
+pub fn apply_overrides(settings: &mut SettingsDto) -> BTreeMap<String, ConfigSource> {
+    let (sources, errors) = resolve(settings);
+    for error in errors {
+        tracing::warn!(%error, "ignoring configuration override");
+    }
+    sources
+}
+
+/// Keys that config.toml, the environment or the command line set,
+/// whatever the saved settings say.
+pub fn overridden_keys() -> Vec<String> {
+    let mut probe = SettingsDto::default();
+    resolve(&mut probe).0.into_keys().collect()
+}
+
+pub fn info() -> ConfigFileInfoDto {
+    let path = config_path();
+    let mut probe = SettingsDto::default();
+    let (sources, errors) = resolve(&mut probe);
+
+    ConfigFileInfoDto {
+        path: path.display().to_string(),
+        exists: path.exists(),
+        overrides: sources
+            .into_iter()
+            .map(|(field, source)| ConfigOverrideDto {
+                field,
+                source: source_to_string(source),
+            })
+            .collect(),
+        errors,
+    }
+}
+
+fn modified_at() -> Option<SystemTime> {
+    fs::metadata(config_path())
+        .and_then(|metadata| metadata.modified())
+        .ok()
+}
+
+pub fn spawn_watcher(app: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        let mut last_modified = modified_at();
+        let mut ticker = tokio::time::interval(WATCH_INTERVAL);
+
+        loop {
+            ticker.tick().await;
+            let modified = modified_at();
+            if modified == last_modified {
+                continue;
+            }
+            // A deleted file just means no overrides; re-resolving drops
+            // the ones it set.
+            last_modified = modified;
+
+            let state = app.state::<BackendState>();
+            let Ok(current) = state
+                .persistent
+                .data
+                .lock()
+                .map(|guard| guard.settings.clone())
+            else {
+                continue;
+            };
+            let lang = i18n::resolve(&current.language);
+
+            let (event, key) = match load() {
+                Ok(_) => match commit_settings(&state, current) {
+                    Ok(_) => (RuntimeEvent::ConfigReloaded, "config.reloaded"),
+                    Err(error) => {
+                        tracing::warn!(%error, "failed to apply config.toml");
+                        (RuntimeEvent::ConfigError, "config.invalid")
+                    }
+                },
+                Err(error) => {
+                    tracing::warn!(%error, "config.toml could not be parsed");
+                    (RuntimeEvent::ConfigError, "config.invalid")
+                }
+            };
+
+            tracing::info!(kind = event.kind(), "config.toml changed");
+            emit_runtime_event(
+                &app,
+                RuntimeEventDto::new(event, i18n::text(lang, key), false),
+            );
+        }
+    });
+}
@@ -0,0 +1,116 @@
+use std::{
+    collections::VecDeque,
+    fs,
+    hash::{DefaultHasher, Hash, Hasher},
+    panic::{self, PanicHookInfo},
+    path::{Path, PathBuf},
+    sync::Mutex,
+    thread,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use serde::{Deserialize, Serialize};
+
+pub const CRASH_REPORT_FILE: &str = "crash-report.json";
+const RECENT_EVENT_LIMIT: usize = 20;
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct CrashReportDto {
+    pub crashed_at: u64,
+    pub message: String,
+    pub location: Option<String>,
+    pub thread: Option<String>,
+    pub recent_events: Vec<String>,
+    pub engine_snapshot: Option<serde_json::Value>,
+    pub settings_hash: Option<String>,
+}
+
+struct CrashContext {
+    recent_events: VecDeque<String>,
+    engine_snapshot: Option<serde_json::Value>,
+    settings_hash: Option<String>,
+}
+
+static CONTEXT: Mutex<CrashContext> = Mutex::new(CrashContext {
+    recent_events: VecDeque::new(),
+    engine_snapshot: None,
+    settings_hash: None,
+});
+
+pub fn record_event(kind: &str, message: &str) {
+    if let Ok(mut context) = CONTEXT.lock() {
+        if context.recent_events.len() == RECENT_EVENT_LIMIT {
+            context.recent_events.pop_front();
+        }
+        context
+            .recent_events
+            .push_back(format!("{kind}: {message}"));
+    }
+}
+
+pub fn record_snapshot<T: Serialize>(snapshot: &T) {
+    let value = serde_json::to_value(snapshot).ok();
+    if let Ok(mut context) = CONTEXT.lock() {
+        context.engine_snapshot = value;
+    }
+}
+
+pub fn record_settings<T: Serialize>(settings: &T) {
+    let Ok(raw) = serde_json::to_string(settings) else {
+        return;
+    };
+    let mut hasher = DefaultHasher::new();
+    raw.hash(&mut hasher);
+    if let Ok(mut context) = CONTEXT.lock() {
+        context.settings_hash = Some(format!("{:016x}", hasher.finish()));
+    }
+}
+
+pub fn install(data_dir: PathBuf) {
+    let previous = panic::take_hook();
+    panic::set_hook(Box::new(move |info| {
+        let report = build_report(info);
+        if let Ok(payload) = serde_json::to_string_pretty(&report) {
+            let _ = fs::create_dir_all(&data_dir);
+            let _ = fs::write(data_dir.join(CRASH_REPORT_FILE), payload);
+        }
+        previous(info);
+    }));
+}
+
+pub fn take_report(data_dir: &Path) -> Option<CrashReportDto> {
+    let path = data_dir.join(CRASH_REPORT_FILE);
+    let raw = fs::read_to_string(&path).ok()?;
+    let _ = fs::remove_file(&path);
+    serde_json::from_str(&raw).ok()
+}
+
+fn build_report(info: &PanicHookInfo<'_>) -> CrashReportDto {
+    let message = info
+        .payload()
+        .downcast_ref::<&str>()
+        .map(|value| value.to_string())
+        .or_else(|| info.payload().downcast_ref::<String>().cloned())
+        .unwrap_or_else(|| "panic".into());
+
+    let mut report = CrashReportDto {
+        crashed_at: SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|duration| duration.as_secs())
+            .unwrap_or(0),
+        message,
+        location: info
+            .location()
+            .map(|location| format!("{}:{}", location.file(), location.line())),
+        thread: thread::current().name().map(str::to_string),
+        ..CrashReportDto::default()
+    };
+
+    if let Ok(context) = CONTEXT.try_lock() {
+        report.recent_events = context.recent_events.iter().cloned().collect();
+        report.engine_snapshot = context.engine_snapshot.clone();
+        report.settings_hash = context.settings_hash.clone();
+    }
+
+    report
+}
@@ -0,0 +1,46 @@
+use std::{
+    io::Write,
+    process::{Command, Stdio},
+};
+
+const REQUEST_TIMEOUT_SECONDS: &str = "10";
+
+/// Runs curl with `config` fed through stdin so credentials and bodies never
+/// show up in the process list, returning the response body.
+pub fn run(config: &str) -> Result<Vec<u8>, String> {
+    let mut child = Command::new("curl")
+        .args([
+            "--silent",
+            "--show-error",
+            "--fail",
+            "--max-time",
+            REQUEST_TIMEOUT_SECONDS,
+            "--config",
+            "-",
+        ])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| e.to_string())?;
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin
+            .write_all(config.as_bytes())
+            .map_err(|e| e.to_string())?;
+    }
+
+    let output = child.wait_with_output().map_err(|e| e.to_string())?;
+    if !output.status.success() {
+        return Err(String::from_utf8_lossy(&output.stderr).trim().to_string());
+    }
+    Ok(output.stdout)
+}
+
+/// Formats one `name = "value"` line of a curl config file.
+pub fn option(name: &str, value: &str) -> String {
+    let value = value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n");
+    format!("{name} = \"{value}\"\n")
+}
@@ -0,0 +1,80 @@
+use lazaro_core::analytics::{AnalyticsStore, BreakHistory, DailyAggregate, FocusLog};
+use lazaro_core::timer::BreakKind;
+use serde::Serialize;
+
+use crate::RuntimeStatusDto;
+
+/// Days before today averaged for the comparison column.
+const AVERAGE_DAYS: i64 = 7;
+
+#[derive(Clone, Debug, Default, Serialize)]
+pub struct DayTotalsDto {
+    active_seconds: u64,
+    breaks_taken: u32,
+    micro_done: u32,
+    rest_done: u32,
+    custom_done: u32,
+    skipped: u32,
+}
+
+impl From<&DailyAggregate> for DayTotalsDto {
+    fn from(stats: &DailyAggregate) -> Self {
+        Self {
+            active_seconds: stats.active_seconds,
+            breaks_taken: stats.breaks_taken(),
+            micro_done: stats.micro_done,
+            rest_done: stats.rest_done,
+            custom_done: stats.custom_done,
+            skipped: stats.skipped,
+        }
+    }
+}
+
+/// `get_dashboard_data`: everything the dashboard shows, in one call.
+/// Hourly arrays hold 24 entries from local midnight.
+#[derive(Clone, Debug, Serialize)]
+pub struct DashboardDto {
+    today: DayTotalsDto,
+    yesterday: DayTotalsDto,
+    /// Average of the recorded days among the previous seven.
+    week_average: DayTotalsDto,
+    hourly_active_seconds: Vec<u64>,
+    hourly_breaks_taken: Vec<u32>,
+    hourly_breaks_skipped: Vec<u32>,
+    current_streak_days: u32,
+    longest_streak_days: u32,
+    running: bool,
+    pending_break: Option<BreakKind>,
+    active_break: Option<BreakKind>,
+    next_break_kind: Option<BreakKind>,
+    next_break_eta_seconds: Option<u64>,
+    next_break_at: Option<u64>,
+}
+
+pub fn build(
+    store: &AnalyticsStore,
+    history: &BreakHistory,
+    focus: &FocusLog,
+    today: i64,
+    day_start: u64,
+    status: RuntimeStatusDto,
+) -> DashboardDto {
+    let hourly = history.hourly(day_start);
+    let streaks = store.streaks(today);
+    DashboardDto {
+        today: DayTotalsDto::from(&store.day(today)),
+        yesterday: DayTotalsDto::from(&store.day(today - 1)),
+        week_average: DayTotalsDto::from(&store.daily_average(today - AVERAGE_DAYS, today - 1)),
+        hourly_active_seconds: focus.active_by_hour(day_start).to_vec(),
+        hourly_breaks_taken: hourly.taken.to_vec(),
+        hourly_breaks_skipped: hourly.skipped.to_vec(),
+        current_streak_days: streaks.current_days,
+        longest_streak_days: streaks.longest_days,
+        running: status.running,
+        pending_break: status.pending_break,
+        active_break: status.active_break,
+        next_break_kind: status.next_break_kind,
+        next_break_eta_seconds: status.next_break_eta_seconds,
+        next_break_at: status.next_break_at,
+    }
+}
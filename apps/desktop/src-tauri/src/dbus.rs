@@ -0,0 +1,63 @@
+use std::process::Command;
+
+pub fn session_call(dest: &str, path: &str, method: &str, args: &[&str]) -> Option<String> {
+    call("--session", dest, path, method, args)
+}
+
+pub fn system_property(dest: &str, path: &str, interface: &str, property: &str) -> Option<String> {
+    call(
+        "--system",
+        dest,
+        path,
+        "org.freedesktop.DBus.Properties.Get",
+        &[interface, property],
+    )
+}
+
+fn call(bus: &str, dest: &str, path: &str, method: &str, args: &[&str]) -> Option<String> {
+    let output = Command::new("gdbus")
+        .args([
+            "call",
+            bus,
+            "--dest",
+            dest,
+            "--object-path",
+            path,
+            "--method",
+            method,
+        ])
+        .args(args)
+        .output()
+        .ok()?;
+
+    if !output.status.success() {
+        return None;
+    }
+    Some(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+pub fn session_property(dest: &str, path: &str, interface: &str, property: &str) -> Option<String> {
+    session_call(
+        dest,
+        path,
+        "org.freedesktop.DBus.Properties.Get",
+        &[interface, property],
+    )
+}
+
+pub fn session_names() -> Vec<String> {
+    let Some(raw) = session_call(
+        "org.freedesktop.DBus",
+        "/org/freedesktop/DBus",
+        "org.freedesktop.DBus.ListNames",
+        &[],
+    ) else {
+        return Vec::new();
+    };
+
+    raw.split('\'')
+        .skip(1)
+        .step_by(2)
+        .map(str::to_string)
+        .collect()
+}
@@ -0,0 +1,77 @@
+use std::{
+    fs::{self, File},
+    io::Write,
+    path::Path,
+};
+
+use serde_json::{Value, json};
+use zip::{CompressionMethod, ZipWriter, write::SimpleFileOptions};
+
+pub fn environment_details() -> Value {
+    let var = |key: &str| std::env::var(key).ok().filter(|value| !value.is_empty());
+    let os_release = fs::read_to_string("/etc/os-release").ok().and_then(|raw| {
+        raw.lines()
+            .find_map(|line| line.strip_prefix("PRETTY_NAME="))
+            .map(|value| value.trim_matches('"').to_string())
+    });
+
+    json!({
+        "os": std::env::consts::OS,
+        "arch": std::env::consts::ARCH,
+        "os_release": os_release,
+        "session_type": var("XDG_SESSION_TYPE"),
+        "current_desktop": var("XDG_CURRENT_DESKTOP"),
+        "desktop_session": var("DESKTOP_SESSION"),
+        "wayland": var("WAYLAND_DISPLAY").is_some(),
+        "x11": var("DISPLAY").is_some(),
+        "flatpak": var("FLATPAK_ID").is_some(),
+        "locale": var("LANG"),
+    })
+}
+
+pub fn redact_home(text: &str) -> String {
+    match std::env::var("HOME") {
+        Ok(home) if !home.is_empty() && home != "/" => text.replace(&home, "~"),
+        _ => text.to_string(),
+    }
+}
+
+pub fn redact_value(value: &mut Value) {
+    match value {
+        Value::String(text) => *text = redact_home(text),
+        Value::Array(items) => items.iter_mut().for_each(redact_value),
+        Value::Object(map) => {
+            for (key, item) in map.iter_mut() {
+                if ["_token", "_password", "_secret"]
+                    .iter()
+                    .any(|suffix| key.ends_with(suffix))
+                    && item.as_str().is_some_and(|token| !token.is_empty())
+                {
+                    *item = Value::String("<redacted>".into());
+                } else {
+                    redact_value(item);
+                }
+            }
+        }
+        _ => {}
+    }
+}
+
+pub fn write_bundle(path: &Path, entries: &[(&str, String)]) -> Result<(), String> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+
+    let file = File::create(path).map_err(|e| e.to_string())?;
+    let mut zip = ZipWriter::new(file);
+    let options = SimpleFileOptions::default().compression_method(CompressionMethod::Deflated);
+
+    for (name, contents) in entries {
+        zip.start_file(*name, options).map_err(|e| e.to_string())?;
+        zip.write_all(contents.as_bytes())
+            .map_err(|e| e.to_string())?;
+    }
+
+    zip.finish().map_err(|e| e.to_string())?;
+    Ok(())
+}
@@ -0,0 +1,103 @@
+use tauri::{
+    AppHandle, Emitter, Manager, PhysicalPosition, Position, WebviewUrl, WebviewWindowBuilder,
+};
+
+const DIM_WINDOW_LABEL: &str = "break-dim";
+
+pub fn target_opacity(
+    eta_seconds: u64,
+    warning_seconds: u64,
+    max_opacity_percent: u8,
+) -> Option<u8> {
+    if warning_seconds == 0 || eta_seconds > warning_seconds {
+        return None;
+    }
+
+    let progress = (warning_seconds - eta_seconds) as f64 / warning_seconds as f64;
+    Some((progress * max_opacity_percent.min(100) as f64).round() as u8)
+}
+
+#[derive(Default)]
+pub struct DimmingController {
+    visible: bool,
+    opacity_percent: u8,
+}
+
+impl DimmingController {
+    pub fn update(&mut self, app: &AppHandle, target: Option<u8>) {
+        match target {
+            Some(opacity) if !self.visible => {
+                self.visible = true;
+                self.opacity_percent = opacity;
+                open_dim_window(app, opacity);
+            }
+            Some(opacity) if opacity != self.opacity_percent => {
+                self.opacity_percent = opacity;
+                let _ = app.emit_to(DIM_WINDOW_LABEL, "overlay://dim", opacity);
+            }
+            Some(_) => {}
+            None => self.clear(app),
+        }
+    }
+
+    pub fn clear(&mut self, app: &AppHandle) {
+        if !self.visible {
+            return;
+        }
+        self.visible = false;
+        self.opacity_percent = 0;
+
+        let app_handle = app.clone();
+        let _ = app.run_on_main_thread(move || {
+            if let Some(window) = app_handle.get_webview_window(DIM_WINDOW_LABEL) {
+                let _ = window.close();
+            }
+        });
+    }
+}
+
+fn open_dim_window(app: &AppHandle, opacity: u8) {
+    let app_handle = app.clone();
+    let init_script = format!("window.__LAZARO_DIM_OPACITY__ = {opacity};");
+    let _ = app.run_on_main_thread(move || {
+        if app_handle.get_webview_window(DIM_WINDOW_LABEL).is_some() {
+            let _ = app_handle.emit_to(DIM_WINDOW_LABEL, "overlay://dim", opacity);
+            return;
+        }
+
+        let monitor = app_handle
+            .get_webview_window("main")
+            .and_then(|window| window.current_monitor().ok().flatten())
+            .or_else(|| app_handle.primary_monitor().ok().flatten());
+
+        let mut builder = WebviewWindowBuilder::new(
+            &app_handle,
+            DIM_WINDOW_LABEL,
+            WebviewUrl::App("dim.html".into()),
+        )
+        .title("Lázaro")
+        .decorations(false)
+        .transparent(true)
+        .always_on_top(true)
+        .resizable(false)
+        .skip_taskbar(true)
+        .focused(false)
+        .closable(false)
+        .initialization_script(&init_script);
+
+        if let Some(monitor) = monitor.as_ref() {
+            let size = monitor.size();
+            builder = builder.inner_size(size.width as f64, size.height as f64);
+        }
+
+        if let Ok(window) = builder.build() {
+            let _ = window.set_ignore_cursor_events(true);
+            if let Some(monitor) = monitor {
+                let position = monitor.position();
+                let _ = window.set_position(Position::Physical(PhysicalPosition::new(
+                    position.x, position.y,
+                )));
+            }
+        }
+    });
+}
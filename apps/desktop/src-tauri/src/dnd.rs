@@ -0,0 +1,28 @@
+use std::process::Command;
+
+use crate::dbus;
+
+pub fn system_dnd_active() -> bool {
+    gnome_banners_disabled() || notifications_inhibited()
+}
+
+fn gnome_banners_disabled() -> bool {
+    Command::new("gsettings")
+        .args(["get", "org.gnome.desktop.notifications", "show-banners"])
+        .output()
+        .is_ok_and(|output| {
+            output.status.success() && String::from_utf8_lossy(&output.stdout).trim() == "false"
+        })
+}
+
+// KDE Plasma (and other servers implementing the spec extension) expose DND
+// through the `Inhibited` property of the notifications service.
+fn notifications_inhibited() -> bool {
+    dbus::session_property(
+        "org.freedesktop.Notifications",
+        "/org/freedesktop/Notifications",
+        "org.freedesktop.Notifications",
+        "Inhibited",
+    )
+    .is_some_and(|value| value.contains("<true>"))
+}
@@ -0,0 +1,50 @@
+use crate::RuntimeStatusDto;
+
+const FINAL_COUNTDOWN_SECONDS: u64 = 10;
+
+#[derive(Default)]
+pub struct StatusEmitter {
+    last: Option<RuntimeStatusDto>,
+    ticks_since_emit: u64,
+}
+
+impl StatusEmitter {
+    pub fn should_emit(&mut self, next: &RuntimeStatusDto, interval_seconds: u64) -> bool {
+        self.ticks_since_emit = self.ticks_since_emit.saturating_add(1);
+
+        let emit = match self.last.as_ref() {
+            None => true,
+            Some(prev) if structural_change(prev, next) => true,
+            Some(prev) => prev != next && self.ticks_since_emit >= interval_seconds.max(1),
+        };
+
+        if emit {
+            self.last = Some(next.clone());
+            self.ticks_since_emit = 0;
+        }
+        emit
+    }
+}
+
+pub fn should_emit_break_tick(remaining_seconds: u64, interval_seconds: u64) -> bool {
+    interval_seconds <= 1
+        || remaining_seconds <= FINAL_COUNTDOWN_SECONDS
+        || remaining_seconds.is_multiple_of(interval_seconds)
+}
+
+fn structural_change(prev: &RuntimeStatusDto, next: &RuntimeStatusDto) -> bool {
+    prev.running != next.running
+        || prev.pending_breaks != next.pending_breaks
+        || prev.active_break != next.active_break
+        || prev.next_break_kind != next.next_break_kind
+        || prev.strict_mode != next.strict_mode
+        || prev.dnd_active != next.dnd_active
+        || prev.power_saving != next.power_saving
+        || prev.vacation_until != next.vacation_until
+        || prev.guest_until != next.guest_until
+        || prev.presentation_until != next.presentation_until
+        || prev.screen_capture_active != next.screen_capture_active
+        || prev.wind_down != next.wind_down
+        || prev.inhibited_by != next.inhibited_by
+        || prev.daily_limit_action_at != next.daily_limit_action_at
+}
@@ -0,0 +1,413 @@
+use std::{cmp::Reverse, collections::BTreeMap};
+
+use chrono::{Datelike, Local, NaiveDate, TimeZone, Timelike};
+use lazaro_core::{
+    analytics::{
+        self, AppUsage, AppUsageStore, BreakHistory, BreakSession, FocusLog, FocusSession,
+        FocusSummary, IntensityBucket, IntensityLog, SessionOutcome,
+    },
+    config::{AnalyticsSettings, AppCategory},
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{break_kind_to_string, parse_break_kind};
+
+pub const RETENTION_DAYS: u64 = 30;
+pub const DAILY_STATS_RETENTION_DAYS: i64 = 400;
+
+const UNIX_EPOCH_DAYS_FROM_CE: i64 = 719_163;
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct BreakSessionDto {
+    kind: String,
+    scheduled_at: u64,
+    started_at: Option<u64>,
+    ended_at: Option<u64>,
+    outcome: Option<String>,
+    snooze_count: u32,
+    #[serde(default)]
+    rating: Option<u8>,
+}
+
+impl BreakSessionDto {
+    pub fn kind(&self) -> &str {
+        &self.kind
+    }
+
+    pub fn scheduled_at(&self) -> u64 {
+        self.scheduled_at
+    }
+}
+
+impl From<&BreakSession> for BreakSessionDto {
+    fn from(value: &BreakSession) -> Self {
+        Self {
+            kind: break_kind_to_string(value.kind),
+            scheduled_at: value.scheduled_at,
+            started_at: value.started_at,
+            ended_at: value.ended_at,
+            outcome: value.outcome.map(outcome_to_string),
+            snooze_count: value.snooze_count,
+            rating: value.rating,
+        }
+    }
+}
+
+fn outcome_to_string(outcome: SessionOutcome) -> String {
+    match outcome {
+        SessionOutcome::Completed => "completed".into(),
+        SessionOutcome::Skipped => "skipped".into(),
+        SessionOutcome::Interrupted => "interrupted".into(),
+    }
+}
+
+fn parse_outcome(value: &str) -> Option<SessionOutcome> {
+    match value {
+        "completed" => Some(SessionOutcome::Completed),
+        "skipped" => Some(SessionOutcome::Skipped),
+        "interrupted" => Some(SessionOutcome::Interrupted),
+        _ => None,
+    }
+}
+
+fn session_from_dto(dto: &BreakSessionDto) -> Option<BreakSession> {
+    Some(BreakSession {
+        kind: parse_break_kind(&dto.kind).ok()?,
+        scheduled_at: dto.scheduled_at,
+        started_at: dto.started_at,
+        ended_at: dto.ended_at,
+        outcome: dto.outcome.as_deref().and_then(parse_outcome),
+        snooze_count: dto.snooze_count,
+        rating: dto.rating,
+    })
+}
+
+pub fn to_history(sessions: &[BreakSessionDto]) -> BreakHistory {
+    BreakHistory::from_sessions(sessions.iter().filter_map(session_from_dto).collect())
+}
+
+pub fn to_dtos(history: &BreakHistory) -> Vec<BreakSessionDto> {
+    history
+        .sessions()
+        .iter()
+        .map(BreakSessionDto::from)
+        .collect()
+}
+
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+pub struct FocusSessionDto {
+    started_at: u64,
+    ended_at: u64,
+    active_seconds: u64,
+}
+
+impl FocusSessionDto {
+    pub fn started_at(&self) -> u64 {
+        self.started_at
+    }
+}
+
+impl From<FocusSession> for FocusSessionDto {
+    fn from(value: FocusSession) -> Self {
+        Self {
+            started_at: value.started_at,
+            ended_at: value.ended_at,
+            active_seconds: value.active_seconds,
+        }
+    }
+}
+
+/// `get_focus_sessions`: one day of focus sessions and their totals.
+#[derive(Clone, Debug, Serialize)]
+pub struct FocusReportDto {
+    pub sessions: Vec<FocusSessionDto>,
+    pub session_count: u32,
+    pub active_seconds: u64,
+    pub longest_seconds: u64,
+    pub average_seconds: u64,
+}
+
+impl FocusReportDto {
+    pub fn new(sessions: Vec<FocusSession>, summary: FocusSummary) -> Self {
+        Self {
+            sessions: sessions.into_iter().map(FocusSessionDto::from).collect(),
+            session_count: summary.sessions,
+            active_seconds: summary.active_seconds,
+            longest_seconds: summary.longest_seconds,
+            average_seconds: summary.average_seconds,
+        }
+    }
+}
+
+pub fn to_focus_log(sessions: &[FocusSessionDto]) -> FocusLog {
+    FocusLog::from_sessions(
+        sessions
+            .iter()
+            .map(|dto| FocusSession {
+                started_at: dto.started_at,
+                ended_at: dto.ended_at,
+                active_seconds: dto.active_seconds,
+            })
+            .collect(),
+    )
+}
+
+pub fn focus_dtos(log: &FocusLog) -> Vec<FocusSessionDto> {
+    log.sessions()
+        .iter()
+        .copied()
+        .map(FocusSessionDto::from)
+        .collect()
+}
+
+/// One hour of input counts from the intensity sampler.
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+pub struct IntensityBucketDto {
+    hour_start: u64,
+    keys: u64,
+    clicks: u64,
+}
+
+pub fn to_intensity_log(buckets: &[IntensityBucketDto]) -> IntensityLog {
+    IntensityLog::from_buckets(
+        buckets
+            .iter()
+            .map(|dto| IntensityBucket {
+                hour_start: dto.hour_start,
+                keys: dto.keys,
+                clicks: dto.clicks,
+            })
+            .collect(),
+    )
+}
+
+pub fn intensity_dtos(log: &IntensityLog) -> Vec<IntensityBucketDto> {
+    log.buckets()
+        .iter()
+        .map(|bucket| IntensityBucketDto {
+            hour_start: bucket.hour_start,
+            keys: bucket.keys,
+            clicks: bucket.clicks,
+        })
+        .collect()
+}
+
+/// One application's active time on one day, keyed by window class in
+/// `AppUsageDayDto`.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct AppUsageDto {
+    active_seconds: u64,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    titles: BTreeMap<String, u64>,
+}
+
+pub type AppUsageDayDto = BTreeMap<String, AppUsageDto>;
+
+pub fn to_app_usage_store(days: &BTreeMap<i64, AppUsageDayDto>) -> AppUsageStore {
+    AppUsageStore::from_days(
+        days.iter()
+            .map(|(day, apps)| {
+                let apps = apps
+                    .iter()
+                    .map(|(app, dto)| {
+                        let usage = AppUsage {
+                            active_seconds: dto.active_seconds,
+                            titles: dto.titles.clone(),
+                        };
+                        (app.clone(), usage)
+                    })
+                    .collect();
+                (*day, apps)
+            })
+            .collect(),
+    )
+}
+
+pub fn app_usage_dtos(store: &AppUsageStore) -> BTreeMap<i64, AppUsageDayDto> {
+    store
+        .days()
+        .iter()
+        .map(|(day, apps)| {
+            let apps = apps
+                .iter()
+                .map(|(app, usage)| {
+                    let dto = AppUsageDto {
+                        active_seconds: usage.active_seconds,
+                        titles: usage.titles.clone(),
+                    };
+                    (app.clone(), dto)
+                })
+                .collect();
+            (*day, apps)
+        })
+        .collect()
+}
+
+/// `get_app_usage`: one day of active time by application, most used first.
+#[derive(Clone, Debug, Serialize)]
+pub struct AppUsageReportDto {
+    pub date: String,
+    /// Whether usage is being recorded now; older days may have data either way.
+    pub enabled: bool,
+    pub active_seconds: u64,
+    pub apps: Vec<AppUsageEntryDto>,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct AppUsageEntryDto {
+    pub app: String,
+    pub active_seconds: u64,
+    /// Share of the day's recorded application time.
+    pub percent: u8,
+    /// `work`, `comms` or `entertainment`; `None` when no rule matches.
+    pub category: Option<&'static str>,
+    pub titles: Vec<TitleUsageDto>,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct TitleUsageDto {
+    pub title: String,
+    pub active_seconds: u64,
+}
+
+impl AppUsageReportDto {
+    pub fn new(store: &AppUsageStore, day_index: i64, settings: &AnalyticsSettings) -> Self {
+        let ranked = store.ranked(day_index);
+        let total: u64 = ranked.iter().map(|(_, usage)| usage.active_seconds).sum();
+        let apps = ranked
+            .into_iter()
+            .map(|(app, usage)| {
+                let mut titles: Vec<TitleUsageDto> = usage
+                    .titles
+                    .iter()
+                    .map(|(title, seconds)| TitleUsageDto {
+                        title: title.clone(),
+                        active_seconds: *seconds,
+                    })
+                    .collect();
+                titles.sort_by_key(|title| Reverse(title.active_seconds));
+                AppUsageEntryDto {
+                    app: app.to_string(),
+                    active_seconds: usage.active_seconds,
+                    percent: (usage.active_seconds.saturating_mul(100) / total.max(1)) as u8,
+                    category: settings.category_of(app).map(AppCategory::as_str),
+                    titles,
+                }
+            })
+            .collect();
+        Self {
+            date: day_index_to_date(day_index),
+            enabled: settings.app_usage_enabled,
+            active_seconds: total,
+            apps,
+        }
+    }
+}
+
+/// `get_category_breakdown`: application time by category over the
+/// calendar week containing a day.
+#[derive(Clone, Debug, Serialize)]
+pub struct CategoryBreakdownDto {
+    pub week_start: String,
+    pub categories: Vec<CategoryUsageDto>,
+    /// Work and comms time.
+    pub productive_seconds: u64,
+    /// Entertainment time.
+    pub leisure_seconds: u64,
+    /// Time in applications without a category rule.
+    pub uncategorized_seconds: u64,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct CategoryUsageDto {
+    pub category: &'static str,
+    pub active_seconds: u64,
+    /// Share of the week's recorded application time, uncategorized included.
+    pub percent: u8,
+}
+
+impl CategoryBreakdownDto {
+    pub fn new(store: &AppUsageStore, day_index: i64, settings: &AnalyticsSettings) -> Self {
+        let start = analytics::week_start_day(day_index, settings.week_start);
+        let breakdown = store.category_breakdown(start, start + 6, settings);
+        let total = breakdown.total_seconds().max(1);
+        let categories = AppCategory::ALL
+            .into_iter()
+            .map(|category| {
+                let seconds = breakdown.seconds(category);
+                CategoryUsageDto {
+                    category: category.as_str(),
+                    active_seconds: seconds,
+                    percent: (seconds.saturating_mul(100) / total) as u8,
+                }
+            })
+            .collect();
+        Self {
+            week_start: day_index_to_date(start),
+            categories,
+            productive_seconds: breakdown.productive_seconds(),
+            leisure_seconds: breakdown.leisure_seconds(),
+            uncategorized_seconds: breakdown.uncategorized_seconds,
+        }
+    }
+}
+
+fn date_to_day_index(date: NaiveDate) -> i64 {
+    i64::from(date.num_days_from_ce()) - UNIX_EPOCH_DAYS_FROM_CE
+}
+
+/// Seconds local time is ahead of UTC right now. chrono re-reads the zone
+/// when `TZ` or `/etc/localtime` changes, so this follows a new zone too.
+pub fn local_utc_offset() -> i64 {
+    i64::from(Local::now().offset().local_minus_utc())
+}
+
+pub fn local_hour() -> u8 {
+    Local::now().hour() as u8
+}
+
+pub fn local_day_index() -> i64 {
+    date_to_day_index(Local::now().date_naive())
+}
+
+pub fn local_day_index_at(unix_seconds: u64) -> i64 {
+    i64::try_from(unix_seconds)
+        .ok()
+        .and_then(|seconds| Local.timestamp_opt(seconds, 0).single())
+        .map(|datetime| date_to_day_index(datetime.date_naive()))
+        .unwrap_or_else(local_day_index)
+}
+
+pub fn parse_day_index(day: Option<&str>) -> Option<i64> {
+    match day {
+        Some(value) => NaiveDate::parse_from_str(value, "%Y-%m-%d")
+            .ok()
+            .map(date_to_day_index),
+        None => Some(local_day_index()),
+    }
+}
+
+pub fn day_index_to_date(day_index: i64) -> String {
+    i32::try_from(day_index + UNIX_EPOCH_DAYS_FROM_CE)
+        .ok()
+        .and_then(NaiveDate::from_num_days_from_ce_opt)
+        .map(|date| date.format("%Y-%m-%d").to_string())
+        .unwrap_or_default()
+}
+
+pub fn local_day_range(day: Option<&str>) -> Option<(u64, u64)> {
+    let date = match day {
+        Some(value) => NaiveDate::parse_from_str(value, "%Y-%m-%d").ok()?,
+        None => Local::now().date_naive(),
+    };
+    let start = Local
+        .from_local_datetime(&date.and_hms_opt(0, 0, 0)?)
+        .earliest()?;
+    let end = Local
+        .from_local_datetime(&date.succ_opt()?.and_hms_opt(0, 0, 0)?)
+        .earliest()?;
+    Some((
+        u64::try_from(start.timestamp()).ok()?,
+        u64::try_from(end.timestamp()).ok()?,
+    ))
+}
@@ -0,0 +1,641 @@
+use lazaro_core::{
+    config::Settings,
+    timer::{AdaptReason, BreakKind},
+};
+
+pub const SYSTEM_LANGUAGE: &str = "system";
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Language {
+    Es,
+    En,
+}
+
+impl Language {
+    pub const ALL: [Language; 2] = [Language::Es, Language::En];
+
+    pub fn code(self) -> &'static str {
+        match self {
+            Language::Es => "es",
+            Language::En => "en",
+        }
+    }
+
+    pub fn native_name(self) -> &'static str {
+        match self {
+            Language::Es => "Español",
+            Language::En => "English",
+        }
+    }
+
+    pub fn from_locale(value: &str) -> Option<Self> {
+        let code = value
+            .split(['_', '-', '.', '@'])
+            .next()
+            .unwrap_or_default()
+            .to_ascii_lowercase();
+        Self::ALL.into_iter().find(|lang| lang.code() == code)
+    }
+}
+
+pub fn detect_system_language() -> Language {
+    ["LC_ALL", "LC_MESSAGES", "LANGUAGE", "LANG"]
+        .iter()
+        .filter_map(|key| std::env::var(key).ok())
+        .filter(|value| !value.is_empty() && value != "C" && value != "POSIX")
+        .flat_map(|value| {
+            value
+                .split(':')
+                .map(str::to_string)
+                .collect::<Vec<String>>()
+        })
+        .find_map(|value| Language::from_locale(&value))
+        .unwrap_or(Language::Es)
+}
+
+pub fn resolve(setting: &str) -> Language {
+    if setting == SYSTEM_LANGUAGE {
+        return detect_system_language();
+    }
+    Language::from_locale(setting).unwrap_or_else(detect_system_language)
+}
+
+pub fn is_supported(setting: &str) -> bool {
+    setting == SYSTEM_LANGUAGE || Language::ALL.iter().any(|lang| lang.code() == setting)
+}
+
+pub fn text(lang: Language, key: &str) -> &'static str {
+    let catalog = match lang {
+        Language::Es => ES,
+        Language::En => EN,
+    };
+    catalog
+        .iter()
+        .chain(ES.iter())
+        .find(|(id, _)| *id == key)
+        .map(|(_, value)| *value)
+        .unwrap_or("")
+}
+
+pub fn format(lang: Language, key: &str, args: &[(&str, &str)]) -> String {
+    interpolate(text(lang, key), args)
+}
+
+pub fn interpolate(template: &str, args: &[(&str, &str)]) -> String {
+    let mut message = template.to_string();
+    for (name, value) in args {
+        message = message.replace(&format!("{{{name}}}"), value);
+    }
+    message
+}
+
+pub fn kind_label(lang: Language, kind: BreakKind) -> &'static str {
+    text(lang, kind_key(kind))
+}
+
+pub fn break_label(lang: Language, settings: &Settings, kind: BreakKind) -> &str {
+    match kind {
+        BreakKind::Custom(index) => settings
+            .custom_timers
+            .get(usize::from(index))
+            .map(|timer| timer.name.as_str())
+            .filter(|name| !name.is_empty())
+            .unwrap_or_else(|| kind_label(lang, kind)),
+        _ => kind_label(lang, kind),
+    }
+}
+
+fn adapt_reason_key(reason: AdaptReason) -> &'static str {
+    match reason {
+        AdaptReason::FrequentSkips => "adaptive.frequent_skips",
+        AdaptReason::LongActiveStreak => "adaptive.long_streak",
+        AdaptReason::GoodCompliance => "adaptive.good_compliance",
+        AdaptReason::Disabled => "adaptive.disabled",
+    }
+}
+
+fn kind_key(kind: BreakKind) -> &'static str {
+    match kind {
+        BreakKind::Micro => "kind.micro",
+        BreakKind::Rest => "kind.rest",
+        BreakKind::DailyLimit => "kind.daily_limit",
+        BreakKind::Custom(_) => "kind.custom",
+    }
+}
+
+/// Whether `key` names a built-in message that a template can replace.
+pub fn is_message_key(key: &str) -> bool {
+    ES.iter().any(|(id, _)| *id == key)
+}
+
+/// Catalog lookups that prefer the user's `message_templates`.
+#[derive(Clone, Copy)]
+pub struct Messages<'a> {
+    lang: Language,
+    settings: &'a Settings,
+}
+
+impl<'a> Messages<'a> {
+    pub fn new(lang: Language, settings: &'a Settings) -> Self {
+        Self { lang, settings }
+    }
+
+    pub fn text(&self, key: &str) -> &'a str {
+        self.settings
+            .notifications
+            .template(key)
+            .unwrap_or_else(|| text(self.lang, key))
+    }
+
+    pub fn format(&self, key: &str, args: &[(&str, &str)]) -> String {
+        interpolate(self.text(key), args)
+    }
+
+    pub fn kind_label(&self, kind: BreakKind) -> &'a str {
+        self.text(kind_key(kind))
+    }
+
+    pub fn break_label(&self, kind: BreakKind) -> &'a str {
+        match kind {
+            BreakKind::Custom(index) => self
+                .settings
+                .custom_timers
+                .get(usize::from(index))
+                .map(|timer| timer.name.as_str())
+                .filter(|name| !name.is_empty())
+                .unwrap_or_else(|| self.kind_label(kind)),
+            _ => self.kind_label(kind),
+        }
+    }
+
+    pub fn adapt_reason_label(&self, reason: AdaptReason) -> &'a str {
+        self.text(adapt_reason_key(reason))
+    }
+}
+
+const ES: &[(&str, &str)] = &[
+    ("app.title", "Lázaro"),
+    ("overlay.title", "Lázaro - Descanso"),
+    ("overlay.heading", "Momento de descanso"),
+    ("overlay.kind", "Tipo: {kind}"),
+    (
+        "overlay.hint",
+        "Respira profundo, suelta hombros y mira lejos de la pantalla.",
+    ),
+    (
+        "overlay.strict",
+        "Modo estricto: este descanso no se puede saltar",
+    ),
+    ("overlay.skip", "Terminar descanso"),
+    ("overlay.preview", "Vista previa: no cuenta como descanso"),
+    ("overlay.skip_locked", "Terminar descanso ({seconds} s)"),
+    (
+        "overlay.activity.eyes",
+        "Mira un punto a más de seis metros durante veinte segundos.",
+    ),
+    (
+        "overlay.activity.shoulders",
+        "Gira los hombros hacia atrás y relaja el cuello.",
+    ),
+    (
+        "overlay.activity.breathe",
+        "Inhala cuatro segundos, retén cuatro y exhala seis.",
+    ),
+    (
+        "overlay.activity.walk",
+        "Levántate y camina un par de minutos.",
+    ),
+    ("overlay.activity.water", "Ve por un vaso de agua."),
+    (
+        "overlay.activity.stretch",
+        "Estira espalda, muñecas y piernas.",
+    ),
+    (
+        "overlay.activity.blink",
+        "Parpadea despacio diez veces para humedecer los ojos.",
+    ),
+    (
+        "overlay.activity.palming",
+        "Cubre los ojos con las palmas y descansa la vista un minuto.",
+    ),
+    (
+        "overlay.activity.stand",
+        "Ponte de pie y cambia el peso de una pierna a otra.",
+    ),
+    (
+        "overlay.activity.breathe_long",
+        "Siéntate cómodo y respira despacio con el abdomen unos minutos.",
+    ),
+    (
+        "overlay.activity.daily_limit",
+        "Has llegado al límite de hoy; es buen momento para desconectar.",
+    ),
+    ("kind.micro", "micro"),
+    ("kind.rest", "largo"),
+    ("kind.daily_limit", "límite diario"),
+    ("kind.custom", "personalizado"),
+    ("break.due", "Descanso {kind} disponible"),
+    ("break.due.notification", "Toca descanso {kind}"),
+    (
+        "break.escalated",
+        "El descanso {kind} sigue pendiente: empiézalo o pospónlo",
+    ),
+    (
+        "break.forced",
+        "El descanso {kind} empieza ya tras ignorarlo demasiado tiempo",
+    ),
+    ("break.deferred", "Descanso {kind} aplazado ({reason})"),
+    ("break.started", "Descanso {kind} iniciado"),
+    ("break.started.notification", "Comienza el descanso {kind}"),
+    ("break.completed", "Descanso {kind} completado"),
+    (
+        "break.completed.notification",
+        "Buen trabajo. Descanso completado.",
+    ),
+    ("break.snoozed", "Descanso {kind} pospuesto hasta {until}"),
+    ("break.snoozed.pending", "Se pospone descanso {kind}"),
+    (
+        "break.postponed.pending",
+        "Descanso {kind} pospuesto {minutes} min",
+    ),
+    (
+        "break.overlay_closed.completed",
+        "Descanso {kind} cerrado antes de tiempo; cuenta como hecho",
+    ),
+    (
+        "break.overlay_closed.skipped",
+        "Descanso {kind} cerrado antes de tiempo; cuenta como omitido",
+    ),
+    (
+        "break.overlay_failed",
+        "No se pudo mostrar el descanso {kind}; se pospone",
+    ),
+    ("break.tick", "Cuenta regresiva activa"),
+    ("daily.reset", "Reinicio diario aplicado"),
+    (
+        "daily.reset.forced",
+        "Contadores del día reiniciados a mano",
+    ),
+    (
+        "daily.warning",
+        "Llevas {used} de {limit} del límite diario ({percent}%)",
+    ),
+    (
+        "weekly.warning",
+        "Llevas {used} de {limit} del límite semanal ({percent}%)",
+    ),
+    (
+        "weekly.reached",
+        "Has alcanzado el límite semanal de {limit}",
+    ),
+    (
+        "app_limit.reached",
+        "Has usado {app} durante {limit} hoy, su límite diario",
+    ),
+    (
+        "milestone.breaks_in_a_row",
+        "¡{count} descansos seguidos completados!",
+    ),
+    (
+        "milestone.streak_days",
+        "¡{count} días seguidos tomando descansos!",
+    ),
+    (
+        "nudge.skip_rate",
+        "Hoy has omitido o pospuesto el {percent}% de los descansos {kind}",
+    ),
+    (
+        "nudge.long_focus",
+        "Llevas {duration} sin un descanso de verdad; levántate un momento",
+    ),
+    (
+        "nudge.late_night",
+        "Son más de las {hour}:00 y sigues frente a la pantalla; ¿vas cerrando?",
+    ),
+    (
+        "wind_down.started",
+        "Empieza la desconexión: los intervalos bajan al {percent}%",
+    ),
+    (
+        "wind_down.cutoff",
+        "Hora de parar: tu jornada terminó por hoy",
+    ),
+    (
+        "wind_down.budget",
+        "Ya usaste los {budget} de trabajo nocturno de hoy; hora de parar",
+    ),
+    (
+        "actions.status",
+        "Próximo descanso ({kind}) en {eta}. Hoy llevas {active} de actividad.",
+    ),
+    ("actions.not_running", "Lázaro no está contando el tiempo"),
+    (
+        "daily.action.lock",
+        "La sesión se bloqueará en {seconds} s por el límite diario. Empieza el descanso o cancela el bloqueo.",
+    ),
+    (
+        "daily.action.suspend",
+        "El equipo se suspenderá en {seconds} s por el límite diario. Empieza el descanso o cancela la suspensión.",
+    ),
+    (
+        "daily.action.cancelled",
+        "Acción del límite diario cancelada",
+    ),
+    (
+        "vacation.started",
+        "De vacaciones hasta el {until}: no se cuenta actividad ni se avisa de descansos",
+    ),
+    (
+        "vacation.ended",
+        "Fin de las vacaciones: los temporizadores empiezan de cero",
+    ),
+    (
+        "guest.started",
+        "Sesión de invitado durante {minutes} min: no se cuenta actividad ni se avisa de descansos",
+    ),
+    (
+        "guest.ended",
+        "Fin de la sesión de invitado: los temporizadores siguen donde estaban",
+    ),
+    (
+        "presentation.started",
+        "Modo presentación durante {minutes} min: sin descansos, avisos ni sonidos",
+    ),
+    (
+        "presentation.ended",
+        "Fin del modo presentación: los temporizadores siguen donde estaban",
+    ),
+    (
+        "presentation.screencast.started",
+        "Se está compartiendo la pantalla: modo presentación activado",
+    ),
+    (
+        "presentation.screencast.ended",
+        "Ya no se comparte la pantalla: fin del modo presentación",
+    ),
+    (
+        "adaptive.adjusted",
+        "Intervalos ajustados al {percent}%: {reason}",
+    ),
+    ("adaptive.frequent_skips", "omites descansos con frecuencia"),
+    (
+        "adaptive.long_streak",
+        "llevas mucho tiempo activo sin pausa",
+    ),
+    ("adaptive.good_compliance", "estás tomando tus descansos"),
+    ("adaptive.disabled", "modo adaptativo desactivado"),
+    ("integrations.status", "En descanso 🧘 hasta {until}"),
+    ("daily.summary.title", "Resumen del día"),
+    ("tray.active", "Activo hoy: {active}"),
+    (
+        "tray.presentation",
+        "Modo presentación: descansos y avisos en pausa",
+    ),
+    ("tray.presentation.title", "Presentando"),
+    ("tray.next", "Próximo descanso {kind} en {eta}"),
+    ("tray.pending", "Descanso {kind} pendiente"),
+    ("tray.break", "Descanso {kind}: quedan {remaining}"),
+    (
+        "daily.summary",
+        "{active} activo, {micro} micropausas, {rest} descansos largos, {skipped} omitidos",
+    ),
+    (
+        "config.reloaded",
+        "Configuración recargada desde config.toml",
+    ),
+    (
+        "config.invalid",
+        "config.toml no es válido; se mantiene la configuración actual",
+    ),
+    ("runtime.crashed", "El motor de descansos falló y se detuvo"),
+    (
+        "runtime.crashed.restarted",
+        "El motor de descansos falló y se reinició desde su último estado",
+    ),
+];
+
+const EN: &[(&str, &str)] = &[
+    ("app.title", "Lázaro"),
+    ("overlay.title", "Lázaro - Break"),
+    ("overlay.heading", "Time for a break"),
+    ("overlay.kind", "Type: {kind}"),
+    (
+        "overlay.hint",
+        "Breathe deeply, drop your shoulders and look away from the screen.",
+    ),
+    (
+        "overlay.strict",
+        "Strict mode: this break cannot be skipped",
+    ),
+    ("overlay.skip", "End break"),
+    ("overlay.preview", "Preview: does not count as a break"),
+    ("overlay.skip_locked", "End break ({seconds}s)"),
+    (
+        "overlay.activity.eyes",
+        "Look at something over six metres away for twenty seconds.",
+    ),
+    (
+        "overlay.activity.shoulders",
+        "Roll your shoulders back and relax your neck.",
+    ),
+    (
+        "overlay.activity.breathe",
+        "Breathe in for four seconds, hold for four, out for six.",
+    ),
+    (
+        "overlay.activity.walk",
+        "Get up and walk for a couple of minutes.",
+    ),
+    ("overlay.activity.water", "Go get a glass of water."),
+    (
+        "overlay.activity.stretch",
+        "Stretch your back, wrists and legs.",
+    ),
+    (
+        "overlay.activity.blink",
+        "Blink slowly ten times to rewet your eyes.",
+    ),
+    (
+        "overlay.activity.palming",
+        "Cup your palms over your eyes and rest them for a minute.",
+    ),
+    (
+        "overlay.activity.stand",
+        "Stand up and shift your weight from one leg to the other.",
+    ),
+    (
+        "overlay.activity.breathe_long",
+        "Sit comfortably and breathe slowly from your belly for a few minutes.",
+    ),
+    (
+        "overlay.activity.daily_limit",
+        "You reached today's limit; a good time to switch off.",
+    ),
+    ("kind.micro", "micro"),
+    ("kind.rest", "rest"),
+    ("kind.daily_limit", "daily limit"),
+    ("kind.custom", "custom"),
+    ("break.due", "{kind} break available"),
+    ("break.due.notification", "Time for a {kind} break"),
+    (
+        "break.escalated",
+        "The {kind} break is still waiting: start or snooze it",
+    ),
+    (
+        "break.forced",
+        "Starting the {kind} break now after it was ignored for too long",
+    ),
+    ("break.deferred", "{kind} break deferred ({reason})"),
+    ("break.started", "{kind} break started"),
+    (
+        "break.started.notification",
+        "Your {kind} break is starting",
+    ),
+    ("break.completed", "{kind} break completed"),
+    (
+        "break.completed.notification",
+        "Well done. Break completed.",
+    ),
+    ("break.snoozed", "{kind} break snoozed until {until}"),
+    ("break.snoozed.pending", "Snoozing {kind} break"),
+    (
+        "break.postponed.pending",
+        "{kind} break postponed for {minutes} min",
+    ),
+    (
+        "break.overlay_closed.completed",
+        "{kind} break closed early; counted as taken",
+    ),
+    (
+        "break.overlay_closed.skipped",
+        "{kind} break closed early; counted as skipped",
+    ),
+    (
+        "break.overlay_failed",
+        "Could not show the {kind} break; snoozing it",
+    ),
+    ("break.tick", "Countdown running"),
+    ("daily.reset", "Daily reset applied"),
+    ("daily.reset.forced", "Daily counters reset by hand"),
+    (
+        "daily.warning",
+        "{used} of {limit} daily limit used ({percent}%)",
+    ),
+    (
+        "weekly.warning",
+        "{used} of {limit} weekly limit used ({percent}%)",
+    ),
+    (
+        "weekly.reached",
+        "You have reached your {limit} weekly limit",
+    ),
+    (
+        "app_limit.reached",
+        "You have used {app} for {limit} today, its daily limit",
+    ),
+    (
+        "milestone.breaks_in_a_row",
+        "{count} breaks in a row completed!",
+    ),
+    (
+        "milestone.streak_days",
+        "{count} days in a row taking breaks!",
+    ),
+    (
+        "nudge.skip_rate",
+        "You skipped or snoozed {percent}% of today's {kind} breaks",
+    ),
+    (
+        "nudge.long_focus",
+        "You have gone {duration} without a real break; stand up for a moment",
+    ),
+    (
+        "nudge.late_night",
+        "It is past {hour}:00 and you are still at the screen; time to wrap up?",
+    ),
+    (
+        "wind_down.started",
+        "Wind-down started: intervals drop to {percent}%",
+    ),
+    ("wind_down.cutoff", "Time to stop: your workday is over"),
+    (
+        "wind_down.budget",
+        "You used tonight's {budget} of late work; time to stop",
+    ),
+    (
+        "actions.status",
+        "Next break ({kind}) in {eta}. {active} active today.",
+    ),
+    ("actions.not_running", "Lázaro is not tracking time"),
+    (
+        "daily.action.lock",
+        "Your session will lock in {seconds} s because of the daily limit. Start the break or cancel the lock.",
+    ),
+    (
+        "daily.action.suspend",
+        "This computer will suspend in {seconds} s because of the daily limit. Start the break or cancel the suspend.",
+    ),
+    ("daily.action.cancelled", "Daily limit action cancelled"),
+    (
+        "vacation.started",
+        "On vacation until {until}: no activity is tracked and no breaks are announced",
+    ),
+    ("vacation.ended", "Vacation over: timers start from zero"),
+    (
+        "guest.started",
+        "Guest session for {minutes} min: no activity is tracked and no breaks are announced",
+    ),
+    (
+        "guest.ended",
+        "Guest session over: timers pick up where they left off",
+    ),
+    (
+        "presentation.started",
+        "Presentation mode for {minutes} min: no breaks, notifications or sounds",
+    ),
+    (
+        "presentation.ended",
+        "Presentation mode over: timers pick up where they left off",
+    ),
+    (
+        "presentation.screencast.started",
+        "Your screen is being shared: presentation mode on",
+    ),
+    (
+        "presentation.screencast.ended",
+        "Screen sharing stopped: presentation mode off",
+    ),
+    (
+        "adaptive.adjusted",
+        "Intervals adjusted to {percent}%: {reason}",
+    ),
+    ("adaptive.frequent_skips", "breaks are skipped often"),
+    ("adaptive.long_streak", "long active streak without a break"),
+    ("adaptive.good_compliance", "breaks are being taken"),
+    ("adaptive.disabled", "adaptive mode disabled"),
+    ("integrations.status", "On a break 🧘 until {until}"),
+    ("daily.summary.title", "Daily summary"),
+    ("tray.active", "Active today: {active}"),
+    (
+        "tray.presentation",
+        "Presentation mode: breaks and notifications on hold",
+    ),
+    ("tray.presentation.title", "Presenting"),
+    ("tray.next", "Next {kind} break in {eta}"),
+    ("tray.pending", "{kind} break pending"),
+    ("tray.break", "{kind} break: {remaining} left"),
+    (
+        "daily.summary",
+        "{active} active, {micro} micro breaks, {rest} rest breaks, {skipped} skipped",
+    ),
+    ("config.reloaded", "Configuration reloaded from config.toml"),
+    (
+        "config.invalid",
+        "config.toml is invalid; keeping current settings",
+    ),
+    ("runtime.crashed", "The break engine crashed and stopped"),
+    (
+        "runtime.crashed.restarted",
+        "The break engine crashed and restarted from its last state",
+    ),
+];
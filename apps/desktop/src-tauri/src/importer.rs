@@ -0,0 +1,154 @@
+use std::{
+    collections::BTreeMap,
+    fs,
+    path::{Path, PathBuf},
+};
+
+use lazaro_core::{
+    config,
+    external::{self, ExternalTool},
+};
+use serde::Serialize;
+
+use crate::{
+    AppError, AppState, DailyStatsDto, ProfileDto, SettingsDto, settings_to_core, storage, unix_now,
+};
+
+#[derive(Clone, Debug, Serialize)]
+pub struct ImportReportDto {
+    pub tool: String,
+    pub path: String,
+    /// Profile created from the tool's timer settings, if it had any.
+    pub profile_id: Option<String>,
+    pub settings: Vec<String>,
+    pub days_imported: usize,
+    /// Days Lazaro already tracked, left untouched.
+    pub days_skipped: usize,
+}
+
+/// Where each tool keeps the file read by default.
+pub fn default_path(tool: ExternalTool) -> PathBuf {
+    let home = PathBuf::from(std::env::var("HOME").unwrap_or_else(|_| ".".to_string()));
+    let config_home = std::env::var("XDG_CONFIG_HOME")
+        .map(PathBuf::from)
+        .unwrap_or_else(|_| home.join(".config"));
+    match tool {
+        ExternalTool::Workrave => home.join(".workrave").join("historystats"),
+        ExternalTool::Stretchly => config_home.join("Stretchly").join("config.json"),
+        ExternalTool::SafeEyes => config_home.join("safeeyes").join("safeeyes.json"),
+    }
+}
+
+/// Workrave history fills in days Lazaro has no record of; Stretchly and
+/// Safe Eyes timers become a profile based on the current settings, left
+/// for the user to activate.
+pub fn import(
+    state: &AppState,
+    tool: ExternalTool,
+    path: &Path,
+) -> Result<ImportReportDto, AppError> {
+    let raw = fs::read_to_string(path)?;
+    let mut report = ImportReportDto {
+        tool: tool.as_str().into(),
+        path: path.display().to_string(),
+        profile_id: None,
+        settings: Vec::new(),
+        days_imported: 0,
+        days_skipped: 0,
+    };
+
+    let mut guard = state.data.lock()?;
+    match tool {
+        ExternalTool::Workrave => {
+            for (day, aggregate) in external::parse_workrave_history(&raw) {
+                if guard.daily_stats.contains_key(&day) {
+                    report.days_skipped += 1;
+                    continue;
+                }
+                guard.daily_stats.insert(
+                    day,
+                    DailyStatsDto {
+                        active_seconds: aggregate.active_seconds,
+                        micro_done: aggregate.micro_done,
+                        rest_done: aggregate.rest_done,
+                        daily_limit_hits: aggregate.daily_limit_hits,
+                        skipped: aggregate.skipped,
+                        ..DailyStatsDto::default()
+                    },
+                );
+                report.days_imported += 1;
+            }
+        }
+        ExternalTool::Stretchly | ExternalTool::SafeEyes => {
+            let values = top_level_values(&raw)?;
+            let changes = match tool {
+                ExternalTool::Stretchly => external::stretchly_settings(&values),
+                _ => external::safe_eyes_settings(&values),
+            };
+            if changes.is_empty() {
+                return Err(AppError::InvalidImport(format!(
+                    "no break settings found in {}",
+                    path.display()
+                )));
+            }
+
+            let mut core = settings_to_core(&guard.settings)?;
+            for (key, value) in &changes {
+                config::apply_setting(&mut core, key, value)
+                    .map_err(|e| AppError::InvalidImport(e.to_string()))?;
+            }
+            let id = format!("import-{}", tool.as_str());
+            let settings = SettingsDto {
+                active_profile_id: id.clone(),
+                ..SettingsDto::from(core)
+            };
+            guard.profiles.insert(
+                id.clone(),
+                ProfileDto {
+                    id: id.clone(),
+                    name: tool.display_name().into(),
+                    settings,
+                },
+            );
+            guard.sync.profiles_updated_at = unix_now();
+            report.profile_id = Some(id);
+            report.settings = changes
+                .into_iter()
+                .map(|(key, _)| key.to_string())
+                .collect();
+        }
+    }
+    drop(guard);
+
+    let sections = match tool {
+        ExternalTool::Workrave => storage::Sections::STATS,
+        _ => storage::Sections::DOCUMENT,
+    };
+    state.save(sections)?;
+    tracing::info!(
+        tool = tool.as_str(),
+        days = report.days_imported,
+        settings = report.settings.len(),
+        "external data imported"
+    );
+    Ok(report)
+}
+
+// Both tools keep their timers as top-level scalars, so nested values are
+// not needed.
+fn top_level_values(raw: &str) -> Result<BTreeMap<String, String>, AppError> {
+    let serde_json::Value::Object(map) =
+        serde_json::from_str(raw).map_err(|e| AppError::InvalidImport(e.to_string()))?
+    else {
+        return Err(AppError::InvalidImport("expected a JSON object".into()));
+    };
+    Ok(map
+        .into_iter()
+        .filter_map(|(key, value)| match value {
+            serde_json::Value::Number(number) => Some((key, number.to_string())),
+            serde_json::Value::Bool(flag) => Some((key, flag.to_string())),
+            serde_json::Value::String(text) => Some((key, text)),
+            _ => None,
+        })
+        .collect())
+}
@@ -0,0 +1,67 @@
+use std::process::Command;
+
+use serde_json::Value;
+
+use crate::dbus;
+
+pub fn fullscreen_window_active() -> bool {
+    let Some(window_id) = active_window_id() else {
+        return false;
+    };
+    xprop(&["-id", &window_id, "_NET_WM_STATE"])
+        .is_some_and(|raw| raw.contains("_NET_WM_STATE_FULLSCREEN"))
+}
+
+/// Only X11 (including XWayland clients) exposes the active window to other
+/// clients; native Wayland compositors do not.
+pub fn active_window_id() -> Option<String> {
+    xprop(&["-root", "_NET_ACTIVE_WINDOW"])
+        .and_then(|raw| raw.split_whitespace().last().map(str::to_string))
+        .filter(|id| id != "0x0")
+}
+
+pub fn media_playing() -> bool {
+    dbus::session_names()
+        .iter()
+        .filter(|name| name.starts_with("org.mpris.MediaPlayer2."))
+        .any(|name| {
+            dbus::session_property(
+                name,
+                "/org/mpris/MediaPlayer2",
+                "org.mpris.MediaPlayer2.Player",
+                "PlaybackStatus",
+            )
+            .is_some_and(|status| status.contains("'Playing'"))
+        })
+}
+
+/// Whether a screencast is streaming: the ScreenCast portal hands capture
+/// out as a PipeWire video source created by the compositor, which unlike a
+/// camera has no device behind it. Paused or idle streams do not count.
+pub fn screencast_active() -> bool {
+    let Ok(output) = Command::new("pw-dump").output() else {
+        return false;
+    };
+    if !output.status.success() {
+        return false;
+    }
+    let Ok(Value::Array(objects)) = serde_json::from_slice::<Value>(&output.stdout) else {
+        return false;
+    };
+    objects.iter().any(|object| {
+        let info = &object["info"];
+        let props = &info["props"];
+        object["type"] == "PipeWire:Interface:Node"
+            && info["state"] == "running"
+            && props["media.class"] == "Video/Source"
+            && props.get("device.id").is_none()
+    })
+}
+
+pub fn xprop(args: &[&str]) -> Option<String> {
+    let output = Command::new("xprop").args(args).output().ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    Some(String::from_utf8_lossy(&output.stdout).into_owned())
+}
@@ -0,0 +1,115 @@
+use std::thread;
+
+use chrono::{DateTime, Local, TimeZone, Utc};
+use serde_json::{Value, json};
+
+use crate::{SettingsDto, curl, i18n};
+
+const SLACK_PROFILE_URL: &str = "https://slack.com/api/users.profile.set";
+const TEAMS_STATUS_URL: &str = "https://graph.microsoft.com/v1.0/me/presence/setStatusMessage";
+
+pub fn enabled(settings: &SettingsDto) -> bool {
+    !settings.slack_token.trim().is_empty() || !settings.teams_token.trim().is_empty()
+}
+
+pub fn status_text(
+    settings: &SettingsDto,
+    lang: i18n::Language,
+    kind: &str,
+    duration_seconds: u64,
+    until: u64,
+) -> String {
+    let template = match settings.break_status_template.trim() {
+        "" => i18n::text(lang, "integrations.status"),
+        template => template,
+    };
+    let until = i64::try_from(until)
+        .ok()
+        .and_then(|seconds| Local.timestamp_opt(seconds, 0).single())
+        .map(|time| time.format("%H:%M").to_string())
+        .unwrap_or_default();
+
+    i18n::interpolate(
+        template,
+        &[
+            ("kind", kind),
+            ("minutes", &duration_seconds.div_ceil(60).to_string()),
+            ("until", &until),
+        ],
+    )
+}
+
+/// Publishes the break status to every configured service. Expiry is set on
+/// the service side too, so a crash mid-break does not leave it behind.
+pub fn set_break_status(settings: &SettingsDto, text: String, until: u64) {
+    update_status(settings, text, settings.break_status_emoji.clone(), until);
+}
+
+pub fn clear_break_status(settings: &SettingsDto) {
+    update_status(settings, String::new(), String::new(), 0);
+}
+
+fn update_status(settings: &SettingsDto, text: String, emoji: String, until: u64) {
+    let slack_token = settings.slack_token.trim().to_string();
+    let teams_token = settings.teams_token.trim().to_string();
+
+    thread::spawn(move || {
+        if !slack_token.is_empty() {
+            let body = json!({
+                "profile": {
+                    "status_text": text,
+                    "status_emoji": emoji,
+                    "status_expiration": until,
+                }
+            });
+            match post_json(SLACK_PROFILE_URL, &slack_token, &body) {
+                // Slack reports failures in the body with a 200 status.
+                Ok(response) if response["ok"] == Value::Bool(true) => {
+                    tracing::debug!("slack status updated");
+                }
+                Ok(response) => {
+                    tracing::warn!(error = %response["error"], "slack rejected status update");
+                }
+                Err(error) => tracing::warn!(%error, "failed to update slack status"),
+            }
+        }
+
+        if !teams_token.is_empty() {
+            let mut status = json!({
+                "statusMessage": {
+                    "message": { "content": text, "contentType": "text" },
+                }
+            });
+            if let Some(expiry) = i64::try_from(until)
+                .ok()
+                .filter(|seconds| *seconds > 0)
+                .and_then(|seconds| DateTime::<Utc>::from_timestamp(seconds, 0))
+            {
+                status["statusMessage"]["expiryDateTime"] = json!({
+                    "dateTime": expiry.format("%Y-%m-%dT%H:%M:%S").to_string(),
+                    "timeZone": "UTC",
+                });
+            }
+            match post_json(TEAMS_STATUS_URL, &teams_token, &status) {
+                Ok(_) => tracing::debug!("teams status updated"),
+                Err(error) => tracing::warn!(%error, "failed to update teams status"),
+            }
+        }
+    });
+}
+
+fn post_json(url: &str, token: &str, body: &Value) -> Result<Value, String> {
+    let config = [
+        curl::option("url", url),
+        curl::option("header", &format!("Authorization: Bearer {token}")),
+        curl::option("header", "Content-Type: application/json; charset=utf-8"),
+        curl::option("data", &body.to_string()),
+    ]
+    .concat();
+
+    let output = curl::run(&config)?;
+    if output.is_empty() {
+        return Ok(Value::Null);
+    }
+    serde_json::from_slice(&output).map_err(|e| e.to_string())
+}
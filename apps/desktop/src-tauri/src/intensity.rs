@@ -0,0 +1,133 @@
+use std::{
+    fs::{self, File},
+    io::Read,
+    ops::RangeInclusive,
+    os::unix::fs::MetadataExt,
+    path::Path,
+    sync::{
+        OnceLock,
+        atomic::{AtomicBool, AtomicU64, Ordering},
+    },
+    thread,
+};
+
+use crate::session;
+
+const EV_KEY: u16 = 0x01;
+const PRESSED: i32 = 1;
+/// `KEY_ESC..=KEY_MICMUTE`: the keyboard proper.
+const KEYBOARD_KEYS: RangeInclusive<u16> = 0x001..=0x0f8;
+/// `BTN_LEFT..=BTN_TASK`.
+const MOUSE_BUTTONS: RangeInclusive<u16> = 0x110..=0x117;
+// `struct input_event`: a `timeval`, then type (u16), code (u16), value (i32).
+const EVENT_SIZE: usize = if cfg!(target_pointer_width = "64") {
+    24
+} else {
+    16
+};
+
+static ENABLED: AtomicBool = AtomicBool::new(false);
+static KEYS: AtomicU64 = AtomicU64::new(0);
+static CLICKS: AtomicU64 = AtomicU64::new(0);
+static READERS: OnceLock<usize> = OnceLock::new();
+
+/// Turns counting on or off. The first call that enables it opens every
+/// evdev device the user may read (usually requires the `input` group) and
+/// parks a reader on each; they stay parked while counting is off. Returns
+/// whether any device could be opened.
+pub fn set_enabled(enabled: bool) -> bool {
+    ENABLED.store(enabled, Ordering::Relaxed);
+    if !enabled {
+        let _ = take();
+        return READERS.get().is_some_and(|readers| *readers > 0);
+    }
+    *READERS.get_or_init(spawn_readers) > 0
+}
+
+/// Key presses and clicks since the last call.
+pub fn take() -> (u64, u64) {
+    (
+        KEYS.swap(0, Ordering::Relaxed),
+        CLICKS.swap(0, Ordering::Relaxed),
+    )
+}
+
+fn spawn_readers() -> usize {
+    let Ok(entries) = fs::read_dir("/dev/input") else {
+        return 0;
+    };
+    let seat = session::seat();
+    let mut readers = 0;
+    for path in entries.flatten().map(|entry| entry.path()) {
+        let is_event_device = path
+            .file_name()
+            .and_then(|name| name.to_str())
+            .is_some_and(|name| name.starts_with("event"));
+        // Devices on other seats belong to other users' sessions.
+        if !is_event_device || device_seat(&path) != seat {
+            continue;
+        }
+        let Ok(device) = File::open(&path) else {
+            continue;
+        };
+        if thread::Builder::new()
+            .name("lazaro-intensity".into())
+            .spawn(move || read_events(device))
+            .is_ok()
+        {
+            readers += 1;
+        }
+    }
+    if readers == 0 {
+        tracing::warn!("no readable input devices; intensity sampling unavailable");
+    } else {
+        tracing::info!(readers, "intensity sampling started");
+    }
+    readers
+}
+
+/// Seat udev assigned the device to, from its database entry.
+fn device_seat(path: &Path) -> String {
+    let Ok(metadata) = fs::metadata(path) else {
+        return session::DEFAULT_SEAT.into();
+    };
+    let rdev = metadata.rdev();
+    let major = ((rdev >> 8) & 0xfff) | ((rdev >> 32) & !0xfff);
+    let minor = (rdev & 0xff) | ((rdev >> 12) & !0xff);
+    fs::read_to_string(format!("/run/udev/data/c{major}:{minor}"))
+        .ok()
+        .and_then(|data| {
+            data.lines()
+                .find_map(|line| line.strip_prefix("E:ID_SEAT="))
+                .map(str::to_string)
+        })
+        .unwrap_or_else(|| session::DEFAULT_SEAT.into())
+}
+
+// Only the event type and code are looked at, to tell keys from buttons;
+// the key itself is never recorded.
+fn read_events(mut device: File) {
+    let mut event = [0u8; EVENT_SIZE];
+    while device.read_exact(&mut event).is_ok() {
+        if !ENABLED.load(Ordering::Relaxed) {
+            continue;
+        }
+        let field = |offset: usize| [event[offset], event[offset + 1]];
+        let kind = u16::from_ne_bytes(field(EVENT_SIZE - 8));
+        let code = u16::from_ne_bytes(field(EVENT_SIZE - 6));
+        let value = i32::from_ne_bytes([
+            event[EVENT_SIZE - 4],
+            event[EVENT_SIZE - 3],
+            event[EVENT_SIZE - 2],
+            event[EVENT_SIZE - 1],
+        ]);
+        if kind != EV_KEY || value != PRESSED {
+            continue;
+        }
+        if KEYBOARD_KEYS.contains(&code) {
+            KEYS.fetch_add(1, Ordering::Relaxed);
+        } else if MOUSE_BUTTONS.contains(&code) {
+            CLICKS.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+}
@@ -0,0 +1,51 @@
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum KeyAction {
+    Start,
+    Snooze,
+}
+
+impl KeyAction {
+    pub fn name(self) -> &'static str {
+        match self {
+            KeyAction::Start => "start",
+            KeyAction::Snooze => "snooze",
+        }
+    }
+}
+
+// X11 keysyms for the usual media keys, mapped to the names keyboard events
+// report in the webview, so either spelling can be configured.
+const XF86_ALIASES: &[(&str, &str)] = &[
+    ("xf86audioplay", "mediaplaypause"),
+    ("xf86audiopause", "mediapause"),
+    ("xf86audiostop", "mediastop"),
+    ("xf86audionext", "mediatracknext"),
+    ("xf86audioprev", "mediatrackprevious"),
+];
+
+fn normalize(key: &str) -> String {
+    let key = key.trim().to_ascii_lowercase();
+    XF86_ALIASES
+        .iter()
+        .find(|(alias, _)| *alias == key)
+        .map(|(_, name)| (*name).to_string())
+        .unwrap_or(key)
+}
+
+fn matches(bindings: &str, key: &str) -> bool {
+    bindings
+        .split(',')
+        .filter(|binding| !binding.trim().is_empty())
+        .any(|binding| normalize(binding) == key)
+}
+
+pub fn resolve(start_keys: &str, snooze_keys: &str, key: &str) -> Option<KeyAction> {
+    let key = normalize(key);
+    if matches(snooze_keys, &key) {
+        Some(KeyAction::Snooze)
+    } else if matches(start_keys, &key) {
+        Some(KeyAction::Start)
+    } else {
+        None
+    }
+}
@@ -0,0 +1,97 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    sync::OnceLock,
+};
+
+use tracing_appender::rolling::{RollingFileAppender, Rotation};
+use tracing_subscriber::{
+    Registry, filter::LevelFilter, fmt, layer::SubscriberExt, reload, util::SubscriberInitExt,
+};
+
+pub const DEFAULT_LOG_LEVEL: &str = "info";
+
+const LOG_FILE_PREFIX: &str = "lazaro";
+const LOG_FILE_SUFFIX: &str = "log";
+const MAX_LOG_FILES: usize = 7;
+
+static LEVEL_HANDLE: OnceLock<reload::Handle<LevelFilter, Registry>> = OnceLock::new();
+
+pub fn log_dir(data_dir: &Path) -> PathBuf {
+    data_dir.join("logs")
+}
+
+pub fn parse_level(value: &str) -> Option<LevelFilter> {
+    match value {
+        "off" => Some(LevelFilter::OFF),
+        "error" => Some(LevelFilter::ERROR),
+        "warn" => Some(LevelFilter::WARN),
+        "info" => Some(LevelFilter::INFO),
+        "debug" => Some(LevelFilter::DEBUG),
+        "trace" => Some(LevelFilter::TRACE),
+        _ => None,
+    }
+}
+
+pub fn init(data_dir: &Path, level: &str) {
+    let filter = parse_level(level).unwrap_or(LevelFilter::INFO);
+    let (filter_layer, handle) = reload::Layer::new(filter);
+
+    let file_layer = RollingFileAppender::builder()
+        .rotation(Rotation::DAILY)
+        .filename_prefix(LOG_FILE_PREFIX)
+        .filename_suffix(LOG_FILE_SUFFIX)
+        .max_log_files(MAX_LOG_FILES)
+        .build(log_dir(data_dir))
+        .ok()
+        .map(|appender| fmt::layer().with_ansi(false).with_writer(appender));
+
+    let installed = tracing_subscriber::registry()
+        .with(filter_layer)
+        .with(fmt::layer().with_writer(std::io::stderr))
+        .with(file_layer)
+        .try_init()
+        .is_ok();
+
+    if installed {
+        let _ = LEVEL_HANDLE.set(handle);
+    }
+}
+
+pub fn set_level(level: &str) {
+    if let (Some(handle), Some(filter)) = (LEVEL_HANDLE.get(), parse_level(level)) {
+        let _ = handle.modify(|current| *current = filter);
+    }
+}
+
+pub fn recent_lines(data_dir: &Path, limit: usize) -> Vec<String> {
+    let Ok(entries) = fs::read_dir(log_dir(data_dir)) else {
+        return Vec::new();
+    };
+
+    let mut files: Vec<PathBuf> = entries
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| {
+            path.file_name()
+                .and_then(|name| name.to_str())
+                .is_some_and(|name| name.starts_with(LOG_FILE_PREFIX))
+        })
+        .collect();
+    files.sort();
+
+    let mut lines: Vec<String> = Vec::new();
+    for file in files.iter().rev() {
+        if lines.len() >= limit {
+            break;
+        }
+        let Ok(raw) = fs::read_to_string(file) else {
+            continue;
+        };
+        let mut chunk: Vec<String> = raw.lines().map(str::to_string).collect();
+        let skip = chunk.len().saturating_sub(limit - lines.len());
+        chunk.drain(..skip);
+        chunk.append(&mut lines);
+        lines = chunk;
+    }
+    lines
+}
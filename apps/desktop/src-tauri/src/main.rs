@@ -1,27 +1,92 @@
+mod actions;
+mod app_usage;
+mod backup;
+mod blocking;
+mod calendar;
+mod config_file;
+mod crash;
+mod curl;
+mod dashboard;
+mod dbus;
+mod diagnostics;
+mod dimming;
+mod dnd;
+mod emission;
+mod history;
+mod i18n;
+mod importer;
+mod inhibit;
+mod integrations;
+mod intensity;
+mod keys;
+mod logging;
+mod notify;
+mod onboarding;
+mod overlay;
+mod peers;
+mod platform;
+mod policy_file;
+mod power;
+mod remote;
+mod reset;
+mod runtime;
+mod session;
+mod sound;
+mod storage;
+mod summary;
+mod sync;
+mod taskbar;
+mod theme;
+mod tray;
+mod undo;
+mod update;
+mod vault;
+mod websocket;
+mod widget;
+
 use std::{
-    collections::BTreeMap,
+    collections::{BTreeMap, VecDeque},
     fs,
     path::{Path, PathBuf},
     process::Command,
-    sync::{
-        Arc, Mutex,
-        mpsc::{self, Sender},
-    },
-    thread::{self, JoinHandle},
+    sync::{Arc, Condvar, Mutex, OnceLock},
     time::{Duration, SystemTime, UNIX_EPOCH},
 };
 
+use ashpd::desktop::background::Background;
 use lazaro_core::{
+    analytics::{
+        AnalyticsStore, BreakHistory, Compliance, DailyAggregate, MAX_BREAK_RATING,
+        MIN_BREAK_RATING, MilestoneProgress,
+    },
     config::{
-        BlockLevel, BreakTimerSettings, DailyLimitSettings, NotificationSettings, Settings,
-        StartupSettings,
+        self, AccessibilitySettings, ActivityPack, AdaptiveSettings, AnalyticsSettings,
+        AppBlockAction, AppLimitSettings, BlockLevel, BlockingSettings, BreakTimerSettings,
+        CalendarSettings, CustomBreakTimer, DailyLimitAction, DailyLimitSettings, DimmingSettings,
+        EscalationSettings, InhibitSettings, IntegrationSettings, IntensitySettings,
+        IntervalChangePolicy, KeyBindingSettings, LimitScope, MAX_BREAK_TICK_MILLIS,
+        MAX_CUSTOM_TIMERS, MAX_FONT_SCALE_PERCENT, MAX_JITTER_PERCENT, MICRO_TIMER_ID,
+        MIN_BREAK_TICK_MILLIS, MIN_FONT_SCALE_PERCENT, MeetingPolicy, MessageTemplate,
+        NotificationSettings, NudgeSettings, PeerSettings, PowerSaverBehavior, PrivacySettings,
+        REST_TIMER_ID, RemoteSettings, RuntimeSettings, SettingChange, Settings, StartupSettings,
+        SyncBackend, SyncSettings, ThemeMode, ThemeSettings, UpdateChannel, UpdateSettings,
+        WeekStart, WeeklyLimitSettings, WindDownSettings,
+    },
+    event::{EVENT_SCHEMA_VERSION, RuntimeEvent},
+    external::ExternalTool,
+    timer::{
+        BreakKind, DailyStatus, DeferReason, EngineSnapshot, ScheduleReason, TimerExplanation,
     },
-    timer::{BreakKind, EngineEvent, TimerEngine},
 };
-use notify_rust::Notification;
+use runtime::RuntimeControl;
 use serde::{Deserialize, Serialize};
 use tauri::{
-    AppHandle, Emitter, Manager, PhysicalPosition, Position, WebviewUrl, WebviewWindowBuilder,
+    AppHandle, Emitter, Manager, PhysicalPosition, Position, RunEvent, WebviewUrl,
+    WebviewWindowBuilder, WindowEvent,
+};
+use tokio::sync::{
+    mpsc::{self, UnboundedSender},
+    watch,
 };
 
 #[derive(Debug, thiserror::Error)]
@@ -36,8 +101,118 @@ enum AppError {
     InvalidBreakKind(String),
     #[error("invalid reset time format: {0}")]
     InvalidResetTime(String),
+    #[error("invalid wind-down time for {0}: {1}")]
+    InvalidWindDownTime(&'static str, String),
     #[error("runtime is not running")]
     RuntimeNotRunning,
+    #[error("unsupported language: {0}")]
+    UnsupportedLanguage(String),
+    #[error("setting locked by policy: {0}")]
+    PolicyLocked(String),
+    #[error("invalid date: {0}")]
+    InvalidDate(String),
+    #[error("invalid custom timer: {0}")]
+    InvalidTimer(String),
+    #[error("invalid app limits: {0}")]
+    InvalidAppLimits(String),
+    #[error("invalid app categories: {0}")]
+    InvalidAppCategories(String),
+    #[error("invalid message templates: {0}")]
+    InvalidMessageTemplates(String),
+    #[error("invalid day overrides: {0}")]
+    InvalidDayOverrides(String),
+    #[error("invalid reset scope: {0}")]
+    InvalidResetScope(String),
+    #[error("reset not confirmed or confirmation expired")]
+    ResetNotConfirmed,
+    #[error("invalid theme: {0}")]
+    InvalidTheme(String),
+    #[error("invalid duration: {0}")]
+    InvalidDuration(u64),
+    #[error("nothing to undo")]
+    NothingToUndo,
+    #[error("invalid rating: {0}")]
+    InvalidRating(u8),
+    #[error("no completed break to rate: {0}")]
+    NothingToRate(String),
+    #[error("storage error: {0}")]
+    Storage(String),
+    #[error("invalid backup: {0}")]
+    InvalidBackup(String),
+    #[error("cannot import: {0}")]
+    InvalidImport(String),
+    #[error("break cannot be ended early: {0}")]
+    SkipNotAllowed(String),
+    #[error("a break overlay is already open")]
+    OverlayBusy,
+    #[error("internal state unavailable: {0}")]
+    Poisoned(String),
+    #[error("serialization error: {0}")]
+    Serialization(String),
+    #[error("background task failed: {0}")]
+    TaskFailed(String),
+    #[error("desktop service unavailable: {0}")]
+    ServiceUnavailable(String),
+    #[error("starting at login was not allowed")]
+    AutostartDenied,
+}
+
+impl AppError {
+    /// Stable id the frontend can branch on; `message` is for people.
+    fn code(&self) -> &'static str {
+        match self {
+            Self::Io(_) => "io",
+            Self::ProfileNotFound(_) => "profile_not_found",
+            Self::CannotDeleteDefaultProfile => "cannot_delete_default_profile",
+            Self::InvalidBreakKind(_) => "invalid_break_kind",
+            Self::InvalidResetTime(_) => "invalid_reset_time",
+            Self::InvalidWindDownTime(..) => "invalid_wind_down_time",
+            Self::RuntimeNotRunning => "runtime_not_running",
+            Self::UnsupportedLanguage(_) => "unsupported_language",
+            Self::PolicyLocked(_) => "policy_locked",
+            Self::InvalidDate(_) => "invalid_date",
+            Self::InvalidTimer(_) => "invalid_timer",
+            Self::InvalidAppLimits(_) => "invalid_app_limits",
+            Self::InvalidAppCategories(_) => "invalid_app_categories",
+            Self::InvalidMessageTemplates(_) => "invalid_message_templates",
+            Self::InvalidDayOverrides(_) => "invalid_day_overrides",
+            Self::InvalidResetScope(_) => "invalid_reset_scope",
+            Self::ResetNotConfirmed => "reset_not_confirmed",
+            Self::InvalidTheme(_) => "invalid_theme",
+            Self::InvalidDuration(_) => "invalid_duration",
+            Self::NothingToUndo => "nothing_to_undo",
+            Self::InvalidRating(_) => "invalid_rating",
+            Self::NothingToRate(_) => "nothing_to_rate",
+            Self::Storage(_) => "storage",
+            Self::InvalidBackup(_) => "invalid_backup",
+            Self::InvalidImport(_) => "invalid_import",
+            Self::SkipNotAllowed(_) => "skip_not_allowed",
+            Self::OverlayBusy => "overlay_busy",
+            Self::Poisoned(_) => "poisoned",
+            Self::Serialization(_) => "serialization",
+            Self::TaskFailed(_) => "task_failed",
+            Self::ServiceUnavailable(_) => "service_unavailable",
+            Self::AutostartDenied => "autostart_denied",
+        }
+    }
+
+    /// The settings field or command argument a validation error is about.
+    fn field(&self) -> Option<&'static str> {
+        match self {
+            Self::InvalidBreakKind(_) => Some("kind"),
+            Self::InvalidResetTime(_) => Some("daily_reset_time"),
+            Self::InvalidWindDownTime(field, _) => Some(*field),
+            Self::UnsupportedLanguage(_) => Some("language"),
+            Self::InvalidTimer(_) => Some("custom_timers"),
+            Self::InvalidAppLimits(_) => Some("app_limits"),
+            Self::InvalidAppCategories(_) => Some("app_categories"),
+            Self::InvalidMessageTemplates(_) => Some("message_templates"),
+            Self::InvalidDayOverrides(_) => Some("day_overrides"),
+            Self::InvalidResetScope(_) => Some("scope"),
+            Self::InvalidRating(_) => Some("rating"),
+            _ => None,
+        }
+    }
 }
 
 impl From<std::io::Error> for AppError {
@@ -46,12 +221,31 @@ impl From<std::io::Error> for AppError {
     }
 }
 
+impl<T> From<std::sync::PoisonError<T>> for AppError {
+    fn from(value: std::sync::PoisonError<T>) -> Self {
+        Self::Poisoned(value.to_string())
+    }
+}
+
+impl From<serde_json::Error> for AppError {
+    fn from(value: serde_json::Error) -> Self {
+        Self::Serialization(value.to_string())
+    }
+}
+
+/// Commands reject with `{code, message, field}`.
 impl Serialize for AppError {
     fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
     where
         S: serde::Serializer,
     {
-        serializer.serialize_str(self.to_string().as_ref())
+        use serde::ser::SerializeStruct;
+
+        let mut error = serializer.serialize_struct("AppError", 3)?;
+        error.serialize_field("code", self.code())?;
+        error.serialize_field("message", &self.to_string())?;
+        error.serialize_field("field", &self.field())?;
+        error.end()
     }
 }
 
@@ -70,33 +264,260 @@ struct ProfileDto {
     settings: SettingsDto,
 }
 
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+struct DailyStatsDto {
+    active_seconds: u64,
+    micro_done: u32,
+    rest_done: u32,
+    #[serde(default)]
+    custom_done: u32,
+    daily_limit_hits: u32,
+    skipped: u32,
+    #[serde(default)]
+    rating_sum: u32,
+    #[serde(default)]
+    ratings: u32,
+}
+
+impl From<&DailyStatsDto> for DailyAggregate {
+    fn from(stats: &DailyStatsDto) -> Self {
+        Self {
+            active_seconds: stats.active_seconds,
+            micro_done: stats.micro_done,
+            rest_done: stats.rest_done,
+            custom_done: stats.custom_done,
+            daily_limit_hits: stats.daily_limit_hits,
+            skipped: stats.skipped,
+            rating_sum: stats.rating_sum,
+            ratings: stats.ratings,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Serialize)]
+struct WeeklyGoalProgressDto {
+    week_start: String,
+    active_seconds: u64,
+    max_active_seconds: u64,
+    active_percent: u8,
+    active_goal_met: bool,
+    breaks_taken: u32,
+    min_breaks: u32,
+    breaks_percent: u8,
+    breaks_goal_met: bool,
+    /// Mean break feedback rating this week, if any was given.
+    average_rating: Option<f64>,
+}
+
 #[derive(Clone, Debug, Serialize, Deserialize)]
 struct WeeklyStatsDto {
     total_active_seconds: u64,
     micro_done: u32,
     rest_done: u32,
+    #[serde(default)]
+    custom_done: u32,
     daily_limit_hits: u32,
     skipped: u32,
+    #[serde(default)]
+    rating_sum: u32,
+    #[serde(default)]
+    ratings: u32,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(default)]
+struct CustomTimerDto {
+    id: String,
+    name: String,
+    interval_seconds: u64,
+    duration_seconds: u64,
+    snooze_seconds: u64,
+    enabled: bool,
+    jitter_percent: u8,
+    /// `default` follows the global block level.
+    #[serde(with = "timer_block_level")]
+    block_level: Option<BlockLevel>,
+    priority: u8,
+    resets: Vec<String>,
+}
+
+impl Default for CustomTimerDto {
+    fn default() -> Self {
+        Self {
+            id: String::new(),
+            name: String::new(),
+            interval_seconds: 1800,
+            duration_seconds: 60,
+            snooze_seconds: 300,
+            enabled: true,
+            jitter_percent: 0,
+            block_level: None,
+            priority: 2,
+            resets: Vec::new(),
+        }
+    }
+}
+
+impl From<CustomBreakTimer> for CustomTimerDto {
+    fn from(value: CustomBreakTimer) -> Self {
+        Self {
+            id: value.id,
+            name: value.name,
+            interval_seconds: value.timer.interval_seconds,
+            duration_seconds: value.timer.duration_seconds,
+            snooze_seconds: value.timer.snooze_seconds,
+            enabled: value.timer.enabled,
+            jitter_percent: value.timer.jitter_percent,
+            block_level: value.timer.block_level,
+            priority: value.priority,
+            resets: value.timer.resets,
+        }
+    }
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(default)]
 struct SettingsDto {
     micro_interval_seconds: u64,
     micro_duration_seconds: u64,
     micro_snooze_seconds: u64,
+    micro_enabled: bool,
+    micro_jitter_percent: u8,
+    #[serde(with = "timer_block_level")]
+    micro_block_level: Option<BlockLevel>,
+    /// Comma-separated timer ids restarted when the break completes.
+    micro_resets: String,
     rest_interval_seconds: u64,
     rest_duration_seconds: u64,
     rest_snooze_seconds: u64,
+    rest_enabled: bool,
+    rest_jitter_percent: u8,
+    #[serde(with = "timer_block_level")]
+    rest_block_level: Option<BlockLevel>,
+    rest_resets: String,
+    custom_timers: Vec<CustomTimerDto>,
+    adaptive_enabled: bool,
+    adaptive_min_interval_percent: u8,
+    adaptive_max_interval_percent: u8,
+    intensity_enabled: bool,
+    intensity_scale_micro: bool,
+    intensity_heavy_events_per_minute: u32,
+    intensity_max_reduction_percent: u8,
+    escalation_enabled: bool,
+    escalation_renotify_seconds: u64,
+    escalation_force_seconds: u64,
     daily_limit_seconds: u64,
     daily_limit_snooze_seconds: u64,
+    daily_limit_enabled: bool,
     daily_reset_time: String,
-    block_level: String,
+    daily_limit_max_snoozes: u32,
+    daily_limit_action: String,
+    daily_limit_resets_timers: bool,
+    daily_limit_warning_thresholds: String,
+    weekly_limit_enabled: bool,
+    weekly_limit_seconds: u64,
+    weekly_limit_warning_thresholds: String,
+    app_limits_enabled: bool,
+    /// `class=minutes` pairs, as `config::parse_app_limits` reads them.
+    app_limits: String,
+    app_limit_block_level: BlockLevel,
+    limit_scope: String,
+    block_level: BlockLevel,
+    fade_on_soft: bool,
+    fade_on_medium: bool,
+    fade_on_strict: bool,
+    fade_warning_seconds: u64,
+    fade_max_opacity_percent: u8,
     desktop_notifications: bool,
     overlay_notifications: bool,
     sound_notifications: bool,
     sound_theme: String,
+    countdown_cue_seconds: u64,
+    respect_system_dnd: bool,
+    defer_breaks_on_dnd: bool,
+    daily_summary_notifications: bool,
+    countdown_widget: bool,
+    taskbar_progress: bool,
+    /// `key=text` lines, as `config::parse_message_templates` reads them.
+    message_templates: String,
+    inhibit_on_fullscreen: bool,
+    inhibit_on_media: bool,
+    presentation_on_screencast: bool,
+    blocking_enabled: bool,
+    blocking_app_classes: String,
+    blocking_app_action: String,
+    blocking_hosts: String,
+    blocking_hosts_file: String,
+    calendar_enabled: bool,
+    calendar_source_path: String,
+    calendar_meeting_policy: String,
+    media_keys_enabled: bool,
+    media_start_keys: String,
+    media_snooze_keys: String,
+    remote_enabled: bool,
+    remote_port: u16,
+    remote_token: String,
+    slack_token: String,
+    teams_token: String,
+    break_status_template: String,
+    break_status_emoji: String,
+    sync_backend: String,
+    sync_directory: String,
+    sync_webdav_url: String,
+    sync_webdav_username: String,
+    sync_webdav_password: String,
+    peer_sharing_enabled: bool,
+    peer_port: u16,
+    peer_secret: String,
+    encrypt_state: bool,
+    high_contrast_overlay: bool,
+    reduce_motion: bool,
+    large_countdown: bool,
+    screen_reader_announcements: bool,
+    theme_mode: String,
+    theme_accent_color: String,
+    theme_background_color: String,
+    theme_text_color: String,
+    theme_background_image: String,
+    theme_font_scale_percent: u16,
+    activity_packs: String,
+    language: String,
+    week_start: String,
+    weekly_max_active_seconds: u64,
+    weekly_min_breaks: u32,
+    focus_gap_seconds: u64,
+    break_feedback_prompt: bool,
+    milestone_celebrations: bool,
+    nudges_enabled: bool,
+    nudge_notifications: bool,
+    nudge_skip_rate: bool,
+    nudge_long_focus: bool,
+    nudge_late_night: bool,
+    nudge_interval_seconds: u64,
+    wind_down_enabled: bool,
+    /// Local `HH:MM` times, like `daily_reset_time`.
+    wind_down_start: String,
+    wind_down_cutoff: String,
+    wind_down_interval_percent: u8,
+    wind_down_budget_seconds: u64,
+    /// `days key=value` lines, as `config::parse_day_overrides` reads them.
+    day_overrides: String,
+    app_usage_enabled: bool,
+    app_usage_titles: bool,
+    /// `class=category` pairs, as `config::parse_app_categories` reads them.
+    app_categories: String,
+    tick_emit_interval_seconds: u64,
+    break_tick_millis: u64,
+    log_level: String,
+    power_saver_behavior: String,
+    interval_change_policy: String,
+    restart_runtime_on_crash: bool,
     startup_xdg: bool,
     startup_systemd_user: bool,
+    warmup_grace_seconds: u64,
+    idle_counts_as_rest: bool,
+    update_check_enabled: bool,
+    update_channel: String,
     active_profile_id: String,
 }
 
@@ -106,45 +527,367 @@ impl Default for SettingsDto {
     }
 }
 
+impl SettingsDto {
+    /// Takes over the daily and weekly limits of `current`, scope included.
+    fn inherit_limits(&mut self, current: &SettingsDto) {
+        self.daily_limit_seconds = current.daily_limit_seconds;
+        self.daily_limit_snooze_seconds = current.daily_limit_snooze_seconds;
+        self.daily_limit_enabled = current.daily_limit_enabled;
+        self.daily_reset_time = current.daily_reset_time.clone();
+        self.daily_limit_max_snoozes = current.daily_limit_max_snoozes;
+        self.daily_limit_action = current.daily_limit_action.clone();
+        self.daily_limit_resets_timers = current.daily_limit_resets_timers;
+        self.daily_limit_warning_thresholds = current.daily_limit_warning_thresholds.clone();
+        self.weekly_limit_enabled = current.weekly_limit_enabled;
+        self.weekly_limit_seconds = current.weekly_limit_seconds;
+        self.weekly_limit_warning_thresholds = current.weekly_limit_warning_thresholds.clone();
+        self.limit_scope = current.limit_scope.clone();
+    }
+}
+
 impl From<Settings> for SettingsDto {
     fn from(value: Settings) -> Self {
-        let block_level = match value.block_level {
-            BlockLevel::Soft => "soft",
-            BlockLevel::Medium => "medium",
-            BlockLevel::Strict => "strict",
-        }
-        .to_string();
-
         Self {
             micro_interval_seconds: value.micro.interval_seconds,
             micro_duration_seconds: value.micro.duration_seconds,
             micro_snooze_seconds: value.micro.snooze_seconds,
+            micro_enabled: value.micro.enabled,
+            micro_jitter_percent: value.micro.jitter_percent,
+            micro_block_level: value.micro.block_level,
+            micro_resets: value.micro.resets.join(","),
             rest_interval_seconds: value.rest.interval_seconds,
             rest_duration_seconds: value.rest.duration_seconds,
             rest_snooze_seconds: value.rest.snooze_seconds,
+            rest_enabled: value.rest.enabled,
+            rest_jitter_percent: value.rest.jitter_percent,
+            rest_block_level: value.rest.block_level,
+            rest_resets: value.rest.resets.join(","),
+            custom_timers: value
+                .custom_timers
+                .into_iter()
+                .map(CustomTimerDto::from)
+                .collect(),
+            adaptive_enabled: value.adaptive.enabled,
+            adaptive_min_interval_percent: value.adaptive.min_interval_percent,
+            adaptive_max_interval_percent: value.adaptive.max_interval_percent,
+            intensity_enabled: value.intensity.enabled,
+            intensity_scale_micro: value.intensity.scale_micro,
+            intensity_heavy_events_per_minute: value.intensity.heavy_events_per_minute,
+            intensity_max_reduction_percent: value.intensity.max_reduction_percent,
+            escalation_enabled: value.escalation.enabled,
+            escalation_renotify_seconds: value.escalation.renotify_after_seconds,
+            escalation_force_seconds: value.escalation.force_after_seconds,
             daily_limit_seconds: value.daily_limit.limit_seconds,
             daily_limit_snooze_seconds: value.daily_limit.snooze_seconds,
+            daily_limit_enabled: value.daily_limit.enabled,
+            daily_limit_max_snoozes: value.daily_limit.max_snoozes,
+            daily_limit_action: match value.daily_limit.action {
+                DailyLimitAction::None => "none",
+                DailyLimitAction::LockSession => "lock",
+                DailyLimitAction::Suspend => "suspend",
+            }
+            .to_string(),
+            daily_limit_resets_timers: value.daily_limit.resets_timers,
+            daily_limit_warning_thresholds: join_percents(&value.daily_limit.warning_thresholds),
+            weekly_limit_enabled: value.weekly_limit.enabled,
+            weekly_limit_seconds: value.weekly_limit.limit_seconds,
+            weekly_limit_warning_thresholds: join_percents(&value.weekly_limit.warning_thresholds),
+            app_limits_enabled: value.app_limits.enabled,
+            app_limits: config::join_app_limits(&value.app_limits.limits),
+            app_limit_block_level: value.app_limits.block_level,
+            limit_scope: match value.limit_scope {
+                LimitScope::Global => "global",
+                LimitScope::Profile => "profile",
+            }
+            .to_string(),
             daily_reset_time: format!(
                 "{:02}:{:02}",
                 value.daily_limit.reset_hour_local, value.daily_limit.reset_minute_local
             ),
-            block_level,
+            block_level: value.block_level,
+            fade_on_soft: value.dimming.soft,
+            fade_on_medium: value.dimming.medium,
+            fade_on_strict: value.dimming.strict,
+            fade_warning_seconds: value.dimming.warning_seconds,
+            fade_max_opacity_percent: value.dimming.max_opacity_percent,
             desktop_notifications: value.notifications.desktop_enabled,
             overlay_notifications: value.notifications.overlay_enabled,
             sound_notifications: value.notifications.sound_enabled,
             sound_theme: value.notifications.sound_theme,
+            countdown_cue_seconds: value.notifications.countdown_cue_seconds,
+            respect_system_dnd: value.notifications.respect_system_dnd,
+            defer_breaks_on_dnd: value.notifications.defer_breaks_on_dnd,
+            daily_summary_notifications: value.notifications.daily_summary_enabled,
+            countdown_widget: value.notifications.countdown_widget_enabled,
+            taskbar_progress: value.notifications.taskbar_progress_enabled,
+            message_templates: config::join_message_templates(
+                &value.notifications.message_templates,
+            ),
+            inhibit_on_fullscreen: value.inhibit.on_fullscreen,
+            inhibit_on_media: value.inhibit.on_media,
+            presentation_on_screencast: value.inhibit.presentation_on_screencast,
+            blocking_enabled: value.blocking.enabled,
+            blocking_app_classes: value.blocking.app_classes,
+            blocking_app_action: match value.blocking.app_action {
+                AppBlockAction::Minimize => "minimize",
+                AppBlockAction::Kill => "kill",
+            }
+            .to_string(),
+            blocking_hosts: value.blocking.hosts,
+            blocking_hosts_file: value.blocking.hosts_file_path,
+            calendar_enabled: value.calendar.enabled,
+            calendar_source_path: value.calendar.source_path,
+            calendar_meeting_policy: match value.calendar.meeting_policy {
+                MeetingPolicy::Defer => "defer",
+                MeetingPolicy::Silent => "silent",
+            }
+            .to_string(),
+            media_keys_enabled: value.keys.media_keys_enabled,
+            media_start_keys: value.keys.start_keys,
+            media_snooze_keys: value.keys.snooze_keys,
+            remote_enabled: value.remote.enabled,
+            remote_port: value.remote.port,
+            remote_token: value.remote.token,
+            slack_token: value.integrations.slack_token,
+            teams_token: value.integrations.teams_token,
+            break_status_template: value.integrations.status_template,
+            break_status_emoji: value.integrations.status_emoji,
+            sync_backend: match value.sync.backend {
+                SyncBackend::Off => "off",
+                SyncBackend::Directory => "directory",
+                SyncBackend::WebDav => "webdav",
+            }
+            .to_string(),
+            sync_directory: value.sync.directory,
+            sync_webdav_url: value.sync.webdav_url,
+            sync_webdav_username: value.sync.webdav_username,
+            sync_webdav_password: value.sync.webdav_password,
+            peer_sharing_enabled: value.peers.enabled,
+            peer_port: value.peers.port,
+            peer_secret: value.peers.secret,
+            encrypt_state: value.privacy.encrypt_state,
+            high_contrast_overlay: value.accessibility.high_contrast,
+            reduce_motion: value.accessibility.reduce_motion,
+            large_countdown: value.accessibility.large_countdown,
+            screen_reader_announcements: value.accessibility.screen_reader_announcements,
+            theme_mode: match value.theme.mode {
+                ThemeMode::Dark => "dark",
+                ThemeMode::Light => "light",
+                ThemeMode::Custom => "custom",
+            }
+            .to_string(),
+            theme_accent_color: value.theme.accent_color,
+            theme_background_color: value.theme.background_color,
+            theme_text_color: value.theme.text_color,
+            theme_background_image: value.theme.background_image_path,
+            theme_font_scale_percent: value.theme.font_scale_percent,
+            activity_packs: value
+                .theme
+                .activity_packs
+                .iter()
+                .map(|pack| pack.as_str())
+                .collect::<Vec<_>>()
+                .join(","),
+            language: value.language,
+            week_start: match value.analytics.week_start {
+                WeekStart::Monday => "monday",
+                WeekStart::Sunday => "sunday",
+            }
+            .to_string(),
+            weekly_max_active_seconds: value.analytics.weekly_max_active_seconds,
+            weekly_min_breaks: value.analytics.weekly_min_breaks,
+            focus_gap_seconds: value.analytics.focus_gap_seconds,
+            break_feedback_prompt: value.analytics.break_feedback_prompt,
+            milestone_celebrations: value.analytics.milestone_celebrations,
+            nudges_enabled: value.nudges.enabled,
+            nudge_notifications: value.nudges.notify,
+            nudge_skip_rate: value.nudges.skip_rate,
+            nudge_long_focus: value.nudges.long_focus,
+            nudge_late_night: value.nudges.late_night,
+            nudge_interval_seconds: value.nudges.interval_seconds,
+            wind_down_enabled: value.wind_down.enabled,
+            wind_down_start: format!(
+                "{:02}:{:02}",
+                value.wind_down.start_hour_local, value.wind_down.start_minute_local
+            ),
+            wind_down_cutoff: format!(
+                "{:02}:{:02}",
+                value.wind_down.cutoff_hour_local, value.wind_down.cutoff_minute_local
+            ),
+            wind_down_interval_percent: value.wind_down.interval_percent,
+            wind_down_budget_seconds: value.wind_down.budget_seconds,
+            day_overrides: config::join_day_overrides(&value.day_overrides),
+            app_usage_enabled: value.analytics.app_usage_enabled,
+            app_usage_titles: value.analytics.app_usage_titles,
+            app_categories: config::join_app_categories(&value.analytics.app_categories),
+            tick_emit_interval_seconds: value.runtime.tick_emit_interval_seconds,
+            break_tick_millis: value.runtime.break_tick_millis,
+            log_level: value.runtime.log_level,
+            power_saver_behavior: match value.runtime.power_saver_behavior {
+                PowerSaverBehavior::Ignore => "ignore",
+                PowerSaverBehavior::Quiet => "quiet",
+                PowerSaverBehavior::QuietAndSlow => "quiet_and_slow",
+            }
+            .to_string(),
+            interval_change_policy: match value.runtime.interval_change_policy {
+                IntervalChangePolicy::Rescale => "rescale",
+                IntervalChangePolicy::Preserve => "preserve",
+                IntervalChangePolicy::Reset => "reset",
+            }
+            .to_string(),
+            restart_runtime_on_crash: value.runtime.restart_on_crash,
             startup_xdg: value.startup.xdg_autostart_enabled,
             startup_systemd_user: value.startup.systemd_user_enabled,
+            warmup_grace_seconds: value.startup.warmup_grace_seconds,
+            idle_counts_as_rest: value.startup.idle_counts_as_rest,
+            update_check_enabled: value.updates.check_enabled,
+            update_channel: match value.updates.channel {
+                UpdateChannel::Stable => "stable",
+                UpdateChannel::Beta => "beta",
+            }
+            .to_string(),
             active_profile_id: value.active_profile_id,
         }
     }
 }
 
+#[derive(Clone, Debug, Serialize)]
+struct OverlayConfigDto {
+    high_contrast: bool,
+    reduce_motion: bool,
+    large_countdown: bool,
+    screen_reader_announcements: bool,
+    media_keys_enabled: bool,
+}
+
+impl From<&SettingsDto> for OverlayConfigDto {
+    fn from(value: &SettingsDto) -> Self {
+        Self {
+            high_contrast: value.high_contrast_overlay,
+            reduce_motion: value.reduce_motion,
+            large_countdown: value.large_countdown,
+            screen_reader_announcements: value.screen_reader_announcements,
+            media_keys_enabled: value.media_keys_enabled,
+        }
+    }
+}
+
+/// Payload of `settings_changed`, sent for every save that changes something,
+/// whether it came from the UI, sync, the config file or the remote API.
+#[derive(Clone, Debug, Serialize)]
+struct SettingsChangedDto {
+    changes: Vec<SettingChangeDto>,
+}
+
+#[derive(Clone, Debug, Serialize)]
+struct SettingChangeDto {
+    key: String,
+    previous: Option<String>,
+    current: Option<String>,
+}
+
+impl From<SettingChange> for SettingChangeDto {
+    fn from(value: SettingChange) -> Self {
+        Self {
+            key: value.key.into(),
+            previous: value.previous,
+            current: value.current,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Serialize)]
+struct LanguageDto {
+    code: String,
+    name: String,
+}
+
 #[derive(Clone, Debug, Serialize, Deserialize)]
 struct AppStateOnDisk {
+    /// Settings as the user saved them.
     settings: SettingsDto,
+    /// `settings` with config.toml, environment, command-line and policy
+    /// overrides applied; what everything runs with. Never saved.
+    #[serde(skip)]
+    effective: SettingsDto,
     profiles: BTreeMap<String, ProfileDto>,
     weekly_stats: WeeklyStatsDto,
+    #[serde(default)]
+    break_history: Vec<history::BreakSessionDto>,
+    #[serde(default)]
+    focus_sessions: Vec<history::FocusSessionDto>,
+    /// Hourly input counts; small enough to live in the document.
+    #[serde(default)]
+    intensity: Vec<history::IntensityBucketDto>,
+    #[serde(default)]
+    daily_stats: BTreeMap<i64, DailyStatsDto>,
+    /// Active time by application, recorded only when `app_usage_enabled`.
+    #[serde(default)]
+    app_usage: BTreeMap<i64, history::AppUsageDayDto>,
+    #[serde(default)]
+    sync: sync::SyncMetaDto,
+    #[serde(default = "onboarding::legacy_meta")]
+    onboarding: onboarding::OnboardingMetaDto,
+    #[serde(default)]
+    widget_position: Option<widget::WidgetPositionDto>,
+    #[serde(default)]
+    engine: Option<EngineSnapshotDto>,
+    /// Tracking stays off until this time, across restarts.
+    #[serde(default)]
+    vacation_until: Option<u64>,
+    /// Recent settings saves and profile removals, newest last.
+    #[serde(default)]
+    undo_history: VecDeque<undo::UndoEntryDto>,
+}
+
+/// Timer counters kept between runs; see `TimerEngine::restore`.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+struct EngineSnapshotDto {
+    taken_at: u64,
+    daily_active_seconds: u64,
+    #[serde(default)]
+    weekly_active_seconds: u64,
+    timer_active_seconds: BTreeMap<String, u64>,
+    #[serde(default)]
+    app_active_seconds: BTreeMap<String, u64>,
+    #[serde(default)]
+    wind_down_active_seconds: u64,
+}
+
+impl EngineSnapshotDto {
+    /// Equal apart from when the snapshot was taken.
+    fn same_counters(&self, other: &Self) -> bool {
+        Self {
+            taken_at: other.taken_at,
+            ..self.clone()
+        } == *other
+    }
+}
+
+impl From<EngineSnapshot> for EngineSnapshotDto {
+    fn from(value: EngineSnapshot) -> Self {
+        Self {
+            taken_at: value.taken_at,
+            daily_active_seconds: value.daily_active_seconds,
+            weekly_active_seconds: value.weekly_active_seconds,
+            timer_active_seconds: value.timer_active_seconds.into_iter().collect(),
+            app_active_seconds: value.app_active_seconds.into_iter().collect(),
+            wind_down_active_seconds: value.wind_down_active_seconds,
+        }
+    }
+}
+
+impl From<EngineSnapshotDto> for EngineSnapshot {
+    fn from(value: EngineSnapshotDto) -> Self {
+        Self {
+            taken_at: value.taken_at,
+            daily_active_seconds: value.daily_active_seconds,
+            weekly_active_seconds: value.weekly_active_seconds,
+            timer_active_seconds: value.timer_active_seconds.into_iter().collect(),
+            app_active_seconds: value.app_active_seconds.into_iter().collect(),
+            wind_down_active_seconds: value.wind_down_active_seconds,
+        }
+    }
 }
 
 impl Default for AppStateOnDisk {
@@ -160,53 +903,214 @@ impl Default for AppStateOnDisk {
 
         Self {
             settings: SettingsDto::default(),
+            effective: SettingsDto::default(),
             profiles,
             weekly_stats: WeeklyStatsDto {
                 total_active_seconds: 0,
                 micro_done: 0,
                 rest_done: 0,
+                custom_done: 0,
                 daily_limit_hits: 0,
                 skipped: 0,
+                rating_sum: 0,
+                ratings: 0,
             },
+            break_history: Vec::new(),
+            focus_sessions: Vec::new(),
+            intensity: Vec::new(),
+            daily_stats: BTreeMap::new(),
+            app_usage: BTreeMap::new(),
+            sync: sync::SyncMetaDto::default(),
+            onboarding: onboarding::OnboardingMetaDto::default(),
+            widget_position: None,
+            engine: None,
+            vacation_until: None,
+            undo_history: VecDeque::new(),
         }
     }
 }
 
+impl AppStateOnDisk {
+    /// Rebuilds `effective` from the saved settings and the overrides in
+    /// place right now.
+    fn resolve_effective(&mut self) {
+        self.effective = match effective_settings(self.settings.clone()) {
+            Ok((settings, _)) => settings,
+            Err(error) => {
+                tracing::warn!(%error, "cannot apply overrides to saved settings");
+                self.settings.clone()
+            }
+        };
+    }
+}
+
 struct AppState {
-    path: PathBuf,
+    storage: storage::Storage,
     data: Mutex<AppStateOnDisk>,
+    /// Per-day totals recorded by other synced devices; never persisted.
+    synced_days: Mutex<BTreeMap<i64, DailyAggregate>>,
+    /// Latest `(day, active seconds)` each other device reported, through
+    /// sync snapshots or live peers.
+    peer_active: Mutex<BTreeMap<String, (i64, u64)>>,
+    /// Set when a sealed state file could not be opened, to be logged once
+    /// logging is up.
+    startup_warning: Option<String>,
+    /// Sections changed since the last save; the writer thread waits on
+    /// `dirty_signal` for them.
+    dirty: Mutex<storage::Sections>,
+    dirty_signal: Condvar,
 }
 
+/// Changes marked dirty are written at most this often.
+const SAVE_DEBOUNCE: Duration = Duration::from_secs(20);
+
 impl AppState {
     fn init() -> Result<Self, AppError> {
         let base = default_data_dir();
         fs::create_dir_all(&base)?;
-        let path = base.join("state.json");
+        let storage = storage::Storage::open(&base.join("state.db"))?;
 
-        let data = if path.exists() {
-            let raw = fs::read_to_string(&path)?;
-            serde_json::from_str(&raw).unwrap_or_default()
-        } else {
-            AppStateOnDisk::default()
+        let mut startup_warning = None;
+        let mut data = match storage.load()? {
+            storage::Loaded::State(data) => *data,
+            storage::Loaded::Empty => {
+                Self::import_legacy(&base.join("state.json"), &mut startup_warning)?
+            }
+            storage::Loaded::Locked(sealed, error) => {
+                // Never overwrite sealed state we cannot read; the keyring
+                // may just be locked or on another machine.
+                let backup = base.join(format!("state.json.sealed-{}", unix_now()));
+                fs::write(&backup, sealed)?;
+                storage.discard_document()?;
+                startup_warning = Some(format!(
+                    "could not decrypt state ({error}); moved it to {} and started with defaults",
+                    backup.display()
+                ));
+                AppStateOnDisk::default()
+            }
         };
+        data.daily_stats = data
+            .daily_stats
+            .split_off(&(history::local_day_index() - history::DAILY_STATS_RETENTION_DAYS));
+        data.app_usage = data
+            .app_usage
+            .split_off(&(history::local_day_index() - history::RETENTION_DAYS as i64));
+        if data.sync.device_id.is_empty() {
+            data.sync.device_id = sync::new_device_id();
+        }
+        data.resolve_effective();
 
         let state = Self {
-            path,
+            storage,
             data: Mutex::new(data),
+            synced_days: Mutex::new(BTreeMap::new()),
+            peer_active: Mutex::new(BTreeMap::new()),
+            startup_warning,
+            dirty: Mutex::new(storage::Sections::default()),
+            dirty_signal: Condvar::new(),
         };
-        state.save()?;
+        state.save(storage::Sections::ALL)?;
         Ok(state)
     }
 
-    fn save(&self) -> Result<(), AppError> {
-        let payload = {
-            let guard = self
-                .data
-                .lock()
-                .map_err(|e| AppError::Io(format!("mutex poisoned: {e}")))?;
-            serde_json::to_string_pretty(&*guard).map_err(|e| AppError::Io(e.to_string()))?
+    /// Reads the `state.json` written before the SQLite store, if any, and
+    /// renames it so it is imported only once.
+    fn import_legacy(
+        path: &Path,
+        startup_warning: &mut Option<String>,
+    ) -> Result<AppStateOnDisk, AppError> {
+        if !path.exists() {
+            return Ok(AppStateOnDisk::default());
+        }
+        let raw = fs::read(path)?;
+        let raw = if vault::is_sealed(&raw) {
+            match vault::open(&raw) {
+                Ok(raw) => raw,
+                Err(error) => {
+                    let backup = path.with_extension(format!("json.sealed-{}", unix_now()));
+                    fs::rename(path, &backup)?;
+                    *startup_warning = Some(format!(
+                        "could not decrypt state ({error}); moved it to {} and started with defaults",
+                        backup.display()
+                    ));
+                    return Ok(AppStateOnDisk::default());
+                }
+            }
+        } else {
+            raw
+        };
+        let data = match serde_json::from_slice(&raw) {
+            Ok(data) => data,
+            Err(error) => {
+                // Keep what could not be read; starting over must not
+                // quietly throw the user's data away.
+                let backup = path.with_extension(format!("json.corrupt-{}", unix_now()));
+                fs::rename(path, &backup)?;
+                *startup_warning = Some(format!(
+                    "could not read state ({error}); moved it to {} and started with defaults",
+                    backup.display()
+                ));
+                return Ok(AppStateOnDisk::default());
+            }
         };
-        fs::write(&self.path, payload)?;
+        fs::rename(path, path.with_extension("json.migrated"))?;
+        tracing::info!(path = %path.display(), "imported legacy state file");
+        Ok(data)
+    }
+
+    /// Writes `sections`, and anything else pending, now, for changes that
+    /// must not wait for the writer thread.
+    fn save(&self, sections: storage::Sections) -> Result<(), AppError> {
+        self.mark_dirty(sections);
+        self.flush()
+    }
+
+    fn mark_dirty(&self, sections: storage::Sections) {
+        if let Ok(mut dirty) = self.dirty.lock() {
+            *dirty = dirty.union(sections);
+            self.dirty_signal.notify_one();
+        }
+    }
+
+    /// Writes the sections marked dirty, if any.
+    fn flush(&self) -> Result<(), AppError> {
+        let sections = std::mem::take(&mut *self.dirty.lock()?);
+        if sections.is_empty() {
+            return Ok(());
+        }
+        let data = self.data.lock()?.clone();
+        if let Err(error) = self.storage.save(&data, sections) {
+            self.mark_dirty(sections);
+            return Err(error);
+        }
+        tracing::debug!(?sections, "state persisted");
+        Ok(())
+    }
+
+    /// Background writer: once something is marked dirty it waits out
+    /// `SAVE_DEBOUNCE`, so bursts of changes end up in one save.
+    fn spawn_writer(state: &Arc<Self>) -> Result<(), AppError> {
+        let state = Arc::clone(state);
+        std::thread::Builder::new()
+            .name("state-writer".into())
+            .spawn(move || {
+                loop {
+                    let Ok(dirty) = state.dirty.lock() else {
+                        return;
+                    };
+                    let Ok(dirty) = state
+                        .dirty_signal
+                        .wait_while(dirty, |dirty| dirty.is_empty())
+                    else {
+                        return;
+                    };
+                    drop(dirty);
+                    std::thread::sleep(SAVE_DEBOUNCE);
+                    if let Err(error) = state.flush() {
+                        tracing::warn!(%error, "failed to persist state");
+                    }
+                }
+            })?;
         Ok(())
     }
 
@@ -216,7 +1120,13 @@ impl AppState {
                 .weekly_stats
                 .total_active_seconds
                 .saturating_add(seconds);
+            let today = guard
+                .daily_stats
+                .entry(history::local_day_index())
+                .or_default();
+            today.active_seconds = today.active_seconds.saturating_add(seconds);
         }
+        self.mark_dirty(storage::Sections::STATS);
     }
 
     fn record_completed_break(&self, kind: BreakKind) {
@@ -228,69 +1138,382 @@ impl AppState {
                 BreakKind::Rest => {
                     guard.weekly_stats.rest_done = guard.weekly_stats.rest_done.saturating_add(1)
                 }
+                BreakKind::Custom(_) => {
+                    guard.weekly_stats.custom_done =
+                        guard.weekly_stats.custom_done.saturating_add(1)
+                }
                 BreakKind::DailyLimit => {
                     guard.weekly_stats.daily_limit_hits =
                         guard.weekly_stats.daily_limit_hits.saturating_add(1)
                 }
             }
+
+            let today = guard
+                .daily_stats
+                .entry(history::local_day_index())
+                .or_default();
+            match kind {
+                BreakKind::Micro => today.micro_done = today.micro_done.saturating_add(1),
+                BreakKind::Rest => today.rest_done = today.rest_done.saturating_add(1),
+                BreakKind::Custom(_) => today.custom_done = today.custom_done.saturating_add(1),
+                BreakKind::DailyLimit => {
+                    today.daily_limit_hits = today.daily_limit_hits.saturating_add(1)
+                }
+            }
         }
+        self.mark_dirty(storage::Sections::STATS);
     }
 
     fn record_skipped_break(&self) {
         if let Ok(mut guard) = self.data.lock() {
             guard.weekly_stats.skipped = guard.weekly_stats.skipped.saturating_add(1);
+            let today = guard
+                .daily_stats
+                .entry(history::local_day_index())
+                .or_default();
+            today.skipped = today.skipped.saturating_add(1);
+        }
+        self.mark_dirty(storage::Sections::STATS);
+    }
+
+    fn local_days(&self) -> Result<BTreeMap<i64, DailyAggregate>, AppError> {
+        let guard = self.data.lock()?;
+        Ok(guard
+            .daily_stats
+            .iter()
+            .map(|(day, stats)| (*day, DailyAggregate::from(stats)))
+            .collect())
+    }
+
+    /// Local analytics combined with whatever other synced devices reported.
+    fn analytics_store(&self) -> Result<AnalyticsStore, AppError> {
+        let local = self.local_days()?;
+        let synced = self.synced_days.lock()?;
+        Ok(AnalyticsStore::from_days(lazaro_core::sync::merge_days([
+            &local, &*synced,
+        ])))
+    }
+
+    // Sync snapshots lag behind live peers, so the larger total for the same
+    // day wins instead of the most recent report.
+    fn record_peer_active(&self, device_id: &str, day: i64, seconds: u64) {
+        if let Ok(mut peers) = self.peer_active.lock() {
+            let entry = peers.entry(device_id.to_string()).or_insert((day, 0));
+            if day > entry.0 {
+                *entry = (day, seconds);
+            } else if day == entry.0 {
+                entry.1 = entry.1.max(seconds);
+            }
+        }
+    }
+
+    /// Active seconds other devices recorded today, counted toward the shared
+    /// daily limit.
+    fn shared_active_today(&self) -> u64 {
+        let today = history::local_day_index();
+        self.peer_active
+            .lock()
+            .map(|peers| {
+                peers
+                    .values()
+                    .filter(|(day, _)| *day == today)
+                    .map(|(_, seconds)| *seconds)
+                    .sum()
+            })
+            .unwrap_or(0)
+    }
+
+    fn recent_compliance(&self) -> Result<Compliance, AppError> {
+        let guard = self.data.lock()?;
+        Ok(history::to_history(&guard.break_history)
+            .compliance_since(unix_now().saturating_sub(86_400)))
+    }
+
+    fn record_break_rating(&self, day_index: i64, rating: u8) {
+        if let Ok(mut guard) = self.data.lock() {
+            let rating = u32::from(rating);
+            guard.weekly_stats.rating_sum = guard.weekly_stats.rating_sum.saturating_add(rating);
+            guard.weekly_stats.ratings = guard.weekly_stats.ratings.saturating_add(1);
+            let day = guard.daily_stats.entry(day_index).or_default();
+            day.rating_sum = day.rating_sum.saturating_add(rating);
+            day.ratings = day.ratings.saturating_add(1);
+        }
+        self.mark_dirty(storage::Sections::STATS);
+    }
+
+    fn record_focus_activity(&self, at: u64, seconds: u64, gap_seconds: u64) {
+        if let Ok(mut guard) = self.data.lock() {
+            let mut log = history::to_focus_log(&guard.focus_sessions);
+            log.record_activity(at, seconds, gap_seconds);
+            log.prune_before(at.saturating_sub(history::RETENTION_DAYS * 86_400));
+            guard.focus_sessions = history::focus_dtos(&log);
+        }
+        self.mark_dirty(storage::Sections::FOCUS_SESSIONS);
+    }
+
+    fn record_intensity(&self, at: u64, keys: u64, clicks: u64) {
+        if let Ok(mut guard) = self.data.lock() {
+            let mut log = history::to_intensity_log(&guard.intensity);
+            log.record(at, keys, clicks);
+            log.prune_before(at.saturating_sub(history::RETENTION_DAYS * 86_400));
+            guard.intensity = history::intensity_dtos(&log);
+        }
+        self.mark_dirty(storage::Sections::DOCUMENT);
+    }
+
+    fn record_app_usage(&self, app: &str, title: Option<&str>, seconds: u64) {
+        let today = history::local_day_index();
+        if let Ok(mut guard) = self.data.lock() {
+            let mut store = history::to_app_usage_store(&guard.app_usage);
+            store.record(today, app, title, seconds);
+            store.prune_before(today - history::RETENTION_DAYS as i64);
+            guard.app_usage = history::app_usage_dtos(&store);
         }
+        self.mark_dirty(storage::Sections::APP_USAGE);
+    }
+
+    /// How today's `kinds` prompts were answered, for the nudge rules.
+    fn compliance_today(&self, kinds: &[BreakKind]) -> Vec<(BreakKind, Compliance)> {
+        let Some((day_start, _)) = history::local_day_range(None) else {
+            return Vec::new();
+        };
+        let Ok(guard) = self.data.lock() else {
+            return Vec::new();
+        };
+        let history = history::to_history(&guard.break_history);
+        kinds
+            .iter()
+            .map(|&kind| (kind, history.kind_compliance_since(day_start, kind)))
+            .collect()
+    }
+
+    /// Runs as they stand now, to compare across a completed break.
+    fn milestone_progress(&self) -> MilestoneProgress {
+        let breaks_in_a_row = self
+            .data
+            .lock()
+            .map(|guard| history::to_history(&guard.break_history).completed_in_a_row())
+            .unwrap_or(0);
+        let streak_days = self
+            .analytics_store()
+            .map(|store| store.streaks(history::local_day_index()).current_days)
+            .unwrap_or(0);
+        MilestoneProgress {
+            breaks_in_a_row,
+            streak_days,
+        }
+    }
+
+    fn update_history(&self, apply: impl FnOnce(&mut BreakHistory)) {
+        if let Ok(mut guard) = self.data.lock() {
+            let mut history = history::to_history(&guard.break_history);
+            apply(&mut history);
+            history.prune_before(unix_now().saturating_sub(history::RETENTION_DAYS * 86_400));
+            guard.break_history = history::to_dtos(&history);
+        }
+        self.mark_dirty(storage::Sections::BREAK_HISTORY);
     }
 }
 
-#[derive(Clone, Debug, Serialize, Deserialize)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 struct RuntimeStatusDto {
     running: bool,
-    pending_break: Option<String>,
-    active_break: Option<String>,
+    pending_break: Option<BreakKind>,
+    /// Every break waiting to start, `pending_break` first.
+    pending_breaks: Vec<BreakKind>,
+    active_break: Option<BreakKind>,
     remaining_seconds: Option<u64>,
-    next_break_kind: Option<String>,
-    next_break_seconds: Option<u64>,
+    next_break_kind: Option<BreakKind>,
+    next_break_eta_seconds: Option<u64>,
+    next_break_at: Option<u64>,
+    micro_progress_percent: u8,
+    rest_progress_percent: u8,
+    daily_limit_progress_percent: u8,
+    daily_active_seconds: u64,
+    daily: DailyStatusDto,
+    /// Scheduler dry run, one entry per timer; see `explain_schedule`.
+    schedule: Vec<ScheduleEntryDto>,
+    weekly_limit_progress_percent: u8,
+    weekly_active_seconds: u64,
+    daily_limit_forecast_seconds: Option<u64>,
+    daily_limit_forecast_at: Option<u64>,
+    daily_limit_forecast_before_reset: bool,
+    /// When the daily limit action runs unless the break starts first.
+    daily_limit_action_at: Option<u64>,
+    activity_density_percent: u8,
+    interval_scale_percent: u8,
+    /// Phase id of the evening wind-down, `None` outside it.
+    wind_down: Option<String>,
+    /// Active time inside tonight's wind-down window.
+    wind_down_active_seconds: u64,
     strict_mode: bool,
+    dnd_active: bool,
+    in_meeting: bool,
+    power_saving: bool,
+    vacation_until: Option<u64>,
+    /// End of the current guest session, during which nothing is recorded.
+    guest_until: Option<u64>,
+    /// End of presentation mode, during which breaks and notifications wait.
+    presentation_until: Option<u64>,
+    /// Presentation mode held by a running screencast.
+    screen_capture_active: bool,
+    inhibited_by: Option<String>,
     last_event: String,
 }
 
+/// The engine's current day, as returned by `get_daily_status`.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+struct DailyStatusDto {
+    limit_enabled: bool,
+    used_seconds: u64,
+    limit_seconds: u64,
+    snoozes_used: u32,
+    max_snoozes: u32,
+    snoozed_until: Option<u64>,
+    next_reset_at: u64,
+}
+
+impl DailyStatusDto {
+    fn new(status: DailyStatus, limit_enabled: bool, now: u64) -> Self {
+        Self {
+            limit_enabled,
+            used_seconds: status.used_seconds,
+            limit_seconds: status.limit_seconds,
+            snoozes_used: status.snoozes_used,
+            max_snoozes: status.max_snoozes,
+            snoozed_until: status.snoozed_until,
+            next_reset_at: now.saturating_add(status.seconds_until_reset),
+        }
+    }
+}
+
+/// Where one timer stands and why, as returned by `explain_schedule`.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+struct ScheduleEntryDto {
+    kind: BreakKind,
+    accumulated_seconds: u64,
+    target_seconds: u64,
+    snoozed_until: Option<u64>,
+    next_due_at: Option<u64>,
+    reason: String,
+    deferred_by: Option<String>,
+}
+
+impl From<TimerExplanation> for ScheduleEntryDto {
+    fn from(entry: TimerExplanation) -> Self {
+        let (reason, deferred_by) = match entry.reason {
+            ScheduleReason::Disabled => ("disabled", None),
+            ScheduleReason::BreakActive => ("break_active", None),
+            ScheduleReason::Warmup => ("warmup", None),
+            ScheduleReason::Snoozed => ("snoozed", None),
+            ScheduleReason::Interval => ("interval", None),
+            ScheduleReason::AfterReset => ("after_reset", None),
+            ScheduleReason::Due => ("due", None),
+            ScheduleReason::Queued => ("queued", None),
+            ScheduleReason::Absorbed => ("absorbed", None),
+            ScheduleReason::Deferred(by) => ("deferred", Some(defer_reason_to_string(by))),
+        };
+        Self {
+            kind: entry.kind,
+            accumulated_seconds: entry.accumulated_seconds,
+            target_seconds: entry.target_seconds,
+            snoozed_until: entry.snoozed_until,
+            next_due_at: entry.next_due_at,
+            reason: reason.into(),
+            deferred_by,
+        }
+    }
+}
+
 impl Default for RuntimeStatusDto {
     fn default() -> Self {
         Self {
             running: false,
             pending_break: None,
+            pending_breaks: Vec::new(),
             active_break: None,
             remaining_seconds: None,
             next_break_kind: None,
-            next_break_seconds: None,
+            next_break_eta_seconds: None,
+            next_break_at: None,
+            micro_progress_percent: 0,
+            rest_progress_percent: 0,
+            daily_limit_progress_percent: 0,
+            daily_active_seconds: 0,
+            daily: DailyStatusDto::default(),
+            schedule: Vec::new(),
+            weekly_limit_progress_percent: 0,
+            weekly_active_seconds: 0,
+            daily_limit_forecast_seconds: None,
+            daily_limit_forecast_at: None,
+            daily_limit_forecast_before_reset: false,
+            daily_limit_action_at: None,
+            activity_density_percent: 0,
+            interval_scale_percent: 100,
+            wind_down: None,
+            wind_down_active_seconds: 0,
             strict_mode: false,
+            dnd_active: false,
+            in_meeting: false,
+            power_saving: false,
+            vacation_until: None,
+            guest_until: None,
+            presentation_until: None,
+            screen_capture_active: false,
+            inhibited_by: None,
             last_event: "idle".into(),
         }
     }
 }
 
+/// `runtime://event` payload. `event` carries the machine fields under the
+/// versioned schema in `lazaro_core::event` and `message` the localized
+/// text; `kind`, `break_kind` and `remaining_seconds` repeat parts of
+/// `event` for consumers written before the schema.
 #[derive(Clone, Debug, Serialize)]
 struct RuntimeEventDto {
+    schema_version: u32,
     kind: String,
     message: String,
-    break_kind: Option<String>,
+    break_kind: Option<BreakKind>,
     remaining_seconds: Option<u64>,
     strict_mode: bool,
+    event: RuntimeEvent,
 }
 
-enum RuntimeControl {
-    Stop,
-    UpdateSettings { core: Settings, dto: SettingsDto },
-    StartBreak(BreakKind),
-    StartPending,
-    SnoozePending,
+impl RuntimeEventDto {
+    fn new(event: RuntimeEvent, message: impl Into<String>, strict_mode: bool) -> Self {
+        let remaining_seconds = match &event {
+            RuntimeEvent::BreakStarted {
+                duration_seconds, ..
+            } => Some(*duration_seconds),
+            RuntimeEvent::BreakTick {
+                remaining_seconds, ..
+            } => Some(*remaining_seconds),
+            RuntimeEvent::BreakCompleted { .. } => Some(0),
+            RuntimeEvent::DailyLimitActionPending { grace_seconds } => Some(*grace_seconds),
+            _ => None,
+        };
+        Self {
+            schema_version: EVENT_SCHEMA_VERSION,
+            kind: event.kind().into(),
+            message: message.into(),
+            break_kind: event.break_kind(),
+            remaining_seconds,
+            strict_mode,
+            event,
+        }
+    }
 }
 
 struct RuntimeController {
-    tx: Option<Sender<RuntimeControl>>,
-    handle: Option<JoinHandle<()>>,
-    status: Arc<Mutex<RuntimeStatusDto>>,
+    tx: Option<UnboundedSender<RuntimeControl>>,
+    handle: Option<tauri::async_runtime::JoinHandle<()>>,
+    /// Outlives any one runtime; the loop sends here only when the status
+    /// changes, so readers never wait on it.
+    status: watch::Sender<RuntimeStatusDto>,
+    /// Bumped on every start, so a supervisor can tell it has been replaced.
+    generation: u64,
 }
 
 impl Default for RuntimeController {
@@ -298,17 +1521,28 @@ impl Default for RuntimeController {
         Self {
             tx: None,
             handle: None,
-            status: Arc::new(Mutex::new(RuntimeStatusDto::default())),
+            status: watch::Sender::new(RuntimeStatusDto::default()),
+            generation: 0,
         }
     }
 }
 
+/// Returned by `start_runtime`; `reused` when a responsive runtime was
+/// already running and nothing was started.
+#[derive(Clone, Debug, Serialize)]
+struct RuntimeStartDto {
+    reused: bool,
+    status: RuntimeStatusDto,
+}
+
 struct BackendState {
     persistent: Arc<AppState>,
     runtime: Mutex<RuntimeController>,
+    /// Set once the app is built; settings changes made before that emit nothing.
+    app: OnceLock<AppHandle>,
 }
 
-fn default_data_dir() -> PathBuf {
+fn shared_data_dir() -> PathBuf {
     if let Ok(xdg) = std::env::var("XDG_DATA_HOME") {
         return PathBuf::from(xdg).join("lazaro");
     }
@@ -317,105 +1551,448 @@ fn default_data_dir() -> PathBuf {
     Path::new(&home).join(".local/share/lazaro")
 }
 
-fn parse_reset_time(value: &str) -> Result<(u8, u8), AppError> {
-    let parts: Vec<&str> = value.split(':').collect();
-    if parts.len() != 2 {
-        return Err(AppError::InvalidResetTime(value.to_string()));
+fn default_data_dir() -> PathBuf {
+    let base = shared_data_dir();
+    match session::seat_scope() {
+        Some(seat) => base.join("seats").join(seat),
+        None => base,
     }
+}
 
-    let hour: u8 = parts[0]
-        .parse()
-        .map_err(|_| AppError::InvalidResetTime(value.to_string()))?;
-    let minute: u8 = parts[1]
-        .parse()
-        .map_err(|_| AppError::InvalidResetTime(value.to_string()))?;
+fn parse_reset_time(value: &str) -> Result<(u8, u8), AppError> {
+    config::parse_reset_time(value).ok_or_else(|| AppError::InvalidResetTime(value.to_string()))
+}
 
-    if hour > 23 || minute > 59 {
-        return Err(AppError::InvalidResetTime(value.to_string()));
-    }
+fn parse_wind_down_time(field: &'static str, value: &str) -> Result<(u8, u8), AppError> {
+    config::parse_reset_time(value)
+        .ok_or_else(|| AppError::InvalidWindDownTime(field, value.to_string()))
+}
 
-    Ok((hour, minute))
+/// Templates may only replace messages the catalog has.
+fn message_templates(value: &str) -> Result<Vec<MessageTemplate>, AppError> {
+    config::parse_message_templates(value)
+        .filter(|templates| {
+            templates
+                .iter()
+                .all(|template| i18n::is_message_key(&template.key))
+        })
+        .ok_or_else(|| AppError::InvalidMessageTemplates(value.to_string()))
 }
 
 fn settings_to_core(dto: &SettingsDto) -> Result<Settings, AppError> {
-    let block_level = match dto.block_level.as_str() {
-        "soft" => BlockLevel::Soft,
-        "medium" => BlockLevel::Medium,
-        "strict" => BlockLevel::Strict,
-        _ => BlockLevel::Medium,
-    };
-
     let (reset_hour, reset_minute) = parse_reset_time(&dto.daily_reset_time)?;
+    let (start_hour, start_minute) = parse_wind_down_time("wind_down_start", &dto.wind_down_start)?;
+    let (cutoff_hour, cutoff_minute) =
+        parse_wind_down_time("wind_down_cutoff", &dto.wind_down_cutoff)?;
 
     Ok(Settings {
         micro: BreakTimerSettings {
             interval_seconds: dto.micro_interval_seconds,
             duration_seconds: dto.micro_duration_seconds,
             snooze_seconds: dto.micro_snooze_seconds,
-            enabled: true,
+            enabled: dto.micro_enabled,
+            jitter_percent: dto.micro_jitter_percent.min(MAX_JITTER_PERCENT),
+            block_level: dto.micro_block_level,
+            resets: timer_id_list(&dto.micro_resets),
         },
         rest: BreakTimerSettings {
             interval_seconds: dto.rest_interval_seconds,
             duration_seconds: dto.rest_duration_seconds,
             snooze_seconds: dto.rest_snooze_seconds,
-            enabled: true,
+            enabled: dto.rest_enabled,
+            jitter_percent: dto.rest_jitter_percent.min(MAX_JITTER_PERCENT),
+            block_level: dto.rest_block_level,
+            resets: timer_id_list(&dto.rest_resets),
+        },
+        custom_timers: custom_timers_to_core(&dto.custom_timers)?,
+        adaptive: AdaptiveSettings {
+            enabled: dto.adaptive_enabled,
+            min_interval_percent: dto.adaptive_min_interval_percent.clamp(10, 100),
+            max_interval_percent: dto.adaptive_max_interval_percent.clamp(100, 200),
+        },
+        intensity: IntensitySettings {
+            enabled: dto.intensity_enabled,
+            scale_micro: dto.intensity_scale_micro,
+            heavy_events_per_minute: dto.intensity_heavy_events_per_minute.max(1),
+            max_reduction_percent: dto.intensity_max_reduction_percent.min(90),
+        },
+        escalation: EscalationSettings {
+            enabled: dto.escalation_enabled,
+            renotify_after_seconds: dto.escalation_renotify_seconds,
+            force_after_seconds: dto.escalation_force_seconds,
         },
         daily_limit: DailyLimitSettings {
             limit_seconds: dto.daily_limit_seconds,
             snooze_seconds: dto.daily_limit_snooze_seconds,
             reset_hour_local: reset_hour,
             reset_minute_local: reset_minute,
-            enabled: true,
+            enabled: dto.daily_limit_enabled,
+            max_snoozes: dto.daily_limit_max_snoozes,
+            action: match dto.daily_limit_action.as_str() {
+                "lock" => DailyLimitAction::LockSession,
+                "suspend" => DailyLimitAction::Suspend,
+                _ => DailyLimitAction::None,
+            },
+            resets_timers: dto.daily_limit_resets_timers,
+            warning_thresholds: percent_list(&dto.daily_limit_warning_thresholds),
+        },
+        weekly_limit: WeeklyLimitSettings {
+            enabled: dto.weekly_limit_enabled,
+            limit_seconds: dto.weekly_limit_seconds,
+            warning_thresholds: percent_list(&dto.weekly_limit_warning_thresholds),
+        },
+        app_limits: AppLimitSettings {
+            enabled: dto.app_limits_enabled,
+            limits: config::parse_app_limits(&dto.app_limits)
+                .ok_or_else(|| AppError::InvalidAppLimits(dto.app_limits.clone()))?,
+            block_level: dto.app_limit_block_level,
+        },
+        limit_scope: match dto.limit_scope.as_str() {
+            "profile" => LimitScope::Profile,
+            _ => LimitScope::Global,
+        },
+        block_level: dto.block_level,
+        dimming: DimmingSettings {
+            soft: dto.fade_on_soft,
+            medium: dto.fade_on_medium,
+            strict: dto.fade_on_strict,
+            warning_seconds: dto.fade_warning_seconds,
+            max_opacity_percent: dto.fade_max_opacity_percent.min(100),
         },
-        block_level,
         notifications: NotificationSettings {
             desktop_enabled: dto.desktop_notifications,
             overlay_enabled: dto.overlay_notifications,
             sound_enabled: dto.sound_notifications,
             sound_theme: dto.sound_theme.clone(),
+            countdown_cue_seconds: dto.countdown_cue_seconds,
+            respect_system_dnd: dto.respect_system_dnd,
+            defer_breaks_on_dnd: dto.defer_breaks_on_dnd,
+            daily_summary_enabled: dto.daily_summary_notifications,
+            countdown_widget_enabled: dto.countdown_widget,
+            taskbar_progress_enabled: dto.taskbar_progress,
+            message_templates: message_templates(&dto.message_templates)?,
         },
-        startup: StartupSettings {
-            xdg_autostart_enabled: dto.startup_xdg,
-            systemd_user_enabled: dto.startup_systemd_user,
+        inhibit: InhibitSettings {
+            on_fullscreen: dto.inhibit_on_fullscreen,
+            on_media: dto.inhibit_on_media,
+            presentation_on_screencast: dto.presentation_on_screencast,
         },
-        active_profile_id: dto.active_profile_id.clone(),
-    })
-}
-
-fn break_kind_to_string(kind: BreakKind) -> String {
-    match kind {
-        BreakKind::Micro => "micro".into(),
-        BreakKind::Rest => "rest".into(),
-        BreakKind::DailyLimit => "daily_limit".into(),
-    }
-}
-
-fn parse_break_kind(value: &str) -> Result<BreakKind, AppError> {
-    match value {
-        "micro" => Ok(BreakKind::Micro),
-        "rest" => Ok(BreakKind::Rest),
-        "daily_limit" => Ok(BreakKind::DailyLimit),
-        _ => Err(AppError::InvalidBreakKind(value.to_string())),
-    }
-}
-
-fn unix_now() -> u64 {
-    SystemTime::now()
-        .duration_since(UNIX_EPOCH)
-        .map(|d| d.as_secs())
-        .unwrap_or(0)
+        blocking: BlockingSettings {
+            enabled: dto.blocking_enabled,
+            app_classes: dto.blocking_app_classes.clone(),
+            app_action: match dto.blocking_app_action.as_str() {
+                "kill" => AppBlockAction::Kill,
+                _ => AppBlockAction::Minimize,
+            },
+            hosts: dto
+                .blocking_hosts
+                .split(',')
+                .map(|host| host.trim().to_ascii_lowercase())
+                .filter(|host| config::is_host_name(host))
+                .collect::<Vec<_>>()
+                .join(","),
+            hosts_file_path: dto.blocking_hosts_file.trim().to_string(),
+        },
+        calendar: CalendarSettings {
+            enabled: dto.calendar_enabled,
+            source_path: dto.calendar_source_path.trim().to_string(),
+            meeting_policy: match dto.calendar_meeting_policy.as_str() {
+                "silent" => MeetingPolicy::Silent,
+                _ => MeetingPolicy::Defer,
+            },
+        },
+        keys: KeyBindingSettings {
+            media_keys_enabled: dto.media_keys_enabled,
+            start_keys: dto.media_start_keys.clone(),
+            snooze_keys: dto.media_snooze_keys.clone(),
+        },
+        remote: RemoteSettings {
+            enabled: dto.remote_enabled,
+            port: dto.remote_port.max(1),
+            token: dto.remote_token.trim().to_string(),
+        },
+        integrations: IntegrationSettings {
+            slack_token: dto.slack_token.trim().to_string(),
+            teams_token: dto.teams_token.trim().to_string(),
+            status_template: dto.break_status_template.clone(),
+            status_emoji: dto.break_status_emoji.trim().to_string(),
+        },
+        sync: SyncSettings {
+            backend: match dto.sync_backend.as_str() {
+                "directory" => SyncBackend::Directory,
+                "webdav" => SyncBackend::WebDav,
+                _ => SyncBackend::Off,
+            },
+            directory: dto.sync_directory.trim().to_string(),
+            webdav_url: dto.sync_webdav_url.trim().to_string(),
+            webdav_username: dto.sync_webdav_username.trim().to_string(),
+            webdav_password: dto.sync_webdav_password.clone(),
+        },
+        peers: PeerSettings {
+            enabled: dto.peer_sharing_enabled,
+            port: dto.peer_port.max(1),
+            secret: dto.peer_secret.clone(),
+        },
+        privacy: PrivacySettings {
+            encrypt_state: dto.encrypt_state,
+        },
+        accessibility: AccessibilitySettings {
+            high_contrast: dto.high_contrast_overlay,
+            reduce_motion: dto.reduce_motion,
+            large_countdown: dto.large_countdown,
+            screen_reader_announcements: dto.screen_reader_announcements,
+        },
+        theme: ThemeSettings {
+            mode: match dto.theme_mode.as_str() {
+                "light" => ThemeMode::Light,
+                "custom" => ThemeMode::Custom,
+                _ => ThemeMode::Dark,
+            },
+            accent_color: dto.theme_accent_color.clone(),
+            background_color: dto.theme_background_color.clone(),
+            text_color: dto.theme_text_color.clone(),
+            background_image_path: dto.theme_background_image.trim().to_string(),
+            font_scale_percent: dto
+                .theme_font_scale_percent
+                .clamp(MIN_FONT_SCALE_PERCENT, MAX_FONT_SCALE_PERCENT),
+            activity_packs: activity_pack_list(&dto.activity_packs),
+        },
+        language: dto.language.clone(),
+        analytics: AnalyticsSettings {
+            week_start: match dto.week_start.as_str() {
+                "sunday" => WeekStart::Sunday,
+                _ => WeekStart::Monday,
+            },
+            weekly_max_active_seconds: dto.weekly_max_active_seconds,
+            weekly_min_breaks: dto.weekly_min_breaks,
+            focus_gap_seconds: dto.focus_gap_seconds,
+            break_feedback_prompt: dto.break_feedback_prompt,
+            milestone_celebrations: dto.milestone_celebrations,
+            app_usage_enabled: dto.app_usage_enabled,
+            app_usage_titles: dto.app_usage_enabled && dto.app_usage_titles,
+            app_categories: config::parse_app_categories(&dto.app_categories)
+                .ok_or_else(|| AppError::InvalidAppCategories(dto.app_categories.clone()))?,
+        },
+        nudges: NudgeSettings {
+            enabled: dto.nudges_enabled,
+            notify: dto.nudge_notifications,
+            skip_rate: dto.nudge_skip_rate,
+            long_focus: dto.nudge_long_focus,
+            late_night: dto.nudge_late_night,
+            interval_seconds: dto.nudge_interval_seconds.max(60),
+        },
+        wind_down: WindDownSettings {
+            enabled: dto.wind_down_enabled,
+            start_hour_local: start_hour,
+            start_minute_local: start_minute,
+            cutoff_hour_local: cutoff_hour,
+            cutoff_minute_local: cutoff_minute,
+            interval_percent: dto.wind_down_interval_percent.clamp(10, 100),
+            budget_seconds: dto.wind_down_budget_seconds,
+        },
+        day_overrides: config::parse_day_overrides(&dto.day_overrides)
+            .ok_or_else(|| AppError::InvalidDayOverrides(dto.day_overrides.clone()))?,
+        runtime: RuntimeSettings {
+            tick_emit_interval_seconds: dto.tick_emit_interval_seconds.max(1),
+            break_tick_millis: dto
+                .break_tick_millis
+                .clamp(MIN_BREAK_TICK_MILLIS, MAX_BREAK_TICK_MILLIS),
+            log_level: if logging::parse_level(&dto.log_level).is_some() {
+                dto.log_level.clone()
+            } else {
+                logging::DEFAULT_LOG_LEVEL.into()
+            },
+            power_saver_behavior: match dto.power_saver_behavior.as_str() {
+                "ignore" => PowerSaverBehavior::Ignore,
+                "quiet_and_slow" => PowerSaverBehavior::QuietAndSlow,
+                _ => PowerSaverBehavior::Quiet,
+            },
+            interval_change_policy: match dto.interval_change_policy.as_str() {
+                "preserve" => IntervalChangePolicy::Preserve,
+                "reset" => IntervalChangePolicy::Reset,
+                _ => IntervalChangePolicy::Rescale,
+            },
+            restart_on_crash: dto.restart_runtime_on_crash,
+        },
+        startup: StartupSettings {
+            xdg_autostart_enabled: dto.startup_xdg,
+            systemd_user_enabled: dto.startup_systemd_user,
+            warmup_grace_seconds: dto.warmup_grace_seconds,
+            idle_counts_as_rest: dto.idle_counts_as_rest,
+        },
+        updates: UpdateSettings {
+            check_enabled: dto.update_check_enabled,
+            channel: match dto.update_channel.as_str() {
+                "beta" => UpdateChannel::Beta,
+                _ => UpdateChannel::Stable,
+            },
+        },
+        active_profile_id: dto.active_profile_id.clone(),
+    })
+}
+
+fn custom_timers_to_core(timers: &[CustomTimerDto]) -> Result<Vec<CustomBreakTimer>, AppError> {
+    if timers.len() > MAX_CUSTOM_TIMERS {
+        return Err(AppError::InvalidTimer(format!(
+            "at most {MAX_CUSTOM_TIMERS} custom timers are supported"
+        )));
+    }
+
+    let mut seen = std::collections::BTreeSet::new();
+    timers
+        .iter()
+        .map(|timer| {
+            let id = timer.id.trim();
+            if id.is_empty()
+                || [MICRO_TIMER_ID, REST_TIMER_ID, "daily_limit"].contains(&id)
+                || !seen.insert(id.to_string())
+            {
+                return Err(AppError::InvalidTimer(timer.id.clone()));
+            }
+            if timer.interval_seconds == 0 {
+                return Err(AppError::InvalidTimer(format!(
+                    "{id}: interval must be positive"
+                )));
+            }
+
+            Ok(CustomBreakTimer {
+                id: id.to_string(),
+                name: timer.name.trim().to_string(),
+                timer: BreakTimerSettings {
+                    interval_seconds: timer.interval_seconds,
+                    duration_seconds: timer.duration_seconds,
+                    snooze_seconds: timer.snooze_seconds,
+                    enabled: timer.enabled,
+                    jitter_percent: timer.jitter_percent.min(MAX_JITTER_PERCENT),
+                    block_level: timer.block_level,
+                    resets: timer.resets.clone(),
+                },
+                priority: timer.priority,
+            })
+        })
+        .collect()
+}
+
+fn join_percents(percents: &[u8]) -> String {
+    percents
+        .iter()
+        .map(u8::to_string)
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+// Out of range entries are dropped rather than failing the whole save.
+fn percent_list(value: &str) -> Vec<u8> {
+    let mut percents = value
+        .split(',')
+        .filter_map(|entry| entry.trim().parse::<u8>().ok())
+        .filter(|percent| (1..=100).contains(percent))
+        .collect::<Vec<_>>();
+    percents.sort_unstable();
+    percents.dedup();
+    percents
+}
+
+// Unknown names are dropped; an empty list falls back to every pack.
+fn activity_pack_list(value: &str) -> Vec<ActivityPack> {
+    let mut packs = Vec::new();
+    for pack in value
+        .split(',')
+        .filter_map(|entry| ActivityPack::parse(entry.trim()))
+    {
+        if !packs.contains(&pack) {
+            packs.push(pack);
+        }
+    }
+    if packs.is_empty() {
+        packs = ActivityPack::ALL.to_vec();
+    }
+    packs
+}
+
+fn timer_id_list(value: &str) -> Vec<String> {
+    value
+        .split(',')
+        .map(str::trim)
+        .filter(|id| !id.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+/// Per-timer block levels cross the IPC boundary as `"default"` when they
+/// follow the global level.
+mod timer_block_level {
+    use lazaro_core::config::BlockLevel;
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(
+        level: &Option<BlockLevel>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(level.map_or("default", BlockLevel::as_str))
+    }
+
+    // Anything else follows the global level, as stored settings always did.
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Option<BlockLevel>, D::Error> {
+        Ok(BlockLevel::parse(&String::deserialize(deserializer)?))
+    }
+}
+
+fn break_kind_to_string(kind: BreakKind) -> String {
+    kind.id()
+}
+
+fn defer_reason_to_string(reason: DeferReason) -> String {
+    reason.as_str().into()
+}
+
+fn parse_break_kind(value: &str) -> Result<BreakKind, AppError> {
+    BreakKind::parse(value).ok_or_else(|| AppError::InvalidBreakKind(value.to_string()))
+}
+
+fn unix_now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
 }
 
 fn emit_runtime_event(app: &AppHandle, event: RuntimeEventDto) {
+    crash::record_event(&event.kind, &event.message);
+    remote::publish("event", &event);
     let _ = app.emit("runtime://event", event);
 }
 
-fn send_notification(settings: &SettingsDto, title: &str, body: &str) {
-    if !settings.desktop_notifications {
+fn emit_status_tick(app: &AppHandle, status: RuntimeStatusDto) {
+    remote::publish("status", &status);
+    let _ = app.emit("runtime://status_tick", status);
+}
+
+fn send_notification(settings: &SettingsDto, dnd_active: bool, title: &str, body: &str) {
+    if !settings.desktop_notifications || dnd_active {
+        return;
+    }
+
+    notify::show(title, body, false);
+}
+
+/// Like `send_notification`, but marked critical so it stays on screen.
+fn send_urgent_notification(settings: &SettingsDto, dnd_active: bool, title: &str, body: &str) {
+    if !settings.desktop_notifications || dnd_active {
+        return;
+    }
+
+    notify::show(title, body, true);
+}
+
+fn play_cue(settings: &SettingsDto, dnd_active: bool, cue: sound::Cue) {
+    if !settings.sound_notifications || settings.countdown_cue_seconds == 0 || dnd_active {
         return;
     }
 
-    let _ = Notification::new().summary(title).body(body).show();
+    sound::play(&settings.sound_theme, cue);
 }
 
 fn open_overlay(
@@ -424,18 +2001,29 @@ fn open_overlay(
     remaining: u64,
     overlay_enabled: bool,
     strict_mode: bool,
+    payload: overlay::OverlayPayloadDto,
+    lang: i18n::Language,
 ) {
     let app_handle = app.clone();
+    let preview = payload.preview;
+    let started = payload.strings.started.clone();
+    let init_script = format!(
+        "window.__LAZARO_OVERLAY_CONFIG__ = {};\nwindow.__LAZARO_OVERLAY__ = {};",
+        serde_json::to_string(&payload.config).unwrap_or_else(|_| "null".into()),
+        serde_json::to_string(&payload).unwrap_or_else(|_| "null".into())
+    );
+    overlay::set(overlay_enabled.then_some(payload));
     let _ = app.run_on_main_thread(move || {
         if !overlay_enabled {
             if let Some(window) = app_handle.get_webview_window("break-overlay") {
-                let _ = window.close();
+                let _ = window.destroy();
             }
             return;
         }
 
+        // Destroying skips `CloseRequested`, which is reserved for the user.
         if let Some(window) = app_handle.get_webview_window("break-overlay") {
-            let _ = window.close();
+            let _ = window.destroy();
         }
 
         let monitor_geometry = resolve_overlay_geometry(&app_handle);
@@ -445,11 +2033,12 @@ fn open_overlay(
             "break-overlay",
             WebviewUrl::App("overlay.html".into()),
         )
-        .title("Lázaro - Descanso")
+        .title(i18n::text(lang, "overlay.title"))
         .decorations(false)
         .always_on_top(true)
         .resizable(false)
         .skip_taskbar(true)
+        .initialization_script(&init_script)
         .inner_size(
             monitor_geometry.width as f64,
             monitor_geometry.height as f64,
@@ -461,30 +2050,58 @@ fn open_overlay(
             base_builder.closable(true)
         };
 
-        if let Ok(window) = builder.build() {
-            if let (Some(x), Some(y)) = (monitor_geometry.x, monitor_geometry.y) {
-                if window
-                    .set_position(Position::Physical(PhysicalPosition::new(x, y)))
-                    .is_err()
-                {
-                    let _ = window.center();
+        let window = match builder.build() {
+            Ok(window) => window,
+            Err(error) => {
+                tracing::warn!(%error, "failed to open break overlay");
+                if preview {
+                    overlay::set(None);
+                } else {
+                    send_control(&app_handle, RuntimeControl::OverlayFailed(kind));
                 }
-            } else {
+                return;
+            }
+        };
+        if let (Some(x), Some(y)) = (monitor_geometry.x, monitor_geometry.y) {
+            if window
+                .set_position(Position::Physical(PhysicalPosition::new(x, y)))
+                .is_err()
+            {
                 let _ = window.center();
             }
-            let _ = window.set_focus();
+        } else {
+            let _ = window.center();
         }
+        let _ = window.set_focus();
+
+        window.on_window_event(move |event| {
+            if let WindowEvent::CloseRequested { api, .. } = event {
+                // Closing is a skip too, so it waits for the same rule.
+                if overlay::check_skip(unix_now()).is_err() {
+                    api.prevent_close();
+                } else if preview {
+                    overlay::set(None);
+                } else {
+                    send_control(&app_handle, RuntimeControl::OverlayClosed(kind));
+                }
+            }
+        });
     });
 
+    if preview {
+        return;
+    }
     emit_runtime_event(
         app,
-        RuntimeEventDto {
-            kind: "break_started".into(),
-            message: "Descanso iniciado".into(),
-            break_kind: Some(break_kind_to_string(kind)),
-            remaining_seconds: Some(remaining),
+        RuntimeEventDto::new(
+            RuntimeEvent::BreakStarted {
+                break_kind: kind,
+                duration_seconds: remaining,
+                strict: strict_mode,
+            },
+            started,
             strict_mode,
-        },
+        ),
     );
 }
 
@@ -540,14 +2157,24 @@ fn clamp_dimension(target: u32, min: u32, max: u32) -> u32 {
 }
 
 fn close_overlay(app: &AppHandle) {
+    overlay::set(None);
     let app_handle = app.clone();
     let _ = app.run_on_main_thread(move || {
         if let Some(window) = app_handle.get_webview_window("break-overlay") {
-            let _ = window.close();
+            let _ = window.destroy();
         }
     });
 }
 
+// For window callbacks, which have no command state to reach the runtime with.
+fn send_control(app: &AppHandle, control: RuntimeControl) {
+    if let Ok(runtime) = app.state::<BackendState>().runtime.lock()
+        && let Some(tx) = runtime.tx.as_ref()
+    {
+        let _ = tx.send(control);
+    }
+}
+
 fn resolve_autostart_exec() -> String {
     let in_flatpak = std::env::var("FLATPAK_ID")
         .map(|value| value == "io.lazaro.Lazaro")
@@ -569,7 +2196,33 @@ fn resolve_autostart_exec() -> String {
     }
 }
 
-fn ensure_xdg_autostart() -> Result<(), AppError> {
+/// Inside Flatpak `~/.config` is the sandbox's own, so autostart goes
+/// through the Background portal instead. The portal may ask the user; this
+/// waits for its answer and returns `AutostartDenied` if they refuse.
+async fn request_portal_autostart(enabled: bool) -> Result<(), AppError> {
+    let mut request = Background::request().auto_start(enabled);
+    if enabled {
+        request = request
+            .reason("Start Lázaro when you log in")
+            .command(["lazaro"]);
+    }
+    match request.send().await.and_then(|request| request.response()) {
+        Ok(background) if background.auto_start() == enabled => Ok(()),
+        Ok(_) | Err(ashpd::Error::Response(_)) => Err(AppError::AutostartDenied),
+        Err(error) => Err(AppError::ServiceUnavailable(format!(
+            "background portal: {error}"
+        ))),
+    }
+}
+
+fn in_flatpak() -> bool {
+    std::env::var("FLATPAK_ID").is_ok_and(|value| !value.is_empty())
+}
+
+async fn ensure_xdg_autostart() -> Result<(), AppError> {
+    if in_flatpak() {
+        return request_portal_autostart(true).await;
+    }
     let home = std::env::var("HOME").unwrap_or_else(|_| ".".into());
     let dir = Path::new(&home).join(".config/autostart");
     fs::create_dir_all(&dir)?;
@@ -599,7 +2252,10 @@ fn ensure_systemd_user_service() -> Result<(), AppError> {
     Ok(())
 }
 
-fn disable_xdg_autostart() -> Result<(), AppError> {
+async fn disable_xdg_autostart() -> Result<(), AppError> {
+    if in_flatpak() {
+        return request_portal_autostart(false).await;
+    }
     let home = std::env::var("HOME").unwrap_or_else(|_| ".".into());
     let file = Path::new(&home)
         .join(".config/autostart")
@@ -621,319 +2277,248 @@ fn disable_systemd_user_service() -> Result<(), AppError> {
     Ok(())
 }
 
-fn runtime_loop(
-    app: AppHandle,
-    persistent: Arc<AppState>,
-    status: Arc<Mutex<RuntimeStatusDto>>,
-    rx: mpsc::Receiver<RuntimeControl>,
-    mut core_settings: Settings,
-    mut settings_dto: SettingsDto,
-) {
-    let mut engine = TimerEngine::new(core_settings.clone(), unix_now());
-    let mut pending_break: Option<BreakKind> = None;
-    let mut running = true;
-    let mut tick_counter: u64 = 0;
-
-    if let Ok(mut guard) = status.lock() {
-        guard.running = true;
-        guard.strict_mode = matches!(core_settings.block_level, BlockLevel::Strict);
-        guard.last_event = "runtime_started".into();
-    }
-
-    while running {
-        while let Ok(message) = rx.try_recv() {
-            match message {
-                RuntimeControl::Stop => {
-                    running = false;
-                }
-                RuntimeControl::UpdateSettings { core, dto } => {
-                    core_settings = core;
-                    *engine.settings_mut() = core_settings.clone();
-                    settings_dto = dto;
-                    if let Ok(mut guard) = status.lock() {
-                        guard.strict_mode = matches!(core_settings.block_level, BlockLevel::Strict);
-                        guard.last_event = "settings_updated".into();
-                    }
-                }
-                RuntimeControl::StartBreak(kind) => {
-                    pending_break = None;
-                    let events = engine.start_break(kind);
-                    for event in events {
-                        if let EngineEvent::BreakStarted(kind) = event {
-                            let remaining = engine.active_break_info().map(|(_, r)| r).unwrap_or(0);
-                            open_overlay(
-                                &app,
-                                kind,
-                                remaining,
-                                settings_dto.overlay_notifications,
-                                matches!(core_settings.block_level, BlockLevel::Strict),
-                            );
-                            send_notification(
-                                &settings_dto,
-                                "Lázaro",
-                                &format!("Comienza el descanso {}", break_kind_to_string(kind)),
-                            );
-                        }
-                    }
-                }
-                RuntimeControl::StartPending => {
-                    if let Some(kind) = pending_break.take() {
-                        let events = engine.start_break(kind);
-                        for event in events {
-                            if let EngineEvent::BreakStarted(kind) = event {
-                                let remaining =
-                                    engine.active_break_info().map(|(_, r)| r).unwrap_or(0);
-                                open_overlay(
-                                    &app,
-                                    kind,
-                                    remaining,
-                                    settings_dto.overlay_notifications,
-                                    matches!(core_settings.block_level, BlockLevel::Strict),
-                                );
-                            }
-                        }
-                    }
-                }
-                RuntimeControl::SnoozePending => {
-                    if !matches!(core_settings.block_level, BlockLevel::Strict)
-                        && let Some(kind) = pending_break.take()
-                    {
-                        let _ = engine.snooze(kind, unix_now());
-                        persistent.record_skipped_break();
-                        emit_runtime_event(
-                            &app,
-                            RuntimeEventDto {
-                                kind: "break_snoozed".into(),
-                                message: format!(
-                                    "Se pospone descanso {}",
-                                    break_kind_to_string(kind)
-                                ),
-                                break_kind: Some(break_kind_to_string(kind)),
-                                remaining_seconds: None,
-                                strict_mode: false,
-                            },
-                        );
-                    }
-                }
-            }
-        }
+#[tauri::command]
+fn get_settings(state: tauri::State<'_, BackendState>) -> Result<SettingsDto, AppError> {
+    let guard = state.persistent.data.lock()?;
+    Ok(guard.effective.clone())
+}
 
-        if !running {
-            break;
-        }
+#[tauri::command]
+fn get_overlay_payload() -> Result<Option<overlay::OverlayPayloadDto>, AppError> {
+    overlay::current()
+}
 
-        let now = unix_now();
-        let events = if engine.active_break_info().is_some() {
-            engine.tick_break(1)
-        } else {
-            persistent.add_active_seconds(1);
-            engine.on_activity(1, now)
-        };
+#[tauri::command]
+fn get_overlay_config(state: tauri::State<'_, BackendState>) -> Result<OverlayConfigDto, AppError> {
+    let guard = state.persistent.data.lock()?;
+    Ok(OverlayConfigDto::from(&guard.effective))
+}
 
-        for event in events {
-            match event {
-                EngineEvent::BreakDue(kind) => {
-                    pending_break = Some(kind);
-                    let strict_mode = matches!(core_settings.block_level, BlockLevel::Strict);
-                    emit_runtime_event(
-                        &app,
-                        RuntimeEventDto {
-                            kind: "break_due".into(),
-                            message: format!("Descanso {} disponible", break_kind_to_string(kind)),
-                            break_kind: Some(break_kind_to_string(kind)),
-                            remaining_seconds: None,
-                            strict_mode,
-                        },
-                    );
-                    send_notification(
-                        &settings_dto,
-                        "Lázaro",
-                        &format!("Toca descanso {}", break_kind_to_string(kind)),
-                    );
-                }
-                EngineEvent::BreakStarted(kind) => {
-                    pending_break = None;
-                    let remaining = engine.active_break_info().map(|(_, r)| r).unwrap_or(0);
-                    open_overlay(
-                        &app,
-                        kind,
-                        remaining,
-                        settings_dto.overlay_notifications,
-                        matches!(core_settings.block_level, BlockLevel::Strict),
-                    );
-                    emit_runtime_event(
-                        &app,
-                        RuntimeEventDto {
-                            kind: "break_started".into(),
-                            message: format!("Descanso {} iniciado", break_kind_to_string(kind)),
-                            break_kind: Some(break_kind_to_string(kind)),
-                            remaining_seconds: Some(remaining),
-                            strict_mode: matches!(core_settings.block_level, BlockLevel::Strict),
-                        },
-                    );
-                }
-                EngineEvent::BreakCompleted(kind) => {
-                    persistent.record_completed_break(kind);
-                    close_overlay(&app);
-                    emit_runtime_event(
-                        &app,
-                        RuntimeEventDto {
-                            kind: "break_completed".into(),
-                            message: format!("Descanso {} completado", break_kind_to_string(kind)),
-                            break_kind: Some(break_kind_to_string(kind)),
-                            remaining_seconds: Some(0),
-                            strict_mode: matches!(core_settings.block_level, BlockLevel::Strict),
-                        },
-                    );
-                    send_notification(
-                        &settings_dto,
-                        "Lázaro",
-                        "Buen trabajo. Descanso completado.",
-                    );
-                    let _ = persistent.save();
-                }
-                EngineEvent::BreakSnoozed(kind, until) => {
-                    emit_runtime_event(
-                        &app,
-                        RuntimeEventDto {
-                            kind: "break_snoozed".into(),
-                            message: format!(
-                                "Descanso {} pospuesto hasta {}",
-                                break_kind_to_string(kind),
-                                until
-                            ),
-                            break_kind: Some(break_kind_to_string(kind)),
-                            remaining_seconds: None,
-                            strict_mode: false,
-                        },
-                    );
-                }
-                EngineEvent::DailyReset => {
-                    emit_runtime_event(
-                        &app,
-                        RuntimeEventDto {
-                            kind: "daily_reset".into(),
-                            message: "Reinicio diario aplicado".into(),
-                            break_kind: None,
-                            remaining_seconds: None,
-                            strict_mode: false,
-                        },
-                    );
-                }
-            }
-        }
+fn effective_settings(mut settings: SettingsDto) -> Result<(SettingsDto, Settings), AppError> {
+    config_file::apply_overrides(&mut settings);
+    let mut core = settings_to_core(&settings)?;
 
-        if let Some((kind, remaining)) = engine.active_break_info() {
-            emit_runtime_event(
-                &app,
-                RuntimeEventDto {
-                    kind: "break_tick".into(),
-                    message: "Cuenta regresiva activa".into(),
-                    break_kind: Some(break_kind_to_string(kind)),
-                    remaining_seconds: Some(remaining),
-                    strict_mode: matches!(core_settings.block_level, BlockLevel::Strict),
-                },
-            );
-        }
+    let (policy, _) = policy_file::load();
+    if !policy.is_empty() {
+        policy.enforce(&mut core);
+        settings = SettingsDto::from(core.clone());
+    }
+    Ok((settings, core))
+}
 
-        if let Ok(mut guard) = status.lock() {
-            let next_break = engine.next_break_eta(now);
-            guard.running = true;
-            guard.pending_break = pending_break.map(break_kind_to_string);
-            guard.active_break = engine
-                .active_break_info()
-                .map(|(kind, _)| break_kind_to_string(kind));
-            guard.remaining_seconds = engine.active_break_info().map(|(_, remaining)| remaining);
-            guard.next_break_kind = next_break.map(|(kind, _)| break_kind_to_string(kind));
-            guard.next_break_seconds = next_break.map(|(_, remaining)| remaining);
-            guard.strict_mode = matches!(core_settings.block_level, BlockLevel::Strict);
-            guard.last_event = "tick".into();
-        }
+fn commit_settings(state: &BackendState, settings: SettingsDto) -> Result<SettingsDto, AppError> {
+    commit_settings_with(state, settings, true)
+}
 
-        tick_counter = tick_counter.saturating_add(1);
-        if tick_counter.is_multiple_of(20) {
-            let _ = persistent.save();
-        }
+/// Like `commit_settings`; `record_undo` is off when the caller records
+/// its own undo step or is undoing one.
+fn commit_settings_with(
+    state: &BackendState,
+    settings: SettingsDto,
+    record_undo: bool,
+) -> Result<SettingsDto, AppError> {
+    let (settings, updated_at) = {
+        let guard = state.persistent.data.lock()?;
+        let settings = without_overrides(settings, &guard.settings)?;
+        // Only changes that sync to other devices move the stamp; otherwise
+        // configuring sync on a new machine would make its defaults win.
+        let updated_at = if sync::same_shared_settings(&guard.settings, &settings) {
+            guard.sync.settings_updated_at
+        } else {
+            unix_now()
+        };
+        (settings, updated_at)
+    };
+    store_settings(state, settings, updated_at, record_undo)
+}
 
-        thread::sleep(Duration::from_secs(1));
+/// `requested` with every overridden field set back to its saved value.
+/// Callers start from the effective settings, and the values config.toml,
+/// `LAZARO_*` variables or command-line flags put there must not be saved.
+fn without_overrides(requested: SettingsDto, saved: &SettingsDto) -> Result<SettingsDto, AppError> {
+    let overridden = config_file::overridden_keys();
+    if overridden.is_empty() {
+        return Ok(requested);
     }
-
-    close_overlay(&app);
-    let _ = persistent.save();
-
-    if let Ok(mut guard) = status.lock() {
-        guard.running = false;
-        guard.pending_break = None;
-        guard.active_break = None;
-        guard.remaining_seconds = None;
-        guard.next_break_kind = None;
-        guard.next_break_seconds = None;
-        guard.last_event = "runtime_stopped".into();
+    let saved = settings_to_core(saved)?;
+    let mut core = settings_to_core(&requested)?;
+    for key in &overridden {
+        if let Some(value) = config::setting_value(&saved, key)
+            && let Err(error) = config::apply_setting(&mut core, key, &value)
+        {
+            tracing::warn!(%error, "cannot restore saved value");
+        }
     }
+    Ok(SettingsDto::from(core))
 }
 
-#[tauri::command]
-fn get_settings(state: tauri::State<'_, BackendState>) -> Result<SettingsDto, AppError> {
-    let guard = state
-        .persistent
-        .data
-        .lock()
-        .map_err(|e| AppError::Io(format!("mutex poisoned: {e}")))?;
-    Ok(guard.settings.clone())
+/// Applies settings stamped with `updated_at`, which sync uses to decide
+/// which device wrote them last.
+fn commit_settings_at(
+    state: &BackendState,
+    settings: SettingsDto,
+    updated_at: u64,
+) -> Result<SettingsDto, AppError> {
+    store_settings(state, settings, updated_at, false)
 }
 
-#[tauri::command]
-fn update_settings(
+fn store_settings(
+    state: &BackendState,
     settings: SettingsDto,
-    state: tauri::State<'_, BackendState>,
+    updated_at: u64,
+    record_undo: bool,
 ) -> Result<SettingsDto, AppError> {
+    let (effective, core) = effective_settings(settings.clone())?;
+    if effective.encrypt_state {
+        vault::ensure_key().map_err(AppError::Io)?;
+    }
+
+    let changes = {
+        let mut guard = state.persistent.data.lock()?;
+        let changes = settings_to_core(&guard.effective)
+            .map(|previous| previous.diff(&core))
+            .unwrap_or_default();
+        if record_undo {
+            let changed = undo::changed_fields(&guard.settings, &settings);
+            if !changed.is_empty() {
+                let previous = guard.settings.clone();
+                undo::push(
+                    &mut guard.undo_history,
+                    unix_now(),
+                    undo::UndoChange::Settings { previous, changed },
+                );
+            }
+        }
+        guard.settings = settings;
+        guard.effective = effective.clone();
+        guard.sync.settings_updated_at = updated_at;
+        changes
+    };
+    state.persistent.save(storage::Sections::DOCUMENT)?;
+
+    // Profile switches, undo and sync can change the theme too, not only
+    // `update_theme`.
+    if changes
+        .iter()
+        .any(|change| change.key.starts_with("theme_"))
+        && let Some(app) = state.app.get()
     {
-        let mut guard = state
-            .persistent
-            .data
-            .lock()
-            .map_err(|e| AppError::Io(format!("mutex poisoned: {e}")))?;
-        guard.settings = settings.clone();
+        let _ = app.emit("theme_changed", theme::resolve(&effective));
+    }
+    if !changes.is_empty() {
+        let event = SettingsChangedDto {
+            changes: changes.into_iter().map(SettingChangeDto::from).collect(),
+        };
+        remote::publish("settings_changed", &event);
+        if let Some(app) = state.app.get() {
+            let _ = app.emit("settings_changed", event);
+        }
     }
-    state.persistent.save()?;
 
-    let core = settings_to_core(&settings)?;
     if let Ok(runtime) = state.runtime.lock()
         && let Some(tx) = runtime.tx.clone()
     {
         let _ = tx.send(RuntimeControl::UpdateSettings {
-            core,
-            dto: settings.clone(),
+            core: Box::new(core),
+            dto: Box::new(effective.clone()),
         });
     }
 
-    Ok(settings)
+    Ok(effective)
+}
+
+#[tauri::command]
+fn update_settings(
+    settings: SettingsDto,
+    state: tauri::State<'_, BackendState>,
+) -> Result<SettingsDto, AppError> {
+    let (policy, _) = policy_file::load();
+    let violations = policy.violations(&settings_to_core(&settings)?);
+    if !violations.is_empty() {
+        return Err(AppError::PolicyLocked(violations.join(", ")));
+    }
+    commit_settings(&state, settings)
+}
+
+#[tauri::command]
+fn get_theme(state: tauri::State<'_, BackendState>) -> Result<theme::ResolvedThemeDto, AppError> {
+    let guard = state.persistent.data.lock()?;
+    Ok(theme::resolve(&guard.effective))
+}
+
+#[tauri::command]
+fn update_theme(
+    theme: theme::ThemeDto,
+    state: tauri::State<'_, BackendState>,
+) -> Result<theme::ResolvedThemeDto, AppError> {
+    let theme = theme.validate()?;
+    let mut settings = {
+        let guard = state.persistent.data.lock()?;
+        guard.effective.clone()
+    };
+    theme.apply(&mut settings);
+
+    let settings = update_settings(settings, state)?;
+    Ok(theme::resolve(&settings))
+}
+
+#[tauri::command]
+fn get_policy_info() -> policy_file::PolicyInfoDto {
+    policy_file::info()
+}
+
+#[tauri::command]
+fn get_config_file_info() -> config_file::ConfigFileInfoDto {
+    config_file::info()
+}
+
+#[tauri::command]
+fn list_languages() -> Vec<LanguageDto> {
+    i18n::Language::ALL
+        .iter()
+        .map(|lang| LanguageDto {
+            code: lang.code().into(),
+            name: lang.native_name().into(),
+        })
+        .collect()
+}
+
+#[tauri::command]
+fn set_language(
+    language: String,
+    state: tauri::State<'_, BackendState>,
+) -> Result<SettingsDto, AppError> {
+    if !i18n::is_supported(&language) {
+        return Err(AppError::UnsupportedLanguage(language));
+    }
+
+    let mut settings = {
+        let guard = state.persistent.data.lock()?;
+        guard.effective.clone()
+    };
+    settings.language = language;
+    commit_settings(&state, settings)
 }
 
 #[tauri::command]
 fn list_profiles(state: tauri::State<'_, BackendState>) -> Result<Vec<ProfileDto>, AppError> {
-    let guard = state
-        .persistent
-        .data
-        .lock()
-        .map_err(|e| AppError::Io(format!("mutex poisoned: {e}")))?;
+    let guard = state.persistent.data.lock()?;
     Ok(guard.profiles.values().cloned().collect())
 }
 
 #[tauri::command]
 fn save_profile(
-    profile: ProfileDto,
+    mut profile: ProfileDto,
     state: tauri::State<'_, BackendState>,
 ) -> Result<ProfileDto, AppError> {
     {
-        let mut guard = state
-            .persistent
-            .data
-            .lock()
-            .map_err(|e| AppError::Io(format!("mutex poisoned: {e}")))?;
+        let mut guard = state.persistent.data.lock()?;
+        // Profiles are saved from the form, which shows the overrides too.
+        profile.settings = without_overrides(profile.settings, &guard.settings)?;
         guard.profiles.insert(profile.id.clone(), profile.clone());
+        guard.sync.profiles_updated_at = unix_now();
     }
-    state.persistent.save()?;
+    state.persistent.save(storage::Sections::DOCUMENT)?;
     Ok(profile)
 }
 
@@ -942,31 +2527,20 @@ fn activate_profile(
     profile_id: String,
     state: tauri::State<'_, BackendState>,
 ) -> Result<(), AppError> {
-    let updated_settings = {
-        let mut guard = state
-            .persistent
-            .data
-            .lock()
-            .map_err(|e| AppError::Io(format!("mutex poisoned: {e}")))?;
+    let mut updated_settings = {
+        let guard = state.persistent.data.lock()?;
         let Some(profile) = guard.profiles.get(&profile_id).cloned() else {
             return Err(AppError::ProfileNotFound(profile_id));
         };
-
-        guard.settings = profile.settings;
-        guard.settings.active_profile_id = profile_id;
-        guard.settings.clone()
+        let mut settings = profile.settings;
+        // Global limits stay as they were, so a laxer profile cannot lift them.
+        if guard.effective.limit_scope != "profile" {
+            settings.inherit_limits(&guard.settings);
+        }
+        settings
     };
-    state.persistent.save()?;
-
-    let core = settings_to_core(&updated_settings)?;
-    if let Ok(runtime) = state.runtime.lock()
-        && let Some(tx) = runtime.tx.clone()
-    {
-        let _ = tx.send(RuntimeControl::UpdateSettings {
-            core,
-            dto: updated_settings,
-        });
-    }
+    updated_settings.active_profile_id = profile_id;
+    commit_settings(&state, updated_settings)?;
 
     Ok(())
 }
@@ -981,210 +2555,776 @@ fn remove_profile(
     }
 
     let updated_settings = {
-        let mut guard = state
-            .persistent
-            .data
-            .lock()
-            .map_err(|e| AppError::Io(format!("mutex poisoned: {e}")))?;
+        let mut guard = state.persistent.data.lock()?;
 
-        let removed = guard.profiles.remove(&profile_id);
-        if removed.is_none() {
+        let Some(removed) = guard.profiles.remove(&profile_id) else {
             return Err(AppError::ProfileNotFound(profile_id));
-        }
+        };
+        guard.sync.profiles_updated_at = unix_now();
 
-        if guard.settings.active_profile_id == profile_id {
+        let updated_settings = if guard.settings.active_profile_id == profile_id {
             let fallback = guard
                 .profiles
                 .get("default")
                 .cloned()
                 .or_else(|| guard.profiles.values().next().cloned());
 
-            if let Some(profile) = fallback {
-                guard.settings = profile.settings;
-                guard.settings.active_profile_id = profile.id;
-                Some(guard.settings.clone())
-            } else {
-                None
-            }
+            fallback.map(|profile| {
+                let mut settings = profile.settings;
+                settings.active_profile_id = profile.id;
+                settings
+            })
         } else {
             None
-        }
+        };
+        let previous_settings = updated_settings
+            .as_ref()
+            .map(|_| Box::new(guard.settings.clone()));
+        undo::push(
+            &mut guard.undo_history,
+            unix_now(),
+            undo::UndoChange::ProfileRemoved {
+                profile: removed,
+                previous_settings,
+            },
+        );
+        updated_settings
     };
 
-    state.persistent.save()?;
+    state.persistent.save(storage::Sections::DOCUMENT)?;
 
     if let Some(settings) = updated_settings {
-        let core = settings_to_core(&settings)?;
-        if let Ok(runtime) = state.runtime.lock()
-            && let Some(tx) = runtime.tx.clone()
-        {
-            let _ = tx.send(RuntimeControl::UpdateSettings {
-                core,
-                dto: settings,
-            });
-        }
+        commit_settings_with(&state, settings, false)?;
     }
 
     Ok(())
 }
 
+/// Newest first.
 #[tauri::command]
-fn get_weekly_stats(state: tauri::State<'_, BackendState>) -> Result<WeeklyStatsDto, AppError> {
-    let guard = state
-        .persistent
-        .data
-        .lock()
-        .map_err(|e| AppError::Io(format!("mutex poisoned: {e}")))?;
-    Ok(guard.weekly_stats.clone())
+fn get_change_history(
+    state: tauri::State<'_, BackendState>,
+) -> Result<Vec<undo::ChangeSummaryDto>, AppError> {
+    let guard = state.persistent.data.lock()?;
+    Ok(guard
+        .undo_history
+        .iter()
+        .rev()
+        .map(undo::ChangeSummaryDto::from)
+        .collect())
 }
 
+/// Reverts the newest recorded change and returns the settings in effect
+/// afterwards. Undoing is not itself recorded.
 #[tauri::command]
-fn set_startup_mode(
-    mode: StartupMode,
-    state: tauri::State<'_, BackendState>,
-) -> Result<(), AppError> {
-    match mode {
-        StartupMode::Disabled => {
-            disable_xdg_autostart()?;
-            disable_systemd_user_service()?;
+fn undo_last_change(state: tauri::State<'_, BackendState>) -> Result<SettingsDto, AppError> {
+    let (entry, current) = {
+        let mut guard = state.persistent.data.lock()?;
+        let entry = guard
+            .undo_history
+            .pop_back()
+            .ok_or(AppError::NothingToUndo)?;
+        if let undo::UndoChange::ProfileRemoved { profile, .. } = &entry.change {
+            guard.profiles.insert(profile.id.clone(), profile.clone());
+            guard.sync.profiles_updated_at = unix_now();
         }
-        StartupMode::XdgOnly => {
-            ensure_xdg_autostart()?;
-            disable_systemd_user_service()?;
+        (entry, guard.settings.clone())
+    };
+    tracing::info!(at = entry.at, "undoing change");
+
+    let restored = match entry.change {
+        undo::UndoChange::Settings { previous, .. } => Some(previous),
+        undo::UndoChange::ProfileRemoved {
+            previous_settings, ..
+        } => previous_settings.map(|settings| *settings),
+    };
+    match restored {
+        Some(settings) => commit_settings_with(&state, settings, false),
+        None => {
+            state.persistent.save(storage::Sections::DOCUMENT)?;
+            Ok(current)
         }
-        StartupMode::XdgAndSystemd => {
-            ensure_xdg_autostart()?;
-            ensure_systemd_user_service()?;
+    }
+}
+
+#[tauri::command]
+fn get_weekly_stats(state: tauri::State<'_, BackendState>) -> Result<WeeklyStatsDto, AppError> {
+    let guard = state.persistent.data.lock()?;
+    Ok(guard.weekly_stats.clone())
+}
+
+#[tauri::command]
+fn get_daily_summary(
+    state: tauri::State<'_, BackendState>,
+    day: Option<String>,
+) -> Result<summary::DailySummaryDto, AppError> {
+    let day_index = history::parse_day_index(day.as_deref())
+        .ok_or_else(|| AppError::InvalidDate(day.clone().unwrap_or_default()))?;
+    let (lang, settings) = {
+        let guard = state.persistent.data.lock()?;
+        (
+            i18n::resolve(&guard.effective.language),
+            settings_to_core(&guard.effective)?,
+        )
+    };
+    let stats = state.persistent.analytics_store()?.day(day_index);
+    Ok(summary::build(
+        i18n::Messages::new(lang, &settings),
+        day_index,
+        &stats,
+    ))
+}
+
+#[tauri::command]
+fn get_upcoming_busy_windows(
+    state: tauri::State<'_, BackendState>,
+    hours: Option<u64>,
+) -> Result<Vec<calendar::BusyWindowDto>, AppError> {
+    let settings = {
+        let guard = state.persistent.data.lock()?;
+        guard.effective.clone()
+    };
+    if !settings.calendar_enabled {
+        return Ok(Vec::new());
+    }
+
+    let horizon = hours
+        .unwrap_or(calendar::DEFAULT_HORIZON_HOURS)
+        .saturating_mul(3_600);
+    let windows = calendar::load_busy_windows(&settings.calendar_source_path);
+    Ok(
+        lazaro_core::calendar::upcoming(&windows, unix_now(), horizon)
+            .into_iter()
+            .map(calendar::BusyWindowDto::from)
+            .collect(),
+    )
+}
+
+#[tauri::command]
+fn get_sync_status() -> Result<sync::SyncStatusDto, AppError> {
+    sync::status()
+}
+
+#[tauri::command]
+async fn check_for_updates(app: AppHandle) -> Result<update::UpdateStatusDto, AppError> {
+    tauri::async_runtime::spawn_blocking(move || update::check(&app))
+        .await
+        .map_err(|e| AppError::TaskFailed(e.to_string()))?
+}
+
+#[tauri::command]
+fn get_update_status() -> Result<update::UpdateStatusDto, AppError> {
+    update::status()
+}
+
+#[tauri::command]
+fn get_notification_status() -> Result<notify::NotificationStatusDto, AppError> {
+    notify::status()
+}
+
+#[tauri::command]
+async fn detect_notification_backend() -> Result<notify::NotificationStatusDto, AppError> {
+    tauri::async_runtime::spawn_blocking(notify::reprobe)
+        .await
+        .map_err(|e| AppError::TaskFailed(e.to_string()))?
+}
+
+#[tauri::command]
+fn get_platform_capabilities() -> platform::PlatformCapabilitiesDto {
+    platform::probe()
+}
+
+#[tauri::command]
+fn get_peer_status() -> Result<Vec<peers::PeerDto>, AppError> {
+    peers::status()
+}
+
+#[tauri::command]
+fn get_weekly_goal_progress(
+    state: tauri::State<'_, BackendState>,
+) -> Result<WeeklyGoalProgressDto, AppError> {
+    let settings = {
+        let guard = state.persistent.data.lock()?;
+        settings_to_core(&guard.effective)?
+    };
+    let store = state.persistent.analytics_store()?;
+    let today = history::local_day_index();
+    let progress = store.weekly_goal_progress(today, &settings.analytics);
+    let average_rating = store
+        .summarize_calendar_week(today, settings.analytics.week_start)
+        .average_rating();
+
+    Ok(WeeklyGoalProgressDto {
+        week_start: history::day_index_to_date(progress.week_start_day),
+        active_seconds: progress.active_seconds,
+        max_active_seconds: progress.max_active_seconds,
+        active_percent: progress.active_percent,
+        active_goal_met: progress.active_goal_met,
+        breaks_taken: progress.breaks_taken,
+        min_breaks: progress.min_breaks,
+        breaks_percent: progress.breaks_percent,
+        breaks_goal_met: progress.breaks_goal_met,
+        average_rating,
+    })
+}
+
+#[tauri::command]
+fn get_dashboard_data(
+    state: tauri::State<'_, BackendState>,
+) -> Result<dashboard::DashboardDto, AppError> {
+    let (day_start, _) =
+        history::local_day_range(None).ok_or_else(|| AppError::InvalidDate("today".into()))?;
+    let store = state.persistent.analytics_store()?;
+    let (breaks, focus) = {
+        let guard = state.persistent.data.lock()?;
+        (
+            history::to_history(&guard.break_history),
+            history::to_focus_log(&guard.focus_sessions),
+        )
+    };
+    let status = get_runtime_status(state)?;
+    Ok(dashboard::build(
+        &store,
+        &breaks,
+        &focus,
+        history::local_day_index(),
+        day_start,
+        status,
+    ))
+}
+
+#[tauri::command]
+fn get_break_history(
+    day: Option<String>,
+    state: tauri::State<'_, BackendState>,
+) -> Result<Vec<history::BreakSessionDto>, AppError> {
+    let (start, end) = history::local_day_range(day.as_deref())
+        .ok_or_else(|| AppError::InvalidDate(day.unwrap_or_default()))?;
+    let guard = state.persistent.data.lock()?;
+    let history = history::to_history(&guard.break_history);
+    Ok(history
+        .between(start, end)
+        .into_iter()
+        .map(history::BreakSessionDto::from)
+        .collect())
+}
+
+/// Rates the latest completed break of `kind`; each break is rated once.
+#[tauri::command]
+fn submit_break_feedback(
+    kind: BreakKind,
+    rating: u8,
+    state: tauri::State<'_, BackendState>,
+) -> Result<(), AppError> {
+    if !(MIN_BREAK_RATING..=MAX_BREAK_RATING).contains(&rating) {
+        return Err(AppError::InvalidRating(rating));
+    }
+    let mut ended_at = None;
+    state.persistent.update_history(|history| {
+        ended_at = history.record_rating(kind, rating);
+    });
+    let ended_at = ended_at.ok_or_else(|| AppError::NothingToRate(kind.id()))?;
+    state
+        .persistent
+        .record_break_rating(history::local_day_index_at(ended_at), rating);
+    Ok(())
+}
+
+#[tauri::command]
+fn get_focus_sessions(
+    day: Option<String>,
+    state: tauri::State<'_, BackendState>,
+) -> Result<history::FocusReportDto, AppError> {
+    let (start, end) = history::local_day_range(day.as_deref())
+        .ok_or_else(|| AppError::InvalidDate(day.unwrap_or_default()))?;
+    let guard = state.persistent.data.lock()?;
+    let log = history::to_focus_log(&guard.focus_sessions);
+    Ok(history::FocusReportDto::new(
+        log.between(start, end),
+        log.summarize(start, end),
+    ))
+}
+
+#[tauri::command]
+fn get_app_usage(
+    day: Option<String>,
+    state: tauri::State<'_, BackendState>,
+) -> Result<history::AppUsageReportDto, AppError> {
+    let day_index = history::parse_day_index(day.as_deref())
+        .ok_or_else(|| AppError::InvalidDate(day.unwrap_or_default()))?;
+    let guard = state.persistent.data.lock()?;
+    let settings = settings_to_core(&guard.effective)?;
+    Ok(history::AppUsageReportDto::new(
+        &history::to_app_usage_store(&guard.app_usage),
+        day_index,
+        &settings.analytics,
+    ))
+}
+
+/// Work, comms and entertainment time over the week containing `day`.
+#[tauri::command]
+fn get_category_breakdown(
+    day: Option<String>,
+    state: tauri::State<'_, BackendState>,
+) -> Result<history::CategoryBreakdownDto, AppError> {
+    let day_index = history::parse_day_index(day.as_deref())
+        .ok_or_else(|| AppError::InvalidDate(day.unwrap_or_default()))?;
+    let guard = state.persistent.data.lock()?;
+    let settings = settings_to_core(&guard.effective)?;
+    Ok(history::CategoryBreakdownDto::new(
+        &history::to_app_usage_store(&guard.app_usage),
+        day_index,
+        &settings.analytics,
+    ))
+}
+
+#[tauri::command]
+async fn set_startup_mode(
+    mode: StartupMode,
+    state: tauri::State<'_, BackendState>,
+) -> Result<(), AppError> {
+    let (xdg, systemd) = match mode {
+        StartupMode::Disabled => (false, false),
+        StartupMode::XdgOnly => (true, false),
+        StartupMode::XdgAndSystemd => (true, true),
+    };
+    apply_startup(&state, xdg, systemd).await
+}
+
+/// Installs the startup entries and saves the choice.
+async fn apply_startup(state: &BackendState, xdg: bool, systemd: bool) -> Result<(), AppError> {
+    match install_startup(xdg, systemd).await {
+        Ok(()) => record_startup(state, xdg, systemd),
+        // Saved off, so the form stops showing autostart as on.
+        Err(AppError::AutostartDenied) => {
+            record_startup(state, false, systemd)?;
+            Err(AppError::AutostartDenied)
         }
+        Err(error) => Err(error),
     }
+}
 
+fn record_startup(state: &BackendState, xdg: bool, systemd: bool) -> Result<(), AppError> {
     {
-        let mut guard = state
-            .persistent
-            .data
-            .lock()
-            .map_err(|e| AppError::Io(format!("mutex poisoned: {e}")))?;
-        match mode {
-            StartupMode::Disabled => {
-                guard.settings.startup_xdg = false;
-                guard.settings.startup_systemd_user = false;
-            }
-            StartupMode::XdgOnly => {
-                guard.settings.startup_xdg = true;
-                guard.settings.startup_systemd_user = false;
-            }
-            StartupMode::XdgAndSystemd => {
-                guard.settings.startup_xdg = true;
-                guard.settings.startup_systemd_user = true;
-            }
-        }
+        let mut guard = state.persistent.data.lock()?;
+        guard.settings.startup_xdg = xdg;
+        guard.settings.startup_systemd_user = systemd;
+        guard.resolve_effective();
+    }
+    state.persistent.save(storage::Sections::DOCUMENT)
+}
+
+async fn install_startup(xdg: bool, systemd: bool) -> Result<(), AppError> {
+    if xdg {
+        ensure_xdg_autostart().await?;
+    } else {
+        disable_xdg_autostart().await?;
+    }
+    if systemd {
+        ensure_systemd_user_service()?;
+    } else {
+        disable_systemd_user_service()?;
     }
-    state.persistent.save()?;
     Ok(())
 }
 
+#[tauri::command]
+fn get_onboarding_state(
+    state: tauri::State<'_, BackendState>,
+) -> Result<onboarding::OnboardingStateDto, AppError> {
+    let meta = state.persistent.data.lock()?.onboarding.clone();
+    Ok(onboarding::state(&meta))
+}
+
+/// Saves the settings chosen in the wizard, if any, and marks onboarding done.
+#[tauri::command]
+async fn complete_onboarding(
+    settings: Option<SettingsDto>,
+    state: tauri::State<'_, BackendState>,
+) -> Result<SettingsDto, AppError> {
+    let settings = match settings {
+        Some(settings) => {
+            let startup = (settings.startup_xdg, settings.startup_systemd_user);
+            update_settings(settings, state.clone())?;
+            apply_startup(&state, startup.0, startup.1).await?;
+            state.persistent.data.lock()?.effective.clone()
+        }
+        None => state.persistent.data.lock()?.effective.clone(),
+    };
+
+    state.persistent.data.lock()?.onboarding.completed_at = Some(unix_now());
+    state.persistent.save(storage::Sections::DOCUMENT)?;
+    Ok(settings)
+}
+
 fn start_runtime_internal(
     app: AppHandle,
     state: &BackendState,
-) -> Result<RuntimeStatusDto, AppError> {
+) -> Result<RuntimeStartDto, AppError> {
     let settings = {
-        let guard = state
-            .persistent
-            .data
-            .lock()
-            .map_err(|e| AppError::Io(format!("mutex poisoned: {e}")))?;
-        guard.settings.clone()
+        let guard = state.persistent.data.lock()?;
+        guard.effective.clone()
     };
     let core = settings_to_core(&settings)?;
 
-    let mut runtime = state
-        .runtime
-        .lock()
-        .map_err(|e| AppError::Io(format!("mutex poisoned: {e}")))?;
+    let mut runtime = state.runtime.lock()?;
 
-    if runtime.tx.is_none() {
-        let (tx, rx) = mpsc::channel::<RuntimeControl>();
-        let status = Arc::clone(&runtime.status);
+    if runtime.tx.as_ref().is_some_and(|tx| tx.is_closed()) {
+        runtime.tx = None;
+        runtime.handle = None;
+    }
+
+    let reused = runtime.tx.is_some();
+    if !reused {
+        let (tx, rx) = mpsc::unbounded_channel::<RuntimeControl>();
+        let status = runtime.status.clone();
         let persistent = Arc::clone(&state.persistent);
         let app_handle = app.clone();
+        runtime.generation += 1;
 
-        let join = thread::spawn(move || {
-            runtime_loop(app_handle, persistent, status, rx, core, settings);
-        });
+        let join = tauri::async_runtime::spawn(runtime::supervise(
+            app_handle,
+            persistent,
+            status,
+            rx,
+            core,
+            settings,
+            runtime.generation,
+        ));
 
         runtime.tx = Some(tx);
         runtime.handle = Some(join);
     }
 
-    let status = runtime
-        .status
-        .lock()
-        .map_err(|e| AppError::Io(format!("mutex poisoned: {e}")))?
-        .clone();
-    Ok(status)
+    let status = runtime.status.borrow().clone();
+    Ok(RuntimeStartDto { reused, status })
 }
 
+/// How long a running loop gets to answer a ping before it counts as wedged.
+const PING_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// Whether a runtime is running and its loop still answers; `None` when
+/// none is running.
+async fn runtime_responsive(state: &BackendState) -> Result<Option<bool>, AppError> {
+    let Some(tx) = state.runtime.lock()?.tx.clone() else {
+        return Ok(None);
+    };
+    let (reply, answered) = tokio::sync::oneshot::channel();
+    if tx.send(RuntimeControl::Ping(reply)).is_err() {
+        return Ok(Some(false));
+    }
+    Ok(Some(matches!(
+        tokio::time::timeout(PING_TIMEOUT, answered).await,
+        Ok(Ok(()))
+    )))
+}
+
+/// Forgets a runtime whose loop stopped answering. The next start bumps the
+/// generation, so should it ever wake up it leaves the new one alone.
+fn abandon_runtime(state: &BackendState) -> Result<(), AppError> {
+    let mut runtime = state.runtime.lock()?;
+    runtime.tx = None;
+    if let Some(handle) = runtime.handle.take() {
+        handle.abort();
+    }
+    runtime.status.send_replace(RuntimeStatusDto::default());
+    tracing::warn!("abandoned an unresponsive runtime");
+    Ok(())
+}
+
+/// Without `confirmation` this only issues a short-lived token; the reset
+/// happens when the same scope is requested again with that token.
 #[tauri::command]
-fn start_runtime(
+async fn reset_all_data(
     app: AppHandle,
     state: tauri::State<'_, BackendState>,
-) -> Result<RuntimeStatusDto, AppError> {
+    scope: String,
+    confirmation: Option<String>,
+) -> Result<reset::ResetResponseDto, AppError> {
+    let parsed = reset::ResetScope::parse(&scope).ok_or(AppError::InvalidResetScope(scope))?;
+    let Some(confirmation) = confirmation else {
+        return reset::challenge(parsed);
+    };
+    reset::confirm(parsed, &confirmation)?;
+
+    let was_running = shutdown_runtime(&state).await?;
+    let removed_files = reset::apply(&state.persistent, parsed, &default_data_dir())?;
+    if was_running {
+        start_runtime_internal(app, &state)?;
+    }
+
+    Ok(reset::ResetResponseDto::Done {
+        removed_files,
+        runtime_restarted: was_running,
+    })
+}
+
+/// Starts the runtime unless a responsive one is already running; a wedged
+/// one is replaced.
+#[tauri::command]
+async fn start_runtime(
+    app: AppHandle,
+    state: tauri::State<'_, BackendState>,
+) -> Result<RuntimeStartDto, AppError> {
+    if runtime_responsive(&state).await? == Some(false) {
+        abandon_runtime(&state)?;
+    }
     start_runtime_internal(app, &state)
 }
 
+/// Stops the runtime, or abandons it if it no longer answers, and starts a
+/// fresh one from the stored snapshot and settings.
 #[tauri::command]
-fn stop_runtime(state: tauri::State<'_, BackendState>) -> Result<RuntimeStatusDto, AppError> {
-    let handle = {
-        let mut runtime = state
-            .runtime
-            .lock()
-            .map_err(|e| AppError::Io(format!("mutex poisoned: {e}")))?;
+async fn restart_runtime(
+    app: AppHandle,
+    state: tauri::State<'_, BackendState>,
+) -> Result<RuntimeStatusDto, AppError> {
+    match runtime_responsive(&state).await? {
+        Some(true) => {
+            shutdown_runtime(&state).await?;
+        }
+        Some(false) => abandon_runtime(&state)?,
+        None => {}
+    }
+    Ok(start_runtime_internal(app, &state)?.status)
+}
 
-        let Some(tx) = runtime.tx.take() else {
-            return Err(AppError::RuntimeNotRunning);
-        };
+/// Pings the runtime loop; `false` when it is not running or is wedged.
+#[tauri::command]
+async fn check_runtime_health(state: tauri::State<'_, BackendState>) -> Result<bool, AppError> {
+    Ok(runtime_responsive(&state).await? == Some(true))
+}
 
-        let _ = tx.send(RuntimeControl::Stop);
-        runtime.handle.take()
+async fn shutdown_runtime(state: &BackendState) -> Result<bool, AppError> {
+    let (tx, handle) = {
+        let mut runtime = state.runtime.lock()?;
+        (runtime.tx.take(), runtime.handle.take())
     };
 
+    let Some(tx) = tx else {
+        return Ok(false);
+    };
+
+    let _ = tx.send(RuntimeControl::Stop);
     if let Some(join) = handle {
-        let _ = join.join();
+        let _ = join.await;
+    }
+    Ok(true)
+}
+
+#[tauri::command]
+async fn stop_runtime(state: tauri::State<'_, BackendState>) -> Result<RuntimeStatusDto, AppError> {
+    if !shutdown_runtime(&state).await? {
+        return Err(AppError::RuntimeNotRunning);
     }
 
-    let runtime = state
-        .runtime
-        .lock()
-        .map_err(|e| AppError::Io(format!("mutex poisoned: {e}")))?;
-    let status = runtime
-        .status
-        .lock()
-        .map_err(|e| AppError::Io(format!("mutex poisoned: {e}")))?
-        .clone();
+    let runtime = state.runtime.lock()?;
+    let status = runtime.status.borrow().clone();
 
     Ok(status)
 }
 
+#[tauri::command]
+fn get_recent_logs(limit: Option<usize>) -> Vec<String> {
+    logging::recent_lines(&default_data_dir(), limit.unwrap_or(200).min(2000))
+}
+
+#[tauri::command]
+fn take_crash_report() -> Option<crash::CrashReportDto> {
+    crash::take_report(&default_data_dir())
+}
+
+#[tauri::command]
+fn export_diagnostics(
+    path: Option<String>,
+    state: tauri::State<'_, BackendState>,
+) -> Result<String, AppError> {
+    let target = match path.filter(|value| !value.trim().is_empty()) {
+        Some(value) => PathBuf::from(value),
+        None => {
+            let home = std::env::var("HOME").unwrap_or_else(|_| ".".to_string());
+            Path::new(&home).join(format!("lazaro-diagnostics-{}.zip", unix_now()))
+        }
+    };
+
+    let settings = {
+        let guard = state.persistent.data.lock()?;
+        guard.effective.clone()
+    };
+    let status = {
+        let runtime = state.runtime.lock()?;
+        runtime.status.borrow().clone()
+    };
+
+    let mut settings_value = serde_json::to_value(&settings)?;
+    diagnostics::redact_value(&mut settings_value);
+    let manifest = serde_json::json!({
+        "app_version": env!("CARGO_PKG_VERSION"),
+        "tauri_version": tauri::VERSION,
+        "exported_at": unix_now(),
+    });
+    let logs = logging::recent_lines(&default_data_dir(), 2000).join("\n");
+    let pretty =
+        |value: &serde_json::Value| serde_json::to_string_pretty(value).unwrap_or_default();
+
+    diagnostics::write_bundle(
+        &target,
+        &[
+            ("manifest.json", pretty(&manifest)),
+            ("settings.json", pretty(&settings_value)),
+            (
+                "runtime_status.json",
+                serde_json::to_string_pretty(&status).unwrap_or_default(),
+            ),
+            (
+                "environment.json",
+                pretty(&diagnostics::environment_details()),
+            ),
+            ("logs.txt", diagnostics::redact_home(&logs)),
+        ],
+    )
+    .map_err(AppError::Io)?;
+
+    tracing::info!(path = %target.display(), "diagnostics bundle exported");
+    Ok(target.display().to_string())
+}
+
+#[tauri::command]
+fn create_backup(
+    path: Option<String>,
+    state: tauri::State<'_, BackendState>,
+) -> Result<String, AppError> {
+    let target = match path.filter(|value| !value.trim().is_empty()) {
+        Some(value) => PathBuf::from(value),
+        None => {
+            let home = std::env::var("HOME").unwrap_or_else(|_| ".".to_string());
+            Path::new(&home).join(format!("lazaro-backup-{}.zip", unix_now()))
+        }
+    };
+    backup::create(&state.persistent, &target)?;
+    tracing::info!(path = %target.display(), "backup created");
+    Ok(target.display().to_string())
+}
+
+/// `tool` is `workrave`, `stretchly` or `safe_eyes`; without `path` the
+/// tool's usual file is read.
+#[tauri::command]
+fn import_external(
+    tool: String,
+    path: Option<String>,
+    state: tauri::State<'_, BackendState>,
+) -> Result<importer::ImportReportDto, AppError> {
+    let tool = ExternalTool::parse(&tool)
+        .ok_or_else(|| AppError::InvalidImport(format!("unknown tool: {tool}")))?;
+    let path = match path.filter(|value| !value.trim().is_empty()) {
+        Some(value) => PathBuf::from(value),
+        None => importer::default_path(tool),
+    };
+    importer::import(&state.persistent, tool, &path)
+}
+
+/// With `dry_run` only reports what the backup would replace.
+#[tauri::command]
+async fn restore_backup(
+    app: AppHandle,
+    state: tauri::State<'_, BackendState>,
+    path: String,
+    dry_run: Option<bool>,
+) -> Result<backup::RestoreReportDto, AppError> {
+    let dry_run = dry_run.unwrap_or(false);
+    let archive = backup::read(Path::new(&path))?;
+    let mut report = {
+        let guard = state.persistent.data.lock()?;
+        archive.report(&guard, dry_run)
+    };
+    if dry_run {
+        return Ok(report);
+    }
+
+    let was_running = shutdown_runtime(&state).await?;
+    archive.apply(&state.persistent)?;
+    if was_running {
+        start_runtime_internal(app, &state)?;
+    }
+    report.runtime_restarted = was_running;
+    Ok(report)
+}
+
+/// Writes the whole state as plain JSON; the file can be moved to another
+/// machine and dropped in as `state.json` to be imported on next start.
+#[tauri::command]
+fn export_state_json(
+    path: Option<String>,
+    state: tauri::State<'_, BackendState>,
+) -> Result<String, AppError> {
+    let target = match path.filter(|value| !value.trim().is_empty()) {
+        Some(value) => PathBuf::from(value),
+        None => {
+            let home = std::env::var("HOME").unwrap_or_else(|_| ".".to_string());
+            Path::new(&home).join(format!("lazaro-state-{}.json", unix_now()))
+        }
+    };
+    state.persistent.flush()?;
+    state.persistent.storage.export_json(&target)?;
+    tracing::info!(path = %target.display(), "state exported");
+    Ok(target.display().to_string())
+}
+
 #[tauri::command]
 fn get_runtime_status(state: tauri::State<'_, BackendState>) -> Result<RuntimeStatusDto, AppError> {
-    let runtime = state
-        .runtime
-        .lock()
-        .map_err(|e| AppError::Io(format!("mutex poisoned: {e}")))?;
-    let status = runtime
-        .status
-        .lock()
-        .map_err(|e| AppError::Io(format!("mutex poisoned: {e}")))?
-        .clone();
+    let runtime = state.runtime.lock()?;
+    let status = runtime.status.borrow().clone();
     Ok(status)
 }
 
+/// Sends the runtime status to `on_status` now and again on every change,
+/// across restarts, until the frontend drops the channel.
+#[tauri::command]
+fn subscribe_status(
+    on_status: tauri::ipc::Channel<RuntimeStatusDto>,
+    state: tauri::State<'_, BackendState>,
+) -> Result<(), AppError> {
+    let mut updates = state.runtime.lock()?.status.subscribe();
+    tauri::async_runtime::spawn(async move {
+        loop {
+            let status = updates.borrow_and_update().clone();
+            if on_status.send(status).is_err() || updates.changed().await.is_err() {
+                break;
+            }
+        }
+    });
+    Ok(())
+}
+
+#[tauri::command]
+fn get_daily_status(state: tauri::State<'_, BackendState>) -> Result<DailyStatusDto, AppError> {
+    Ok(get_runtime_status(state)?.daily)
+}
+
+/// Scheduler dry run for "why didn't my break fire?" reports: each timer's
+/// accumulated time, snooze, next due time and the constraint holding it.
+#[tauri::command]
+fn explain_schedule(
+    state: tauri::State<'_, BackendState>,
+) -> Result<Vec<ScheduleEntryDto>, AppError> {
+    Ok(get_runtime_status(state)?.schedule)
+}
+
+/// Starts the day's counters over now, e.g. after a long flight or after
+/// lending the machine. Break history and stats are kept.
+#[tauri::command]
+fn force_daily_reset(state: tauri::State<'_, BackendState>) -> Result<(), AppError> {
+    let runtime = state.runtime.lock()?;
+    let Some(tx) = runtime.tx.clone() else {
+        return Err(AppError::RuntimeNotRunning);
+    };
+    let _ = tx.send(RuntimeControl::ForceDailyReset);
+    Ok(())
+}
+
 #[tauri::command]
 fn start_pending_break(state: tauri::State<'_, BackendState>) -> Result<(), AppError> {
-    let runtime = state
-        .runtime
-        .lock()
-        .map_err(|e| AppError::Io(format!("mutex poisoned: {e}")))?;
+    let runtime = state.runtime.lock()?;
     let Some(tx) = runtime.tx.clone() else {
         return Err(AppError::RuntimeNotRunning);
     };
@@ -1192,12 +3332,46 @@ fn start_pending_break(state: tauri::State<'_, BackendState>) -> Result<(), AppE
     Ok(())
 }
 
+/// The overlay's skip button; refused unless the break's skip rule allows it.
+#[tauri::command]
+fn end_break_early(app: AppHandle, state: tauri::State<'_, BackendState>) -> Result<(), AppError> {
+    let kind = overlay::check_skip(unix_now())?;
+    if overlay::current()?.is_some_and(|payload| payload.preview) {
+        close_overlay(&app);
+        return Ok(());
+    }
+    let runtime = state.runtime.lock()?;
+    let Some(tx) = runtime.tx.clone() else {
+        return Err(AppError::RuntimeNotRunning);
+    };
+    let _ = tx.send(RuntimeControl::EndBreakEarly(kind));
+    Ok(())
+}
+
+const MAX_POSTPONE_SECONDS: u64 = 2 * 3600;
+
+/// Snoozes the pending `kind` break for `seconds` instead of the timer's
+/// configured snooze, e.g. from the 5/10/15 minute choices in the prompt.
+#[tauri::command]
+fn postpone_pending(
+    kind: BreakKind,
+    seconds: u64,
+    state: tauri::State<'_, BackendState>,
+) -> Result<(), AppError> {
+    if seconds == 0 || seconds > MAX_POSTPONE_SECONDS {
+        return Err(AppError::InvalidDuration(seconds));
+    }
+    let runtime = state.runtime.lock()?;
+    let Some(tx) = runtime.tx.clone() else {
+        return Err(AppError::RuntimeNotRunning);
+    };
+    let _ = tx.send(RuntimeControl::PostponePending(kind, seconds));
+    Ok(())
+}
+
 #[tauri::command]
 fn snooze_pending_break(state: tauri::State<'_, BackendState>) -> Result<(), AppError> {
-    let runtime = state
-        .runtime
-        .lock()
-        .map_err(|e| AppError::Io(format!("mutex poisoned: {e}")))?;
+    let runtime = state.runtime.lock()?;
     let Some(tx) = runtime.tx.clone() else {
         return Err(AppError::RuntimeNotRunning);
     };
@@ -1206,59 +3380,386 @@ fn snooze_pending_break(state: tauri::State<'_, BackendState>) -> Result<(), App
 }
 
 #[tauri::command]
-fn trigger_break(kind: String, state: tauri::State<'_, BackendState>) -> Result<String, AppError> {
-    let break_kind = parse_break_kind(&kind)?;
-    let runtime = state
-        .runtime
-        .lock()
-        .map_err(|e| AppError::Io(format!("mutex poisoned: {e}")))?;
+fn cancel_daily_limit_action(state: tauri::State<'_, BackendState>) -> Result<(), AppError> {
+    let runtime = state.runtime.lock()?;
+    let Some(tx) = runtime.tx.clone() else {
+        return Err(AppError::RuntimeNotRunning);
+    };
+    let _ = tx.send(RuntimeControl::CancelLimitAction);
+    Ok(())
+}
+
+/// Pauses tracking and break notifications until `until`, or ends the
+/// vacation early when it is `None`.
+#[tauri::command]
+fn set_vacation(
+    until: Option<u64>,
+    state: tauri::State<'_, BackendState>,
+) -> Result<Option<u64>, AppError> {
+    if let Some(until) = until
+        && until <= unix_now()
+    {
+        return Err(AppError::InvalidDate(until.to_string()));
+    }
+
+    state.persistent.data.lock()?.vacation_until = until;
+    state.persistent.save(storage::Sections::DOCUMENT)?;
+
+    let runtime = state.runtime.lock()?;
+    if let Some(tx) = runtime.tx.as_ref() {
+        let _ = tx.send(RuntimeControl::SetVacation(until));
+    }
+    Ok(until)
+}
+
+const MAX_GUEST_SESSION_SECONDS: u64 = 12 * 3600;
+
+/// Stops recording activity and announcing breaks for `duration` seconds,
+/// for when someone else uses the machine. Not kept across restarts.
+#[tauri::command]
+fn start_guest_session(
+    duration: u64,
+    state: tauri::State<'_, BackendState>,
+) -> Result<u64, AppError> {
+    if duration == 0 || duration > MAX_GUEST_SESSION_SECONDS {
+        return Err(AppError::InvalidDuration(duration));
+    }
+    let runtime = state.runtime.lock()?;
+    let Some(tx) = runtime.tx.clone() else {
+        return Err(AppError::RuntimeNotRunning);
+    };
+    let until = unix_now().saturating_add(duration);
+    let _ = tx.send(RuntimeControl::SetGuest(Some(until)));
+    Ok(until)
+}
+
+#[tauri::command]
+fn end_guest_session(state: tauri::State<'_, BackendState>) -> Result<(), AppError> {
+    let runtime = state.runtime.lock()?;
+    let Some(tx) = runtime.tx.clone() else {
+        return Err(AppError::RuntimeNotRunning);
+    };
+    let _ = tx.send(RuntimeControl::SetGuest(None));
+    Ok(())
+}
+
+const DEFAULT_PRESENTATION_SECONDS: u64 = 3600;
+const MAX_PRESENTATION_SECONDS: u64 = 8 * 3600;
+
+/// Turns presentation mode on for `duration` seconds (an hour if not given)
+/// or off. Returns when it ends. Settings are left alone, and it is not
+/// kept across restarts.
+#[tauri::command]
+fn set_presentation_mode(
+    enabled: bool,
+    duration: Option<u64>,
+    state: tauri::State<'_, BackendState>,
+) -> Result<Option<u64>, AppError> {
+    let until = if enabled {
+        let duration = duration.unwrap_or(DEFAULT_PRESENTATION_SECONDS);
+        if duration == 0 || duration > MAX_PRESENTATION_SECONDS {
+            return Err(AppError::InvalidDuration(duration));
+        }
+        Some(unix_now().saturating_add(duration))
+    } else {
+        None
+    };
+    let runtime = state.runtime.lock()?;
+    let Some(tx) = runtime.tx.clone() else {
+        return Err(AppError::RuntimeNotRunning);
+    };
+    let _ = tx.send(RuntimeControl::SetPresentation(until));
+    Ok(until)
+}
+
+#[tauri::command]
+fn handle_break_key(
+    key: String,
+    state: tauri::State<'_, BackendState>,
+) -> Result<Option<String>, AppError> {
+    let action = {
+        let guard = state.persistent.data.lock()?;
+        let settings = &guard.effective;
+        if !settings.media_keys_enabled {
+            return Ok(None);
+        }
+        keys::resolve(
+            &settings.media_start_keys,
+            &settings.media_snooze_keys,
+            &key,
+        )
+    };
+    let Some(action) = action else {
+        return Ok(None);
+    };
+
+    let runtime = state.runtime.lock()?;
+    let Some(tx) = runtime.tx.clone() else {
+        return Err(AppError::RuntimeNotRunning);
+    };
+    let _ = tx.send(RuntimeControl::BreakKey(action));
+    Ok(Some(action.name().to_string()))
+}
+
+const DEFAULT_PREVIEW_SECONDS: u64 = 10;
+const MAX_PREVIEW_SECONDS: u64 = 60;
+
+/// Opens the real overlay for `kind` with a short countdown, using the
+/// current theme, sounds and skip rules, so they can be tried out. The
+/// engine never hears of it: no counters, history or stats change.
+#[tauri::command]
+fn preview_break(
+    app: AppHandle,
+    kind: BreakKind,
+    duration: Option<u64>,
+    state: tauri::State<'_, BackendState>,
+) -> Result<(), AppError> {
+    let duration = duration.unwrap_or(DEFAULT_PREVIEW_SECONDS);
+    if duration == 0 || duration > MAX_PREVIEW_SECONDS {
+        return Err(AppError::InvalidDuration(duration));
+    }
+    if overlay::current()?.is_some() {
+        return Err(AppError::OverlayBusy);
+    }
+    let settings = {
+        let guard = state.persistent.data.lock()?;
+        guard.effective.clone()
+    };
+    let core = settings_to_core(&settings)?;
+    let lang = i18n::resolve(&settings.language);
+    let block_level = lazaro_core::timer::block_level(&core, kind);
+    let mut payload = overlay::payload(kind, duration, block_level, &core, &settings, lang);
+    payload.preview = true;
+    let started_at = payload.started_at;
+    tracing::info!(?kind, duration, "previewing break overlay");
+    open_overlay(
+        &app,
+        kind,
+        duration,
+        true,
+        matches!(block_level, BlockLevel::Strict),
+        payload,
+        lang,
+    );
+
+    // Plays the same cues a real break would, until the preview ends or is
+    // closed.
+    let showing = move || {
+        overlay::current()
+            .ok()
+            .flatten()
+            .is_some_and(|payload| payload.preview && payload.started_at == started_at)
+    };
+    std::thread::Builder::new()
+        .name("lazaro-preview".into())
+        .spawn(move || {
+            for remaining in (1..=duration).rev() {
+                if !showing() {
+                    return;
+                }
+                if sound::is_final_countdown(remaining, settings.countdown_cue_seconds) {
+                    play_cue(&settings, false, sound::Cue::Tick);
+                }
+                std::thread::sleep(Duration::from_secs(1));
+            }
+            if showing() {
+                close_overlay(&app);
+                play_cue(&settings, false, sound::Cue::Chime);
+            }
+        })?;
+    Ok(())
+}
+
+#[tauri::command]
+fn trigger_break(
+    kind: BreakKind,
+    state: tauri::State<'_, BackendState>,
+) -> Result<String, AppError> {
+    let runtime = state.runtime.lock()?;
     let Some(tx) = runtime.tx.clone() else {
         return Err(AppError::RuntimeNotRunning);
     };
 
-    let _ = tx.send(RuntimeControl::StartBreak(break_kind));
-    Ok(format!("break_triggered:{kind}"))
+    let _ = tx.send(RuntimeControl::StartBreak(kind));
+    Ok(format!("break_triggered:{}", kind.id()))
 }
 
 fn main() {
+    let quick_action = actions::requested();
+    if let Some(action) = quick_action
+        && let Ok(reply) = actions::forward(action)
+    {
+        println!("{reply}");
+        return;
+    }
     configure_linux_webkit_runtime();
+    crash::install(default_data_dir());
 
     let persistent = Arc::new(AppState::init().expect("failed to initialize state"));
+    AppState::spawn_writer(&persistent).expect("failed to start state writer");
+    let log_level = persistent
+        .data
+        .lock()
+        .map(|guard| guard.effective.log_level.clone())
+        .unwrap_or_else(|_| logging::DEFAULT_LOG_LEVEL.into());
+    logging::init(&default_data_dir(), &log_level);
+    tracing::info!(version = env!("CARGO_PKG_VERSION"), "lazaro starting");
+    if let Some(warning) = &persistent.startup_warning {
+        tracing::error!("{warning}");
+    }
     let backend = BackendState {
         persistent,
         runtime: Mutex::new(RuntimeController::default()),
+        app: OnceLock::new(),
     };
 
     tauri::Builder::default()
         .manage(backend)
-        .setup(|app| {
+        .setup(move |app| {
             let app_handle = app.handle().clone();
             let state = app.state::<BackendState>();
-            if let Err(error) = start_runtime_internal(app_handle, &state) {
-                eprintln!("failed to auto-start runtime: {error}");
+            let _ = state.app.set(app_handle.clone());
+            notify::init(app_handle.clone());
+            if let Err(error) = start_runtime_internal(app_handle.clone(), &state) {
+                tracing::error!(%error, "failed to auto-start runtime");
+            }
+            let widget_enabled = state
+                .persistent
+                .data
+                .lock()
+                .is_ok_and(|guard| guard.effective.countdown_widget);
+            if let Err(error) = tray::install(&app_handle, widget_enabled) {
+                tracing::warn!(%error, "failed to create tray icon");
             }
+            widget::sync(&app_handle, widget_enabled);
+            hide_during_vacation(&app_handle);
+            config_file::spawn_watcher(app_handle.clone());
+            remote::spawn_supervisor(app_handle.clone());
+            peers::spawn_supervisor(app_handle.clone());
+            sync::spawn_supervisor(app_handle.clone());
+            update::spawn_checker(app_handle.clone());
+            actions::spawn_listener(app_handle.clone(), quick_action);
+            spawn_signal_handler(app_handle);
             Ok(())
         })
         .invoke_handler(tauri::generate_handler![
             get_settings,
             update_settings,
+            get_overlay_config,
+            get_overlay_payload,
+            get_theme,
+            update_theme,
+            list_languages,
+            set_language,
             list_profiles,
+            get_change_history,
+            undo_last_change,
             save_profile,
             activate_profile,
             remove_profile,
             get_weekly_stats,
+            get_sync_status,
+            get_peer_status,
+            reset_all_data,
+            get_onboarding_state,
+            complete_onboarding,
+            get_platform_capabilities,
             set_startup_mode,
             start_runtime,
+            restart_runtime,
+            check_runtime_health,
             stop_runtime,
             get_runtime_status,
+            subscribe_status,
             start_pending_break,
             snooze_pending_break,
-            trigger_break
+            postpone_pending,
+            end_break_early,
+            cancel_daily_limit_action,
+            set_vacation,
+            start_guest_session,
+            end_guest_session,
+            set_presentation_mode,
+            get_daily_status,
+            explain_schedule,
+            force_daily_reset,
+            preview_break,
+            trigger_break,
+            take_crash_report,
+            get_recent_logs,
+            export_diagnostics,
+            export_state_json,
+            create_backup,
+            restore_backup,
+            import_external,
+            check_for_updates,
+            get_update_status,
+            get_notification_status,
+            detect_notification_backend,
+            get_config_file_info,
+            get_policy_info,
+            get_break_history,
+            get_focus_sessions,
+            get_app_usage,
+            get_category_breakdown,
+            submit_break_feedback,
+            get_weekly_goal_progress,
+            get_dashboard_data,
+            get_daily_summary,
+            get_upcoming_busy_windows,
+            handle_break_key
         ])
-        .run(tauri::generate_context!())
-        .expect("error while running tauri application");
+        .build(tauri::generate_context!())
+        .expect("error while building tauri application")
+        .run(|app, event| {
+            if let RunEvent::Exit = event {
+                let state = app.state::<BackendState>();
+                if let Err(error) = tauri::async_runtime::block_on(shutdown_runtime(&state)) {
+                    tracing::error!(%error, "failed to stop runtime on exit");
+                }
+                if let Err(error) = state.persistent.flush() {
+                    tracing::error!(%error, "failed to persist state on exit");
+                }
+            }
+        });
 }
 
+// Autostart during a vacation should not pop the main window up; it stays
+// reachable from the tray, when there is one.
+fn hide_during_vacation(app: &AppHandle) {
+    let on_vacation = app
+        .state::<BackendState>()
+        .persistent
+        .data
+        .lock()
+        .is_ok_and(|guard| guard.vacation_until.is_some_and(|until| until > unix_now()));
+    if on_vacation
+        && platform::probe().tray
+        && let Some(window) = app.get_webview_window("main")
+    {
+        let _ = window.hide();
+    }
+}
+
+#[cfg(unix)]
+fn spawn_signal_handler(app: AppHandle) {
+    use tokio::signal::unix::{SignalKind, signal};
+
+    tauri::async_runtime::spawn(async move {
+        let Ok(mut terminate) = signal(SignalKind::terminate()) else {
+            return;
+        };
+        tokio::select! {
+            _ = terminate.recv() => {}
+            _ = tokio::signal::ctrl_c() => {}
+        }
+        app.exit(0);
+    });
+}
+
+#[cfg(not(unix))]
+fn spawn_signal_handler(_app: AppHandle) {}
+
 #[cfg(target_os = "linux")]
 fn configure_linux_webkit_runtime() {
     let wayland_session = std::env::var("WAYLAND_DISPLAY").is_ok()
@@ -0,0 +1,218 @@
+use std::{
+    sync::{Mutex, OnceLock},
+    time::Duration,
+};
+
+use notify_rust::{Notification, Urgency};
+use serde::Serialize;
+use tauri::{AppHandle, Emitter};
+
+use crate::{AppError, dbus, unix_now};
+
+const DEST: &str = "org.freedesktop.Notifications";
+const PATH: &str = "/org/freedesktop/Notifications";
+const APP_NAME: &str = "Lázaro";
+const APP_ICON: &str = "io.lazaro.Lazaro";
+/// How long a missing notification daemon is trusted before asking again;
+/// minimal sessions often start one after the app.
+const REPROBE_INTERVAL: Duration = Duration::from_secs(60);
+
+/// How notifications currently reach the user.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Backend {
+    NotifyRust,
+    /// `org.freedesktop.Notifications.Notify` called directly, for setups
+    /// where notify-rust fails but a daemon answers on the bus.
+    Dbus,
+    /// No daemon: messages go to the main window and the overlay only.
+    InApp,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct UndeliveredDto {
+    pub title: String,
+    pub body: String,
+    pub at: u64,
+}
+
+/// `get_notification_status`: the backend in use and how it has fared.
+#[derive(Clone, Debug, Serialize)]
+pub struct NotificationStatusDto {
+    backend: Option<Backend>,
+    server: Option<String>,
+    capabilities: Vec<String>,
+    probed_at: Option<u64>,
+    delivered: u64,
+    undelivered: u64,
+    last_error: Option<String>,
+    last_undelivered: Option<UndeliveredDto>,
+}
+
+static STATUS: Mutex<NotificationStatusDto> = Mutex::new(NotificationStatusDto {
+    backend: None,
+    server: None,
+    capabilities: Vec::new(),
+    probed_at: None,
+    delivered: 0,
+    undelivered: 0,
+    last_error: None,
+    last_undelivered: None,
+});
+
+static APP: OnceLock<AppHandle> = OnceLock::new();
+
+/// Gives the in-app fallback a window to report to.
+pub fn init(app: AppHandle) {
+    let _ = APP.set(app);
+}
+
+pub fn status() -> Result<NotificationStatusDto, AppError> {
+    STATUS
+        .lock()
+        .map(|status| status.clone())
+        .map_err(AppError::from)
+}
+
+/// The daemon's name and the capabilities it reports, or `None` when no
+/// daemon owns the name.
+pub fn detect() -> Option<(String, Vec<String>)> {
+    let server = dbus::session_call(DEST, PATH, &format!("{DEST}.GetServerInformation"), &[])
+        .and_then(|raw| raw.split('\'').nth(1).map(str::to_string))?;
+    let capabilities = dbus::session_call(DEST, PATH, &format!("{DEST}.GetCapabilities"), &[])
+        .map(|raw| {
+            raw.split('\'')
+                .skip(1)
+                .step_by(2)
+                .map(str::to_string)
+                .collect()
+        })
+        .unwrap_or_default();
+    Some((server, capabilities))
+}
+
+/// Detects the backend again, e.g. after the user starts a daemon.
+pub fn reprobe() -> Result<NotificationStatusDto, AppError> {
+    let detected = detect();
+    let mut status = STATUS.lock()?;
+    apply_probe(&mut status, detected);
+    Ok(status.clone())
+}
+
+fn apply_probe(status: &mut NotificationStatusDto, detected: Option<(String, Vec<String>)>) {
+    let backend = match &detected {
+        Some(_) => Backend::NotifyRust,
+        None => Backend::InApp,
+    };
+    if status.backend != Some(backend) {
+        tracing::info!(?backend, "notification backend selected");
+    }
+    status.backend = Some(backend);
+    let (server, capabilities) = detected.unzip();
+    status.server = server;
+    status.capabilities = capabilities.unwrap_or_default();
+    status.probed_at = Some(unix_now());
+}
+
+/// Shows a notification with the current backend. When notify-rust fails
+/// the daemon is called directly, and when that fails too the message goes
+/// to the main window.
+pub fn show(title: &str, body: &str, urgent: bool) {
+    let backend = {
+        let Ok(mut status) = STATUS.lock() else {
+            return;
+        };
+        let stale = status.probed_at.is_none_or(|at| {
+            status.backend == Some(Backend::InApp)
+                && unix_now().saturating_sub(at) >= REPROBE_INTERVAL.as_secs()
+        });
+        if stale {
+            apply_probe(&mut status, detect());
+        }
+        status.backend.unwrap_or(Backend::InApp)
+    };
+
+    let mut error = None;
+    if backend == Backend::NotifyRust {
+        let mut notification = Notification::new();
+        notification.summary(title).body(body);
+        if urgent {
+            notification.urgency(Urgency::Critical);
+        }
+        match notification.show() {
+            Ok(_) => return record(Backend::NotifyRust, None),
+            Err(failure) => error = Some(failure.to_string()),
+        }
+    }
+    if backend != Backend::InApp {
+        match notify_dbus(title, body, urgent) {
+            Some(()) => return record(Backend::Dbus, error),
+            None => error = Some("Notify call failed".into()),
+        }
+    }
+
+    record(Backend::InApp, error);
+    if let Ok(mut status) = STATUS.lock() {
+        status.undelivered += 1;
+        status.last_undelivered = Some(UndeliveredDto {
+            title: title.into(),
+            body: body.into(),
+            at: unix_now(),
+        });
+        if let Some(app) = APP.get() {
+            let _ = app.emit("notification_fallback", &status.last_undelivered);
+        }
+    }
+}
+
+// Keeps the backend that last worked, so later messages skip the ones that
+// failed; a notify-rust error is kept even when D-Bus then succeeded.
+fn record(backend: Backend, error: Option<String>) {
+    let Ok(mut status) = STATUS.lock() else {
+        return;
+    };
+    if status.backend != Some(backend) {
+        tracing::warn!(?backend, error = ?error, "notification backend changed");
+        status.backend = Some(backend);
+        if backend == Backend::InApp {
+            status.probed_at = Some(unix_now());
+        }
+    }
+    if backend != Backend::InApp {
+        status.delivered += 1;
+    }
+    if error.is_some() {
+        status.last_error = error;
+    }
+}
+
+fn notify_dbus(title: &str, body: &str, urgent: bool) -> Option<()> {
+    let hints = if urgent {
+        "{'urgency': <byte 2>}"
+    } else {
+        "@a{sv} {}"
+    };
+    let (app_name, app_icon, title, body) =
+        (quote(APP_NAME), quote(APP_ICON), quote(title), quote(body));
+    dbus::session_call(
+        DEST,
+        PATH,
+        &format!("{DEST}.Notify"),
+        &[
+            app_name.as_str(),
+            "uint32 0",
+            app_icon.as_str(),
+            title.as_str(),
+            body.as_str(),
+            "@as []",
+            hints,
+            "int32 -1",
+        ],
+    )
+    .map(|_| ())
+}
+
+// GVariant text form of a string, as gdbus parses its arguments.
+fn quote(value: &str) -> String {
+    format!("'{}'", value.replace('\\', "\\\\").replace('\'', "\\'"))
+}
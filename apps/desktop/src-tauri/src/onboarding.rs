@@ -0,0 +1,92 @@
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
+
+use crate::platform::{self, PlatformCapabilitiesDto};
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct OnboardingMetaDto {
+    /// `Some(0)` marks state files written before onboarding existed.
+    pub completed_at: Option<u64>,
+}
+
+/// Older state files skip the wizard; only fresh installs start without it.
+pub fn legacy_meta() -> OnboardingMetaDto {
+    OnboardingMetaDto {
+        completed_at: Some(0),
+    }
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct RecommendationDto {
+    /// A `SettingsDto` field name.
+    pub key: String,
+    pub value: Value,
+    pub reason: String,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct OnboardingStateDto {
+    pub completed: bool,
+    pub completed_at: Option<u64>,
+    pub environment: PlatformCapabilitiesDto,
+    pub recommendations: Vec<RecommendationDto>,
+}
+
+pub fn state(meta: &OnboardingMetaDto) -> OnboardingStateDto {
+    let environment = platform::probe();
+    OnboardingStateDto {
+        completed: meta.completed_at.is_some(),
+        completed_at: meta.completed_at,
+        recommendations: recommend(&environment),
+        environment,
+    }
+}
+
+pub fn recommend(environment: &PlatformCapabilitiesDto) -> Vec<RecommendationDto> {
+    let mut recommendations = Vec::new();
+    let mut push = |key: &str, value: Value, reason: &str| {
+        recommendations.push(RecommendationDto {
+            key: key.into(),
+            value,
+            reason: reason.into(),
+        });
+    };
+
+    let notifications = environment.notification_server.is_some();
+    if !notifications {
+        push(
+            "desktop_notifications",
+            json!(false),
+            "No hay un servidor de notificaciones activo en la sesión.",
+        );
+    }
+    if !environment.overlay_on_top {
+        push(
+            "overlay_notifications",
+            json!(false),
+            "El compositor no permite mantener la ventana de descanso por encima de las demás.",
+        );
+        if notifications {
+            push(
+                "desktop_notifications",
+                json!(true),
+                "Las notificaciones del escritorio sustituyen a la ventana de descanso.",
+            );
+        }
+    }
+
+    if environment.xdg_autostart {
+        push(
+            "startup_xdg",
+            json!(true),
+            "El escritorio inicia las aplicaciones de ~/.config/autostart.",
+        );
+    } else if environment.systemd_user {
+        push(
+            "startup_systemd_user",
+            json!(true),
+            "El escritorio no usa autostart XDG, pero hay una sesión de systemd --user.",
+        );
+    }
+    recommendations
+}
@@ -0,0 +1,195 @@
+use std::sync::Mutex;
+
+use lazaro_core::{
+    config::{ActivityPack, BlockLevel, Settings},
+    timer::BreakKind,
+};
+use serde::Serialize;
+
+use crate::{
+    AppError, OverlayConfigDto, SettingsDto, break_kind_to_string, i18n, parse_break_kind, theme,
+    unix_now,
+};
+
+// Suggestions for micro and rest breaks, in that order.
+fn pack_activities(pack: ActivityPack) -> (&'static [&'static str], &'static [&'static str]) {
+    match pack {
+        ActivityPack::Eyes => (
+            &["overlay.activity.eyes", "overlay.activity.blink"],
+            &["overlay.activity.palming"],
+        ),
+        ActivityPack::Stretch => (
+            &["overlay.activity.shoulders"],
+            &["overlay.activity.stretch"],
+        ),
+        ActivityPack::Movement => (
+            &["overlay.activity.stand"],
+            &["overlay.activity.walk", "overlay.activity.water"],
+        ),
+        ActivityPack::Breathing => (
+            &["overlay.activity.breathe"],
+            &["overlay.activity.breathe_long"],
+        ),
+    }
+}
+
+// Rotate suggestions every few minutes so back-to-back breaks differ.
+const ACTIVITY_ROTATION_SECONDS: u64 = 300;
+
+#[derive(Clone, Debug, Serialize)]
+pub struct OverlayStringsDto {
+    pub heading: String,
+    pub kind: String,
+    pub hint: String,
+    pub strict: String,
+    pub started: String,
+    pub completed: String,
+    pub skip: String,
+    pub preview: String,
+    /// `{seconds}` is filled in by the overlay while the button is locked.
+    pub skip_locked: String,
+}
+
+/// Whether the overlay may offer to end the break early, and from when.
+/// `end_break_early` checks the same rule, so the window cannot grant
+/// itself more than it was sent.
+#[derive(Clone, Copy, Debug, Serialize)]
+pub struct SkipRuleDto {
+    pub allowed: bool,
+    pub available_at: Option<u64>,
+}
+
+impl SkipRuleDto {
+    fn new(level: BlockLevel, started_at: u64) -> Self {
+        let available_at = level
+            .skip_delay_seconds()
+            .map(|delay| started_at.saturating_add(delay));
+        Self {
+            allowed: available_at.is_some(),
+            available_at,
+        }
+    }
+
+    pub fn permits(&self, now: u64) -> bool {
+        self.available_at.is_some_and(|at| now >= at)
+    }
+}
+
+/// Everything the overlay window renders, so it never has to infer state.
+#[derive(Clone, Debug, Serialize)]
+pub struct OverlayPayloadDto {
+    pub break_kind: String,
+    pub break_label: String,
+    pub duration_seconds: u64,
+    pub started_at: u64,
+    pub activity: String,
+    pub allow_skip: bool,
+    pub skip: SkipRuleDto,
+    pub language: String,
+    /// Opened by `preview_break`: counts down on its own and closes without
+    /// the runtime.
+    pub preview: bool,
+    /// Tints the overlay during the evening wind-down.
+    pub wind_down: bool,
+    pub theme: theme::ResolvedThemeDto,
+    pub config: OverlayConfigDto,
+    pub strings: OverlayStringsDto,
+}
+
+static CURRENT: Mutex<Option<OverlayPayloadDto>> = Mutex::new(None);
+
+pub fn payload(
+    kind: BreakKind,
+    duration_seconds: u64,
+    block_level: BlockLevel,
+    settings: &Settings,
+    dto: &SettingsDto,
+    lang: i18n::Language,
+) -> OverlayPayloadDto {
+    let started_at = unix_now();
+    let messages = i18n::Messages::new(lang, settings);
+    let break_label = messages.break_label(kind).to_string();
+    let activities: Vec<&str> = match kind {
+        BreakKind::DailyLimit => vec!["overlay.activity.daily_limit"],
+        _ => settings
+            .theme
+            .activity_packs
+            .iter()
+            .flat_map(|&pack| {
+                let (micro, rest) = pack_activities(pack);
+                if matches!(kind, BreakKind::Micro) {
+                    micro
+                } else {
+                    rest
+                }
+            })
+            .copied()
+            .collect(),
+    };
+    let kind_arg = [("kind", break_label.as_str())];
+    let activity = activities[(started_at / ACTIVITY_ROTATION_SECONDS) as usize % activities.len()];
+    let skip = SkipRuleDto::new(block_level, started_at);
+
+    OverlayPayloadDto {
+        break_kind: break_kind_to_string(kind),
+        duration_seconds,
+        started_at,
+        activity: messages.text(activity).into(),
+        allow_skip: skip.allowed,
+        skip,
+        language: lang.code().into(),
+        preview: false,
+        wind_down: false,
+        theme: overlay_theme(dto),
+        config: OverlayConfigDto::from(dto),
+        strings: OverlayStringsDto {
+            heading: messages.text("overlay.heading").into(),
+            kind: messages.format("overlay.kind", &kind_arg),
+            hint: messages.text("overlay.hint").into(),
+            strict: messages.text("overlay.strict").into(),
+            started: messages.format("break.started", &kind_arg),
+            completed: messages.format("break.completed", &kind_arg),
+            skip: messages.text("overlay.skip").into(),
+            preview: messages.text("overlay.preview").into(),
+            skip_locked: messages.text("overlay.skip_locked").into(),
+        },
+        break_label,
+    }
+}
+
+// High contrast wins over whatever theme is selected.
+fn overlay_theme(settings: &SettingsDto) -> theme::ResolvedThemeDto {
+    let mut resolved = theme::resolve(settings);
+    if settings.high_contrast_overlay {
+        resolved.background = "#000000".into();
+        resolved.foreground = "#ffffff".into();
+        resolved.accent = "#ffea00".into();
+        resolved.background_image = None;
+    }
+    resolved
+}
+
+pub fn set(payload: Option<OverlayPayloadDto>) {
+    if let Ok(mut current) = CURRENT.lock() {
+        *current = payload;
+    }
+}
+
+pub fn current() -> Result<Option<OverlayPayloadDto>, AppError> {
+    Ok(CURRENT.lock()?.clone())
+}
+
+/// The break the overlay shows, if its skip rule lets it end at `now`.
+pub fn check_skip(now: u64) -> Result<BreakKind, AppError> {
+    let Some(payload) = current()? else {
+        return Err(AppError::SkipNotAllowed("no break overlay is open".into()));
+    };
+    if !payload.skip.allowed {
+        return Err(AppError::SkipNotAllowed("strict break".into()));
+    }
+    if !payload.skip.permits(now) {
+        let wait = payload.skip.available_at.unwrap_or(now).saturating_sub(now);
+        return Err(AppError::SkipNotAllowed(format!("available in {wait}s")));
+    }
+    parse_break_kind(&payload.break_kind)
+}
@@ -0,0 +1,374 @@
+use std::{
+    collections::BTreeMap,
+    io::Read,
+    net::{IpAddr, SocketAddr},
+    process::{Child, Command, Stdio},
+    sync::Mutex,
+    time::Duration,
+};
+
+use hmac::{Hmac, Mac};
+use serde::{Deserialize, Serialize, de::DeserializeOwned};
+use sha2::Sha256;
+use tauri::{AppHandle, Manager, async_runtime::JoinHandle};
+use tokio::{
+    io::{AsyncBufReadExt, AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt, BufReader, Lines},
+    net::{TcpListener, TcpStream},
+    time::timeout,
+};
+
+use crate::{AppError, BackendState, history, remote, unix_now};
+
+const SERVICE_TYPE: &str = "_lazaro._tcp";
+const SUPERVISE_INTERVAL: Duration = Duration::from_secs(2);
+const EXCHANGE_INTERVAL_TICKS: u64 = 8;
+const EXCHANGE_TIMEOUT: Duration = Duration::from_secs(5);
+const MAX_MESSAGE_BYTES: u64 = 4 * 1024;
+const NONCE_BYTES: usize = 16;
+const ACCEPT_RETRY_DELAY: Duration = Duration::from_secs(1);
+
+#[derive(Clone, Debug, Serialize)]
+pub struct PeerDto {
+    device_id: String,
+    address: String,
+    active_seconds: u64,
+    last_seen: u64,
+}
+
+static PEERS: Mutex<BTreeMap<String, PeerDto>> = Mutex::new(BTreeMap::new());
+
+pub fn status() -> Result<Vec<PeerDto>, AppError> {
+    PEERS
+        .lock()
+        .map(|peers| peers.values().cloned().collect())
+        .map_err(AppError::from)
+}
+
+// The exchange is one connection per pair: the server sends a challenge, the
+// client answers with its signed report and a challenge of its own, and the
+// server replies with its report signed over that. Both sides learn the
+// other's total and neither accepts data that is not keyed with the secret.
+#[derive(Serialize, Deserialize)]
+struct Challenge {
+    nonce: String,
+}
+
+#[derive(Serialize, Deserialize)]
+struct Report {
+    device_id: String,
+    day: i64,
+    active_seconds: u64,
+    #[serde(default)]
+    nonce: String,
+    mac: String,
+}
+
+struct LocalDevice {
+    device_id: String,
+    secret: String,
+    port: u16,
+    day: i64,
+    active_seconds: u64,
+}
+
+impl LocalDevice {
+    fn report(&self, challenge: &str, nonce: String) -> Report {
+        Report {
+            device_id: self.device_id.clone(),
+            day: self.day,
+            active_seconds: self.active_seconds,
+            nonce,
+            mac: sign(
+                &self.secret,
+                challenge,
+                &self.device_id,
+                self.day,
+                self.active_seconds,
+            ),
+        }
+    }
+
+    fn accepts(&self, report: &Report, challenge: &str) -> bool {
+        report.device_id != self.device_id
+            && remote::token_matches(
+                &report.mac,
+                &sign(
+                    &self.secret,
+                    challenge,
+                    &report.device_id,
+                    report.day,
+                    report.active_seconds,
+                ),
+            )
+    }
+}
+
+fn local_device(app: &AppHandle) -> Option<LocalDevice> {
+    let state = app.state::<BackendState>();
+    let guard = state.persistent.data.lock().ok()?;
+    if !guard.effective.peer_sharing_enabled || guard.effective.peer_secret.is_empty() {
+        return None;
+    }
+    let day = history::local_day_index();
+    Some(LocalDevice {
+        device_id: guard.sync.device_id.clone(),
+        secret: guard.effective.peer_secret.clone(),
+        port: guard.effective.peer_port,
+        day,
+        active_seconds: guard
+            .daily_stats
+            .get(&day)
+            .map(|stats| stats.active_seconds)
+            .unwrap_or(0),
+    })
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{byte:02x}")).collect()
+}
+
+fn sign(secret: &str, challenge: &str, device_id: &str, day: i64, active_seconds: u64) -> String {
+    let mut mac =
+        Hmac::<Sha256>::new_from_slice(secret.as_bytes()).expect("HMAC takes keys of any length");
+    mac.update(format!("{challenge}|{device_id}|{day}|{active_seconds}").as_bytes());
+    hex(&mac.finalize().into_bytes())
+}
+
+fn random_nonce() -> Result<String, String> {
+    let mut bytes = [0u8; NONCE_BYTES];
+    std::fs::File::open("/dev/urandom")
+        .and_then(|mut file| file.read_exact(&mut bytes))
+        .map_err(|e| e.to_string())?;
+    Ok(hex(&bytes))
+}
+
+async fn send<W: AsyncWrite + Unpin, T: Serialize>(
+    writer: &mut W,
+    message: &T,
+) -> Result<(), String> {
+    let mut line = serde_json::to_vec(message).map_err(|e| e.to_string())?;
+    line.push(b'\n');
+    writer.write_all(&line).await.map_err(|e| e.to_string())
+}
+
+async fn receive<R: AsyncRead + Unpin, T: DeserializeOwned>(
+    lines: &mut Lines<BufReader<R>>,
+) -> Result<T, String> {
+    let line = lines
+        .next_line()
+        .await
+        .map_err(|e| e.to_string())?
+        .ok_or_else(|| "connection closed".to_string())?;
+    serde_json::from_str(&line).map_err(|e| e.to_string())
+}
+
+fn record(app: &AppHandle, report: &Report, address: IpAddr) {
+    app.state::<BackendState>().persistent.record_peer_active(
+        &report.device_id,
+        report.day,
+        report.active_seconds,
+    );
+    if let Ok(mut peers) = PEERS.lock() {
+        peers.insert(
+            report.device_id.clone(),
+            PeerDto {
+                device_id: report.device_id.clone(),
+                address: address.to_string(),
+                active_seconds: report.active_seconds,
+                last_seen: unix_now(),
+            },
+        );
+    }
+}
+
+pub fn spawn_supervisor(app: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        let mut server: Option<(u16, JoinHandle<()>, Option<Child>)> = None;
+        let mut failed_port = None;
+        let mut ticker = tokio::time::interval(SUPERVISE_INTERVAL);
+        let mut ticks: u64 = 0;
+
+        loop {
+            ticker.tick().await;
+            ticks = ticks.wrapping_add(1);
+            let wanted = local_device(&app);
+            if server.as_ref().map(|(port, ..)| *port) != wanted.as_ref().map(|device| device.port)
+            {
+                if let Some((port, handle, advertiser)) = server.take() {
+                    handle.abort();
+                    if let Some(mut child) = advertiser {
+                        let _ = child.kill();
+                        let _ = child.wait();
+                    }
+                    tracing::info!(port, "peer sharing stopped");
+                }
+                match &wanted {
+                    None => {
+                        failed_port = None;
+                        if let Ok(mut peers) = PEERS.lock() {
+                            peers.clear();
+                        }
+                    }
+                    Some(device) => match TcpListener::bind(("0.0.0.0", device.port)).await {
+                        Ok(listener) => {
+                            tracing::info!(port = device.port, "peer sharing listening");
+                            failed_port = None;
+                            server = Some((
+                                device.port,
+                                tauri::async_runtime::spawn(serve(app.clone(), listener)),
+                                advertise(&device.device_id, device.port),
+                            ));
+                        }
+                        Err(error) => {
+                            if failed_port != Some(device.port) {
+                                tracing::warn!(%error, port = device.port, "failed to bind peer sharing port");
+                            }
+                            failed_port = Some(device.port);
+                        }
+                    },
+                }
+            }
+
+            if let Some(device) = wanted
+                && server.is_some()
+                && ticks.is_multiple_of(EXCHANGE_INTERVAL_TICKS)
+            {
+                exchange_all(&app, device).await;
+            }
+        }
+    });
+}
+
+fn advertise(device_id: &str, port: u16) -> Option<Child> {
+    match Command::new("avahi-publish-service")
+        .args([
+            &format!("Lazaro {device_id}"),
+            SERVICE_TYPE,
+            &port.to_string(),
+            &format!("device={device_id}"),
+        ])
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()
+    {
+        Ok(child) => Some(child),
+        Err(error) => {
+            tracing::warn!(%error, "failed to advertise peer service; is avahi installed?");
+            None
+        }
+    }
+}
+
+/// Resolved `_lazaro._tcp` services on the LAN as `(device id, address)`.
+fn discover() -> Vec<(String, SocketAddr)> {
+    let output = match Command::new("avahi-browse")
+        .args(["--resolve", "--parsable", "--terminate", SERVICE_TYPE])
+        .stderr(Stdio::null())
+        .output()
+    {
+        Ok(output) => output,
+        Err(error) => {
+            tracing::debug!(%error, "avahi-browse unavailable");
+            return Vec::new();
+        }
+    };
+
+    // =;iface;IPv4;name;type;domain;host;address;port;"txt" "txt"
+    let mut found: Vec<(String, SocketAddr)> = String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .filter_map(|line| {
+            let fields: Vec<&str> = line.split(';').collect();
+            if fields.first() != Some(&"=") || fields.get(2) != Some(&"IPv4") {
+                return None;
+            }
+            let address: IpAddr = fields.get(7)?.parse().ok()?;
+            let port: u16 = fields.get(8)?.parse().ok()?;
+            let device_id = fields
+                .get(9)?
+                .split('"')
+                .find_map(|record| record.strip_prefix("device="))?;
+            Some((device_id.to_string(), SocketAddr::new(address, port)))
+        })
+        .collect();
+    found.sort();
+    found.dedup_by(|a, b| a.0 == b.0);
+    found
+}
+
+async fn exchange_all(app: &AppHandle, device: LocalDevice) {
+    let Ok(peers) = tauri::async_runtime::spawn_blocking(discover).await else {
+        return;
+    };
+    for (peer_id, address) in peers {
+        if peer_id == device.device_id {
+            continue;
+        }
+        match timeout(EXCHANGE_TIMEOUT, exchange_with(address, &device)).await {
+            Ok(Ok(report)) => record(app, &report, address.ip()),
+            Ok(Err(error)) => tracing::debug!(%error, peer = %peer_id, "peer exchange failed"),
+            Err(_) => tracing::debug!(peer = %peer_id, "peer exchange timed out"),
+        }
+    }
+}
+
+async fn exchange_with(address: SocketAddr, device: &LocalDevice) -> Result<Report, String> {
+    let stream = TcpStream::connect(address)
+        .await
+        .map_err(|e| e.to_string())?;
+    let (reader, mut writer) = stream.into_split();
+    let mut lines = BufReader::new(reader.take(MAX_MESSAGE_BYTES)).lines();
+
+    let challenge: Challenge = receive(&mut lines).await?;
+    let nonce = random_nonce()?;
+    send(&mut writer, &device.report(&challenge.nonce, nonce.clone())).await?;
+
+    let reply: Report = receive(&mut lines).await?;
+    if !device.accepts(&reply, &nonce) {
+        return Err("peer failed authentication".into());
+    }
+    Ok(reply)
+}
+
+async fn serve(app: AppHandle, listener: TcpListener) {
+    loop {
+        let (stream, address) = match listener.accept().await {
+            Ok(accepted) => accepted,
+            Err(error) => {
+                // Usually out of file descriptors; retrying at once would spin.
+                tracing::warn!(%error, "failed to accept peer connection");
+                tokio::time::sleep(ACCEPT_RETRY_DELAY).await;
+                continue;
+            }
+        };
+        let app = app.clone();
+        tauri::async_runtime::spawn(async move {
+            match timeout(EXCHANGE_TIMEOUT, handle_peer(&app, stream)).await {
+                Ok(Ok(report)) => record(&app, &report, address.ip()),
+                Ok(Err(error)) => tracing::debug!(%error, %address, "peer connection rejected"),
+                Err(_) => tracing::debug!(%address, "peer connection timed out"),
+            }
+        });
+    }
+}
+
+async fn handle_peer(app: &AppHandle, stream: TcpStream) -> Result<Report, String> {
+    let device = local_device(app).ok_or_else(|| "peer sharing disabled".to_string())?;
+    let (reader, mut writer) = stream.into_split();
+    let mut lines = BufReader::new(reader.take(MAX_MESSAGE_BYTES)).lines();
+
+    let nonce = random_nonce()?;
+    send(
+        &mut writer,
+        &Challenge {
+            nonce: nonce.clone(),
+        },
+    )
+    .await?;
+
+    let report: Report = receive(&mut lines).await?;
+    if !device.accepts(&report, &nonce) {
+        return Err("peer failed authentication".into());
+    }
+    send(&mut writer, &device.report(&report.nonce, String::new())).await?;
+    Ok(report)
+}
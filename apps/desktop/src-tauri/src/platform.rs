@@ -0,0 +1,114 @@
+use std::process::Command;
+
+use serde::Serialize;
+
+use crate::{dbus, notify};
+
+// Bare compositors that do not run XDG autostart entries on their own.
+const NO_XDG_AUTOSTART: [&str; 6] = ["sway", "hyprland", "i3", "river", "niri", "wayfire"];
+// Wayland compositors implementing wlr-layer-shell; Mutter does not.
+const LAYER_SHELL: [&str; 8] = [
+    "sway", "hyprland", "river", "niri", "wayfire", "labwc", "kde", "cosmic",
+];
+
+#[derive(Clone, Debug, Serialize)]
+pub struct PlatformCapabilitiesDto {
+    pub desktop: Option<String>,
+    /// `wayland`, `x11` or `unknown`.
+    pub session_type: String,
+    pub flatpak: bool,
+    /// `mutter`, `screensaver` or `xprintidle` when idle time can be read.
+    pub idle_source: Option<String>,
+    pub layer_shell: bool,
+    pub overlay_on_top: bool,
+    pub notification_server: Option<String>,
+    pub notification_actions: bool,
+    pub xdg_autostart: bool,
+    pub systemd_user: bool,
+    pub tray: bool,
+}
+
+pub fn probe() -> PlatformCapabilitiesDto {
+    let var = |key: &str| std::env::var(key).ok().filter(|value| !value.is_empty());
+    let session_type = match var("XDG_SESSION_TYPE").as_deref() {
+        Some("wayland") => "wayland",
+        Some("x11") => "x11",
+        _ if var("WAYLAND_DISPLAY").is_some() => "wayland",
+        _ if var("DISPLAY").is_some() => "x11",
+        _ => "unknown",
+    }
+    .to_string();
+    let desktop = var("XDG_CURRENT_DESKTOP");
+    let flatpak = var("FLATPAK_ID").is_some();
+    let names = dbus::session_names();
+    let desktop_is = |list: &[&str]| {
+        desktop.as_deref().is_some_and(|desktop| {
+            desktop
+                .split(':')
+                .any(|name| list.iter().any(|entry| name.eq_ignore_ascii_case(entry)))
+        })
+    };
+
+    // GTK has no keep-above on native Wayland; it only works through XWayland.
+    let overlay_on_top = session_type != "wayland"
+        || var("GDK_BACKEND").is_some_and(|backend| backend.starts_with("x11"));
+    let (notification_server, notification_actions) =
+        notify::detect().map_or((None, false), |(server, capabilities)| {
+            let actions = capabilities
+                .iter()
+                .any(|capability| capability == "actions");
+            (Some(server), actions)
+        });
+
+    PlatformCapabilitiesDto {
+        idle_source: idle_source(&names, &session_type),
+        layer_shell: session_type == "wayland" && desktop_is(&LAYER_SHELL),
+        overlay_on_top,
+        notification_server,
+        notification_actions,
+        xdg_autostart: desktop.is_some() && !desktop_is(&NO_XDG_AUTOSTART),
+        systemd_user: !flatpak && systemd_user_available(),
+        tray: names
+            .iter()
+            .any(|name| name == "org.kde.StatusNotifierWatcher"),
+        desktop,
+        session_type,
+        flatpak,
+    }
+}
+
+fn idle_source(names: &[String], session_type: &str) -> Option<String> {
+    if names
+        .iter()
+        .any(|name| name == "org.gnome.Mutter.IdleMonitor")
+    {
+        return Some("mutter".into());
+    }
+    // GNOME owns this name too but answers GetSessionIdleTime with an error.
+    if dbus::session_call(
+        "org.freedesktop.ScreenSaver",
+        "/org/freedesktop/ScreenSaver",
+        "org.freedesktop.ScreenSaver.GetSessionIdleTime",
+        &[],
+    )
+    .is_some()
+    {
+        return Some("screensaver".into());
+    }
+    let xprintidle = session_type == "x11"
+        && Command::new("xprintidle")
+            .output()
+            .is_ok_and(|output| output.status.success());
+    xprintidle.then(|| "xprintidle".into())
+}
+
+// `degraded` still means the user manager is up and can start services.
+fn systemd_user_available() -> bool {
+    Command::new("systemctl")
+        .args(["--user", "is-system-running"])
+        .output()
+        .is_ok_and(|output| {
+            let state = String::from_utf8_lossy(&output.stdout);
+            matches!(state.trim(), "running" | "degraded" | "starting")
+        })
+}
@@ -0,0 +1,99 @@
+use std::path::PathBuf;
+
+use lazaro_core::policy::Policy;
+use serde::Serialize;
+
+use crate::config_file::value_to_string;
+
+const DEFAULT_POLICY_PATH: &str = "/etc/lazaro/policy.toml";
+
+#[derive(Clone, Debug, Serialize)]
+pub struct PolicyMinimumDto {
+    pub field: String,
+    pub minimum: u64,
+}
+
+#[derive(Clone, Debug, Default, Serialize)]
+pub struct PolicyInfoDto {
+    pub path: String,
+    pub active: bool,
+    pub locked_fields: Vec<String>,
+    pub minimums: Vec<PolicyMinimumDto>,
+    pub errors: Vec<String>,
+}
+
+pub fn policy_path() -> PathBuf {
+    std::env::var("LAZARO_POLICY_PATH")
+        .ok()
+        .filter(|value| !value.is_empty())
+        .map(PathBuf::from)
+        .unwrap_or_else(|| PathBuf::from(DEFAULT_POLICY_PATH))
+}
+
+pub fn load() -> (Policy, Vec<String>) {
+    let mut policy = Policy::default();
+    let mut errors = Vec::new();
+
+    let path = policy_path();
+    if !path.exists() {
+        return (policy, errors);
+    }
+
+    let table = match std::fs::read_to_string(&path)
+        .map_err(|e| e.to_string())
+        .and_then(|raw| raw.parse::<toml::Table>().map_err(|e| e.to_string()))
+    {
+        Ok(table) => table,
+        Err(error) => {
+            errors.push(format!("policy.toml: {error}"));
+            return (policy, errors);
+        }
+    };
+
+    if let Some(locked) = table.get("locked").and_then(|value| value.as_table()) {
+        for (key, value) in locked {
+            if let Err(error) = policy.lock(key, &value_to_string(value)) {
+                errors.push(error.to_string());
+            }
+        }
+    }
+
+    if let Some(minimums) = table.get("minimum").and_then(|value| value.as_table()) {
+        for (key, value) in minimums {
+            let result = value
+                .as_integer()
+                .and_then(|minimum| u64::try_from(minimum).ok())
+                .ok_or_else(|| format!("invalid minimum for {key}: {value}"))
+                .and_then(|minimum| {
+                    policy
+                        .require_minimum(key, minimum)
+                        .map_err(|error| error.to_string())
+                });
+            if let Err(error) = result {
+                errors.push(error);
+            }
+        }
+    }
+
+    for error in &errors {
+        tracing::warn!(%error, "ignoring policy entry");
+    }
+    (policy, errors)
+}
+
+pub fn info() -> PolicyInfoDto {
+    let (policy, errors) = load();
+    PolicyInfoDto {
+        path: policy_path().display().to_string(),
+        active: !policy.is_empty(),
+        locked_fields: policy.locked_fields().map(str::to_string).collect(),
+        minimums: policy
+            .minimums()
+            .map(|(field, minimum)| PolicyMinimumDto {
+                field: field.to_string(),
+                minimum,
+            })
+            .collect(),
+        errors,
+    }
+}
@@ -0,0 +1,43 @@
+use crate::dbus;
+
+const UPOWER: &str = "org.freedesktop.UPower";
+// power-profiles-daemon moved to this name in 0.20 but still answers on the old one.
+const POWER_PROFILES: [(&str, &str); 2] = [
+    (
+        "org.freedesktop.UPower.PowerProfiles",
+        "/org/freedesktop/UPower/PowerProfiles",
+    ),
+    ("net.hadess.PowerProfiles", "/net/hadess/PowerProfiles"),
+];
+// Treated like a saver profile when running on battery without one.
+const LOW_BATTERY_PERCENT: f64 = 20.0;
+
+pub fn saver_active() -> bool {
+    power_saver_profile()
+        || (on_battery() && battery_percent().is_some_and(|percent| percent <= LOW_BATTERY_PERCENT))
+}
+
+fn power_saver_profile() -> bool {
+    POWER_PROFILES.iter().any(|(dest, path)| {
+        dbus::system_property(dest, path, dest, "ActiveProfile")
+            .is_some_and(|value| value.contains("'power-saver'"))
+    })
+}
+
+fn on_battery() -> bool {
+    dbus::system_property(UPOWER, "/org/freedesktop/UPower", UPOWER, "OnBattery")
+        .is_some_and(|value| value.contains("<true>"))
+}
+
+// gdbus prints the variant as `(<42.0>,)`.
+fn battery_percent() -> Option<f64> {
+    let raw = dbus::system_property(
+        UPOWER,
+        "/org/freedesktop/UPower/devices/DisplayDevice",
+        "org.freedesktop.UPower.Device",
+        "Percentage",
+    )?;
+    let start = raw.find('<')? + 1;
+    let end = raw[start..].find('>')? + start;
+    raw[start..end].trim().parse().ok()
+}
@@ -0,0 +1,360 @@
+use std::{io::Read, sync::OnceLock, time::Duration};
+
+use serde::Serialize;
+use tauri::{AppHandle, Manager, async_runtime::JoinHandle};
+use tokio::{
+    io::{AsyncReadExt, AsyncWrite, AsyncWriteExt},
+    net::{TcpListener, TcpStream},
+    sync::broadcast::{self, error::RecvError},
+    time::timeout,
+};
+
+use crate::{
+    AppError, BackendState, SettingsDto, commit_settings, get_daily_summary, get_runtime_status,
+    get_weekly_stats, parse_break_kind, snooze_pending_break, start_pending_break, trigger_break,
+    websocket,
+};
+
+const SUPERVISE_INTERVAL: Duration = Duration::from_secs(2);
+const MAX_REQUEST_BYTES: usize = 8 * 1024;
+const EVENT_BUFFER: usize = 64;
+const TOKEN_BYTES: usize = 16;
+/// A client that has not sent its headers by then is dropped, so idle
+/// connections cannot pile up.
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+const ACCEPT_RETRY_DELAY: Duration = Duration::from_secs(1);
+
+static EVENTS: OnceLock<broadcast::Sender<String>> = OnceLock::new();
+
+fn events() -> &'static broadcast::Sender<String> {
+    EVENTS.get_or_init(|| broadcast::channel(EVENT_BUFFER).0)
+}
+
+/// Forwards a runtime event or status snapshot to connected WebSocket clients.
+pub fn publish<T: Serialize>(kind: &str, payload: &T) {
+    let sender = events();
+    if sender.receiver_count() == 0 {
+        return;
+    }
+    let message = serde_json::json!({ "type": kind, "payload": payload });
+    let _ = sender.send(message.to_string());
+}
+
+pub fn spawn_supervisor(app: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        let mut server: Option<(u16, JoinHandle<()>)> = None;
+        let mut failed_port = None;
+        let mut ticker = tokio::time::interval(SUPERVISE_INTERVAL);
+
+        loop {
+            ticker.tick().await;
+            let Some(settings) = current_settings(&app) else {
+                continue;
+            };
+            if settings.remote_enabled && settings.remote_token.is_empty() {
+                generate_token(&app, settings);
+                continue;
+            }
+
+            let wanted = settings.remote_enabled.then_some(settings.remote_port);
+            if server.as_ref().map(|(port, _)| *port) == wanted {
+                continue;
+            }
+            if let Some((port, handle)) = server.take() {
+                handle.abort();
+                tracing::info!(port, "remote server stopped");
+            }
+            let Some(port) = wanted else {
+                failed_port = None;
+                continue;
+            };
+
+            match TcpListener::bind(("127.0.0.1", port)).await {
+                Ok(listener) => {
+                    tracing::info!(port, "remote server listening on localhost");
+                    failed_port = None;
+                    server = Some((
+                        port,
+                        tauri::async_runtime::spawn(serve(app.clone(), listener)),
+                    ));
+                }
+                Err(error) => {
+                    if failed_port != Some(port) {
+                        tracing::warn!(%error, port, "failed to bind remote server");
+                    }
+                    failed_port = Some(port);
+                }
+            }
+        }
+    });
+}
+
+fn current_settings(app: &AppHandle) -> Option<SettingsDto> {
+    let state = app.state::<BackendState>();
+    let guard = state.persistent.data.lock().ok()?;
+    Some(guard.effective.clone())
+}
+
+fn generate_token(app: &AppHandle, mut settings: SettingsDto) {
+    let mut bytes = [0u8; TOKEN_BYTES];
+    if let Err(error) =
+        std::fs::File::open("/dev/urandom").and_then(|mut file| file.read_exact(&mut bytes))
+    {
+        tracing::warn!(%error, "failed to generate remote token");
+        return;
+    }
+
+    settings.remote_token = bytes.iter().map(|byte| format!("{byte:02x}")).collect();
+    match commit_settings(&app.state::<BackendState>(), settings) {
+        Ok(_) => tracing::info!("generated remote access token"),
+        Err(error) => tracing::warn!(%error, "failed to store remote token"),
+    }
+}
+
+async fn serve(app: AppHandle, listener: TcpListener) {
+    loop {
+        let stream = match listener.accept().await {
+            Ok((stream, _)) => stream,
+            Err(error) => {
+                // Usually out of file descriptors; retrying at once would spin.
+                tracing::warn!(%error, "failed to accept remote connection");
+                tokio::time::sleep(ACCEPT_RETRY_DELAY).await;
+                continue;
+            }
+        };
+        let app = app.clone();
+        tauri::async_runtime::spawn(async move {
+            if let Err(error) = handle_connection(app, stream).await {
+                tracing::debug!(%error, "remote connection closed");
+            }
+        });
+    }
+}
+
+struct Request {
+    method: String,
+    path: String,
+    query: String,
+    headers: Vec<(String, String)>,
+}
+
+impl Request {
+    fn parse(raw: &str) -> Option<Self> {
+        let mut lines = raw.split("\r\n");
+        let mut request_line = lines.next()?.split_whitespace();
+        let method = request_line.next()?.to_string();
+        let target = request_line.next()?;
+        let (path, query) = target.split_once('?').unwrap_or((target, ""));
+
+        let headers = lines
+            .take_while(|line| !line.is_empty())
+            .filter_map(|line| line.split_once(':'))
+            .map(|(name, value)| (name.trim().to_ascii_lowercase(), value.trim().to_string()))
+            .collect();
+
+        Some(Self {
+            method,
+            path: path.to_string(),
+            query: query.to_string(),
+            headers,
+        })
+    }
+
+    fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(header, _)| header == name)
+            .map(|(_, value)| value.as_str())
+    }
+
+    // Browsers cannot set headers on WebSocket connections, so the token may
+    // also be passed as `?token=`.
+    fn token(&self) -> Option<&str> {
+        self.header("authorization")
+            .and_then(|value| value.strip_prefix("Bearer "))
+            .or_else(|| {
+                self.query
+                    .split('&')
+                    .find_map(|pair| pair.strip_prefix("token="))
+            })
+    }
+}
+
+pub fn token_matches(given: &str, expected: &str) -> bool {
+    given.len() == expected.len()
+        && given
+            .bytes()
+            .zip(expected.bytes())
+            .fold(0u8, |diff, (a, b)| diff | (a ^ b))
+            == 0
+}
+
+async fn read_request(stream: &mut TcpStream) -> std::io::Result<Option<Request>> {
+    let mut buffer = Vec::new();
+    let mut chunk = [0u8; 1024];
+    while !buffer.windows(4).any(|window| window == b"\r\n\r\n") {
+        let read = stream.read(&mut chunk).await?;
+        if read == 0 || buffer.len() + read > MAX_REQUEST_BYTES {
+            return Ok(None);
+        }
+        buffer.extend_from_slice(&chunk[..read]);
+    }
+    Ok(Request::parse(&String::from_utf8_lossy(&buffer)))
+}
+
+async fn handle_connection(app: AppHandle, mut stream: TcpStream) -> std::io::Result<()> {
+    let Ok(request) = timeout(REQUEST_TIMEOUT, read_request(&mut stream)).await else {
+        return respond(&mut stream, 408, &error_body("request timeout")).await;
+    };
+    let Some(request) = request? else {
+        return respond(&mut stream, 400, &error_body("bad request")).await;
+    };
+    let Some(settings) = current_settings(&app) else {
+        return respond(&mut stream, 503, &error_body("state unavailable")).await;
+    };
+    if !settings.remote_enabled {
+        return respond(&mut stream, 503, &error_body("remote access disabled")).await;
+    }
+    if settings.remote_token.is_empty()
+        || !request
+            .token()
+            .is_some_and(|token| token_matches(token, &settings.remote_token))
+    {
+        return respond(&mut stream, 401, &error_body("unauthorized")).await;
+    }
+
+    tracing::debug!(method = %request.method, path = %request.path, "remote request");
+    let (status, body) = match (request.method.as_str(), request.path.as_str()) {
+        ("GET", "/api/events") => return stream_events(stream, &request, &app).await,
+        ("GET", "/api/status") => json_result(get_runtime_status(app.state())),
+        ("GET", "/api/stats") => json_result(get_weekly_stats(app.state())),
+        ("GET", "/api/today") => json_result(get_daily_summary(app.state(), None)),
+        ("POST", "/api/breaks/start") => json_result(start_pending_break(app.state())),
+        ("POST", "/api/breaks/snooze") => json_result(snooze_pending_break(app.state())),
+        ("POST", path) if path.starts_with("/api/breaks/") => {
+            let kind = parse_break_kind(path.trim_start_matches("/api/breaks/"));
+            json_result(kind.and_then(|kind| trigger_break(kind, app.state())))
+        }
+        (_, "/api/events" | "/api/status" | "/api/stats" | "/api/today") => {
+            (405, error_body("method not allowed"))
+        }
+        _ => (404, error_body("not found")),
+    };
+    respond(&mut stream, status, &body).await
+}
+
+fn json_result<T: Serialize>(result: Result<T, AppError>) -> (u16, String) {
+    match result {
+        Ok(value) => (
+            200,
+            serde_json::to_string(&value).unwrap_or_else(|_| "null".into()),
+        ),
+        Err(error) => {
+            let status = match error {
+                AppError::RuntimeNotRunning => 409,
+                AppError::InvalidBreakKind(_) => 400,
+                _ => 500,
+            };
+            (status, error_body(&error.to_string()))
+        }
+    }
+}
+
+fn error_body(message: &str) -> String {
+    serde_json::json!({ "error": message }).to_string()
+}
+
+fn reason_phrase(status: u16) -> &'static str {
+    match status {
+        200 => "OK",
+        400 => "Bad Request",
+        401 => "Unauthorized",
+        404 => "Not Found",
+        405 => "Method Not Allowed",
+        408 => "Request Timeout",
+        409 => "Conflict",
+        503 => "Service Unavailable",
+        _ => "Internal Server Error",
+    }
+}
+
+async fn respond(stream: &mut TcpStream, status: u16, body: &str) -> std::io::Result<()> {
+    let response = format!(
+        "HTTP/1.1 {status} {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+        reason_phrase(status),
+        body.len()
+    );
+    stream.write_all(response.as_bytes()).await?;
+    stream.shutdown().await
+}
+
+async fn stream_events(
+    mut stream: TcpStream,
+    request: &Request,
+    app: &AppHandle,
+) -> std::io::Result<()> {
+    let Some(key) = request.header("sec-websocket-key").filter(|_| {
+        request
+            .header("upgrade")
+            .is_some_and(|value| value.eq_ignore_ascii_case("websocket"))
+    }) else {
+        return respond(&mut stream, 400, &error_body("websocket upgrade required")).await;
+    };
+
+    let handshake = format!(
+        "HTTP/1.1 101 Switching Protocols\r\nUpgrade: websocket\r\nConnection: Upgrade\r\nSec-WebSocket-Accept: {}\r\n\r\n",
+        websocket::accept_key(key)
+    );
+    stream.write_all(handshake.as_bytes()).await?;
+
+    let mut receiver = events().subscribe();
+    if let Ok(status) = get_runtime_status(app.state()) {
+        let message = serde_json::json!({ "type": "status", "payload": status });
+        send_text(&mut stream, &message.to_string()).await?;
+    }
+
+    let (mut reader, mut writer) = stream.split();
+    let mut buffer = Vec::new();
+    let mut chunk = [0u8; 512];
+    loop {
+        tokio::select! {
+            message = receiver.recv() => match message {
+                Ok(message) => send_text(&mut writer, &message).await?,
+                Err(RecvError::Lagged(skipped)) => {
+                    tracing::debug!(skipped, "remote client lagging behind events");
+                }
+                Err(RecvError::Closed) => return Ok(()),
+            },
+            read = reader.read(&mut chunk) => {
+                let read = read?;
+                if read == 0 || buffer.len() + read > MAX_REQUEST_BYTES {
+                    return Ok(());
+                }
+                buffer.extend_from_slice(&chunk[..read]);
+                while let Some((opcode, payload, length)) = websocket::parse_frame(&buffer) {
+                    buffer.drain(..length);
+                    match opcode {
+                        websocket::OPCODE_CLOSE => {
+                            writer
+                                .write_all(&websocket::frame(websocket::OPCODE_CLOSE, &[]))
+                                .await?;
+                            return Ok(());
+                        }
+                        websocket::OPCODE_PING => {
+                            writer
+                                .write_all(&websocket::frame(websocket::OPCODE_PONG, &payload))
+                                .await?;
+                        }
+                        _ => {}
+                    }
+                }
+            }
+        }
+    }
+}
+
+async fn send_text<W: AsyncWrite + Unpin>(writer: &mut W, text: &str) -> std::io::Result<()> {
+    writer
+        .write_all(&websocket::frame(websocket::OPCODE_TEXT, text.as_bytes()))
+        .await
+}
@@ -0,0 +1,148 @@
+use std::{fs, io::Read, path::Path, sync::Mutex};
+
+use serde::Serialize;
+
+use crate::{
+    AppError, AppState, AppStateOnDisk, crash, logging, remote, storage, sync, unix_now, vault,
+};
+
+const CONFIRMATION_TTL_SECONDS: u64 = 60;
+const TOKEN_BYTES: usize = 8;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ResetScope {
+    /// Weekly and daily stats plus break history.
+    Stats,
+    /// Every profile but the default one.
+    Profiles,
+    /// All persisted state, logs, crash reports and the encryption key.
+    Everything,
+}
+
+impl ResetScope {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "stats" => Some(ResetScope::Stats),
+            "profiles" => Some(ResetScope::Profiles),
+            "everything" => Some(ResetScope::Everything),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Serialize)]
+#[serde(tag = "status", rename_all = "snake_case")]
+pub enum ResetResponseDto {
+    /// Call again with this token within the expiry to actually reset.
+    ConfirmationRequired { token: String, expires_at: u64 },
+    Done {
+        removed_files: Vec<String>,
+        runtime_restarted: bool,
+    },
+}
+
+static PENDING: Mutex<Option<(ResetScope, String, u64)>> = Mutex::new(None);
+
+pub fn challenge(scope: ResetScope) -> Result<ResetResponseDto, AppError> {
+    let mut bytes = [0u8; TOKEN_BYTES];
+    fs::File::open("/dev/urandom").and_then(|mut file| file.read_exact(&mut bytes))?;
+    let token: String = bytes.iter().map(|byte| format!("{byte:02x}")).collect();
+    let expires_at = unix_now().saturating_add(CONFIRMATION_TTL_SECONDS);
+
+    *PENDING.lock()? = Some((scope, token.clone(), expires_at));
+    Ok(ResetResponseDto::ConfirmationRequired { token, expires_at })
+}
+
+/// Consumes the pending token; it is single use even when it does not match.
+pub fn confirm(scope: ResetScope, token: &str) -> Result<(), AppError> {
+    let pending = PENDING.lock()?.take();
+    match pending {
+        Some((pending_scope, pending_token, expires_at))
+            if pending_scope == scope
+                && remote::token_matches(token, &pending_token)
+                && unix_now() <= expires_at =>
+        {
+            Ok(())
+        }
+        _ => Err(AppError::ResetNotConfirmed),
+    }
+}
+
+/// Wipes `scope` from `state` and the data directory. The runtime must be
+/// stopped first so it does not write the old data back.
+pub fn apply(
+    state: &AppState,
+    scope: ResetScope,
+    data_dir: &Path,
+) -> Result<Vec<String>, AppError> {
+    let mut removed_files = Vec::new();
+    {
+        let mut guard = state.data.lock()?;
+        let defaults = AppStateOnDisk::default();
+        match scope {
+            ResetScope::Stats => {
+                guard.weekly_stats = defaults.weekly_stats;
+                guard.daily_stats.clear();
+                guard.break_history.clear();
+                guard.focus_sessions.clear();
+                guard.intensity.clear();
+            }
+            ResetScope::Profiles => {
+                guard.profiles = defaults.profiles;
+                guard.settings.active_profile_id = "default".into();
+                guard.resolve_effective();
+                guard.sync.profiles_updated_at = unix_now();
+            }
+            ResetScope::Everything => {
+                *guard = defaults;
+                guard.sync.device_id = sync::new_device_id();
+                // Plain defaults are saved; overrides only reach `effective`.
+                guard.resolve_effective();
+            }
+        }
+    }
+
+    if matches!(scope, ResetScope::Stats | ResetScope::Everything) {
+        if let Ok(mut synced) = state.synced_days.lock() {
+            synced.clear();
+        }
+        if let Ok(mut peers) = state.peer_active.lock() {
+            peers.clear();
+        }
+    }
+
+    if scope == ResetScope::Everything {
+        if let Err(error) = vault::forget_key() {
+            tracing::warn!(%error, "failed to remove state encryption key");
+        }
+        let crash_report = data_dir.join(crash::CRASH_REPORT_FILE);
+        if fs::remove_file(&crash_report).is_ok() {
+            removed_files.push(crash::CRASH_REPORT_FILE.to_string());
+        }
+        for dir in [data_dir.to_path_buf(), logging::log_dir(data_dir)] {
+            let Ok(entries) = fs::read_dir(&dir) else {
+                continue;
+            };
+            for entry in entries.flatten() {
+                let name = entry.file_name().to_string_lossy().into_owned();
+                let stale = name.starts_with("state.json.sealed-")
+                    || name.starts_with("state.json.corrupt-")
+                    || (dir != data_dir && entry.path().is_file());
+                if stale && fs::remove_file(entry.path()).is_ok() {
+                    removed_files.push(name);
+                }
+            }
+        }
+    }
+
+    let sections = match scope {
+        ResetScope::Stats => storage::Sections::STATS
+            .union(storage::Sections::BREAK_HISTORY)
+            .union(storage::Sections::FOCUS_SESSIONS),
+        ResetScope::Profiles => storage::Sections::DOCUMENT,
+        ResetScope::Everything => storage::Sections::ALL,
+    };
+    state.save(sections)?;
+    tracing::info!(?scope, removed = removed_files.len(), "data reset");
+    Ok(removed_files)
+}
@@ -0,0 +1,2042 @@
+use std::{
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+use lazaro_core::{
+    analytics::{MilestoneKind, MilestoneProgress, SessionOutcome},
+    clock::{Clock, ElapsedMillis, ElapsedSeconds, SystemClock},
+    config::{AppBlockAction, BlockLevel, DailyLimitAction, PowerSaverBehavior, Settings},
+    event::RuntimeEvent,
+    nudge::{Nudge, NudgeInput, NudgeTracker},
+    timer::{
+        BreakKind, BreakProgress, DeferReason, EngineEvent, EngineSnapshot, EscalationStage,
+        TimerEngine, WindDownPhase,
+    },
+};
+use tauri::{AppHandle, Manager};
+use tokio::{
+    sync::{
+        mpsc::{self, UnboundedReceiver},
+        oneshot, watch,
+    },
+    time::{self, MissedTickBehavior},
+};
+
+use crate::{
+    AppState, BackendState, DailyStatusDto, EngineSnapshotDto, RuntimeEventDto, RuntimeStatusDto,
+    ScheduleEntryDto, SettingsDto, app_usage, blocking, calendar, close_overlay, crash,
+    defer_reason_to_string, dimming, dnd, emission, emit_runtime_event, emit_status_tick, history,
+    i18n, inhibit, integrations, intensity, keys, logging, open_overlay, overlay, play_cue, power,
+    send_notification, send_urgent_notification, session, settings_to_core, sound, storage,
+    summary, taskbar, tray, widget,
+};
+
+const INHIBIT_POLL_TICKS: u64 = 15;
+const ADAPT_INTERVAL_TICKS: u64 = 300;
+// One tick per second, so each sample is an events-per-minute rate.
+const INTENSITY_SAMPLE_TICKS: u64 = 60;
+const APP_USAGE_SAMPLE_TICKS: u64 = 5;
+const NUDGE_CHECK_TICKS: u64 = 60;
+const POWER_SAVER_EMIT_INTERVAL_SECONDS: u64 = 5;
+const POWER_SAVER_POLL_FACTOR: u64 = 4;
+const LIMIT_ACTION_GRACE_SECONDS: u64 = 60;
+// Longest activity credited for one tick; a loop stalled for longer (say,
+// stopped in a debugger) does not fire every timer at once when it resumes.
+const MAX_TICK_SECONDS: u64 = 30;
+// A loop that ran this long before crashing starts a fresh restart budget.
+const CRASH_WINDOW: Duration = Duration::from_secs(600);
+const MAX_CRASH_RESTARTS: u32 = 3;
+// Settings read by `refresh_inhibitor`; other changes wait for the next poll.
+const INHIBITOR_KEYS: &[&str] = &[
+    "respect_system_dnd",
+    "defer_breaks_on_dnd",
+    "calendar_enabled",
+    "calendar_source_path",
+    "calendar_meeting_policy",
+    "inhibit_on_fullscreen",
+    "inhibit_on_media",
+    "presentation_on_screencast",
+    "power_saver_behavior",
+    "block_level",
+    "micro_block_level",
+    "rest_block_level",
+    "custom_timers",
+];
+
+pub enum RuntimeControl {
+    Stop,
+    // Boxed, as the settings dwarf every other message.
+    UpdateSettings {
+        core: Box<Settings>,
+        dto: Box<SettingsDto>,
+    },
+    StartBreak(BreakKind),
+    StartPending,
+    SnoozePending,
+    BreakKey(keys::KeyAction),
+    CancelLimitAction,
+    SetVacation(Option<u64>),
+    SetGuest(Option<u64>),
+    SetPresentation(Option<u64>),
+    ForceDailyReset,
+    /// The overlay of a running break was closed from outside the runtime.
+    OverlayClosed(BreakKind),
+    OverlayFailed(BreakKind),
+    /// The overlay's skip button, already checked against its skip rule.
+    EndBreakEarly(BreakKind),
+    /// Snoozes a pending break for a time the user picked.
+    PostponePending(BreakKind, u64),
+    /// Answered as soon as the loop gets to it; no answer means it is wedged.
+    Ping(oneshot::Sender<()>),
+}
+
+impl RuntimeControl {
+    fn name(&self) -> &'static str {
+        match self {
+            RuntimeControl::Stop => "stop",
+            RuntimeControl::UpdateSettings { .. } => "update_settings",
+            RuntimeControl::StartBreak(_) => "start_break",
+            RuntimeControl::StartPending => "start_pending",
+            RuntimeControl::SnoozePending => "snooze_pending",
+            RuntimeControl::BreakKey(_) => "break_key",
+            RuntimeControl::CancelLimitAction => "cancel_limit_action",
+            RuntimeControl::SetVacation(_) => "set_vacation",
+            RuntimeControl::SetGuest(_) => "set_guest",
+            RuntimeControl::SetPresentation(_) => "set_presentation",
+            RuntimeControl::ForceDailyReset => "force_daily_reset",
+            RuntimeControl::OverlayClosed(_) => "overlay_closed",
+            RuntimeControl::OverlayFailed(_) => "overlay_failed",
+            RuntimeControl::EndBreakEarly(_) => "end_break_early",
+            RuntimeControl::PostponePending(..) => "postpone_pending",
+            RuntimeControl::Ping(_) => "ping",
+        }
+    }
+}
+
+async fn run(
+    app: AppHandle,
+    persistent: Arc<AppState>,
+    status: watch::Sender<RuntimeStatusDto>,
+    mut rx: UnboundedReceiver<RuntimeControl>,
+    core_settings: Settings,
+    settings_dto: SettingsDto,
+    generation: u64,
+) {
+    let mut runtime = RuntimeLoop::new(
+        app,
+        persistent,
+        status,
+        core_settings,
+        settings_dto,
+        Box::new(SystemClock),
+    );
+    let mut ticker = time::interval(Duration::from_secs(1));
+    ticker.set_missed_tick_behavior(MissedTickBehavior::Delay);
+    let mut break_ticker = break_interval(runtime.break_tick_period());
+
+    loop {
+        let on_break = runtime.engine.active_break_info().is_some();
+        tokio::select! {
+            message = rx.recv() => {
+                match message {
+                    Some(RuntimeControl::Stop) | None => break,
+                    Some(message) => runtime.handle_control(message).await,
+                }
+                runtime.publish_status(runtime.now(), None);
+            }
+            _ = ticker.tick() => runtime.tick().await,
+            _ = break_ticker.tick(), if on_break => runtime.tick_break(),
+        }
+        // Follows settings changes and power saving.
+        if break_ticker.period() != runtime.break_tick_period() {
+            break_ticker = break_interval(runtime.break_tick_period());
+        }
+    }
+
+    // A loop abandoned as wedged that wakes up later must not tear down, or
+    // persist over, the runtime that replaced it.
+    if superseded(&runtime.app, generation) {
+        tracing::warn!("abandoned runtime woke up; leaving its successor alone");
+        return;
+    }
+    runtime.shutdown();
+}
+
+// Late ticks are skipped rather than bunched up: the countdown is measured
+// on the monotonic clock, so a skipped tick loses no time.
+fn break_interval(period: Duration) -> time::Interval {
+    let mut ticker = time::interval(period);
+    ticker.set_missed_tick_behavior(MissedTickBehavior::Skip);
+    ticker
+}
+
+fn superseded(app: &AppHandle, generation: u64) -> bool {
+    app.state::<BackendState>()
+        .runtime
+        .lock()
+        .is_ok_and(|runtime| runtime.generation != generation)
+}
+
+/// Runs the engine loop and notices when it dies from a panic rather than a
+/// `Stop`, so the app does not go on believing it is running. The loop is
+/// started again from the last persisted snapshot unless
+/// `restart_on_crash` is off or it keeps crashing.
+pub async fn supervise(
+    app: AppHandle,
+    persistent: Arc<AppState>,
+    status: watch::Sender<RuntimeStatusDto>,
+    mut rx: UnboundedReceiver<RuntimeControl>,
+    mut core_settings: Settings,
+    mut settings_dto: SettingsDto,
+    generation: u64,
+) {
+    let mut crashes = 0;
+    loop {
+        let started = Instant::now();
+        let restart_on_crash = core_settings.runtime.restart_on_crash;
+        let blocking_settings = core_settings.blocking.clone();
+        let lang = i18n::resolve(&settings_dto.language);
+        let result = tauri::async_runtime::spawn(run(
+            app.clone(),
+            Arc::clone(&persistent),
+            status.clone(),
+            rx,
+            core_settings,
+            settings_dto,
+            generation,
+        ))
+        .await;
+        let Err(error) = result else {
+            return;
+        };
+
+        tracing::error!(%error, "runtime crashed");
+        if started.elapsed() >= CRASH_WINDOW {
+            crashes = 0;
+        }
+        crashes += 1;
+        // The loop never reached `shutdown`; nothing may be left holding the
+        // screen or the hosts file.
+        close_overlay(&app);
+        blocking::release_stale(&blocking_settings);
+        if !superseded(&app, generation) {
+            status.send_replace(RuntimeStatusDto {
+                last_event: "runtime_crashed".into(),
+                ..RuntimeStatusDto::default()
+            });
+        }
+
+        let restart = restart_on_crash && crashes <= MAX_CRASH_RESTARTS;
+        let next = if restart {
+            restart_settings(&persistent)
+        } else {
+            None
+        };
+        let next_rx = {
+            let state = app.state::<BackendState>();
+            let Ok(mut runtime) = state.runtime.lock() else {
+                return;
+            };
+            match next {
+                // Stopped or replaced while crashing; leave it be.
+                _ if runtime.generation != generation => None,
+                Some(_) if runtime.tx.is_none() => None,
+                Some(_) => {
+                    let (tx, rx) = mpsc::unbounded_channel();
+                    runtime.tx = Some(tx);
+                    Some(rx)
+                }
+                None => {
+                    runtime.tx = None;
+                    runtime.handle = None;
+                    None
+                }
+            }
+        };
+
+        let restarted = next_rx.is_some();
+        let key = if restarted {
+            "runtime.crashed.restarted"
+        } else {
+            "runtime.crashed"
+        };
+        emit_runtime_event(
+            &app,
+            RuntimeEventDto::new(
+                RuntimeEvent::RuntimeCrashed { restarted },
+                i18n::text(lang, key),
+                false,
+            ),
+        );
+
+        let (Some(next_rx), Some((core, dto))) = (next_rx, next) else {
+            return;
+        };
+        tracing::warn!(crashes, "restarting runtime from its last snapshot");
+        rx = next_rx;
+        core_settings = core;
+        settings_dto = dto;
+    }
+}
+
+/// Current settings for a restart; they may have changed since the crashed
+/// loop started.
+fn restart_settings(persistent: &AppState) -> Option<(Settings, SettingsDto)> {
+    let dto = persistent.data.lock().ok()?.effective.clone();
+    match settings_to_core(&dto) {
+        Ok(core) => Some((core, dto)),
+        Err(error) => {
+            tracing::warn!(%error, "cannot restart runtime with stored settings");
+            None
+        }
+    }
+}
+
+fn resolve_inhibitor(
+    settings: &SettingsDto,
+    dnd_active: bool,
+    in_meeting: bool,
+    strict_mode: bool,
+) -> Option<DeferReason> {
+    if dnd_active && settings.defer_breaks_on_dnd && !strict_mode {
+        return Some(DeferReason::DoNotDisturb);
+    }
+    if in_meeting && settings.calendar_meeting_policy == "defer" && !strict_mode {
+        return Some(DeferReason::Meeting);
+    }
+    if settings.inhibit_on_fullscreen && inhibit::fullscreen_window_active() {
+        return Some(DeferReason::Fullscreen);
+    }
+    if settings.inhibit_on_media && inhibit::media_playing() {
+        return Some(DeferReason::MediaPlaying);
+    }
+    None
+}
+
+struct RuntimeLoop {
+    app: AppHandle,
+    /// Wall-clock time for scheduling and resets; activity is measured on
+    /// its monotonic side so NTP corrections neither add nor remove any.
+    clock: Box<dyn Clock + Send>,
+    elapsed: ElapsedSeconds,
+    /// Advances the active break's countdown, which ticks more often than
+    /// activity is counted.
+    break_elapsed: ElapsedMillis,
+    persistent: Arc<AppState>,
+    status: watch::Sender<RuntimeStatusDto>,
+    engine: TimerEngine,
+    /// Settings as saved, before day overrides. Everything else reads
+    /// today's from `engine.settings()`.
+    core_settings: Settings,
+    settings_dto: SettingsDto,
+    tick_counter: u64,
+    /// Active seconds not yet credited to an application.
+    app_usage_seconds: u64,
+    nudges: NudgeTracker,
+    dnd_active: bool,
+    in_meeting: bool,
+    power_saving: bool,
+    /// False while another user's session has the seat; no activity is
+    /// credited then.
+    session_active: bool,
+    limit_action_at: Option<u64>,
+    vacation_until: Option<u64>,
+    guest_until: Option<u64>,
+    presentation_until: Option<u64>,
+    /// A screencast is running and `presentation_on_screencast` is on; holds
+    /// presentation mode for as long as the capture lasts.
+    screen_capture: bool,
+    break_status_set: bool,
+    lang: i18n::Language,
+    dimmer: dimming::DimmingController,
+    blocker: blocking::BlockingController,
+    tray: tray::TrayProgress,
+    taskbar: taskbar::TaskbarProgress,
+    status_emitter: emission::StatusEmitter,
+}
+
+impl RuntimeLoop {
+    fn new(
+        app: AppHandle,
+        persistent: Arc<AppState>,
+        status: watch::Sender<RuntimeStatusDto>,
+        core_settings: Settings,
+        settings_dto: SettingsDto,
+        clock: Box<dyn Clock + Send>,
+    ) -> Self {
+        let (snapshot, vacation_until) = persistent
+            .data
+            .lock()
+            .map(|guard| (guard.engine.clone(), guard.vacation_until))
+            .unwrap_or_default();
+        let snapshot = snapshot.map(EngineSnapshot::from);
+        let engine = TimerEngine::restore_in_zone(
+            core_settings.clone(),
+            snapshot.as_ref(),
+            clock.now_unix(),
+            history::local_utc_offset(),
+        );
+        let lang = i18n::resolve(&settings_dto.language);
+        crash::record_settings(&settings_dto);
+        blocking::release_stale(&core_settings.blocking);
+        intensity::set_enabled(core_settings.intensity.enabled);
+        let mut elapsed = ElapsedSeconds::new(MAX_TICK_SECONDS);
+        elapsed.advance(clock.monotonic());
+        let mut break_elapsed = ElapsedMillis::new(MAX_TICK_SECONDS * 1000);
+        break_elapsed.advance(clock.monotonic());
+
+        tracing::info!("runtime started");
+        status.send_modify(|status| {
+            status.running = true;
+            status.strict_mode = matches!(core_settings.block_level, BlockLevel::Strict);
+            status.last_event = "runtime_started".into();
+        });
+
+        Self {
+            app,
+            clock,
+            elapsed,
+            break_elapsed,
+            persistent,
+            status,
+            engine,
+            core_settings,
+            settings_dto,
+            tick_counter: 0,
+            app_usage_seconds: 0,
+            nudges: NudgeTracker::default(),
+            dnd_active: false,
+            in_meeting: false,
+            power_saving: false,
+            session_active: true,
+            limit_action_at: None,
+            vacation_until,
+            guest_until: None,
+            presentation_until: None,
+            screen_capture: false,
+            break_status_set: false,
+            lang,
+            dimmer: dimming::DimmingController::default(),
+            blocker: blocking::BlockingController::default(),
+            tray: tray::TrayProgress::default(),
+            taskbar: taskbar::TaskbarProgress::default(),
+            status_emitter: emission::StatusEmitter::default(),
+        }
+    }
+
+    fn now(&self) -> u64 {
+        self.clock.now_unix()
+    }
+
+    /// Strictness of the break in progress or pending, otherwise the global
+    /// level.
+    fn block_level(&self) -> BlockLevel {
+        self.engine
+            .active_break_info()
+            .map(|(kind, _)| kind)
+            .or(self.engine.pending_break())
+            .map_or(self.engine.settings().block_level, |kind| {
+                self.engine.block_level(kind)
+            })
+    }
+
+    fn strict_mode(&self) -> bool {
+        matches!(self.block_level(), BlockLevel::Strict)
+    }
+
+    fn meeting_silenced(&self) -> bool {
+        self.in_meeting && self.settings_dto.calendar_meeting_policy == "silent"
+    }
+
+    fn quiet(&self) -> bool {
+        self.dnd_active || self.meeting_silenced() || self.presenting()
+    }
+
+    fn messages(&self) -> i18n::Messages<'_> {
+        i18n::Messages::new(self.lang, self.engine.settings())
+    }
+
+    /// Whether desktop notifications are held back.
+    fn notifications_blocked(&self) -> bool {
+        self.dnd_active || self.presenting()
+    }
+
+    fn presenting(&self) -> bool {
+        self.presentation_until.is_some() || self.screen_capture
+    }
+
+    fn muted(&self) -> bool {
+        self.quiet() || self.power_saving
+    }
+
+    fn break_tick_period(&self) -> Duration {
+        let millis = if self.power_saving {
+            1000
+        } else {
+            self.engine.settings().runtime.break_tick_millis
+        };
+        Duration::from_millis(millis)
+    }
+
+    fn emit_interval(&self) -> u64 {
+        let interval = self.engine.settings().runtime.tick_emit_interval_seconds;
+        if self.power_saving {
+            interval.max(POWER_SAVER_EMIT_INTERVAL_SECONDS)
+        } else {
+            interval
+        }
+    }
+
+    fn inhibit_poll_ticks(&self) -> u64 {
+        let slow = self.power_saving
+            && self.engine.settings().runtime.power_saver_behavior
+                == PowerSaverBehavior::QuietAndSlow;
+        if slow {
+            INHIBIT_POLL_TICKS * POWER_SAVER_POLL_FACTOR
+        } else {
+            INHIBIT_POLL_TICKS
+        }
+    }
+
+    async fn refresh_inhibitor(&mut self) {
+        let settings = self.settings_dto.clone();
+        let strict_mode = self.strict_mode();
+        let watch_power =
+            self.engine.settings().runtime.power_saver_behavior != PowerSaverBehavior::Ignore;
+        let now = self.now();
+        let probe = tokio::task::spawn_blocking(move || {
+            let dnd_active = settings.respect_system_dnd && dnd::system_dnd_active();
+            let in_meeting = settings.calendar_enabled
+                && lazaro_core::calendar::busy_at(
+                    &calendar::load_busy_windows(&settings.calendar_source_path),
+                    now,
+                )
+                .is_some();
+            (
+                dnd_active,
+                in_meeting,
+                resolve_inhibitor(&settings, dnd_active, in_meeting, strict_mode),
+                watch_power && power::saver_active(),
+                session::is_active(),
+                settings.presentation_on_screencast && inhibit::screencast_active(),
+            )
+        })
+        .await;
+
+        if let Ok((dnd_active, in_meeting, inhibitor, power_saving, session_active, capturing)) =
+            probe
+        {
+            if power_saving != self.power_saving {
+                tracing::info!(power_saving, "power saver state changed");
+            }
+            if inhibitor != self.engine.inhibitor() {
+                tracing::info!(
+                    ?inhibitor,
+                    dnd_active,
+                    in_meeting,
+                    "break inhibitor changed"
+                );
+            }
+            if session_active != self.session_active {
+                tracing::info!(session_active, "session activity changed");
+            }
+            self.dnd_active = dnd_active;
+            self.in_meeting = in_meeting;
+            self.power_saving = power_saving;
+            self.session_active = session_active;
+            self.engine.set_inhibitor(inhibitor);
+            if capturing != self.screen_capture {
+                self.set_screen_capture(capturing);
+            }
+        }
+    }
+
+    async fn handle_control(&mut self, message: RuntimeControl) {
+        tracing::debug!(control = message.name(), "runtime control received");
+        let starts_or_snoozes = matches!(
+            message,
+            RuntimeControl::StartBreak(_)
+                | RuntimeControl::StartPending
+                | RuntimeControl::SnoozePending
+                | RuntimeControl::PostponePending(..)
+                | RuntimeControl::BreakKey(_)
+        );
+        if starts_or_snoozes && self.tracking_paused() {
+            return;
+        }
+        match message {
+            RuntimeControl::Stop => {}
+            RuntimeControl::Ping(reply) => {
+                let _ = reply.send(());
+            }
+            RuntimeControl::UpdateSettings { core, dto } => {
+                let changes = self.core_settings.diff(&core);
+                let changed =
+                    |keys: &[&str]| changes.iter().any(|change| keys.contains(&change.key));
+                tracing::info!(
+                    changed = ?changes.iter().map(|change| change.key).collect::<Vec<_>>(),
+                    "settings updated"
+                );
+                self.core_settings = *core;
+                self.engine.apply_settings(
+                    self.core_settings.clone(),
+                    self.core_settings.runtime.interval_change_policy,
+                );
+                self.settings_dto = *dto;
+                if changed(&["countdown_widget"]) {
+                    widget::sync(&self.app, self.settings_dto.countdown_widget);
+                }
+                crash::record_settings(&self.settings_dto);
+                if changed(&["log_level"]) {
+                    logging::set_level(&self.engine.settings().runtime.log_level);
+                }
+                if changed(&["intensity_enabled"]) {
+                    intensity::set_enabled(self.engine.settings().intensity.enabled);
+                }
+                self.lang = i18n::resolve(&self.settings_dto.language);
+                if self.engine.settings().daily_limit.action == DailyLimitAction::None {
+                    self.limit_action_at = None;
+                }
+                if changed(INHIBITOR_KEYS) {
+                    self.refresh_inhibitor().await;
+                }
+                self.status.send_modify(|status| {
+                    status.last_event = "settings_updated".into();
+                });
+            }
+            RuntimeControl::StartBreak(kind) => {
+                for event in self.engine.start_break(kind) {
+                    if let EngineEvent::BreakStarted(kind) = event {
+                        self.show_break(kind, true);
+                    }
+                }
+            }
+            RuntimeControl::StartPending => self.start_pending(),
+            RuntimeControl::OverlayClosed(kind) => self.overlay_closed(kind),
+            RuntimeControl::OverlayFailed(kind) => self.overlay_failed(kind),
+            RuntimeControl::EndBreakEarly(kind) => self.end_break_early(kind),
+            RuntimeControl::SnoozePending => self.snooze_pending(),
+            RuntimeControl::PostponePending(kind, seconds) => {
+                self.postpone_pending(kind, Some(seconds))
+            }
+            RuntimeControl::BreakKey(keys::KeyAction::Start) => self.start_pending(),
+            RuntimeControl::BreakKey(keys::KeyAction::Snooze) => {
+                if self.engine.active_break_info().is_some() {
+                    self.snooze_active();
+                } else {
+                    self.snooze_pending();
+                }
+            }
+            RuntimeControl::SetVacation(until) => self.set_vacation(until),
+            RuntimeControl::SetGuest(until) => self.set_guest(until),
+            RuntimeControl::SetPresentation(until) => self.set_presentation(until),
+            RuntimeControl::ForceDailyReset => self.force_daily_reset(),
+            RuntimeControl::CancelLimitAction => {
+                if self.limit_action_at.take().is_some() {
+                    tracing::info!("daily limit action cancelled");
+                    emit_runtime_event(
+                        &self.app,
+                        RuntimeEventDto::new(
+                            RuntimeEvent::DailyLimitActionCancelled,
+                            self.messages().text("daily.action.cancelled"),
+                            self.strict_mode(),
+                        ),
+                    );
+                }
+            }
+        }
+    }
+
+    fn start_pending(&mut self) {
+        if let Some(kind) = self.engine.pending_break() {
+            for event in self.engine.start_break(kind) {
+                if let EngineEvent::BreakStarted(kind) = event {
+                    self.show_break(kind, false);
+                }
+            }
+        }
+    }
+
+    fn snooze_pending(&mut self) {
+        if let Some(kind) = self.engine.pending_break() {
+            self.postpone_pending(kind, None);
+        }
+    }
+
+    // `None` snoozes for the timer's configured time. Blocked while the
+    // daily limit action counts down: it has to be cancelled explicitly
+    // first.
+    fn postpone_pending(&mut self, kind: BreakKind, seconds: Option<u64>) {
+        if matches!(self.engine.block_level(kind), BlockLevel::Strict)
+            || self.limit_action_at.is_some()
+            || !self.engine.pending_breaks().contains(&kind)
+        {
+            return;
+        }
+        let now = self.now();
+        let until = match seconds {
+            Some(seconds) => self.engine.snooze_for(kind, now, seconds),
+            None => self.engine.snooze(kind, now),
+        }
+        .and_then(|event| match event {
+            EngineEvent::BreakSnoozed(_, until) => Some(until),
+            _ => None,
+        });
+        self.persistent.record_skipped_break();
+        self.persistent
+            .update_history(|history| history.record_snooze(kind, now));
+        let label = self.messages().break_label(kind);
+        let message = match seconds {
+            Some(seconds) => self.messages().format(
+                "break.postponed.pending",
+                &[
+                    ("kind", label),
+                    ("minutes", &seconds.div_ceil(60).to_string()),
+                ],
+            ),
+            None => self
+                .messages()
+                .format("break.snoozed.pending", &[("kind", label)]),
+        };
+        emit_runtime_event(
+            &self.app,
+            RuntimeEventDto::new(
+                RuntimeEvent::BreakSnoozed {
+                    break_kind: kind,
+                    until,
+                },
+                message,
+                false,
+            ),
+        );
+    }
+
+    fn snooze_active(&mut self) {
+        if self.strict_mode() {
+            return;
+        }
+        let now = self.now();
+        if let Some(event @ EngineEvent::BreakSnoozed(kind, _)) = self.engine.snooze_active(now) {
+            self.persistent.record_skipped_break();
+            self.persistent.update_history(|history| {
+                history.record_snooze(kind, now);
+                history.record_end(kind, now, SessionOutcome::Interrupted);
+            });
+            self.clear_break_status();
+            close_overlay(&self.app);
+            self.handle_engine_event(event);
+        }
+    }
+
+    // Closing the overlay early ends the break; past the halfway mark it
+    // still counts as taken.
+    fn overlay_closed(&mut self, kind: BreakKind) {
+        if self.strict_mode() || !self.is_active_break(kind) {
+            return;
+        }
+        self.abandon_active(kind, false);
+    }
+
+    // Checked again here, as another break may have started since the
+    // command ran.
+    fn end_break_early(&mut self, kind: BreakKind) {
+        if !self.is_active_break(kind) || overlay::check_skip(self.now()).ok() != Some(kind) {
+            return;
+        }
+        self.abandon_active(kind, true);
+    }
+
+    fn abandon_active(&mut self, kind: BreakKind, ended_early: bool) {
+        let now = self.now();
+        let completed = match self.engine.abandon_break(now) {
+            Some(event @ EngineEvent::BreakCompleted(_)) => {
+                self.handle_engine_event(event);
+                true
+            }
+            Some(event) => {
+                self.record_skipped_active(kind, event);
+                false
+            }
+            None => return,
+        };
+        let key = if completed {
+            "break.overlay_closed.completed"
+        } else {
+            "break.overlay_closed.skipped"
+        };
+        let event = if ended_early {
+            RuntimeEvent::BreakEndedEarly {
+                break_kind: kind,
+                completed,
+            }
+        } else {
+            RuntimeEvent::OverlayClosed {
+                break_kind: kind,
+                completed,
+            }
+        };
+        self.emit_overlay_event(event, kind, key);
+    }
+
+    // Without an overlay the break cannot be shown, so it is recorded as
+    // skipped and its timer snoozed, strict mode or not.
+    fn overlay_failed(&mut self, kind: BreakKind) {
+        if !self.is_active_break(kind) {
+            return;
+        }
+        let now = self.now();
+        if let Some(event) = self.engine.snooze_active(now) {
+            self.record_skipped_active(kind, event);
+        }
+        self.emit_overlay_event(
+            RuntimeEvent::OverlayFailed { break_kind: kind },
+            kind,
+            "break.overlay_failed",
+        );
+        send_notification(
+            &self.settings_dto,
+            self.notifications_blocked(),
+            self.messages().text("app.title"),
+            &self.messages().format(
+                "break.overlay_failed",
+                &[("kind", self.messages().break_label(kind))],
+            ),
+        );
+    }
+
+    fn notify_weekly_limit(&self, event: RuntimeEvent, message: String) {
+        emit_runtime_event(
+            &self.app,
+            RuntimeEventDto::new(event, message.clone(), false),
+        );
+        send_notification(
+            &self.settings_dto,
+            self.notifications_blocked(),
+            self.messages().text("app.title"),
+            &message,
+        );
+    }
+
+    fn is_active_break(&self, kind: BreakKind) -> bool {
+        self.engine
+            .active_break_info()
+            .is_some_and(|(active, _)| active == kind)
+    }
+
+    fn record_skipped_active(&mut self, kind: BreakKind, event: EngineEvent) {
+        let now = self.now();
+        self.persistent.record_skipped_break();
+        self.persistent
+            .update_history(|history| history.record_end(kind, now, SessionOutcome::Skipped));
+        self.clear_break_status();
+        close_overlay(&self.app);
+        self.handle_engine_event(event);
+    }
+
+    // Picks up DST changes and a zone changed while running, e.g. by
+    // timedatectl or when travelling.
+    fn follow_time_zone(&mut self) {
+        let offset = history::local_utc_offset();
+        if offset != self.engine.utc_offset() {
+            tracing::info!(
+                from = self.engine.utc_offset(),
+                to = offset,
+                "local UTC offset changed"
+            );
+            self.engine.set_utc_offset(offset);
+        }
+    }
+
+    fn emit_overlay_event(&self, event: RuntimeEvent, kind: BreakKind, key: &str) {
+        emit_runtime_event(
+            &self.app,
+            RuntimeEventDto::new(
+                event,
+                self.messages()
+                    .format(key, &[("kind", self.messages().break_label(kind))]),
+                self.strict_mode(),
+            ),
+        );
+    }
+
+    async fn tick(&mut self) {
+        let now = self.now();
+        let elapsed = self.elapsed.advance(self.clock.monotonic());
+        self.follow_time_zone();
+        if let Some(until) = self.vacation_until {
+            if now < until {
+                self.publish_status(now, None);
+                return;
+            }
+            self.end_vacation(now);
+        }
+        if let Some(until) = self.guest_until {
+            if now < until {
+                self.publish_status(now, None);
+                return;
+            }
+            self.end_guest();
+        }
+
+        if self.tick_counter.is_multiple_of(self.inhibit_poll_ticks()) {
+            self.refresh_inhibitor().await;
+            self.engine
+                .set_shared_daily_active(self.persistent.shared_active_today());
+        }
+        // Checked after the poll above, which notices a screencast ending.
+        if self.presentation_until.is_some_and(|until| now >= until) {
+            self.end_presentation();
+        }
+        if self.presenting() {
+            self.tick_counter = self.tick_counter.saturating_add(1);
+            self.publish_status(now, None);
+            return;
+        }
+        if self.tick_counter.is_multiple_of(ADAPT_INTERVAL_TICKS) {
+            self.adapt_intervals();
+        }
+        if self.tick_counter.is_multiple_of(INTENSITY_SAMPLE_TICKS) {
+            self.sample_intensity(now);
+        }
+
+        // A running break counts down in `tick_break`.
+        let events =
+            if elapsed == 0 || self.engine.active_break_info().is_some() || !self.session_active {
+                Vec::new()
+            } else {
+                self.persistent.add_active_seconds(elapsed);
+                if self.tracks_apps() {
+                    self.app_usage_seconds = self.app_usage_seconds.saturating_add(elapsed);
+                }
+                self.persistent.record_focus_activity(
+                    now,
+                    elapsed,
+                    self.engine.settings().analytics.focus_gap_seconds,
+                );
+                self.engine.on_activity(elapsed, now)
+            };
+
+        for event in events {
+            self.handle_engine_event(event);
+        }
+        self.update_taskbar_progress();
+        if self.tick_counter.is_multiple_of(NUDGE_CHECK_TICKS) {
+            self.check_nudges(now);
+        }
+        if self.tick_counter.is_multiple_of(APP_USAGE_SAMPLE_TICKS) {
+            self.sample_app_usage().await;
+        }
+
+        self.run_limit_action(now);
+        self.update_dimming(now);
+        self.update_blocking();
+        self.publish_status(now, Some("tick"));
+
+        self.tick_counter = self.tick_counter.saturating_add(1);
+        if self.snapshot_engine() {
+            self.persistent.mark_dirty(storage::Sections::DOCUMENT);
+        }
+    }
+
+    /// Counts the active break down by the time measured since the last
+    /// break tick, so the overlay's whole seconds change on time however
+    /// late the ticks run. `BreakTick` goes out when the seconds change.
+    fn tick_break(&mut self) {
+        let elapsed = self.break_elapsed.advance(self.clock.monotonic());
+        if elapsed == 0
+            || self.presenting()
+            || self.vacation_until.is_some()
+            || self.guest_until.is_some()
+        {
+            return;
+        }
+        let before = self
+            .engine
+            .active_break_info()
+            .map(|(_, remaining)| remaining);
+        let events = self.engine.tick_break_millis(elapsed);
+        let after = self.engine.active_break_info();
+        if events.is_empty() && after.map(|(_, remaining)| remaining) == before {
+            return;
+        }
+        for event in events {
+            self.handle_engine_event(event);
+        }
+        self.update_taskbar_progress();
+
+        if let Some((kind, remaining)) = after
+            && emission::should_emit_break_tick(remaining, self.emit_interval())
+        {
+            emit_runtime_event(
+                &self.app,
+                RuntimeEventDto::new(
+                    RuntimeEvent::BreakTick {
+                        break_kind: kind,
+                        remaining_seconds: remaining,
+                    },
+                    self.messages().format(
+                        "break.tick",
+                        &[
+                            ("kind", self.messages().break_label(kind)),
+                            (
+                                "remaining",
+                                &format!("{}:{:02}", remaining / 60, remaining % 60),
+                            ),
+                        ],
+                    ),
+                    self.strict_mode(),
+                ),
+            );
+
+            if sound::is_final_countdown(remaining, self.settings_dto.countdown_cue_seconds) {
+                play_cue(&self.settings_dto, self.muted(), sound::Cue::Tick);
+            }
+        }
+        self.publish_status(self.now(), None);
+    }
+
+    /// Starts the countdown to the daily limit action once the limit is due
+    /// with no snoozes left, leaving time to start the break or cancel.
+    fn schedule_limit_action(&mut self) {
+        let action = self.engine.settings().daily_limit.action;
+        let key = match action {
+            DailyLimitAction::None => return,
+            DailyLimitAction::LockSession => "daily.action.lock",
+            DailyLimitAction::Suspend => "daily.action.suspend",
+        };
+        if self.limit_action_at.is_some() || !self.engine.daily_snoozes_exhausted() {
+            return;
+        }
+
+        let at = self.now().saturating_add(LIMIT_ACTION_GRACE_SECONDS);
+        self.limit_action_at = Some(at);
+        tracing::info!(?action, at, "daily limit action scheduled");
+        let message = self
+            .messages()
+            .format(key, &[("seconds", &LIMIT_ACTION_GRACE_SECONDS.to_string())]);
+        emit_runtime_event(
+            &self.app,
+            RuntimeEventDto::new(
+                RuntimeEvent::DailyLimitActionPending {
+                    grace_seconds: LIMIT_ACTION_GRACE_SECONDS,
+                },
+                message.clone(),
+                self.strict_mode(),
+            ),
+        );
+        // Shown even under DND: locking without warning is worse than noise.
+        send_notification(
+            &self.settings_dto,
+            false,
+            self.messages().text("app.title"),
+            &message,
+        );
+    }
+
+    fn run_limit_action(&mut self, now: u64) {
+        if self.limit_action_at.is_none_or(|at| now < at) {
+            return;
+        }
+        self.limit_action_at = None;
+        if !self
+            .engine
+            .pending_breaks()
+            .contains(&BreakKind::DailyLimit)
+        {
+            return;
+        }
+
+        let action = self.engine.settings().daily_limit.action;
+        tracing::info!(?action, "running daily limit action");
+        tokio::task::spawn_blocking(move || {
+            if let Err(error) = session::run(action) {
+                tracing::warn!(%error, "daily limit action failed");
+            }
+        });
+    }
+
+    fn set_vacation(&mut self, until: Option<u64>) {
+        let Some(until) = until else {
+            if self.vacation_until.is_some() {
+                let now = self.now();
+                self.end_vacation(now);
+            }
+            return;
+        };
+
+        tracing::info!(until, "vacation started");
+        self.vacation_until = Some(until);
+        self.interrupt_breaks();
+
+        let date = history::day_index_to_date(history::local_day_index_at(until));
+        emit_runtime_event(
+            &self.app,
+            RuntimeEventDto::new(
+                RuntimeEvent::VacationStarted { until },
+                self.messages()
+                    .format("vacation.started", &[("until", &date)]),
+                false,
+            ),
+        );
+        self.persist();
+    }
+
+    /// Unlike a vacation, the owner's timers are left where they were and
+    /// pick up again once the guest session is over.
+    fn set_guest(&mut self, until: Option<u64>) {
+        let Some(until) = until else {
+            if self.guest_until.is_some() {
+                self.end_guest();
+            }
+            return;
+        };
+
+        tracing::info!(until, "guest session started");
+        self.guest_until = Some(until);
+        self.interrupt_breaks();
+
+        let minutes = until.saturating_sub(self.now()).div_ceil(60).to_string();
+        emit_runtime_event(
+            &self.app,
+            RuntimeEventDto::new(
+                RuntimeEvent::GuestStarted { until },
+                self.messages()
+                    .format("guest.started", &[("minutes", &minutes)]),
+                false,
+            ),
+        );
+    }
+
+    fn end_guest(&mut self) {
+        tracing::info!("guest session ended");
+        self.guest_until = None;
+
+        let message = self.messages().text("guest.ended");
+        emit_runtime_event(
+            &self.app,
+            RuntimeEventDto::new(RuntimeEvent::GuestEnded, message, false),
+        );
+        send_notification(
+            &self.settings_dto,
+            self.notifications_blocked(),
+            self.messages().text("app.title"),
+            message,
+        );
+    }
+
+    /// Holds breaks, notifications and sounds while the screen is shown to
+    /// others. Timers keep their place, like in a guest session, and no
+    /// setting is touched, so nothing needs restoring afterwards.
+    fn set_presentation(&mut self, until: Option<u64>) {
+        let Some(until) = until else {
+            if self.presentation_until.is_some() {
+                self.end_presentation();
+            }
+            return;
+        };
+
+        tracing::info!(until, "presentation mode started");
+        let was_presenting = self.presenting();
+        self.presentation_until = Some(until);
+        if !was_presenting {
+            self.interrupt_breaks();
+        }
+
+        let minutes = until.saturating_sub(self.now()).div_ceil(60).to_string();
+        self.emit_presentation_event(
+            RuntimeEvent::PresentationStarted {
+                until: Some(until),
+                screencast: false,
+            },
+            self.messages()
+                .format("presentation.started", &[("minutes", &minutes)]),
+        );
+    }
+
+    fn end_presentation(&mut self) {
+        tracing::info!("presentation mode ended");
+        self.presentation_until = None;
+        // A screencast still running keeps it on.
+        if self.presenting() {
+            return;
+        }
+
+        let message = self.messages().text("presentation.ended");
+        self.emit_presentation_event(
+            RuntimeEvent::PresentationEnded { screencast: false },
+            message.into(),
+        );
+        send_notification(
+            &self.settings_dto,
+            self.notifications_blocked(),
+            self.messages().text("app.title"),
+            message,
+        );
+    }
+
+    /// The day's recorded stats stay as they are; only the engine's daily
+    /// counters start over, so the summary is not sent either.
+    fn force_daily_reset(&mut self) {
+        let now = self.now();
+        tracing::info!("daily reset forced");
+        self.engine.force_reset(now);
+        self.limit_action_at = None;
+        emit_runtime_event(
+            &self.app,
+            RuntimeEventDto::new(
+                RuntimeEvent::DailyReset { forced: true },
+                self.messages().text("daily.reset.forced"),
+                false,
+            ),
+        );
+        self.persist();
+    }
+
+    /// Enters presentation mode when a screencast starts and leaves it when
+    /// the capture ends, unless it was also turned on by hand.
+    fn set_screen_capture(&mut self, capturing: bool) {
+        tracing::info!(capturing, "screen capture changed");
+        let was_presenting = self.presenting();
+        self.screen_capture = capturing;
+        if capturing && !was_presenting {
+            self.interrupt_breaks();
+            self.emit_presentation_event(
+                RuntimeEvent::PresentationStarted {
+                    until: None,
+                    screencast: true,
+                },
+                self.messages()
+                    .text("presentation.screencast.started")
+                    .into(),
+            );
+        } else if !capturing && !self.presenting() {
+            self.emit_presentation_event(
+                RuntimeEvent::PresentationEnded { screencast: true },
+                self.messages().text("presentation.screencast.ended").into(),
+            );
+        }
+    }
+
+    fn emit_presentation_event(&self, event: RuntimeEvent, message: String) {
+        emit_runtime_event(&self.app, RuntimeEventDto::new(event, message, false));
+    }
+
+    fn tracking_paused(&self) -> bool {
+        self.vacation_until.is_some() || self.guest_until.is_some() || self.presenting()
+    }
+
+    /// Drops whatever break is pending or running and undoes its effects on
+    /// the desktop, without counting it as skipped.
+    fn interrupt_breaks(&mut self) {
+        let now = self.now();
+        self.engine.clear_pending();
+        self.limit_action_at = None;
+        let _ = self.engine.snooze_active(now);
+        self.persistent
+            .update_history(|history| history.close_open(now, SessionOutcome::Interrupted));
+        self.clear_break_status();
+        close_overlay(&self.app);
+        self.dimmer.clear(&self.app);
+        self.blocker.clear();
+    }
+
+    /// Time away counts as rest, so every timer starts over.
+    fn end_vacation(&mut self, now: u64) {
+        tracing::info!("vacation ended");
+        self.vacation_until = None;
+        if let Ok(mut guard) = self.persistent.data.lock() {
+            guard.vacation_until = None;
+        }
+        self.engine = TimerEngine::restore_in_zone(
+            self.core_settings.clone(),
+            None,
+            now,
+            history::local_utc_offset(),
+        );
+
+        let message = self.messages().text("vacation.ended");
+        emit_runtime_event(
+            &self.app,
+            RuntimeEventDto::new(RuntimeEvent::VacationEnded, message, false),
+        );
+        send_notification(
+            &self.settings_dto,
+            self.notifications_blocked(),
+            self.messages().text("app.title"),
+            message,
+        );
+        self.persist();
+    }
+
+    /// Queues the engine counters and other runtime changes for the
+    /// writer thread.
+    fn persist(&self) {
+        self.snapshot_engine();
+        self.persistent.mark_dirty(storage::Sections::DOCUMENT);
+    }
+
+    /// Stores the engine counters in memory; true when they changed since
+    /// the last snapshot.
+    fn snapshot_engine(&self) -> bool {
+        let snapshot = EngineSnapshotDto::from(self.engine.snapshot(self.now()));
+        let Ok(mut guard) = self.persistent.data.lock() else {
+            return false;
+        };
+        let changed = guard
+            .engine
+            .as_ref()
+            .is_none_or(|stored| !stored.same_counters(&snapshot));
+        guard.engine = Some(snapshot);
+        changed
+    }
+
+    fn show_break(&mut self, kind: BreakKind, notify: bool) {
+        // The countdown starts now, not at the last break tick.
+        self.break_elapsed.advance(self.clock.monotonic());
+        if kind == BreakKind::DailyLimit {
+            self.limit_action_at = None;
+        }
+        let now = self.now();
+        self.persistent
+            .update_history(|history| history.record_start(kind, now));
+        let remaining = self
+            .engine
+            .active_break_info()
+            .map(|(_, remaining)| remaining)
+            .unwrap_or(0);
+        if kind == BreakKind::Rest && integrations::enabled(&self.settings_dto) {
+            let until = now.saturating_add(remaining);
+            let text = integrations::status_text(
+                &self.settings_dto,
+                self.lang,
+                self.messages().break_label(kind),
+                remaining,
+                until,
+            );
+            integrations::set_break_status(&self.settings_dto, text, until);
+            self.break_status_set = true;
+        }
+        self.dimmer.clear(&self.app);
+        let mut payload = overlay::payload(
+            kind,
+            remaining,
+            self.block_level(),
+            self.engine.settings(),
+            &self.settings_dto,
+            self.lang,
+        );
+        payload.wind_down = self.engine.wind_down_phase() != WindDownPhase::Off;
+        open_overlay(
+            &self.app,
+            kind,
+            remaining,
+            self.settings_dto.overlay_notifications && !self.meeting_silenced(),
+            self.strict_mode(),
+            payload,
+            self.lang,
+        );
+
+        // A silenced meeting swaps the overlay for a plain notification.
+        if notify || self.meeting_silenced() {
+            send_notification(
+                &self.settings_dto,
+                self.notifications_blocked(),
+                self.messages().text("app.title"),
+                &self.messages().format(
+                    "break.started.notification",
+                    &[("kind", self.messages().break_label(kind))],
+                ),
+            );
+        }
+    }
+
+    fn handle_engine_event(&mut self, event: EngineEvent) {
+        match event {
+            EngineEvent::BreakDue(kind) => {
+                let now = self.now();
+                self.persistent
+                    .update_history(|history| history.record_due(kind, now));
+                emit_runtime_event(
+                    &self.app,
+                    RuntimeEventDto::new(
+                        RuntimeEvent::BreakDue { break_kind: kind },
+                        self.messages()
+                            .format("break.due", &[("kind", self.messages().break_label(kind))]),
+                        self.strict_mode(),
+                    ),
+                );
+                send_notification(
+                    &self.settings_dto,
+                    self.notifications_blocked(),
+                    self.messages().text("app.title"),
+                    &self.messages().format(
+                        "break.due.notification",
+                        &[("kind", self.messages().break_label(kind))],
+                    ),
+                );
+                if kind == BreakKind::DailyLimit {
+                    self.schedule_limit_action();
+                }
+            }
+            EngineEvent::BreakDeferred(kind, reason) => {
+                emit_runtime_event(
+                    &self.app,
+                    RuntimeEventDto::new(
+                        RuntimeEvent::BreakDeferred {
+                            break_kind: kind,
+                            reason,
+                        },
+                        self.messages().format(
+                            "break.deferred",
+                            &[
+                                ("kind", self.messages().break_label(kind)),
+                                ("reason", &defer_reason_to_string(reason)),
+                            ],
+                        ),
+                        self.strict_mode(),
+                    ),
+                );
+            }
+            EngineEvent::BreakStarted(kind) => self.show_break(kind, false),
+            EngineEvent::BreakCompleted(kind) => {
+                let milestones_before = self
+                    .engine
+                    .settings()
+                    .analytics
+                    .milestone_celebrations
+                    .then(|| self.persistent.milestone_progress());
+                self.persistent.record_completed_break(kind);
+                let now = self.now();
+                self.persistent.update_history(|history| {
+                    history.record_end(kind, now, SessionOutcome::Completed)
+                });
+                self.adapt_intervals();
+                self.clear_break_status();
+                close_overlay(&self.app);
+                play_cue(&self.settings_dto, self.muted(), sound::Cue::Chime);
+                emit_runtime_event(
+                    &self.app,
+                    RuntimeEventDto::new(
+                        RuntimeEvent::BreakCompleted { break_kind: kind },
+                        self.messages().format(
+                            "break.completed",
+                            &[("kind", self.messages().break_label(kind))],
+                        ),
+                        self.strict_mode(),
+                    ),
+                );
+                send_notification(
+                    &self.settings_dto,
+                    self.notifications_blocked(),
+                    self.messages().text("app.title"),
+                    self.messages().text("break.completed.notification"),
+                );
+                if let Some(before) = milestones_before {
+                    self.celebrate_milestones(before);
+                }
+            }
+            EngineEvent::BreakSnoozed(kind, until) => {
+                emit_runtime_event(
+                    &self.app,
+                    RuntimeEventDto::new(
+                        RuntimeEvent::BreakSnoozed {
+                            break_kind: kind,
+                            until: Some(until),
+                        },
+                        self.messages().format(
+                            "break.snoozed",
+                            &[
+                                ("kind", self.messages().break_label(kind)),
+                                ("until", &until.to_string()),
+                            ],
+                        ),
+                        false,
+                    ),
+                );
+            }
+            EngineEvent::DailyLimitWarning(percent) => {
+                let daily = self.engine.progress().daily_limit;
+                let message = self.messages().format(
+                    "daily.warning",
+                    &[
+                        ("used", &summary::format_duration(daily.elapsed_seconds)),
+                        ("limit", &summary::format_duration(daily.target_seconds)),
+                        ("percent", &percent.to_string()),
+                    ],
+                );
+                emit_runtime_event(
+                    &self.app,
+                    RuntimeEventDto::new(
+                        RuntimeEvent::DailyLimitWarning {
+                            percent,
+                            used_seconds: daily.elapsed_seconds,
+                            limit_seconds: daily.target_seconds,
+                        },
+                        message.clone(),
+                        false,
+                    ),
+                );
+                send_notification(
+                    &self.settings_dto,
+                    self.notifications_blocked(),
+                    self.messages().text("app.title"),
+                    &message,
+                );
+            }
+            EngineEvent::BreakEscalated(kind, stage) => {
+                let (event, key) = match stage {
+                    EscalationStage::Renotify => (
+                        RuntimeEvent::BreakEscalated { break_kind: kind },
+                        "break.escalated",
+                    ),
+                    EscalationStage::ForceStart => (
+                        RuntimeEvent::BreakForced { break_kind: kind },
+                        "break.forced",
+                    ),
+                };
+                let message = self
+                    .messages()
+                    .format(key, &[("kind", self.messages().break_label(kind))]);
+                emit_runtime_event(
+                    &self.app,
+                    RuntimeEventDto::new(event, message.clone(), self.strict_mode()),
+                );
+                // A forced start is announced by the BreakStarted that follows.
+                if stage == EscalationStage::Renotify {
+                    send_urgent_notification(
+                        &self.settings_dto,
+                        self.notifications_blocked(),
+                        self.messages().text("app.title"),
+                        &message,
+                    );
+                    play_cue(&self.settings_dto, self.muted(), sound::Cue::Chime);
+                }
+            }
+            EngineEvent::IntervalsAdjusted(percent, reason) => {
+                emit_runtime_event(
+                    &self.app,
+                    RuntimeEventDto::new(
+                        RuntimeEvent::IntervalsAdjusted {
+                            scale_percent: percent,
+                            reason,
+                        },
+                        self.messages().format(
+                            "adaptive.adjusted",
+                            &[
+                                ("percent", &percent.to_string()),
+                                ("reason", self.messages().adapt_reason_label(reason)),
+                            ],
+                        ),
+                        false,
+                    ),
+                );
+            }
+            EngineEvent::WeeklyLimitWarning(percent) => {
+                let weekly = self.engine.progress().weekly_limit;
+                let message = self.messages().format(
+                    "weekly.warning",
+                    &[
+                        ("used", &summary::format_duration(weekly.elapsed_seconds)),
+                        ("limit", &summary::format_duration(weekly.target_seconds)),
+                        ("percent", &percent.to_string()),
+                    ],
+                );
+                self.notify_weekly_limit(
+                    RuntimeEvent::WeeklyLimitWarning {
+                        percent,
+                        used_seconds: weekly.elapsed_seconds,
+                        limit_seconds: weekly.target_seconds,
+                    },
+                    message,
+                );
+            }
+            EngineEvent::WeeklyLimitReached => {
+                let message = self.messages().format(
+                    "weekly.reached",
+                    &[(
+                        "limit",
+                        &summary::format_duration(
+                            self.engine.settings().weekly_limit.limit_seconds,
+                        ),
+                    )],
+                );
+                self.notify_weekly_limit(
+                    RuntimeEvent::WeeklyLimitReached {
+                        limit_seconds: self.engine.settings().weekly_limit.limit_seconds,
+                    },
+                    message,
+                );
+            }
+            EngineEvent::AppLimitReached(app) => {
+                let limits = &self.engine.settings().app_limits;
+                let limit_seconds = limits
+                    .limit_for(&app)
+                    .map_or(0, |limit| limit.limit_seconds);
+                let block_level = limits.block_level;
+                let message = self.messages().format(
+                    "app_limit.reached",
+                    &[
+                        ("app", &app),
+                        ("limit", &summary::format_duration(limit_seconds)),
+                    ],
+                );
+                emit_runtime_event(
+                    &self.app,
+                    RuntimeEventDto::new(
+                        RuntimeEvent::AppLimitReached {
+                            app,
+                            limit_seconds,
+                            block_level,
+                        },
+                        message.clone(),
+                        block_level == BlockLevel::Strict,
+                    ),
+                );
+                send_notification(
+                    &self.settings_dto,
+                    self.notifications_blocked(),
+                    self.messages().text("app.title"),
+                    &message,
+                );
+            }
+            EngineEvent::DailyReset => {
+                emit_runtime_event(
+                    &self.app,
+                    RuntimeEventDto::new(
+                        RuntimeEvent::DailyReset { forced: false },
+                        self.messages().text("daily.reset"),
+                        false,
+                    ),
+                );
+                self.send_daily_summary();
+            }
+            EngineEvent::WindDownStarted => {
+                let percent = self.engine.settings().wind_down.interval_percent;
+                let message = self
+                    .messages()
+                    .format("wind_down.started", &[("percent", &percent.to_string())]);
+                emit_runtime_event(
+                    &self.app,
+                    RuntimeEventDto::new(
+                        RuntimeEvent::WindDownStarted {
+                            interval_percent: percent,
+                        },
+                        message.clone(),
+                        false,
+                    ),
+                );
+                send_notification(
+                    &self.settings_dto,
+                    self.notifications_blocked(),
+                    self.messages().text("app.title"),
+                    &message,
+                );
+            }
+            EngineEvent::WindDownCutoff => {
+                let message = self.messages().text("wind_down.cutoff").to_string();
+                self.prompt_to_stop(RuntimeEvent::WindDownCutoff, message);
+            }
+            EngineEvent::WindDownBudgetReached => {
+                let budget_seconds = self.engine.settings().wind_down.budget_seconds;
+                let message = self.messages().format(
+                    "wind_down.budget",
+                    &[("budget", &summary::format_duration(budget_seconds))],
+                );
+                self.prompt_to_stop(
+                    RuntimeEvent::WindDownBudgetReached { budget_seconds },
+                    message,
+                );
+            }
+        }
+    }
+
+    /// The "time to stop" prompt: the frontend opens a dialog for these
+    /// events, and the notification stays on screen until dismissed.
+    fn prompt_to_stop(&self, event: RuntimeEvent, message: String) {
+        emit_runtime_event(
+            &self.app,
+            RuntimeEventDto::new(event, message.clone(), false),
+        );
+        send_urgent_notification(
+            &self.settings_dto,
+            self.notifications_blocked(),
+            self.messages().text("app.title"),
+            &message,
+        );
+        play_cue(&self.settings_dto, self.muted(), sound::Cue::Chime);
+    }
+
+    fn check_nudges(&mut self, now: u64) {
+        if !self.engine.settings().nudges.enabled || self.engine.active_break_info().is_some() {
+            return;
+        }
+        let input = NudgeInput {
+            compliance: self
+                .persistent
+                .compliance_today(&[BreakKind::Micro, BreakKind::Rest]),
+            focus_seconds: self.engine.active_streak_seconds(),
+            local_hour: history::local_hour(),
+            active: self.session_active,
+        };
+        let Some(nudge) = self
+            .nudges
+            .evaluate(&self.engine.settings().nudges, &input, now)
+        else {
+            return;
+        };
+        let messages = self.messages();
+        let message = match nudge {
+            Nudge::SkipRate {
+                break_kind,
+                percent,
+            } => messages.format(
+                "nudge.skip_rate",
+                &[
+                    ("kind", messages.break_label(break_kind)),
+                    ("percent", &percent.to_string()),
+                ],
+            ),
+            Nudge::LongFocus { seconds } => messages.format(
+                "nudge.long_focus",
+                &[("duration", &summary::format_duration(seconds))],
+            ),
+            Nudge::LateNight { hour } => {
+                messages.format("nudge.late_night", &[("hour", &hour.to_string())])
+            }
+        };
+        tracing::info!(rule = nudge.rule().as_str(), "nudge");
+        emit_runtime_event(
+            &self.app,
+            RuntimeEventDto::new(RuntimeEvent::Nudge { nudge }, message.clone(), false),
+        );
+        if self.engine.settings().nudges.notify {
+            send_notification(
+                &self.settings_dto,
+                self.notifications_blocked(),
+                messages.text("app.title"),
+                &message,
+            );
+        }
+    }
+
+    fn celebrate_milestones(&self, before: MilestoneProgress) {
+        let reached = self.persistent.milestone_progress().reached_since(&before);
+        for &(milestone, count) in &reached {
+            let key = match milestone {
+                MilestoneKind::BreaksInARow => "milestone.breaks_in_a_row",
+                MilestoneKind::StreakDays => "milestone.streak_days",
+            };
+            let message = self
+                .messages()
+                .format(key, &[("count", &count.to_string())]);
+            emit_runtime_event(
+                &self.app,
+                RuntimeEventDto::new(
+                    RuntimeEvent::MilestoneReached { milestone, count },
+                    message.clone(),
+                    false,
+                ),
+            );
+            send_notification(
+                &self.settings_dto,
+                self.notifications_blocked(),
+                self.messages().text("app.title"),
+                &message,
+            );
+        }
+        if !reached.is_empty() {
+            play_cue(&self.settings_dto, self.muted(), sound::Cue::Celebrate);
+        }
+    }
+
+    /// App usage and app limits both need the foreground application.
+    fn tracks_apps(&self) -> bool {
+        self.engine.settings().analytics.app_usage_enabled
+            || self.engine.settings().app_limits.enabled
+    }
+
+    /// Credits the active time since the last sample to the application in
+    /// the foreground now. Sampling every few seconds keeps `xprop` calls
+    /// down, at the cost of crediting a switch that much late.
+    async fn sample_app_usage(&mut self) {
+        let seconds = std::mem::take(&mut self.app_usage_seconds);
+        if seconds == 0 || !self.tracks_apps() {
+            return;
+        }
+        let analytics = &self.engine.settings().analytics;
+        let record = analytics.app_usage_enabled;
+        let with_title = record && analytics.app_usage_titles;
+        let probe = tokio::task::spawn_blocking(move || app_usage::active_window(with_title)).await;
+        let Ok(Some(window)) = probe else {
+            return;
+        };
+        if record {
+            self.persistent
+                .record_app_usage(&window.class, window.title.as_deref(), seconds);
+        }
+        for event in self.engine.on_app_activity(&window.class, seconds) {
+            self.handle_engine_event(event);
+        }
+        self.enforce_app_limits(&window.class);
+    }
+
+    /// Keeps applications that spent their budget away, as hard as
+    /// `app_limit_block_level` says.
+    fn enforce_app_limits(&self, foreground: &str) {
+        match self.engine.settings().app_limits.block_level {
+            BlockLevel::Soft => {}
+            BlockLevel::Medium => {
+                if self.engine.app_limit_reached(foreground) {
+                    blocking::enforce_in_background(
+                        vec![foreground.to_string()],
+                        AppBlockAction::Minimize,
+                    );
+                }
+            }
+            BlockLevel::Strict => blocking::enforce_in_background(
+                self.engine.exhausted_apps(),
+                self.engine.settings().blocking.app_action,
+            ),
+        }
+    }
+
+    /// Feeds the last minute of key presses and clicks to the engine and
+    /// the hourly intensity log.
+    fn sample_intensity(&mut self, now: u64) {
+        let (keys, clicks) = intensity::take();
+        if !self.engine.settings().intensity.enabled || !self.session_active {
+            self.engine.set_input_intensity(0);
+            return;
+        }
+        let events = u32::try_from(keys.saturating_add(clicks)).unwrap_or(u32::MAX);
+        self.engine.set_input_intensity(events);
+        if events > 0 {
+            self.persistent.record_intensity(now, keys, clicks);
+        }
+    }
+
+    fn adapt_intervals(&mut self) {
+        let compliance = match self.persistent.recent_compliance() {
+            Ok(compliance) => compliance,
+            Err(error) => {
+                tracing::warn!(%error, "failed to read break compliance");
+                return;
+            }
+        };
+        if let Some(event) = self.engine.adapt(&compliance) {
+            self.handle_engine_event(event);
+        }
+    }
+
+    fn send_daily_summary(&self) {
+        if !self.settings_dto.daily_summary_notifications {
+            return;
+        }
+
+        let day = summary::ended_day_index(self.now());
+        let stats = match self.persistent.analytics_store() {
+            Ok(store) => store.day(day),
+            Err(error) => {
+                tracing::warn!(%error, "failed to build daily summary");
+                return;
+            }
+        };
+        let summary = summary::build(self.messages(), day, &stats);
+        tracing::info!(day = %summary.day, "sending daily summary");
+
+        emit_runtime_event(
+            &self.app,
+            RuntimeEventDto::new(
+                RuntimeEvent::DailySummary {
+                    day: summary.day.clone(),
+                },
+                summary.message.clone(),
+                false,
+            ),
+        );
+        send_notification(
+            &self.settings_dto,
+            self.notifications_blocked(),
+            self.messages().text("daily.summary.title"),
+            &summary.message,
+        );
+    }
+
+    fn update_dimming(&mut self, now: u64) {
+        let dimming = &self.engine.settings().dimming;
+        let target = if self.engine.pending_break().is_none()
+            && self.engine.inhibitor().is_none()
+            && !self.meeting_silenced()
+        {
+            self.engine
+                .next_break_eta(now)
+                .filter(|(kind, _)| dimming.enabled_for(self.engine.block_level(*kind)))
+                .and_then(|(_, eta)| {
+                    dimming::target_opacity(
+                        eta,
+                        dimming.warning_seconds,
+                        dimming.max_opacity_percent,
+                    )
+                })
+        } else {
+            None
+        };
+        self.dimmer.update(&self.app, target);
+    }
+
+    fn update_taskbar_progress(&mut self) {
+        let percent = self
+            .engine
+            .active_break_remaining_percent()
+            .filter(|_| self.settings_dto.taskbar_progress);
+        self.taskbar.update(&self.app, percent);
+    }
+
+    fn update_blocking(&mut self) {
+        let strict_break = self.strict_mode()
+            && self
+                .engine
+                .active_break_info()
+                .is_some_and(|(kind, _)| kind != BreakKind::Micro);
+        self.blocker.update(
+            &self.engine.settings().blocking,
+            strict_break,
+            self.tick_counter,
+        );
+    }
+
+    /// Sends the status to subscribers when it differs from the last one.
+    fn publish_status(&mut self, now: u64, last_event: Option<&str>) {
+        let mut status = self.status.borrow().clone();
+        let next_break = self
+            .engine
+            .next_break_eta(now)
+            .filter(|_| !self.tracking_paused());
+        let active_break = self.engine.active_break_info();
+        let progress = self.engine.progress();
+        let forecast = self.engine.forecast_daily_limit(now);
+        status.running = true;
+        status.pending_break = self.engine.pending_break();
+        status.pending_breaks = self.engine.pending_breaks().to_vec();
+        status.active_break = active_break.map(|(kind, _)| kind);
+        status.remaining_seconds = active_break.map(|(_, remaining)| remaining);
+        status.next_break_kind = next_break.map(|(kind, _)| kind);
+        status.next_break_eta_seconds = next_break.map(|(_, remaining)| remaining);
+        status.next_break_at = next_break.map(|(_, remaining)| now.saturating_add(remaining));
+        let enabled_percent = |enabled: bool, progress: BreakProgress| {
+            if enabled { progress.percent() } else { 0 }
+        };
+        status.micro_progress_percent =
+            enabled_percent(self.engine.settings().micro.enabled, progress.micro);
+        status.rest_progress_percent =
+            enabled_percent(self.engine.settings().rest.enabled, progress.rest);
+        status.daily_limit_progress_percent = enabled_percent(
+            self.engine.settings().daily_limit.enabled,
+            progress.daily_limit,
+        );
+        status.daily_active_seconds = progress.daily_limit.elapsed_seconds;
+        status.daily = DailyStatusDto::new(
+            self.engine.daily_status(now),
+            self.engine.settings().daily_limit.enabled,
+            now,
+        );
+        status.schedule = self
+            .engine
+            .explain(now)
+            .into_iter()
+            .map(ScheduleEntryDto::from)
+            .collect();
+        status.weekly_limit_progress_percent = enabled_percent(
+            self.engine.settings().weekly_limit.enabled,
+            progress.weekly_limit,
+        );
+        status.weekly_active_seconds = progress.weekly_limit.elapsed_seconds;
+        status.daily_limit_forecast_seconds =
+            forecast.and_then(|forecast| forecast.seconds_until_limit);
+        status.daily_limit_forecast_at = forecast.and_then(|forecast| forecast.reached_at);
+        status.daily_limit_forecast_before_reset =
+            forecast.is_some_and(|forecast| forecast.before_reset);
+        status.daily_limit_action_at = self.limit_action_at;
+        status.activity_density_percent =
+            forecast.map_or(0, |forecast| forecast.activity_density_percent);
+        status.interval_scale_percent = self.engine.interval_scale_percent();
+        status.wind_down = match self.engine.wind_down_phase() {
+            WindDownPhase::Off => None,
+            phase => Some(phase.as_str().into()),
+        };
+        status.wind_down_active_seconds = self.engine.wind_down_progress().elapsed_seconds;
+        status.strict_mode = self.strict_mode();
+        status.dnd_active = self.dnd_active;
+        status.in_meeting = self.in_meeting;
+        status.power_saving = self.power_saving;
+        status.vacation_until = self.vacation_until;
+        status.guest_until = self.guest_until;
+        status.presentation_until = self.presentation_until;
+        status.screen_capture_active = self.screen_capture;
+        status.inhibited_by = self.engine.inhibitor().map(defer_reason_to_string);
+        if let Some(last_event) = last_event {
+            status.last_event = last_event.into();
+        }
+        self.status.send_if_modified(|current| {
+            let changed = *current != status;
+            if changed {
+                current.clone_from(&status);
+            }
+            changed
+        });
+
+        crash::record_snapshot(&status);
+        self.tray
+            .update(&self.app, &status, self.engine.settings(), self.lang);
+        if self
+            .status_emitter
+            .should_emit(&status, self.emit_interval())
+        {
+            emit_status_tick(&self.app, status);
+        }
+    }
+
+    fn clear_break_status(&mut self) {
+        if self.break_status_set {
+            integrations::clear_break_status(&self.settings_dto);
+            self.break_status_set = false;
+        }
+    }
+
+    fn shutdown(mut self) {
+        self.clear_break_status();
+        intensity::set_enabled(false);
+        self.tray.clear(&self.app);
+        self.taskbar.update(&self.app, None);
+        close_overlay(&self.app);
+        self.dimmer.clear(&self.app);
+        self.blocker.clear();
+        let now = self.now();
+        self.persistent
+            .update_history(|history| history.close_open(now, SessionOutcome::Interrupted));
+        self.persist();
+        tracing::info!("runtime stopped");
+
+        self.status.send_modify(|status| {
+            status.running = false;
+            status.pending_break = None;
+            status.pending_breaks.clear();
+            status.active_break = None;
+            status.remaining_seconds = None;
+            status.next_break_kind = None;
+            status.next_break_eta_seconds = None;
+            status.next_break_at = None;
+            status.dnd_active = false;
+            status.power_saving = false;
+            status.vacation_until = None;
+            status.guest_until = None;
+            status.presentation_until = None;
+            status.screen_capture_active = false;
+            status.daily_limit_action_at = None;
+            status.schedule.clear();
+            status.inhibited_by = None;
+            status.last_event = "runtime_stopped".into();
+        });
+    }
+}
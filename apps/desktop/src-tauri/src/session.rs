@@ -0,0 +1,94 @@
+use std::{env, process::Command};
+
+use lazaro_core::config::DailyLimitAction;
+
+use crate::{AppError, dbus};
+
+pub const DEFAULT_SEAT: &str = "seat0";
+
+pub fn run(action: DailyLimitAction) -> Result<(), AppError> {
+    match action {
+        DailyLimitAction::None => Ok(()),
+        DailyLimitAction::LockSession => lock(),
+        DailyLimitAction::Suspend => suspend(),
+    }
+}
+
+/// logind session this process runs in.
+pub fn id() -> Option<String> {
+    env_name("XDG_SESSION_ID")
+}
+
+/// Seat this session sits at; sessions without one are treated as on the
+/// default seat.
+pub fn seat() -> String {
+    env_name("XDG_SEAT").unwrap_or_else(|| DEFAULT_SEAT.into())
+}
+
+/// Seat to keep state apart for, so users logged in on different seats of one
+/// machine never share breaks or stats. The default seat keeps the unscoped
+/// layout.
+pub fn seat_scope() -> Option<String> {
+    Some(seat()).filter(|seat| seat != DEFAULT_SEAT)
+}
+
+/// Whether this session is in the foreground of its seat; false while another
+/// user has switched in. Sessions logind does not know about count as active.
+pub fn is_active() -> bool {
+    let Some(id) = id() else {
+        return true;
+    };
+    let Ok(output) = Command::new("loginctl")
+        .args(["show-session", &id, "--property=Active", "--value"])
+        .output()
+    else {
+        return true;
+    };
+    !(output.status.success() && String::from_utf8_lossy(&output.stdout).trim() == "no")
+}
+
+// Only names safe to use as a path component are accepted.
+fn env_name(key: &str) -> Option<String> {
+    env::var(key).ok().filter(|value| {
+        !value.is_empty()
+            && value
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+    })
+}
+
+// logind covers most sessions; the ScreenSaver call catches desktops that run
+// their own locker outside of it. The session is named so another user's
+// session on a different seat is never the one locked.
+fn lock() -> Result<(), AppError> {
+    let mut command = Command::new("loginctl");
+    command.arg("lock-session");
+    if let Some(id) = id() {
+        command.arg(id);
+    }
+    if succeeds(&mut command) {
+        return Ok(());
+    }
+    dbus::session_call(
+        "org.freedesktop.ScreenSaver",
+        "/org/freedesktop/ScreenSaver",
+        "org.freedesktop.ScreenSaver.Lock",
+        &[],
+    )
+    .map(|_| ())
+    .ok_or_else(|| AppError::ServiceUnavailable("no session locker answered".into()))
+}
+
+fn suspend() -> Result<(), AppError> {
+    if succeeds(Command::new("systemctl").arg("suspend")) {
+        Ok(())
+    } else {
+        Err(AppError::ServiceUnavailable(
+            "systemctl suspend failed".into(),
+        ))
+    }
+}
+
+fn succeeds(command: &mut Command) -> bool {
+    command.output().is_ok_and(|output| output.status.success())
+}
@@ -0,0 +1,43 @@
+use std::{path::Path, process::Command, thread};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Cue {
+    Tick,
+    Chime,
+    /// A streak or run milestone, after the completion chime.
+    Celebrate,
+}
+
+impl Cue {
+    fn event_id(self) -> &'static str {
+        match self {
+            Cue::Tick => "audio-volume-change",
+            Cue::Chime => "complete",
+            Cue::Celebrate => "service-login",
+        }
+    }
+}
+
+pub fn play(theme: &str, cue: Cue) {
+    let theme = theme.to_string();
+    thread::spawn(move || {
+        let mut canberra = Command::new("canberra-gtk-play");
+        canberra.arg("--id").arg(cue.event_id());
+        if !theme.is_empty() && theme != "default" {
+            canberra.arg(format!("--property=canberra.xdg-theme.name={theme}"));
+        }
+        if canberra.status().is_ok_and(|status| status.success()) {
+            return;
+        }
+
+        let fallback = Path::new("/usr/share/sounds/freedesktop/stereo")
+            .join(format!("{}.oga", cue.event_id()));
+        if fallback.exists() {
+            let _ = Command::new("paplay").arg(fallback).status();
+        }
+    });
+}
+
+pub fn is_final_countdown(remaining_seconds: u64, cue_seconds: u64) -> bool {
+    remaining_seconds > 0 && remaining_seconds <= cue_seconds
+}
@@ -0,0 +1,438 @@
+use std::{collections::BTreeMap, fs, path::Path, sync::Mutex};
+
+use rusqlite::{Connection, OptionalExtension, Transaction, params};
+use serde::{Serialize, de::DeserializeOwned};
+
+use crate::{
+    AppError, AppStateOnDisk, DailyStatsDto,
+    history::{AppUsageDayDto, BreakSessionDto, FocusSessionDto},
+    vault,
+};
+
+/// Schema steps, applied in order; `PRAGMA user_version` counts the ones
+/// already run. Only ever append.
+const MIGRATIONS: &[&str] = &[
+    "
+    CREATE TABLE document (
+        id INTEGER PRIMARY KEY CHECK (id = 1),
+        body BLOB NOT NULL
+    );
+    CREATE TABLE daily_stats (
+        day INTEGER PRIMARY KEY,
+        body TEXT NOT NULL
+    );
+    CREATE TABLE break_history (
+        kind TEXT NOT NULL,
+        scheduled_at INTEGER NOT NULL,
+        body TEXT NOT NULL,
+        PRIMARY KEY (kind, scheduled_at)
+    );
+    CREATE TABLE focus_sessions (
+        started_at INTEGER PRIMARY KEY,
+        body TEXT NOT NULL
+    );
+",
+    "
+    CREATE TABLE app_usage (
+        day INTEGER PRIMARY KEY,
+        body TEXT NOT NULL
+    );
+",
+];
+
+impl From<rusqlite::Error> for AppError {
+    fn from(value: rusqlite::Error) -> Self {
+        Self::Storage(value.to_string())
+    }
+}
+
+/// Which parts of the state changed since the last save.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Sections {
+    pub document: bool,
+    pub daily_stats: bool,
+    pub break_history: bool,
+    pub focus_sessions: bool,
+    pub app_usage: bool,
+}
+
+impl Sections {
+    pub const ALL: Self = Self {
+        document: true,
+        daily_stats: true,
+        break_history: true,
+        focus_sessions: true,
+        app_usage: true,
+    };
+    pub const DOCUMENT: Self = Self {
+        document: true,
+        ..Self::NONE
+    };
+    pub const STATS: Self = Self {
+        document: true,
+        daily_stats: true,
+        ..Self::NONE
+    };
+    pub const BREAK_HISTORY: Self = Self {
+        break_history: true,
+        ..Self::NONE
+    };
+    pub const FOCUS_SESSIONS: Self = Self {
+        focus_sessions: true,
+        ..Self::NONE
+    };
+    pub const APP_USAGE: Self = Self {
+        app_usage: true,
+        ..Self::NONE
+    };
+    const NONE: Self = Self {
+        document: false,
+        daily_stats: false,
+        break_history: false,
+        focus_sessions: false,
+        app_usage: false,
+    };
+
+    pub fn is_empty(self) -> bool {
+        self == Self::NONE
+    }
+
+    pub fn union(self, other: Self) -> Self {
+        Self {
+            document: self.document || other.document,
+            daily_stats: self.daily_stats || other.daily_stats,
+            break_history: self.break_history || other.break_history,
+            focus_sessions: self.focus_sessions || other.focus_sessions,
+            app_usage: self.app_usage || other.app_usage,
+        }
+    }
+}
+
+pub enum Loaded {
+    Empty,
+    State(Box<AppStateOnDisk>),
+    /// The sealed document could not be opened; carries the raw blob and
+    /// the reason.
+    Locked(Vec<u8>, String),
+}
+
+/// Application state in SQLite. Settings and the other small parts live in
+/// a single JSON document row; per-day stats, break history, focus
+/// sessions and application usage get their own tables so they can be queried by range. With
+/// `encrypt_state` the whole state is sealed into the document row and the
+/// tables stay empty.
+pub struct Storage {
+    conn: Mutex<Connection>,
+}
+
+impl Storage {
+    pub fn open(path: &Path) -> Result<Self, AppError> {
+        let mut conn = Connection::open(path)?;
+        conn.pragma_update(None, "journal_mode", "WAL")?;
+        conn.pragma_update(None, "synchronous", "NORMAL")?;
+        migrate(&mut conn)?;
+        Ok(Self {
+            conn: Mutex::new(conn),
+        })
+    }
+
+    pub fn load(&self) -> Result<Loaded, AppError> {
+        let body = {
+            let conn = self.lock()?;
+            conn.query_row("SELECT body FROM document WHERE id = 1", [], |row| {
+                row.get::<_, Vec<u8>>(0)
+            })
+            .optional()?
+        };
+        let Some(body) = body else {
+            return Ok(Loaded::Empty);
+        };
+        if vault::is_sealed(&body) {
+            return Ok(match vault::open(&body) {
+                Ok(raw) => Loaded::State(Box::new(from_json(&raw)?)),
+                Err(error) => Loaded::Locked(body, error),
+            });
+        }
+
+        let mut state: AppStateOnDisk = from_json(&body)?;
+        state.daily_stats = self.daily_stats(i64::MIN, i64::MAX)?;
+        state.break_history = self.break_history(0, u64::MAX)?;
+        state.focus_sessions = self.focus_sessions(0, u64::MAX)?;
+        state.app_usage = self.app_usage(i64::MIN, i64::MAX)?;
+        Ok(Loaded::State(Box::new(state)))
+    }
+
+    /// Writes the `sections` of `state` in one transaction, so a crash
+    /// mid-save leaves the previous state intact. Sealed state has no
+    /// sections and is written whole.
+    pub fn save(&self, state: &AppStateOnDisk, sections: Sections) -> Result<(), AppError> {
+        let mut conn = self.lock()?;
+        let tx = conn.transaction()?;
+        if state.effective.encrypt_state {
+            let body = vault::seal(&to_json(state)?).map_err(AppError::Storage)?;
+            write_document(&tx, &body)?;
+            tx.execute_batch(
+                "DELETE FROM daily_stats; DELETE FROM break_history; DELETE FROM focus_sessions;
+                 DELETE FROM app_usage;",
+            )?;
+        } else {
+            // Switching encryption off must not leave the tables emptied
+            // by a sealed save behind.
+            let sections = if is_sealed(&tx)? {
+                Sections::ALL
+            } else {
+                sections
+            };
+            if sections.document {
+                let document = AppStateOnDisk {
+                    daily_stats: BTreeMap::new(),
+                    break_history: Vec::new(),
+                    focus_sessions: Vec::new(),
+                    app_usage: BTreeMap::new(),
+                    ..state.clone()
+                };
+                write_document(&tx, &to_json(&document)?)?;
+            }
+            if sections.daily_stats {
+                write_daily_stats(&tx, &state.daily_stats)?;
+            }
+            if sections.break_history {
+                write_break_history(&tx, &state.break_history)?;
+            }
+            if sections.focus_sessions {
+                write_focus_sessions(&tx, &state.focus_sessions)?;
+            }
+            if sections.app_usage {
+                write_app_usage(&tx, &state.app_usage)?;
+            }
+        }
+        tx.commit()?;
+        Ok(())
+    }
+
+    /// Drops the sealed document, once it has been copied elsewhere.
+    pub fn discard_document(&self) -> Result<(), AppError> {
+        self.lock()?.execute("DELETE FROM document", [])?;
+        Ok(())
+    }
+
+    /// Per-day stats for days `from..=to`, as local day indexes.
+    pub fn daily_stats(
+        &self,
+        from: i64,
+        to: i64,
+    ) -> Result<BTreeMap<i64, DailyStatsDto>, AppError> {
+        let conn = self.lock()?;
+        let mut statement = conn.prepare(
+            "SELECT day, body FROM daily_stats WHERE day BETWEEN ?1 AND ?2 ORDER BY day",
+        )?;
+        let rows = statement.query_map(params![from, to], |row| {
+            Ok((row.get::<_, i64>(0)?, row.get::<_, String>(1)?))
+        })?;
+        let mut days = BTreeMap::new();
+        for row in rows {
+            let (day, body) = row?;
+            days.insert(day, from_json(body.as_bytes())?);
+        }
+        Ok(days)
+    }
+
+    /// Application usage for days `from..=to`, as local day indexes.
+    pub fn app_usage(&self, from: i64, to: i64) -> Result<BTreeMap<i64, AppUsageDayDto>, AppError> {
+        let conn = self.lock()?;
+        let mut statement = conn
+            .prepare("SELECT day, body FROM app_usage WHERE day BETWEEN ?1 AND ?2 ORDER BY day")?;
+        let rows = statement.query_map(params![from, to], |row| {
+            Ok((row.get::<_, i64>(0)?, row.get::<_, String>(1)?))
+        })?;
+        let mut days = BTreeMap::new();
+        for row in rows {
+            let (day, body) = row?;
+            days.insert(day, from_json(body.as_bytes())?);
+        }
+        Ok(days)
+    }
+
+    /// Breaks scheduled in `start..end`, oldest first.
+    pub fn break_history(&self, start: u64, end: u64) -> Result<Vec<BreakSessionDto>, AppError> {
+        self.bodies(
+            "SELECT body FROM break_history
+             WHERE scheduled_at >= ?1 AND scheduled_at < ?2 ORDER BY scheduled_at",
+            start,
+            end,
+        )
+    }
+
+    /// Focus sessions started in `start..end`, oldest first.
+    pub fn focus_sessions(&self, start: u64, end: u64) -> Result<Vec<FocusSessionDto>, AppError> {
+        self.bodies(
+            "SELECT body FROM focus_sessions
+             WHERE started_at >= ?1 AND started_at < ?2 ORDER BY started_at",
+            start,
+            end,
+        )
+    }
+
+    /// Writes the stored state as one plain JSON file, the format used
+    /// before SQLite, for backups or moving to another machine.
+    pub fn export_json(&self, target: &Path) -> Result<(), AppError> {
+        let state = match self.load()? {
+            Loaded::State(state) => *state,
+            Loaded::Empty => AppStateOnDisk::default(),
+            Loaded::Locked(_, error) => return Err(AppError::Storage(error)),
+        };
+        let payload = serde_json::to_vec_pretty(&state)?;
+        fs::write(target, payload)?;
+        Ok(())
+    }
+
+    fn bodies<T: DeserializeOwned>(
+        &self,
+        sql: &str,
+        start: u64,
+        end: u64,
+    ) -> Result<Vec<T>, AppError> {
+        let conn = self.lock()?;
+        let mut statement = conn.prepare(sql)?;
+        let rows = statement.query_map(params![to_sql(start), to_sql(end)], |row| {
+            row.get::<_, String>(0)
+        })?;
+        let mut values = Vec::new();
+        for row in rows {
+            values.push(from_json(row?.as_bytes())?);
+        }
+        Ok(values)
+    }
+
+    fn lock(&self) -> Result<std::sync::MutexGuard<'_, Connection>, AppError> {
+        self.conn.lock().map_err(AppError::from)
+    }
+}
+
+fn migrate(conn: &mut Connection) -> Result<(), AppError> {
+    let applied: i64 = conn.pragma_query_value(None, "user_version", |row| row.get(0))?;
+    for (version, step) in (1..).zip(MIGRATIONS).skip(applied.max(0) as usize) {
+        let tx = conn.transaction()?;
+        tx.execute_batch(step)?;
+        tx.pragma_update(None, "user_version", version)?;
+        tx.commit()?;
+        tracing::info!(version, "storage schema migrated");
+    }
+    Ok(())
+}
+
+fn is_sealed(tx: &Transaction<'_>) -> Result<bool, AppError> {
+    let body = tx
+        .query_row("SELECT body FROM document WHERE id = 1", [], |row| {
+            row.get::<_, Vec<u8>>(0)
+        })
+        .optional()?;
+    Ok(body.is_some_and(|body| vault::is_sealed(&body)))
+}
+
+fn write_document(tx: &Transaction<'_>, body: &[u8]) -> Result<(), AppError> {
+    tx.execute(
+        "INSERT INTO document (id, body) VALUES (1, ?1)
+         ON CONFLICT (id) DO UPDATE SET body = excluded.body",
+        params![body],
+    )?;
+    Ok(())
+}
+
+// The in-memory lists only lose entries from the front when pruned, so
+// dropping rows older than the first entry and upserting the rest keeps the
+// tables in step without rewriting them.
+
+fn write_daily_stats(
+    tx: &Transaction<'_>,
+    days: &BTreeMap<i64, DailyStatsDto>,
+) -> Result<(), AppError> {
+    let first = days.keys().next().copied().unwrap_or(i64::MAX);
+    tx.execute("DELETE FROM daily_stats WHERE day < ?1", params![first])?;
+    let mut statement =
+        tx.prepare_cached("INSERT OR REPLACE INTO daily_stats (day, body) VALUES (?1, ?2)")?;
+    for (day, stats) in days {
+        statement.execute(params![day, to_json_text(stats)?])?;
+    }
+    Ok(())
+}
+
+fn write_app_usage(
+    tx: &Transaction<'_>,
+    days: &BTreeMap<i64, AppUsageDayDto>,
+) -> Result<(), AppError> {
+    let first = days.keys().next().copied().unwrap_or(i64::MAX);
+    tx.execute("DELETE FROM app_usage WHERE day < ?1", params![first])?;
+    let mut statement =
+        tx.prepare_cached("INSERT OR REPLACE INTO app_usage (day, body) VALUES (?1, ?2)")?;
+    for (day, apps) in days {
+        statement.execute(params![day, to_json_text(apps)?])?;
+    }
+    Ok(())
+}
+
+fn write_break_history(tx: &Transaction<'_>, sessions: &[BreakSessionDto]) -> Result<(), AppError> {
+    let first = sessions
+        .iter()
+        .map(BreakSessionDto::scheduled_at)
+        .min()
+        .unwrap_or(u64::MAX);
+    tx.execute(
+        "DELETE FROM break_history WHERE scheduled_at < ?1",
+        params![to_sql(first)],
+    )?;
+    let mut statement = tx.prepare_cached(
+        "INSERT OR REPLACE INTO break_history (kind, scheduled_at, body) VALUES (?1, ?2, ?3)",
+    )?;
+    for session in sessions {
+        statement.execute(params![
+            session.kind(),
+            to_sql(session.scheduled_at()),
+            to_json_text(session)?
+        ])?;
+    }
+    Ok(())
+}
+
+fn write_focus_sessions(
+    tx: &Transaction<'_>,
+    sessions: &[FocusSessionDto],
+) -> Result<(), AppError> {
+    let first = sessions
+        .iter()
+        .map(FocusSessionDto::started_at)
+        .min()
+        .unwrap_or(u64::MAX);
+    tx.execute(
+        "DELETE FROM focus_sessions WHERE started_at < ?1",
+        params![to_sql(first)],
+    )?;
+    let mut statement = tx.prepare_cached(
+        "INSERT OR REPLACE INTO focus_sessions (started_at, body) VALUES (?1, ?2)",
+    )?;
+    for session in sessions {
+        statement.execute(params![
+            to_sql(session.started_at()),
+            to_json_text(session)?
+        ])?;
+    }
+    Ok(())
+}
+
+/// SQLite integers are signed; timestamps past `i64::MAX` only appear as
+/// open range ends.
+fn to_sql(value: u64) -> i64 {
+    i64::try_from(value).unwrap_or(i64::MAX)
+}
+
+fn to_json<T: Serialize>(value: &T) -> Result<Vec<u8>, AppError> {
+    serde_json::to_vec(value).map_err(|e| AppError::Storage(e.to_string()))
+}
+
+fn to_json_text<T: Serialize>(value: &T) -> Result<String, AppError> {
+    serde_json::to_string(value).map_err(|e| AppError::Storage(e.to_string()))
+}
+
+fn from_json<T: DeserializeOwned>(raw: &[u8]) -> Result<T, AppError> {
+    serde_json::from_slice(raw).map_err(|e| AppError::Storage(e.to_string()))
+}
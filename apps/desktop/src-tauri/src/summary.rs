@@ -0,0 +1,59 @@
+use lazaro_core::analytics::DailyAggregate;
+use serde::Serialize;
+
+use crate::{history, i18n};
+
+// The day that just ended is the calendar day holding the middle of the
+// 24h window closed by the reset, so late reset times still report "today".
+const RESET_WINDOW_MIDPOINT_SECONDS: u64 = 12 * 3_600;
+
+#[derive(Clone, Debug, Serialize)]
+pub struct DailySummaryDto {
+    pub day: String,
+    pub active_seconds: u64,
+    pub micro_done: u32,
+    pub rest_done: u32,
+    pub daily_limit_hits: u32,
+    pub skipped: u32,
+    pub message: String,
+}
+
+pub fn format_duration(seconds: u64) -> String {
+    let hours = seconds / 3_600;
+    let minutes = (seconds % 3_600) / 60;
+    if hours > 0 {
+        format!("{hours}h{minutes:02}m")
+    } else {
+        format!("{minutes}m")
+    }
+}
+
+pub fn ended_day_index(reset_at: u64) -> i64 {
+    history::local_day_index_at(reset_at.saturating_sub(RESET_WINDOW_MIDPOINT_SECONDS))
+}
+
+pub fn build(
+    messages: i18n::Messages<'_>,
+    day_index: i64,
+    stats: &DailyAggregate,
+) -> DailySummaryDto {
+    let message = messages.format(
+        "daily.summary",
+        &[
+            ("active", &format_duration(stats.active_seconds)),
+            ("micro", &stats.micro_done.to_string()),
+            ("rest", &stats.rest_done.to_string()),
+            ("skipped", &stats.skipped.to_string()),
+        ],
+    );
+
+    DailySummaryDto {
+        day: history::day_index_to_date(day_index),
+        active_seconds: stats.active_seconds,
+        micro_done: stats.micro_done,
+        rest_done: stats.rest_done,
+        daily_limit_hits: stats.daily_limit_hits,
+        skipped: stats.skipped,
+        message,
+    }
+}
@@ -0,0 +1,456 @@
+use std::{
+    collections::BTreeMap,
+    fs,
+    io::Read,
+    path::PathBuf,
+    sync::Mutex,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use lazaro_core::{
+    analytics::DailyAggregate,
+    sync::{Stamped, last_writer_wins, merge_days},
+};
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Manager};
+
+use crate::{
+    AppError, BackendState, DailyStatsDto, ProfileDto, SettingsDto, commit_settings_at, curl,
+    default_data_dir, history, storage, unix_now,
+};
+
+const SYNC_INTERVAL: Duration = Duration::from_secs(60);
+const SNAPSHOT_PREFIX: &str = "lazaro-";
+const SNAPSHOT_SUFFIX: &str = ".json";
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct SyncMetaDto {
+    pub device_id: String,
+    pub settings_updated_at: u64,
+    pub profiles_updated_at: u64,
+}
+
+/// What each device publishes: its own analytics plus the settings and
+/// profiles it last wrote, stamped for last-writer-wins.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+struct DeviceSnapshot {
+    device_id: String,
+    written_at: u64,
+    settings_updated_at: u64,
+    settings: SettingsDto,
+    profiles_updated_at: u64,
+    profiles: BTreeMap<String, ProfileDto>,
+    daily_stats: BTreeMap<i64, DailyStatsDto>,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct SyncStatusDto {
+    backend: String,
+    device_id: String,
+    peers: Vec<String>,
+    last_synced_at: Option<u64>,
+    last_error: Option<String>,
+}
+
+static STATUS: Mutex<SyncStatusDto> = Mutex::new(SyncStatusDto {
+    backend: String::new(),
+    device_id: String::new(),
+    peers: Vec::new(),
+    last_synced_at: None,
+    last_error: None,
+});
+
+pub fn status() -> Result<SyncStatusDto, AppError> {
+    STATUS
+        .lock()
+        .map(|status| status.clone())
+        .map_err(AppError::from)
+}
+
+/// Host name plus a random suffix, restricted to characters that are safe in
+/// file names and URLs.
+pub fn new_device_id() -> String {
+    let host = fs::read_to_string("/etc/hostname")
+        .ok()
+        .or_else(|| std::env::var("HOSTNAME").ok())
+        .unwrap_or_default();
+    let host: String = host
+        .trim()
+        .to_ascii_lowercase()
+        .chars()
+        .filter(|c| c.is_ascii_alphanumeric() || *c == '-')
+        .take(32)
+        .collect();
+
+    let mut bytes = [0u8; 4];
+    if fs::File::open("/dev/urandom")
+        .and_then(|mut file| file.read_exact(&mut bytes))
+        .is_err()
+    {
+        let nanos = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|elapsed| elapsed.subsec_nanos())
+            .unwrap_or_default();
+        bytes = nanos.to_be_bytes();
+    }
+    let suffix: String = bytes.iter().map(|byte| format!("{byte:02x}")).collect();
+
+    if host.is_empty() {
+        suffix
+    } else {
+        format!("{host}-{suffix}")
+    }
+}
+
+// Fields that describe this machine or hold credentials. They are blanked
+// before publishing and kept as-is when another device's settings win.
+fn keep_device_fields(settings: &mut SettingsDto, device: &SettingsDto) {
+    settings.sync_backend = device.sync_backend.clone();
+    settings.sync_directory = device.sync_directory.clone();
+    settings.sync_webdav_url = device.sync_webdav_url.clone();
+    settings.sync_webdav_username = device.sync_webdav_username.clone();
+    settings.sync_webdav_password = device.sync_webdav_password.clone();
+    settings.remote_enabled = device.remote_enabled;
+    settings.remote_port = device.remote_port;
+    settings.remote_token = device.remote_token.clone();
+    settings.slack_token = device.slack_token.clone();
+    settings.teams_token = device.teams_token.clone();
+    settings.calendar_source_path = device.calendar_source_path.clone();
+    settings.theme_background_image = device.theme_background_image.clone();
+    settings.blocking_hosts_file = device.blocking_hosts_file.clone();
+    settings.startup_xdg = device.startup_xdg;
+    settings.startup_systemd_user = device.startup_systemd_user;
+    settings.peer_sharing_enabled = device.peer_sharing_enabled;
+    settings.peer_port = device.peer_port;
+    settings.peer_secret = device.peer_secret.clone();
+    settings.encrypt_state = device.encrypt_state;
+}
+
+fn scrubbed(settings: &SettingsDto) -> SettingsDto {
+    let mut settings = settings.clone();
+    keep_device_fields(&mut settings, &SettingsDto::default());
+    settings
+}
+
+enum Store {
+    Directory(PathBuf),
+    WebDav {
+        url: String,
+        username: String,
+        password: String,
+    },
+}
+
+impl Store {
+    fn from_settings(settings: &SettingsDto) -> Option<Self> {
+        match settings.sync_backend.as_str() {
+            "directory" => match settings.sync_directory.trim() {
+                "" => None,
+                path => Some(Store::Directory(match path.strip_prefix("~/") {
+                    Some(relative) => {
+                        PathBuf::from(std::env::var("HOME").unwrap_or_else(|_| ".".into()))
+                            .join(relative)
+                    }
+                    None => PathBuf::from(path),
+                })),
+            },
+            "webdav" if !settings.sync_webdav_url.trim().is_empty() => Some(Store::WebDav {
+                url: settings
+                    .sync_webdav_url
+                    .trim()
+                    .trim_end_matches('/')
+                    .to_string(),
+                username: settings.sync_webdav_username.trim().to_string(),
+                password: settings.sync_webdav_password.clone(),
+            }),
+            _ => None,
+        }
+    }
+
+    fn webdav_config(url: &str, username: &str, password: &str, name: &str) -> String {
+        let mut config = curl::option("url", &format!("{url}/{name}"));
+        if !username.is_empty() {
+            config.push_str(&curl::option("user", &format!("{username}:{password}")));
+        }
+        config
+    }
+
+    fn write(&self, name: &str, contents: &[u8]) -> Result<(), String> {
+        match self {
+            Store::Directory(dir) => {
+                fs::create_dir_all(dir).map_err(|e| e.to_string())?;
+                // Written under a hidden name first so peers never read a
+                // half-written snapshot.
+                let partial = dir.join(format!(".{name}.partial"));
+                fs::write(&partial, contents).map_err(|e| e.to_string())?;
+                fs::rename(&partial, dir.join(name)).map_err(|e| e.to_string())
+            }
+            Store::WebDav {
+                url,
+                username,
+                password,
+            } => {
+                let upload = default_data_dir().join("sync-upload.json");
+                fs::write(&upload, contents).map_err(|e| e.to_string())?;
+                let mut config = Self::webdav_config(url, username, password, name);
+                config.push_str(&curl::option("upload-file", &upload.to_string_lossy()));
+                let result = curl::run(&config).map(|_| ());
+                let _ = fs::remove_file(&upload);
+                result
+            }
+        }
+    }
+
+    fn list(&self) -> Result<Vec<String>, String> {
+        let names: Vec<String> = match self {
+            Store::Directory(dir) => fs::read_dir(dir)
+                .map_err(|e| e.to_string())?
+                .flatten()
+                .filter_map(|entry| entry.file_name().into_string().ok())
+                .collect(),
+            Store::WebDav {
+                url,
+                username,
+                password,
+            } => {
+                let mut config = Self::webdav_config(url, username, password, "");
+                config.push_str(&curl::option("request", "PROPFIND"));
+                config.push_str(&curl::option("header", "Depth: 1"));
+                let body = curl::run(&config)?;
+                String::from_utf8_lossy(&body)
+                    .split("href>")
+                    .filter_map(|chunk| chunk.split('<').next())
+                    .filter_map(|href| href.trim_end_matches('/').rsplit('/').next())
+                    .map(str::to_string)
+                    .collect()
+            }
+        };
+
+        Ok(names
+            .into_iter()
+            .filter(|name| name.starts_with(SNAPSHOT_PREFIX) && name.ends_with(SNAPSHOT_SUFFIX))
+            .collect())
+    }
+
+    fn read(&self, name: &str) -> Result<Vec<u8>, String> {
+        match self {
+            Store::Directory(dir) => fs::read(dir.join(name)).map_err(|e| e.to_string()),
+            Store::WebDav {
+                url,
+                username,
+                password,
+            } => curl::run(&Self::webdav_config(url, username, password, name)),
+        }
+    }
+}
+
+fn file_name(device_id: &str) -> String {
+    format!("{SNAPSHOT_PREFIX}{device_id}{SNAPSHOT_SUFFIX}")
+}
+
+pub fn spawn_supervisor(app: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        let mut ticker = tokio::time::interval(SYNC_INTERVAL);
+        loop {
+            ticker.tick().await;
+            let app = app.clone();
+            let _ = tauri::async_runtime::spawn_blocking(move || sync_once(&app)).await;
+        }
+    });
+}
+
+fn sync_once(app: &AppHandle) {
+    let state = app.state::<BackendState>();
+    let (local, store) = match local_snapshot(&state) {
+        Ok(snapshot) => snapshot,
+        Err(error) => {
+            tracing::warn!(%error, "failed to read local state for sync");
+            return;
+        }
+    };
+
+    let Some((backend, store)) = store else {
+        if let Ok(mut synced) = state.persistent.synced_days.lock() {
+            synced.clear();
+        }
+        if let Ok(mut status) = STATUS.lock() {
+            status.backend = "off".into();
+            status.device_id = local.device_id;
+            status.peers.clear();
+            status.last_error = None;
+        }
+        return;
+    };
+
+    let result = exchange(&state, &store, &local);
+    if let Err(error) = &result {
+        tracing::warn!(%error, "settings sync failed");
+    }
+    if let Ok(mut status) = STATUS.lock() {
+        status.backend = backend;
+        status.device_id = local.device_id;
+        match result {
+            Ok(peers) => {
+                status.peers = peers;
+                status.last_synced_at = Some(unix_now());
+                status.last_error = None;
+            }
+            Err(error) => status.last_error = Some(error),
+        }
+    }
+}
+
+type LocalSnapshot = (DeviceSnapshot, Option<(String, Store)>);
+
+fn local_snapshot(state: &BackendState) -> Result<LocalSnapshot, AppError> {
+    let guard = state.persistent.data.lock()?;
+    let store = Store::from_settings(&guard.effective)
+        .map(|store| (guard.effective.sync_backend.clone(), store));
+    let snapshot = DeviceSnapshot {
+        device_id: guard.sync.device_id.clone(),
+        written_at: unix_now(),
+        settings_updated_at: guard.sync.settings_updated_at,
+        settings: guard.settings.clone(),
+        profiles_updated_at: guard.sync.profiles_updated_at,
+        profiles: guard.profiles.clone(),
+        daily_stats: guard.daily_stats.clone(),
+    };
+    Ok((snapshot, store))
+}
+
+fn exchange(
+    state: &BackendState,
+    store: &Store,
+    local: &DeviceSnapshot,
+) -> Result<Vec<String>, String> {
+    let mut published = local.clone();
+    published.settings = scrubbed(&local.settings);
+    for profile in published.profiles.values_mut() {
+        profile.settings = scrubbed(&profile.settings);
+    }
+    let payload = serde_json::to_vec_pretty(&published).map_err(|e| e.to_string())?;
+    store.write(&file_name(&local.device_id), &payload)?;
+
+    let own = file_name(&local.device_id);
+    let peers: Vec<DeviceSnapshot> = store
+        .list()?
+        .into_iter()
+        .filter(|name| *name != own)
+        .filter_map(|name| {
+            let parsed = store.read(&name).and_then(|raw| {
+                serde_json::from_slice::<DeviceSnapshot>(&raw).map_err(|e| e.to_string())
+            });
+            match parsed {
+                Ok(snapshot) => Some(snapshot),
+                Err(error) => {
+                    tracing::debug!(%error, file = %name, "skipping unreadable sync snapshot");
+                    None
+                }
+            }
+        })
+        .collect();
+
+    apply_settings(state, local, &peers).map_err(|e| e.to_string())?;
+    apply_profiles(state, local, &peers).map_err(|e| e.to_string())?;
+
+    let peer_days: Vec<BTreeMap<i64, DailyAggregate>> = peers
+        .iter()
+        .map(|peer| {
+            peer.daily_stats
+                .iter()
+                .map(|(day, stats)| (*day, DailyAggregate::from(stats)))
+                .collect()
+        })
+        .collect();
+    if let Ok(mut synced) = state.persistent.synced_days.lock() {
+        *synced = merge_days(&peer_days);
+    }
+    let today = history::local_day_index();
+    for peer in &peers {
+        if let Some(stats) = peer.daily_stats.get(&today) {
+            state
+                .persistent
+                .record_peer_active(&peer.device_id, today, stats.active_seconds);
+        }
+    }
+
+    Ok(peers.into_iter().map(|peer| peer.device_id).collect())
+}
+
+fn same<T: Serialize>(a: &T, b: &T) -> bool {
+    serde_json::to_value(a).ok() == serde_json::to_value(b).ok()
+}
+
+pub fn same_shared_settings(a: &SettingsDto, b: &SettingsDto) -> bool {
+    same(&scrubbed(a), &scrubbed(b))
+}
+
+fn apply_settings(
+    state: &BackendState,
+    local: &DeviceSnapshot,
+    peers: &[DeviceSnapshot],
+) -> Result<(), AppError> {
+    let Some(winner) =
+        last_writer_wins(std::iter::once(local).chain(peers).map(|snapshot| Stamped {
+            device_id: snapshot.device_id.clone(),
+            updated_at: snapshot.settings_updated_at,
+            value: &snapshot.settings,
+        }))
+    else {
+        return Ok(());
+    };
+    if winner.device_id == local.device_id {
+        return Ok(());
+    }
+
+    let mut settings = winner.value.clone();
+    keep_device_fields(&mut settings, &local.settings);
+    // Both sides are saved settings, before any local overrides.
+    if same(&settings, &local.settings) {
+        return Ok(());
+    }
+
+    tracing::info!(from = %winner.device_id, "applying synced settings");
+    commit_settings_at(state, settings, winner.updated_at)?;
+    Ok(())
+}
+
+fn apply_profiles(
+    state: &BackendState,
+    local: &DeviceSnapshot,
+    peers: &[DeviceSnapshot],
+) -> Result<(), AppError> {
+    let Some(winner) =
+        last_writer_wins(std::iter::once(local).chain(peers).map(|snapshot| Stamped {
+            device_id: snapshot.device_id.clone(),
+            updated_at: snapshot.profiles_updated_at,
+            value: &snapshot.profiles,
+        }))
+    else {
+        return Ok(());
+    };
+    if winner.device_id == local.device_id {
+        return Ok(());
+    }
+
+    let mut profiles = winner.value.clone();
+    for profile in profiles.values_mut() {
+        let device = local
+            .profiles
+            .get(&profile.id)
+            .map(|existing| existing.settings.clone())
+            .unwrap_or_else(|| local.settings.clone());
+        keep_device_fields(&mut profile.settings, &device);
+    }
+    if same(&profiles, &local.profiles) {
+        return Ok(());
+    }
+
+    tracing::info!(from = %winner.device_id, "applying synced profiles");
+    {
+        let mut guard = state.persistent.data.lock()?;
+        guard.profiles = profiles;
+        guard.sync.profiles_updated_at = winner.updated_at;
+    }
+    state.persistent.save(storage::Sections::DOCUMENT)
+}
@@ -0,0 +1,37 @@
+use tauri::{
+    AppHandle, Manager,
+    window::{ProgressBarState, ProgressBarStatus},
+};
+
+/// Shows how much of the active break is left on the main window's taskbar
+/// or dock entry. Shells without the API just ignore it.
+#[derive(Default)]
+pub struct TaskbarProgress {
+    last: Option<u8>,
+}
+
+impl TaskbarProgress {
+    /// `None` clears the bar.
+    pub fn update(&mut self, app: &AppHandle, percent: Option<u8>) {
+        if self.last == percent {
+            return;
+        }
+        let Some(window) = app.get_webview_window("main") else {
+            return;
+        };
+        let state = match percent {
+            Some(percent) => ProgressBarState {
+                status: Some(ProgressBarStatus::Normal),
+                progress: Some(u64::from(percent)),
+            },
+            None => ProgressBarState {
+                status: Some(ProgressBarStatus::None),
+                progress: None,
+            },
+        };
+        if let Err(error) = window.set_progress_bar(state) {
+            tracing::debug!(%error, "taskbar progress not available");
+        }
+        self.last = percent;
+    }
+}
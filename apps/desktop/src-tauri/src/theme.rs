@@ -0,0 +1,155 @@
+use std::{fs, path::Path};
+
+use lazaro_core::config::{self, Settings};
+use serde::{Deserialize, Serialize};
+
+use crate::{AppError, SettingsDto, websocket};
+
+// Embedded as a data URL in the overlay payload, so keep it modest.
+const MAX_BACKGROUND_IMAGE_BYTES: u64 = 4 * 1024 * 1024;
+const IMAGE_TYPES: [(&str, &str); 5] = [
+    ("png", "image/png"),
+    ("jpg", "image/jpeg"),
+    ("jpeg", "image/jpeg"),
+    ("webp", "image/webp"),
+    ("gif", "image/gif"),
+];
+
+/// The theme fields of `SettingsDto`, as sent by `update_theme`.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ThemeDto {
+    pub mode: String,
+    pub accent_color: String,
+    pub background_color: String,
+    pub text_color: String,
+    pub background_image: String,
+    pub font_scale_percent: u16,
+}
+
+impl From<&SettingsDto> for ThemeDto {
+    fn from(value: &SettingsDto) -> Self {
+        Self {
+            mode: value.theme_mode.clone(),
+            accent_color: value.theme_accent_color.clone(),
+            background_color: value.theme_background_color.clone(),
+            text_color: value.theme_text_color.clone(),
+            background_image: value.theme_background_image.clone(),
+            font_scale_percent: value.theme_font_scale_percent,
+        }
+    }
+}
+
+impl ThemeDto {
+    /// Runs every field through the same parser as config.toml, then checks
+    /// that the background image can actually be shown.
+    pub fn validate(mut self) -> Result<Self, AppError> {
+        let mut scratch = Settings::default();
+        let background_image = self.background_image.trim().to_string();
+        let font_scale = self.font_scale_percent.to_string();
+        for (key, value) in [
+            ("theme_mode", self.mode.as_str()),
+            ("theme_accent_color", self.accent_color.as_str()),
+            ("theme_background_color", self.background_color.as_str()),
+            ("theme_text_color", self.text_color.as_str()),
+            ("theme_background_image", background_image.as_str()),
+            ("theme_font_scale_percent", font_scale.as_str()),
+        ] {
+            config::apply_setting(&mut scratch, key, value)
+                .map_err(|error| AppError::InvalidTheme(error.to_string()))?;
+        }
+        if !background_image.is_empty() {
+            check_image(Path::new(&background_image))?;
+        }
+
+        let theme = scratch.theme;
+        self.mode = self.mode.trim().to_ascii_lowercase();
+        self.accent_color = theme.accent_color;
+        self.background_color = theme.background_color;
+        self.text_color = theme.text_color;
+        self.background_image = theme.background_image_path;
+        Ok(self)
+    }
+
+    pub fn apply(self, settings: &mut SettingsDto) {
+        settings.theme_mode = self.mode;
+        settings.theme_accent_color = self.accent_color;
+        settings.theme_background_color = self.background_color;
+        settings.theme_text_color = self.text_color;
+        settings.theme_background_image = self.background_image;
+        settings.theme_font_scale_percent = self.font_scale_percent;
+    }
+}
+
+/// Concrete colors for the current mode, sent to the windows on
+/// `theme_changed` and inside the overlay payload.
+#[derive(Clone, Debug, Serialize)]
+pub struct ResolvedThemeDto {
+    pub mode: String,
+    pub background: String,
+    pub foreground: String,
+    pub accent: String,
+    /// A data URL, since the webviews cannot read arbitrary files.
+    pub background_image: Option<String>,
+    pub font_scale_percent: u16,
+}
+
+pub fn resolve(settings: &SettingsDto) -> ResolvedThemeDto {
+    let (background, foreground) = match settings.theme_mode.as_str() {
+        "light" => ("#f4f7fb", "#0b1620"),
+        "custom" => (
+            settings.theme_background_color.as_str(),
+            settings.theme_text_color.as_str(),
+        ),
+        _ => ("#050c14", "#f7fbff"),
+    };
+    let path = settings.theme_background_image.trim();
+    let background_image = if path.is_empty() {
+        None
+    } else {
+        match background_data_url(Path::new(path)) {
+            Ok(url) => Some(url),
+            Err(error) => {
+                tracing::warn!(%error, "failed to load theme background image");
+                None
+            }
+        }
+    };
+
+    ResolvedThemeDto {
+        mode: settings.theme_mode.clone(),
+        background: background.into(),
+        foreground: foreground.into(),
+        accent: settings.theme_accent_color.clone(),
+        background_image,
+        font_scale_percent: settings.theme_font_scale_percent,
+    }
+}
+
+fn check_image(path: &Path) -> Result<&'static str, AppError> {
+    let invalid = |reason: &str| AppError::InvalidTheme(format!("{}: {reason}", path.display()));
+    let extension = path
+        .extension()
+        .and_then(|extension| extension.to_str())
+        .map(str::to_ascii_lowercase)
+        .unwrap_or_default();
+    let mime = IMAGE_TYPES
+        .iter()
+        .find(|(known, _)| *known == extension)
+        .map(|(_, mime)| *mime)
+        .ok_or_else(|| invalid("unsupported image type"))?;
+
+    let metadata = fs::metadata(path).map_err(|e| invalid(&e.to_string()))?;
+    if !metadata.is_file() {
+        return Err(invalid("not a file"));
+    }
+    if metadata.len() > MAX_BACKGROUND_IMAGE_BYTES {
+        return Err(invalid("image is larger than 4 MiB"));
+    }
+    Ok(mime)
+}
+
+fn background_data_url(path: &Path) -> Result<String, AppError> {
+    let mime = check_image(path)?;
+    let bytes = fs::read(path)?;
+    Ok(format!("data:{mime};base64,{}", websocket::base64(&bytes)))
+}
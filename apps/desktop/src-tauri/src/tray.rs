@@ -0,0 +1,250 @@
+use std::f64::consts::TAU;
+
+use lazaro_core::{config::Settings, timer::BreakKind};
+use tauri::{
+    AppHandle, Manager, Wry,
+    image::Image,
+    menu::{CheckMenuItem, Menu, MenuItem, PredefinedMenuItem},
+    tray::TrayIconBuilder,
+};
+
+use crate::{AppError, BackendState, RuntimeStatusDto, i18n, summary, update_settings, widget};
+
+pub const TRAY_ID: &str = "main";
+// The arc moves in steps so the icon is not redrawn every tick.
+const PROGRESS_STEP_PERCENT: u8 = 5;
+const RING_WIDTH_RATIO: f64 = 0.2;
+const RING_DONE: [u8; 4] = [244, 185, 66, 255];
+const RING_TODO: [u8; 4] = [63, 101, 115, 200];
+
+/// Menu items whose state follows the settings.
+struct TrayMenu {
+    widget: CheckMenuItem<Wry>,
+}
+
+pub fn install(app: &AppHandle, widget_enabled: bool) -> tauri::Result<()> {
+    let show = MenuItem::with_id(app, "show", "Mostrar Lázaro", true, None::<&str>)?;
+    let widget_item = CheckMenuItem::with_id(
+        app,
+        "widget",
+        "Widget de cuenta atrás",
+        true,
+        widget_enabled,
+        None::<&str>,
+    )?;
+    let quit = MenuItem::with_id(app, "quit", "Salir", true, None::<&str>)?;
+    let separator = PredefinedMenuItem::separator(app)?;
+    let menu = Menu::with_items(app, &[&show, &widget_item, &separator, &quit])?;
+
+    let mut builder = TrayIconBuilder::with_id(TRAY_ID)
+        .tooltip("Lázaro")
+        .menu(&menu)
+        .on_menu_event(|app, event| match event.id().as_ref() {
+            "show" => show_main_window(app),
+            "widget" => {
+                if let Err(error) = toggle_widget(app) {
+                    tracing::warn!(%error, "failed to toggle countdown widget");
+                }
+            }
+            "quit" => app.exit(0),
+            _ => {}
+        });
+    if let Some(icon) = app.default_window_icon() {
+        builder = builder.icon(icon.clone());
+    }
+    builder.build(app)?;
+
+    app.manage(TrayMenu {
+        widget: widget_item,
+    });
+    Ok(())
+}
+
+pub fn set_widget_checked(app: &AppHandle, checked: bool) {
+    if let Some(menu) = app.try_state::<TrayMenu>() {
+        let _ = menu.widget.set_checked(checked);
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct Indicator {
+    percent: u8,
+    tooltip: String,
+    /// Shown in place of the percentage, e.g. while presenting.
+    title: Option<String>,
+}
+
+/// Mirrors the runtime status on the tray icon, touching it only when what
+/// it shows has changed.
+#[derive(Default)]
+pub struct TrayProgress {
+    last: Option<Indicator>,
+}
+
+impl TrayProgress {
+    pub fn update(
+        &mut self,
+        app: &AppHandle,
+        status: &RuntimeStatusDto,
+        settings: &Settings,
+        lang: i18n::Language,
+    ) {
+        let next = indicator(status, settings, lang);
+        if self.last.as_ref() == Some(&next) {
+            return;
+        }
+        let Some(tray) = app.tray_by_id(TRAY_ID) else {
+            return;
+        };
+
+        if let Some(base) = app.default_window_icon() {
+            let _ = tray.set_icon(Some(progress_icon(base, next.percent)));
+        }
+        let _ = tray.set_tooltip(Some(&next.tooltip));
+        let title = next
+            .title
+            .clone()
+            .unwrap_or_else(|| format!("{}%", next.percent));
+        let _ = tray.set_title(Some(title));
+        self.last = Some(next);
+    }
+
+    pub fn clear(&mut self, app: &AppHandle) {
+        if self.last.take().is_none() {
+            return;
+        }
+        if let Some(tray) = app.tray_by_id(TRAY_ID) {
+            let _ = tray.set_icon(app.default_window_icon().cloned());
+            let _ = tray.set_tooltip(Some("Lázaro"));
+            let _ = tray.set_title(None::<&str>);
+        }
+    }
+}
+
+fn indicator(status: &RuntimeStatusDto, settings: &Settings, lang: i18n::Language) -> Indicator {
+    let label = |kind: BreakKind| i18n::break_label(lang, settings, kind).to_string();
+    let active = i18n::format(
+        lang,
+        "tray.active",
+        &[(
+            "active",
+            &summary::format_duration(status.daily_active_seconds),
+        )],
+    );
+
+    let mut title = None;
+    let (percent, detail) = if status.presentation_until.is_some() || status.screen_capture_active {
+        title = Some(i18n::text(lang, "tray.presentation.title").to_string());
+        (0, i18n::text(lang, "tray.presentation").to_string())
+    } else if let Some(kind) = status.active_break {
+        let remaining = summary::format_duration(status.remaining_seconds.unwrap_or(0));
+        (
+            100,
+            i18n::format(
+                lang,
+                "tray.break",
+                &[("kind", &label(kind)), ("remaining", &remaining)],
+            ),
+        )
+    } else if let Some(kind) = status.pending_break {
+        (
+            100,
+            i18n::format(lang, "tray.pending", &[("kind", &label(kind))]),
+        )
+    } else if let Some(kind) = status.next_break_kind {
+        let eta = status.next_break_eta_seconds.unwrap_or(0);
+        (
+            next_break_percent(status, settings, kind, eta),
+            i18n::format(
+                lang,
+                "tray.next",
+                &[
+                    ("kind", &label(kind)),
+                    ("eta", &summary::format_duration(eta)),
+                ],
+            ),
+        )
+    } else {
+        (0, String::new())
+    };
+
+    let percent = percent.min(100) / PROGRESS_STEP_PERCENT * PROGRESS_STEP_PERCENT;
+    let tooltip = [
+        i18n::text(lang, "app.title"),
+        active.as_str(),
+        detail.as_str(),
+    ]
+    .into_iter()
+    .filter(|line| !line.is_empty())
+    .collect::<Vec<_>>()
+    .join("\n");
+    Indicator {
+        percent,
+        tooltip,
+        title,
+    }
+}
+
+fn next_break_percent(
+    status: &RuntimeStatusDto,
+    settings: &Settings,
+    kind: BreakKind,
+    eta: u64,
+) -> u8 {
+    let interval = match kind {
+        BreakKind::Micro => return status.micro_progress_percent,
+        BreakKind::Rest => return status.rest_progress_percent,
+        BreakKind::DailyLimit => return status.daily_limit_progress_percent,
+        BreakKind::Custom(index) => settings
+            .custom_timers
+            .get(usize::from(index))
+            .map_or(0, |timer| timer.timer.interval_seconds),
+    };
+    if interval == 0 {
+        return 0;
+    }
+    (interval.saturating_sub(eta).saturating_mul(100) / interval).min(100) as u8
+}
+
+/// Draws a ring around the edge of `base`, filled clockwise from the top.
+fn progress_icon(base: &Image<'_>, percent: u8) -> Image<'static> {
+    let (width, height) = (base.width(), base.height());
+    let mut rgba = base.rgba().to_vec();
+    let outer = f64::from(width.min(height)) / 2.0;
+    let inner = outer * (1.0 - RING_WIDTH_RATIO);
+    let filled = f64::from(percent) / 100.0;
+
+    for y in 0..height {
+        for x in 0..width {
+            let dx = f64::from(x) + 0.5 - f64::from(width) / 2.0;
+            let dy = f64::from(y) + 0.5 - f64::from(height) / 2.0;
+            let distance = dx.hypot(dy);
+            if distance < inner || distance > outer {
+                continue;
+            }
+            let angle = dx.atan2(-dy).rem_euclid(TAU) / TAU;
+            let color = if angle < filled { RING_DONE } else { RING_TODO };
+            let offset = ((y * width + x) * 4) as usize;
+            rgba[offset..offset + 4].copy_from_slice(&color);
+        }
+    }
+    Image::new_owned(rgba, width, height)
+}
+
+fn show_main_window(app: &AppHandle) {
+    if let Some(window) = app.get_webview_window("main") {
+        let _ = window.unminimize();
+        let _ = window.show();
+        let _ = window.set_focus();
+    }
+}
+
+fn toggle_widget(app: &AppHandle) -> Result<(), AppError> {
+    let state = app.state::<BackendState>();
+    let mut settings = state.persistent.data.lock()?.effective.clone();
+    settings.countdown_widget = !settings.countdown_widget;
+
+    let settings = update_settings(settings, state)?;
+    widget::sync(app, settings.countdown_widget);
+    Ok(())
+}
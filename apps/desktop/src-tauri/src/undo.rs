@@ -0,0 +1,74 @@
+use std::collections::VecDeque;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{ProfileDto, SettingsDto};
+
+const UNDO_LIMIT: usize = 20;
+
+/// What an undo puts back.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum UndoChange {
+    Settings {
+        previous: SettingsDto,
+        changed: Vec<String>,
+    },
+    ProfileRemoved {
+        profile: ProfileDto,
+        /// Set when the removed profile was active and another one took over.
+        previous_settings: Option<Box<SettingsDto>>,
+    },
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct UndoEntryDto {
+    pub at: u64,
+    pub change: UndoChange,
+}
+
+/// One line of `get_change_history`, without the stored snapshots.
+#[derive(Clone, Debug, Serialize)]
+pub struct ChangeSummaryDto {
+    pub at: u64,
+    pub kind: String,
+    pub changed: Vec<String>,
+    pub profile_name: Option<String>,
+}
+
+impl From<&UndoEntryDto> for ChangeSummaryDto {
+    fn from(entry: &UndoEntryDto) -> Self {
+        let (kind, changed, profile_name) = match &entry.change {
+            UndoChange::Settings { changed, .. } => ("settings", changed.clone(), None),
+            UndoChange::ProfileRemoved { profile, .. } => {
+                ("profile_removed", Vec::new(), Some(profile.name.clone()))
+            }
+        };
+        Self {
+            at: entry.at,
+            kind: kind.into(),
+            changed,
+            profile_name,
+        }
+    }
+}
+
+pub fn push(history: &mut VecDeque<UndoEntryDto>, at: u64, change: UndoChange) {
+    if history.len() == UNDO_LIMIT {
+        history.pop_front();
+    }
+    history.push_back(UndoEntryDto { at, change });
+}
+
+/// Names of the settings fields that differ, so no-op saves are not recorded.
+pub fn changed_fields(previous: &SettingsDto, next: &SettingsDto) -> Vec<String> {
+    let (Ok(serde_json::Value::Object(previous)), Ok(serde_json::Value::Object(next))) =
+        (serde_json::to_value(previous), serde_json::to_value(next))
+    else {
+        return Vec::new();
+    };
+    next.iter()
+        .filter(|(key, value)| previous.get(*key) != Some(*value))
+        .map(|(key, _)| key.clone())
+        .collect()
+}
@@ -0,0 +1,142 @@
+use std::{sync::Mutex, time::Duration};
+
+use lazaro_core::{
+    config::UpdateChannel,
+    update::{self, Release},
+};
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Emitter, Manager};
+
+use crate::{AppError, BackendState, curl, unix_now};
+
+const CHECK_INTERVAL: Duration = Duration::from_secs(6 * 60 * 60);
+const FEED_URL: &str = "https://api.github.com/repos/carlosrm22/lazaro/releases";
+const CURRENT_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+/// `get_update_status`: the result of the last check. Installing is left
+/// to the package manager or the release page.
+#[derive(Clone, Debug, Serialize)]
+pub struct UpdateStatusDto {
+    current_version: String,
+    channel: String,
+    checked_at: Option<u64>,
+    latest_version: Option<String>,
+    release_url: Option<String>,
+    update_available: bool,
+    last_error: Option<String>,
+}
+
+static STATUS: Mutex<UpdateStatusDto> = Mutex::new(UpdateStatusDto {
+    current_version: String::new(),
+    channel: String::new(),
+    checked_at: None,
+    latest_version: None,
+    release_url: None,
+    update_available: false,
+    last_error: None,
+});
+
+#[derive(Deserialize)]
+struct FeedEntryDto {
+    tag_name: String,
+    #[serde(default)]
+    prerelease: bool,
+    #[serde(default)]
+    draft: bool,
+    #[serde(default)]
+    html_url: String,
+}
+
+pub fn status() -> Result<UpdateStatusDto, AppError> {
+    STATUS
+        .lock()
+        .map(|status| UpdateStatusDto {
+            current_version: CURRENT_VERSION.into(),
+            ..status.clone()
+        })
+        .map_err(AppError::from)
+}
+
+/// Checks the feed now, whatever `update_check_enabled` says.
+pub fn check(app: &AppHandle) -> Result<UpdateStatusDto, AppError> {
+    let channel = {
+        let state = app.state::<BackendState>();
+        let guard = state.persistent.data.lock()?;
+        guard.effective.update_channel.clone()
+    };
+    let parsed = match channel.as_str() {
+        "beta" => UpdateChannel::Beta,
+        _ => UpdateChannel::Stable,
+    };
+
+    let result =
+        fetch().map(|releases| update::newest_release(CURRENT_VERSION, &releases, parsed).cloned());
+    let (status, newly_available) = {
+        let mut status = STATUS.lock()?;
+        let previous = status.latest_version.clone();
+        status.current_version = CURRENT_VERSION.into();
+        status.channel = channel;
+        status.checked_at = Some(unix_now());
+        match result {
+            Ok(release) => {
+                status.update_available = release.is_some();
+                status.latest_version = release.as_ref().map(|release| release.tag.clone());
+                status.release_url = release.map(|release| release.url);
+                status.last_error = None;
+            }
+            Err(error) => {
+                tracing::warn!(%error, "update check failed");
+                status.last_error = Some(error);
+            }
+        }
+        let newly_available = status.update_available && status.latest_version != previous;
+        (status.clone(), newly_available)
+    };
+
+    if newly_available {
+        tracing::info!(version = ?status.latest_version, "update available");
+        let _ = app.emit("update_available", &status);
+    }
+    Ok(status)
+}
+
+fn fetch() -> Result<Vec<Release>, String> {
+    let config = [
+        curl::option("url", FEED_URL),
+        curl::option("header", "Accept: application/vnd.github+json"),
+        curl::option("user-agent", &format!("lazaro/{CURRENT_VERSION}")),
+    ]
+    .concat();
+    let body = curl::run(&config)?;
+    let entries: Vec<FeedEntryDto> = serde_json::from_slice(&body).map_err(|e| e.to_string())?;
+    Ok(entries
+        .into_iter()
+        .filter(|entry| !entry.draft)
+        .map(|entry| Release {
+            tag: entry.tag_name,
+            prerelease: entry.prerelease,
+            url: entry.html_url,
+        })
+        .collect())
+}
+
+pub fn spawn_checker(app: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        let mut ticker = tokio::time::interval(CHECK_INTERVAL);
+        loop {
+            ticker.tick().await;
+            let enabled = app
+                .state::<BackendState>()
+                .persistent
+                .data
+                .lock()
+                .map(|guard| guard.effective.update_check_enabled)
+                .unwrap_or(false);
+            if !enabled {
+                continue;
+            }
+            let app = app.clone();
+            let _ = tauri::async_runtime::spawn_blocking(move || check(&app)).await;
+        }
+    });
+}
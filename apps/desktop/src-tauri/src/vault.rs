@@ -0,0 +1,146 @@
+use std::{
+    fs::File,
+    io::{Read, Write},
+    process::{Command, Stdio},
+    sync::Mutex,
+};
+
+use lazaro_core::crypto::{self, KEY_LEN, NONCE_LEN};
+
+// Sealed files start with this header, which is also bound in as associated
+// data so it cannot be swapped.
+const MAGIC: &[u8] = b"LAZARO-SEALED-1\n";
+const KEY_ATTRIBUTES: [&str; 4] = ["application", "lazaro", "purpose", "state-encryption"];
+const KEY_LABEL: &str = "Lazaro state encryption key";
+
+static KEY: Mutex<Option<[u8; KEY_LEN]>> = Mutex::new(None);
+
+pub fn is_sealed(raw: &[u8]) -> bool {
+    raw.starts_with(MAGIC)
+}
+
+/// Makes sure a key exists in the keyring, creating one if needed, so
+/// encryption can be turned on without later saves failing.
+pub fn ensure_key() -> Result<(), String> {
+    key(true).map(|_| ())
+}
+
+pub fn seal(plaintext: &[u8]) -> Result<Vec<u8>, String> {
+    let key = key(true)?;
+    let mut nonce = [0u8; NONCE_LEN];
+    random(&mut nonce)?;
+
+    let mut sealed = MAGIC.to_vec();
+    sealed.extend_from_slice(&nonce);
+    sealed.extend(
+        crypto::seal(&key, &nonce, MAGIC, plaintext)
+            .ok_or_else(|| "state too large".to_string())?,
+    );
+    Ok(sealed)
+}
+
+pub fn open(raw: &[u8]) -> Result<Vec<u8>, String> {
+    let body = raw
+        .strip_prefix(MAGIC)
+        .ok_or_else(|| "not a sealed file".to_string())?;
+    if body.len() < NONCE_LEN {
+        return Err("sealed file is truncated".into());
+    }
+    let (nonce, sealed) = body.split_at(NONCE_LEN);
+    let nonce: [u8; NONCE_LEN] = nonce.try_into().map_err(|_| "bad nonce".to_string())?;
+
+    let key = key(false)?;
+    crypto::open(&key, &nonce, MAGIC, sealed)
+        .ok_or_else(|| "wrong key or corrupted file".to_string())
+}
+
+pub fn forget_key() -> Result<(), String> {
+    *KEY.lock().map_err(|e| format!("mutex poisoned: {e}"))? = None;
+    let status = Command::new("secret-tool")
+        .arg("clear")
+        .args(KEY_ATTRIBUTES)
+        .stderr(Stdio::null())
+        .status()
+        .map_err(|e| format!("secret-tool unavailable: {e}"))?;
+    if !status.success() {
+        return Err("secret-tool clear failed".into());
+    }
+    Ok(())
+}
+
+fn key(create: bool) -> Result<[u8; KEY_LEN], String> {
+    let mut cached = KEY.lock().map_err(|e| format!("mutex poisoned: {e}"))?;
+    if let Some(key) = *cached {
+        return Ok(key);
+    }
+
+    let key = match lookup()? {
+        Some(key) => key,
+        None if create => {
+            let mut key = [0u8; KEY_LEN];
+            random(&mut key)?;
+            store(&key)?;
+            tracing::info!("created state encryption key in the keyring");
+            key
+        }
+        None => return Err("no state encryption key in the keyring".into()),
+    };
+    *cached = Some(key);
+    Ok(key)
+}
+
+// secret-tool exits non-zero when nothing matches, which is not an error here.
+fn lookup() -> Result<Option<[u8; KEY_LEN]>, String> {
+    let output = Command::new("secret-tool")
+        .arg("lookup")
+        .args(KEY_ATTRIBUTES)
+        .stderr(Stdio::null())
+        .output()
+        .map_err(|e| format!("secret-tool unavailable: {e}"))?;
+    if !output.status.success() {
+        return Ok(None);
+    }
+
+    let hex = String::from_utf8_lossy(&output.stdout);
+    decode_hex(hex.trim())
+        .map(Some)
+        .ok_or_else(|| "malformed state encryption key in the keyring".to_string())
+}
+
+fn store(key: &[u8; KEY_LEN]) -> Result<(), String> {
+    let mut child = Command::new("secret-tool")
+        .args(["store", "--label", KEY_LABEL])
+        .args(KEY_ATTRIBUTES)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| format!("secret-tool unavailable: {e}"))?;
+    if let Some(mut stdin) = child.stdin.take() {
+        let hex: String = key.iter().map(|byte| format!("{byte:02x}")).collect();
+        stdin.write_all(hex.as_bytes()).map_err(|e| e.to_string())?;
+    }
+
+    let output = child.wait_with_output().map_err(|e| e.to_string())?;
+    if !output.status.success() {
+        return Err(String::from_utf8_lossy(&output.stderr).trim().to_string());
+    }
+    Ok(())
+}
+
+fn decode_hex(hex: &str) -> Option<[u8; KEY_LEN]> {
+    if hex.len() != KEY_LEN * 2 {
+        return None;
+    }
+    let mut key = [0u8; KEY_LEN];
+    for (index, byte) in key.iter_mut().enumerate() {
+        *byte = u8::from_str_radix(hex.get(index * 2..index * 2 + 2)?, 16).ok()?;
+    }
+    Some(key)
+}
+
+fn random(buffer: &mut [u8]) -> Result<(), String> {
+    File::open("/dev/urandom")
+        .and_then(|mut file| file.read_exact(buffer))
+        .map_err(|e| e.to_string())
+}
@@ -0,0 +1,91 @@
+use serde::{Deserialize, Serialize};
+use tauri::{
+    AppHandle, Manager, PhysicalPosition, Position, WebviewUrl, WebviewWindowBuilder, WindowEvent,
+};
+
+use crate::{BackendState, tray};
+
+pub const WIDGET_WINDOW_LABEL: &str = "countdown-widget";
+const WIDGET_WIDTH: f64 = 220.0;
+const WIDGET_HEIGHT: f64 = 84.0;
+const WIDGET_MARGIN: i32 = 24;
+
+/// Last place the widget was dragged to, in physical pixels.
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+pub struct WidgetPositionDto {
+    pub x: i32,
+    pub y: i32,
+}
+
+/// Opens or closes the widget to match the setting.
+pub fn sync(app: &AppHandle, enabled: bool) {
+    tray::set_widget_checked(app, enabled);
+    let app_handle = app.clone();
+    let _ = app.run_on_main_thread(move || {
+        let existing = app_handle.get_webview_window(WIDGET_WINDOW_LABEL);
+        match (enabled, existing) {
+            (true, None) => open(&app_handle),
+            (false, Some(window)) => {
+                let _ = window.close();
+            }
+            _ => {}
+        }
+    });
+}
+
+fn open(app: &AppHandle) {
+    let saved = app
+        .state::<BackendState>()
+        .persistent
+        .data
+        .lock()
+        .ok()
+        .and_then(|guard| guard.widget_position);
+
+    let builder = WebviewWindowBuilder::new(
+        app,
+        WIDGET_WINDOW_LABEL,
+        WebviewUrl::App("widget.html".into()),
+    )
+    .title("Lázaro")
+    .decorations(false)
+    .always_on_top(true)
+    .resizable(false)
+    .skip_taskbar(true)
+    .focused(false)
+    .inner_size(WIDGET_WIDTH, WIDGET_HEIGHT);
+
+    let Ok(window) = builder.build() else {
+        tracing::warn!("failed to open countdown widget");
+        return;
+    };
+
+    let position = saved.or_else(|| {
+        let monitor = app.primary_monitor().ok().flatten()?;
+        let window_width = window.outer_size().map(|size| size.width).unwrap_or(0);
+        Some(WidgetPositionDto {
+            x: monitor.position().x + monitor.size().width as i32
+                - window_width as i32
+                - WIDGET_MARGIN,
+            y: monitor.position().y + WIDGET_MARGIN,
+        })
+    });
+    if let Some(position) = position {
+        let _ = window.set_position(Position::Physical(PhysicalPosition::new(
+            position.x, position.y,
+        )));
+    }
+
+    // Saved with the rest of the state on the next periodic persist or exit.
+    let app_handle = app.clone();
+    window.on_window_event(move |event| {
+        if let WindowEvent::Moved(position) = event
+            && let Ok(mut guard) = app_handle.state::<BackendState>().persistent.data.lock()
+        {
+            guard.widget_position = Some(WidgetPositionDto {
+                x: position.x,
+                y: position.y,
+            });
+        }
+    });
+}
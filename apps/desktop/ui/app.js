@@ -38,11 +38,34 @@ async function invoke(command, args = {}) {
   return invokeRaw(command, args);
 }
 
+// Commands reject with `{code, message, field}`; bridge failures are plain
+// errors or strings.
+function errorText(err) {
+  return typeof err?.message === "string" ? err.message : String(err);
+}
+
+function focusErrorField(err) {
+  const element = err?.field ? document.getElementById(err.field) : null;
+  element?.focus();
+}
+
 const state = {
   settings: null,
   stats: null,
   runtime: null,
   profiles: [],
+  configFile: null,
+  history: [],
+  goals: null,
+  categories: null,
+  dailySummary: null,
+  dashboard: null,
+  busyWindows: [],
+  syncStatus: null,
+  lanPeers: [],
+  policy: null,
+  capabilities: null,
+  themeLoaded: false,
   events: [],
   refreshTimer: null,
   showDebug: false,
@@ -54,19 +77,128 @@ const settingsFields = [
   "micro_interval_seconds",
   "micro_duration_seconds",
   "micro_snooze_seconds",
+  "micro_enabled",
+  "micro_jitter_percent",
+  "micro_block_level",
+  "micro_resets",
   "rest_interval_seconds",
   "rest_duration_seconds",
   "rest_snooze_seconds",
+  "rest_enabled",
+  "rest_jitter_percent",
+  "rest_block_level",
+  "rest_resets",
+  "adaptive_enabled",
+  "adaptive_min_interval_percent",
+  "adaptive_max_interval_percent",
+  "intensity_enabled",
+  "intensity_scale_micro",
+  "intensity_heavy_events_per_minute",
+  "intensity_max_reduction_percent",
+  "escalation_enabled",
+  "escalation_renotify_seconds",
+  "escalation_force_seconds",
   "daily_limit_seconds",
   "daily_limit_snooze_seconds",
+  "daily_limit_enabled",
   "daily_reset_time",
+  "daily_limit_max_snoozes",
+  "daily_limit_action",
+  "daily_limit_resets_timers",
+  "daily_limit_warning_thresholds",
+  "weekly_limit_enabled",
+  "weekly_limit_seconds",
+  "weekly_limit_warning_thresholds",
+  "app_limits_enabled",
+  "app_limits",
+  "app_limit_block_level",
+  "nudges_enabled",
+  "nudge_notifications",
+  "nudge_skip_rate",
+  "nudge_long_focus",
+  "nudge_late_night",
+  "nudge_interval_seconds",
+  "wind_down_enabled",
+  "wind_down_start",
+  "wind_down_cutoff",
+  "wind_down_interval_percent",
+  "wind_down_budget_seconds",
+  "day_overrides",
+  "limit_scope",
   "block_level",
+  "fade_on_soft",
+  "fade_on_medium",
+  "fade_on_strict",
+  "fade_warning_seconds",
+  "fade_max_opacity_percent",
   "desktop_notifications",
   "overlay_notifications",
   "sound_notifications",
   "sound_theme",
+  "activity_packs",
+  "countdown_cue_seconds",
+  "respect_system_dnd",
+  "defer_breaks_on_dnd",
+  "daily_summary_notifications",
+  "countdown_widget",
+  "taskbar_progress",
+  "message_templates",
+  "inhibit_on_fullscreen",
+  "inhibit_on_media",
+  "presentation_on_screencast",
+  "blocking_enabled",
+  "blocking_app_classes",
+  "blocking_app_action",
+  "blocking_hosts",
+  "blocking_hosts_file",
+  "calendar_enabled",
+  "calendar_source_path",
+  "calendar_meeting_policy",
+  "media_keys_enabled",
+  "media_start_keys",
+  "media_snooze_keys",
+  "remote_enabled",
+  "remote_port",
+  "remote_token",
+  "slack_token",
+  "teams_token",
+  "break_status_template",
+  "break_status_emoji",
+  "sync_backend",
+  "sync_directory",
+  "sync_webdav_url",
+  "sync_webdav_username",
+  "sync_webdav_password",
+  "peer_sharing_enabled",
+  "peer_port",
+  "peer_secret",
+  "encrypt_state",
+  "high_contrast_overlay",
+  "reduce_motion",
+  "large_countdown",
+  "screen_reader_announcements",
+  "language",
+  "week_start",
+  "weekly_max_active_seconds",
+  "weekly_min_breaks",
+  "focus_gap_seconds",
+  "break_feedback_prompt",
+  "milestone_celebrations",
+  "app_usage_enabled",
+  "app_usage_titles",
+  "app_categories",
+  "tick_emit_interval_seconds",
+  "break_tick_millis",
+  "log_level",
+  "power_saver_behavior",
+  "interval_change_policy",
+  "restart_runtime_on_crash",
   "startup_xdg",
   "startup_systemd_user",
+  "warmup_grace_seconds",
+  "idle_counts_as_rest",
+  "update_check_enabled",
+  "update_channel",
   "active_profile_id",
 ];
 
@@ -79,14 +211,65 @@ const timeFields = new Set([
   "rest_snooze_seconds",
   "daily_limit_seconds",
   "daily_limit_snooze_seconds",
+  "weekly_limit_seconds",
+  "weekly_max_active_seconds",
+  "focus_gap_seconds",
+  "warmup_grace_seconds",
+  "escalation_renotify_seconds",
+  "escalation_force_seconds",
+  "nudge_interval_seconds",
+  "wind_down_budget_seconds",
 ]);
 
 const autoSaveCheckboxFields = new Set([
+  "micro_enabled",
+  "rest_enabled",
+  "daily_limit_enabled",
+  "daily_limit_resets_timers",
+  "weekly_limit_enabled",
+  "break_feedback_prompt",
+  "milestone_celebrations",
+  "nudges_enabled",
+  "nudge_notifications",
+  "nudge_skip_rate",
+  "nudge_long_focus",
+  "nudge_late_night",
+  "wind_down_enabled",
+  "app_usage_enabled",
+  "app_usage_titles",
   "desktop_notifications",
   "overlay_notifications",
   "sound_notifications",
+  "respect_system_dnd",
+  "defer_breaks_on_dnd",
+  "daily_summary_notifications",
+  "countdown_widget",
+  "taskbar_progress",
+  "inhibit_on_fullscreen",
+  "inhibit_on_media",
+  "presentation_on_screencast",
+  "blocking_enabled",
+  "adaptive_enabled",
+  "intensity_enabled",
+  "intensity_scale_micro",
+  "escalation_enabled",
+  "calendar_enabled",
+  "media_keys_enabled",
+  "remote_enabled",
+  "peer_sharing_enabled",
+  "encrypt_state",
+  "high_contrast_overlay",
+  "reduce_motion",
+  "large_countdown",
+  "screen_reader_announcements",
+  "fade_on_soft",
+  "fade_on_medium",
+  "fade_on_strict",
   "startup_xdg",
   "startup_systemd_user",
+  "idle_counts_as_rest",
+  "restart_runtime_on_crash",
+  "update_check_enabled",
 ]);
 
 function unitSelectId(fieldId) {
@@ -219,6 +402,23 @@ function beep() {
   }
 }
 
+const confettiColors = ["var(--accent)", "var(--ok)", "var(--info)", "var(--warn)", "var(--danger)"];
+
+function showConfetti() {
+  if (state.settings?.reduce_motion) return;
+  const layer = document.createElement("div");
+  layer.className = "confetti";
+  for (let index = 0; index < 60; index += 1) {
+    const piece = document.createElement("span");
+    piece.style.left = `${Math.random() * 100}%`;
+    piece.style.background = confettiColors[index % confettiColors.length];
+    piece.style.animationDelay = `${Math.random() * 0.6}s`;
+    layer.appendChild(piece);
+  }
+  document.body.appendChild(layer);
+  setTimeout(() => layer.remove(), 3500);
+}
+
 function pushEvent(kind, message) {
   const item = {
     kind,
@@ -270,6 +470,24 @@ function renderEvents() {
   }
 }
 
+function formatTimerProgress(enabledField, percent) {
+  if (state.settings && state.settings[enabledField] === false) return "desactivado";
+  return `${percent ?? 0}%`;
+}
+
+function formatDailyForecast(runtime) {
+  if (!runtime.running || runtime.daily_limit_forecast_seconds == null) return "-";
+  if (runtime.daily_limit_forecast_seconds === 0) return "alcanzado";
+  if (!runtime.daily_limit_forecast_before_reset) return "no antes del reinicio";
+  return `en ${formatSeconds(runtime.daily_limit_forecast_seconds)} (${formatClock(runtime.daily_limit_forecast_at)})`;
+}
+
+function formatLimitAction(runtime) {
+  if (runtime.daily_limit_action_at == null) return "-";
+  const remaining = Math.max(0, runtime.daily_limit_action_at - Math.floor(Date.now() / 1000));
+  return `en ${formatSeconds(remaining)}`;
+}
+
 function renderRuntime() {
   const runtime = state.runtime || {};
   const container = document.getElementById("runtime-grid");
@@ -277,21 +495,41 @@ function renderRuntime() {
   if (runtime.running) {
     if (runtime.active_break) {
       nextBreakIn = "descanso en curso";
-    } else if (runtime.next_break_seconds != null) {
+    } else if (runtime.next_break_eta_seconds != null) {
       nextBreakIn =
-        runtime.next_break_seconds === 0
+        runtime.next_break_eta_seconds === 0
           ? "ahora"
-          : formatSeconds(runtime.next_break_seconds);
+          : formatSeconds(runtime.next_break_eta_seconds);
     }
   }
 
   const entries = [
     ["running", runtime.running ? "sí" : "no"],
-    ["pendiente", runtime.pending_break || "ninguno"],
+    ["pendiente", runtime.pending_breaks?.length ? runtime.pending_breaks.join(" → ") : "ninguno"],
     ["en descanso", runtime.active_break || "ninguno"],
     ["restante", runtime.remaining_seconds != null ? formatSeconds(runtime.remaining_seconds) : "-"],
     ["próximo tipo", runtime.next_break_kind || "-"],
     ["próximo descanso en", nextBreakIn],
+    ["progreso micro", formatTimerProgress("micro_enabled", runtime.micro_progress_percent)],
+    ["progreso largo", formatTimerProgress("rest_enabled", runtime.rest_progress_percent)],
+    ["progreso límite diario", formatTimerProgress("daily_limit_enabled", runtime.daily_limit_progress_percent)],
+    ["progreso límite semanal", formatTimerProgress("weekly_limit_enabled", runtime.weekly_limit_progress_percent)],
+    ["límite diario previsto", formatDailyForecast(runtime)],
+    ["acción del límite diario", formatLimitAction(runtime)],
+    ["escala de intervalos", `${runtime.interval_scale_percent ?? 100}%`],
+    ["desconexión nocturna", formatWindDown(runtime)],
+    ["en reunión", runtime.in_meeting ? "sí" : "no"],
+    ["vacaciones", runtime.vacation_until ? `hasta ${new Date(runtime.vacation_until * 1000).toLocaleDateString()}` : "no"],
+    ["sesión de invitado", runtime.guest_until ? `hasta las ${new Date(runtime.guest_until * 1000).toLocaleTimeString()}` : "no"],
+    [
+      "modo presentación",
+      runtime.screen_capture_active
+        ? "compartiendo pantalla"
+        : runtime.presentation_until
+          ? `hasta las ${new Date(runtime.presentation_until * 1000).toLocaleTimeString()}`
+          : "no",
+    ],
+    ["ahorro de energía", runtime.power_saving ? "sí" : "no"],
     ["modo estricto", runtime.strict_mode ? "sí" : "no"],
     ["último evento", runtime.last_event || "-"]
   ];
@@ -315,6 +553,23 @@ function renderRuntime() {
   const pill = document.getElementById("runtime-pill");
   pill.textContent = runtime.running ? "activo" : "detenido";
   pill.classList.toggle("running", Boolean(runtime.running));
+  document.getElementById("cancel-limit-action").hidden = runtime.daily_limit_action_at == null;
+  document.getElementById("vacation-end").hidden = runtime.vacation_until == null;
+  document.getElementById("guest-end").hidden = runtime.guest_until == null;
+  document.getElementById("presentation-end").hidden = runtime.presentation_until == null;
+  document.body.classList.toggle("wind-down", runtime.wind_down != null);
+}
+
+function formatWindDown(runtime) {
+  if (!runtime.wind_down) return "no";
+  const label = runtime.wind_down === "past_cutoff" ? "pasada la hora de parar" : "en curso";
+  return `${label} · ${formatSeconds(runtime.wind_down_active_seconds ?? 0)}`;
+}
+
+function promptToStop(message) {
+  document.getElementById("stop-message").textContent = message;
+  const dialog = document.getElementById("stop-dialog");
+  if (!dialog.open) dialog.showModal();
 }
 
 function renderProfiles() {
@@ -337,14 +592,89 @@ function renderProfiles() {
   }
 }
 
+function renderConfigFileNote() {
+  const note = document.getElementById("config-file-note");
+  const info = state.configFile;
+  const overrides = info?.overrides || [];
+  const errors = info?.errors || [];
+  const lines = [];
+
+  if (overrides.length > 0) {
+    const fields = overrides.map((entry) => `${entry.field} (${overrideSourceLabel(entry.source)})`);
+    lines.push(`Definido fuera de la app: ${fields.join(", ")}`);
+  }
+  if (errors.length > 0) {
+    lines.push(`Ignorado: ${errors.join("; ")}`);
+  }
+  note.textContent = lines.join(" · ");
+  note.classList.toggle("hidden", lines.length === 0);
+}
+
+function renderPolicyNote() {
+  const note = document.getElementById("policy-note");
+  const policy = state.policy;
+  const lines = [];
+
+  if (policy?.locked_fields?.length) {
+    lines.push(`Bloqueado por la organización: ${policy.locked_fields.join(", ")}`);
+  }
+  if (policy?.minimums?.length) {
+    const minimums = policy.minimums.map((entry) => `${entry.field} ≥ ${entry.minimum}`);
+    lines.push(`Mínimos de la organización: ${minimums.join(", ")}`);
+  }
+  note.textContent = lines.join(" · ");
+  note.classList.toggle("hidden", lines.length === 0);
+}
+
+// Toggles that only work when the platform has the matching capability.
+const capabilityFields = {
+  desktop_notifications: (caps) => Boolean(caps.notification_server),
+  overlay_notifications: (caps) => caps.overlay_on_top,
+  countdown_widget: (caps) => caps.overlay_on_top,
+  startup_xdg: (caps) => caps.xdg_autostart,
+  startup_systemd_user: (caps) => caps.systemd_user,
+};
+
+function renderCapabilities() {
+  const caps = state.capabilities;
+  if (!caps) return;
+  for (const [key, supported] of Object.entries(capabilityFields)) {
+    const element = document.getElementById(key);
+    if (!element) continue;
+    // Keep a toggle that is already on visible so it can still be turned off.
+    const hide = !supported(caps) && !state.settings?.[key];
+    element.closest("label")?.classList.toggle("hidden", hide);
+  }
+}
+
+function overrideSourceLabel(source) {
+  if (source === "env") return "variable de entorno";
+  if (source === "cli") return "argumento";
+  return "config.toml";
+}
+
 function renderSettingsForm() {
   if (!state.settings) return;
+  renderPolicyNote();
+  renderConfigFileNote();
   if (state.settingsDirty) return;
 
+  const locked = new Set(state.policy?.locked_fields || []);
+
+  const overrides = new Map(
+    (state.configFile?.overrides || []).map((entry) => [entry.field, entry.source])
+  );
   for (const key of settingsFields) {
     const element = document.getElementById(key);
     if (!element) continue;
 
+    element.disabled = locked.has(key) || overrides.has(key);
+    if (locked.has(key)) {
+      element.title = "Bloqueado por la organización";
+    } else {
+      element.title = overrides.has(key) ? `Definido por ${overrideSourceLabel(overrides.get(key))}` : "";
+    }
+
     const value = state.settings[key];
     if (element.type === "checkbox") {
       element.checked = Boolean(value);
@@ -360,6 +690,107 @@ function renderSettingsForm() {
       element.value = value ?? "";
     }
   }
+
+  renderCustomTimers();
+}
+
+function customTimerRow(timer) {
+  const row = document.createElement("div");
+  row.className = "custom-timer";
+  row.innerHTML = `
+    <label>Nombre <input type="text" data-field="name" /></label>
+    <label>Identificador <input type="text" data-field="id" /></label>
+    <label>Intervalo (min) <input type="number" data-field="interval_minutes" min="1" step="any" /></label>
+    <label>Duración (seg) <input type="number" data-field="duration_seconds" min="1" step="1" /></label>
+    <label>Posponer (min) <input type="number" data-field="snooze_minutes" min="0" step="any" /></label>
+    <label>Variación (±%) <input type="number" data-field="jitter_percent" min="0" max="50" step="1" /></label>
+    <label>Nivel de bloqueo
+      <select data-field="block_level">
+        <option value="default">Como el global</option>
+        <option value="soft">Suave</option>
+        <option value="medium">Medio</option>
+        <option value="strict">Estricto</option>
+      </select>
+    </label>
+    <label>Prioridad <input type="number" data-field="priority" min="0" max="255" step="1" /></label>
+    <label>Reinicia (ids separados por comas) <input type="text" data-field="resets" /></label>
+    <label class="checkbox-label"><input type="checkbox" data-field="enabled" /> Activo</label>
+    <button type="button" class="danger" data-action="remove">Quitar</button>
+  `;
+
+  const field = (name) => row.querySelector(`[data-field="${name}"]`);
+  field("name").value = timer.name || "";
+  field("id").value = timer.id || "";
+  field("interval_minutes").value = formatNumberForInput(Number(timer.interval_seconds || 0) / 60);
+  field("duration_seconds").value = String(timer.duration_seconds ?? 0);
+  field("snooze_minutes").value = formatNumberForInput(Number(timer.snooze_seconds || 0) / 60);
+  field("jitter_percent").value = String(timer.jitter_percent ?? 0);
+  field("block_level").value = timer.block_level || "default";
+  field("priority").value = String(timer.priority ?? 2);
+  field("resets").value = (timer.resets || []).join(", ");
+  field("enabled").checked = timer.enabled !== false;
+  return row;
+}
+
+function renderCustomTimers() {
+  const list = document.getElementById("custom-timers-list");
+  list.innerHTML = "";
+  for (const timer of state.settings?.custom_timers || []) {
+    list.appendChild(customTimerRow(timer));
+  }
+}
+
+function collectCustomTimers() {
+  return Array.from(document.querySelectorAll("#custom-timers-list .custom-timer")).map((row) => {
+    const field = (name) => row.querySelector(`[data-field="${name}"]`);
+    return {
+      id: field("id").value.trim(),
+      name: field("name").value.trim(),
+      interval_seconds: displayToSeconds(field("interval_minutes").value, "minutes"),
+      duration_seconds: Number(field("duration_seconds").value || 0),
+      snooze_seconds: displayToSeconds(field("snooze_minutes").value, "minutes"),
+      jitter_percent: Number(field("jitter_percent").value || 0),
+      block_level: field("block_level").value,
+      priority: Number(field("priority").value || 0),
+      resets: field("resets")
+        .value.split(",")
+        .map((id) => id.trim())
+        .filter(Boolean),
+      enabled: field("enabled").checked,
+    };
+  });
+}
+
+function setupCustomTimers() {
+  const list = document.getElementById("custom-timers-list");
+  list.addEventListener("input", () => {
+    state.settingsDirty = true;
+  });
+  list.addEventListener("click", (event) => {
+    const button = event.target.closest("[data-action=\"remove\"]");
+    if (!button) return;
+    button.closest(".custom-timer").remove();
+    state.settingsDirty = true;
+  });
+
+  document.getElementById("custom-timer-add").addEventListener("click", () => {
+    const count = list.children.length + 1;
+    list.appendChild(
+      customTimerRow({
+        id: `timer-${count}`,
+        name: "",
+        interval_seconds: 1800,
+        duration_seconds: 60,
+        snooze_seconds: 300,
+        jitter_percent: 0,
+        block_level: "default",
+        priority: 2,
+        resets: [],
+        enabled: true,
+      })
+    );
+    state.settingsDirty = true;
+  });
 }
 
 function setupSettingsDirtyTracking() {
@@ -402,8 +833,14 @@ function queueAutoSaveCheckbox(fieldKey, options = {}) {
       pushEvent("info", `auto-guardado: ${fieldKey}`);
       renderAll();
     })
-    .catch((err) => {
-      pushEvent("error", `ERROR auto-guardado ${fieldKey}: ${String(err)}`);
+    .catch(async (err) => {
+      pushEvent("error", `ERROR auto-guardado ${fieldKey}: ${errorText(err)}`);
+      focusErrorField(err);
+      // The backend saved autostart off; show that instead of the click.
+      if (err?.code === "autostart_denied") {
+        state.settings = await invoke("get_settings").catch(() => state.settings);
+        renderAll();
+      }
     });
 }
 
@@ -432,6 +869,8 @@ function collectSettingsFromForm() {
     next[key] = element.value;
   }
 
+  next.custom_timers = collectCustomTimers();
+
   if (!next.active_profile_id) {
     next.active_profile_id = state.settings?.active_profile_id || "default";
   }
@@ -439,6 +878,19 @@ function collectSettingsFromForm() {
   return next;
 }
 
+function setupBreakKeys() {
+  document.addEventListener("keydown", (event) => {
+    if (!state.settings?.media_keys_enabled || !state.runtime?.pending_break || event.repeat) return;
+    if (event.target instanceof HTMLInputElement || event.target instanceof HTMLSelectElement) return;
+
+    invoke("handle_break_key", { key: event.key })
+      .then((action) => {
+        if (action) pushEvent("info", `tecla ${event.key}: ${action}`);
+      })
+      .catch((err) => pushEvent("warn", `tecla: ${errorText(err)}`));
+  });
+}
+
 function setupUnitSelectors() {
   for (const field of timeFields) {
     const select = document.getElementById(unitSelectId(field));
@@ -472,12 +924,237 @@ function renderAnalytics() {
   document.getElementById("metric-daily").textContent = String(stats.daily_limit_hits ?? 0);
   document.getElementById("metric-skipped").textContent = String(stats.skipped ?? 0);
 
-  const weeklyTarget = Math.max(1, Number(settings.daily_limit_seconds || 0) * 7);
-  const percent = Math.min(100, Math.round(((Number(stats.total_active_seconds || 0)) / weeklyTarget) * 100));
+  const goals = state.goals;
+  if (goals && goals.max_active_seconds > 0) {
+    const percent = Math.min(100, goals.active_percent);
+    document.getElementById("progress-text").textContent =
+      `${formatSeconds(goals.active_seconds)} / ${formatSeconds(goals.max_active_seconds)} (${goals.active_percent}%)`;
+    document.getElementById("progress-bar").style.width = `${percent}%`;
+    document.getElementById("analytics-summary").textContent = `semana desde ${goals.week_start}`;
+  } else {
+    const weeklyTarget = Math.max(1, Number(settings.daily_limit_seconds || 0) * 7);
+    const percent = Math.min(100, Math.round(((Number(stats.total_active_seconds || 0)) / weeklyTarget) * 100));
+
+    document.getElementById("progress-text").textContent = `${percent}%`;
+    document.getElementById("progress-bar").style.width = `${percent}%`;
+    document.getElementById("analytics-summary").textContent = `objetivo: ${formatSeconds(weeklyTarget)}`;
+  }
+
+  document.getElementById("metric-rating").textContent =
+    goals?.average_rating != null ? goals.average_rating.toFixed(1) : "-";
+
+  const summary = state.dailySummary;
+  document.getElementById("daily-summary").textContent = summary ? `Hoy: ${summary.message}` : "";
+
+  const breaksWrap = document.getElementById("breaks-goal-wrap");
+  breaksWrap.hidden = !(goals && goals.min_breaks > 0);
+  if (!breaksWrap.hidden) {
+    document.getElementById("breaks-goal-text").textContent =
+      `${goals.breaks_taken} / ${goals.min_breaks} (${goals.breaks_percent}%)`;
+    document.getElementById("breaks-goal-bar").style.width = `${Math.min(100, goals.breaks_percent)}%`;
+  }
+
+  renderCategories();
+}
+
+const categoryLabels = {
+  work: "trabajo",
+  comms: "comunicación",
+  entertainment: "ocio",
+};
+
+function renderCategories() {
+  const node = document.getElementById("category-breakdown");
+  const breakdown = state.categories;
+  const total = breakdown
+    ? breakdown.productive_seconds + breakdown.leisure_seconds + breakdown.uncategorized_seconds
+    : 0;
+  node.hidden = total === 0;
+  if (node.hidden) return;
+  const parts = breakdown.categories
+    .filter((entry) => entry.active_seconds > 0)
+    .map((entry) => `${categoryLabels[entry.category] ?? entry.category} ${formatSeconds(entry.active_seconds)} (${entry.percent}%)`);
+  if (breakdown.uncategorized_seconds > 0) {
+    parts.push(`sin categoría ${formatSeconds(breakdown.uncategorized_seconds)}`);
+  }
+  node.textContent =
+    `Productivo ${formatSeconds(breakdown.productive_seconds)} · ocio ${formatSeconds(breakdown.leisure_seconds)} — ${parts.join(" · ")}`;
+}
 
-  document.getElementById("progress-text").textContent = `${percent}%`;
-  document.getElementById("progress-bar").style.width = `${percent}%`;
-  document.getElementById("analytics-summary").textContent = `objetivo: ${formatSeconds(weeklyTarget)}`;
+const sparkBlocks = "▁▂▃▄▅▆▇█";
+
+function sparkline(values) {
+  const max = Math.max(0, ...values);
+  if (max === 0) return sparkBlocks[0].repeat(values.length);
+  return values
+    .map((value) => sparkBlocks[Math.round((value / max) * (sparkBlocks.length - 1))])
+    .join("");
+}
+
+function renderDashboard() {
+  const data = state.dashboard;
+  const compare = document.getElementById("dashboard-compare");
+  compare.innerHTML = "";
+  if (!data) return;
+
+  for (const [label, totals] of [
+    ["Hoy", data.today],
+    ["Ayer", data.yesterday],
+    ["Media 7 días", data.week_average],
+  ]) {
+    const metric = document.createElement("article");
+    metric.className = "metric";
+    const title = document.createElement("span");
+    title.textContent = label;
+    const value = document.createElement("strong");
+    value.textContent = `${formatSeconds(totals.active_seconds)} · ${totals.breaks_taken} pausas`;
+    metric.append(title, value);
+    compare.appendChild(metric);
+  }
+
+  document.getElementById("spark-active").textContent = sparkline(data.hourly_active_seconds);
+  document.getElementById("spark-breaks").textContent = sparkline(data.hourly_breaks_taken);
+  document.getElementById("dashboard-streak").textContent =
+    `Racha: ${data.current_streak_days} días (máxima ${data.longest_streak_days})`;
+}
+
+function renderBusyWindows() {
+  const container = document.getElementById("busy-windows");
+  container.innerHTML = "";
+  if (!state.settings?.calendar_enabled) return;
+
+  if (!state.busyWindows.length) {
+    container.textContent = "Sin reuniones en las próximas 24 h";
+    return;
+  }
+
+  for (const window of state.busyWindows) {
+    const item = document.createElement("li");
+    item.textContent = `${formatClock(window.start)}–${formatClock(window.end)} ${window.summary || "Ocupado"}`;
+    container.appendChild(item);
+  }
+}
+
+function renderSyncStatus() {
+  const target = document.getElementById("sync-status");
+  const sync = state.syncStatus;
+  if (!sync || !sync.backend || sync.backend === "off") {
+    target.textContent = sync?.device_id ? `Este equipo: ${sync.device_id}` : "";
+    return;
+  }
+
+  const peers = sync.peers.length ? sync.peers.join(", ") : "ninguno todavía";
+  const last = sync.last_synced_at ? formatClock(sync.last_synced_at) : "-";
+  target.textContent = sync.last_error
+    ? `Error de sincronización: ${sync.last_error}`
+    : `Este equipo: ${sync.device_id} · Otros equipos: ${peers} · Última sincronización: ${last}`;
+}
+
+function renderLanPeers() {
+  const container = document.getElementById("lan-peers");
+  container.innerHTML = "";
+  if (!state.settings?.peer_sharing_enabled) return;
+
+  if (!state.lanPeers.length) {
+    container.textContent = "Sin otros equipos detectados";
+    return;
+  }
+
+  for (const peer of state.lanPeers) {
+    const item = document.createElement("li");
+    item.textContent = `${peer.device_id} (${peer.address}): ${formatSeconds(peer.active_seconds)} hoy · visto ${formatClock(peer.last_seen)}`;
+    container.appendChild(item);
+  }
+}
+
+function selectedHistoryDay() {
+  return document.getElementById("history-day").value || null;
+}
+
+function formatClock(unixSeconds) {
+  if (!unixSeconds) return "-";
+  return new Date(unixSeconds * 1000).toLocaleTimeString([], { hour: "2-digit", minute: "2-digit" });
+}
+
+const historyOutcomeLabels = {
+  completed: "completado",
+  skipped: "omitido",
+  interrupted: "interrumpido",
+};
+
+const historyOutcomeClasses = {
+  completed: "break_completed",
+  skipped: "error",
+  interrupted: "error",
+};
+
+function renderFocus() {
+  const node = document.getElementById("focus-summary");
+  const focus = state.focus;
+  if (!focus || focus.session_count === 0) {
+    node.textContent = "Sin bloques de trabajo registrados.";
+    return;
+  }
+  node.textContent = `${focus.session_count} bloques de trabajo · el más largo ${formatSeconds(focus.longest_seconds)} · media ${formatSeconds(focus.average_seconds)}`;
+}
+
+function renderAppUsage() {
+  const node = document.getElementById("app-usage-list");
+  node.innerHTML = "";
+  const usage = state.appUsage;
+  if (!usage || usage.apps.length === 0) {
+    node.hidden = !usage?.enabled;
+    return;
+  }
+  node.hidden = false;
+  for (const entry of usage.apps) {
+    const li = document.createElement("li");
+    li.className = "event-item";
+    li.textContent = `${entry.app} · ${formatSeconds(entry.active_seconds)} (${entry.percent}%)`;
+    if (entry.titles.length > 0) {
+      li.title = entry.titles
+        .map((title) => `${title.title}: ${formatSeconds(title.active_seconds)}`)
+        .join("\n");
+    }
+    node.appendChild(li);
+  }
+}
+
+function renderHistory() {
+  renderFocus();
+  renderAppUsage();
+  const node = document.getElementById("history-list");
+  node.innerHTML = "";
+
+  if (!state.history || state.history.length === 0) {
+    const li = document.createElement("li");
+    li.className = "event-item";
+    li.textContent = "Sin descansos registrados.";
+    node.appendChild(li);
+    return;
+  }
+
+  for (const session of state.history) {
+    const li = document.createElement("li");
+    li.className = `event-item ${historyOutcomeClasses[session.outcome] || "break_due"}`;
+
+    const meta = document.createElement("div");
+    meta.className = "meta";
+    const left = document.createElement("span");
+    left.textContent = `${session.kind.replaceAll("_", " ")} · ${historyOutcomeLabels[session.outcome] || "pendiente"}`;
+    const right = document.createElement("span");
+    right.textContent = formatClock(session.scheduled_at);
+    meta.appendChild(left);
+    meta.appendChild(right);
+
+    const msg = document.createElement("div");
+    const snoozes = session.snooze_count > 0 ? ` · pospuesto ${session.snooze_count}x` : "";
+    msg.textContent = `inicio ${formatClock(session.started_at)} · fin ${formatClock(session.ended_at)}${snoozes}`;
+
+    li.appendChild(meta);
+    li.appendChild(msg);
+    node.appendChild(li);
+  }
 }
 
 function renderDebug() {
@@ -496,11 +1173,42 @@ function renderDebug() {
   );
 }
 
+function applyTheme(theme) {
+  if (!theme) return;
+  const root = document.documentElement;
+  root.dataset.theme = theme.mode;
+  root.style.setProperty("--bg", theme.background);
+  root.style.setProperty("--text", theme.foreground);
+  root.style.setProperty("--accent", theme.accent);
+  root.style.fontSize = `${theme.font_scale_percent}%`;
+}
+
+// Only filled on load and after a change, so the refresh loop does not
+// overwrite what is being edited.
+function renderThemeForm() {
+  if (state.themeLoaded || !state.settings) return;
+  state.themeLoaded = true;
+  document.getElementById("theme-mode").value = state.settings.theme_mode;
+  document.getElementById("theme-accent-color").value = state.settings.theme_accent_color;
+  document.getElementById("theme-background-color").value = state.settings.theme_background_color;
+  document.getElementById("theme-text-color").value = state.settings.theme_text_color;
+  document.getElementById("theme-background-image").value = state.settings.theme_background_image;
+  document.getElementById("theme-font-scale").value = state.settings.theme_font_scale_percent;
+}
+
 function renderAll() {
   renderRuntime();
   renderProfiles();
+  renderUndo();
   renderSettingsForm();
+  renderCapabilities();
+  renderThemeForm();
   renderAnalytics();
+  renderDashboard();
+  renderHistory();
+  renderBusyWindows();
+  renderSyncStatus();
+  renderLanPeers();
   renderEvents();
   renderDebug();
 }
@@ -518,17 +1226,37 @@ async function refresh() {
     return;
   }
 
-  const [settings, stats, runtime, profiles] = await Promise.all([
+  const [settings, stats, runtime, profiles, configFile, policy] = await Promise.all([
     invoke("get_settings"),
     invoke("get_weekly_stats"),
     invoke("get_runtime_status"),
     invoke("list_profiles"),
+    invoke("get_config_file_info"),
+    invoke("get_policy_info"),
   ]);
 
   state.settings = settings;
   state.stats = stats;
   state.runtime = runtime;
   state.profiles = profiles || [];
+  state.configFile = configFile;
+  state.history = await invoke("get_break_history", { day: selectedHistoryDay() });
+  state.focus = await invoke("get_focus_sessions", { day: selectedHistoryDay() });
+  state.appUsage = await invoke("get_app_usage", { day: selectedHistoryDay() });
+  state.goals = await invoke("get_weekly_goal_progress");
+  state.categories = await invoke("get_category_breakdown");
+  state.dailySummary = await invoke("get_daily_summary");
+  state.dashboard = await invoke("get_dashboard_data");
+  state.busyWindows = await invoke("get_upcoming_busy_windows");
+  state.changeHistory = await invoke("get_change_history");
+  state.syncStatus = await invoke("get_sync_status");
+  state.lanPeers = await invoke("get_peer_status");
+  renderUpdateStatus(await invoke("get_update_status"));
+  renderNotificationStatus(await invoke("get_notification_status"));
+  if (!state.capabilities) {
+    state.capabilities = await invoke("get_platform_capabilities");
+  }
+  state.policy = policy;
   renderAll();
 }
 
@@ -551,20 +1279,25 @@ async function withAction(name, action) {
     await action();
     pushEvent("info", `OK: ${name}`);
   } catch (err) {
-    pushEvent("error", `ERROR en ${name}: ${String(err)}`);
+    pushEvent("error", `ERROR en ${name}: ${errorText(err)}`);
+    focusErrorField(err);
   }
   await refresh();
 }
 
 document.getElementById("refresh").addEventListener("click", () => {
   state.settingsDirty = false;
-  refresh().catch((err) => pushEvent("error", `ERROR refresh: ${String(err)}`));
+  refresh().catch((err) => pushEvent("error", `ERROR refresh: ${errorText(err)}`));
 });
 
 document.getElementById("runtime-start").addEventListener("click", () =>
   withAction("iniciar runtime", () => invoke("start_runtime"))
 );
 
+document.getElementById("runtime-restart").addEventListener("click", () =>
+  withAction("reiniciar runtime", () => invoke("restart_runtime"))
+);
+
 document.getElementById("runtime-stop").addEventListener("click", () =>
   withAction("detener runtime", () => invoke("stop_runtime"))
 );
@@ -577,6 +1310,79 @@ document.getElementById("snooze-pending").addEventListener("click", () =>
   withAction("posponer descanso pendiente", () => invoke("snooze_pending_break"))
 );
 
+// Exact postpone times; the backend still refuses strict breaks.
+for (const button of document.querySelectorAll("[data-postpone-minutes]")) {
+  const minutes = Number(button.dataset.postponeMinutes);
+  button.addEventListener("click", () =>
+    withAction(`posponer descanso ${minutes} min`, () =>
+      invoke("postpone_pending", {
+        kind: state.runtime?.pending_break || "",
+        seconds: minutes * 60,
+      })
+    )
+  );
+}
+
+document.getElementById("cancel-limit-action").addEventListener("click", () =>
+  withAction("cancelar acción del límite diario", () => invoke("cancel_daily_limit_action"))
+);
+
+const limitActionLabels = {
+  lock: "bloqueará la sesión",
+  suspend: "suspenderá el equipo",
+};
+
+document.getElementById("daily_limit_action").addEventListener("change", (event) => {
+  const action = event.target.value;
+  if (action === "none") return;
+  const confirmed = window.confirm(
+    `Al alcanzar el límite diario sin posposiciones restantes, Lázaro ${limitActionLabels[action]} tras un aviso de 60 segundos. ¿Continuar?`
+  );
+  if (!confirmed) {
+    event.target.value = state.settings?.daily_limit_action ?? "none";
+  }
+});
+
+document.getElementById("vacation-start").addEventListener("click", () =>
+  withAction("activar vacaciones", async () => {
+    const date = document.getElementById("vacation-until").value;
+    if (!date) throw new Error("elige la fecha de vuelta");
+    // Tracking resumes at the start of the chosen day.
+    const until = Math.floor(new Date(`${date}T00:00`).getTime() / 1000);
+    await invoke("set_vacation", { until });
+  })
+);
+
+document.getElementById("vacation-end").addEventListener("click", () =>
+  withAction("terminar vacaciones", () => invoke("set_vacation", { until: null }))
+);
+
+document.getElementById("guest-start").addEventListener("click", () =>
+  withAction("iniciar sesión de invitado", async () => {
+    const minutes = Number(document.getElementById("guest-minutes").value);
+    if (!Number.isFinite(minutes) || minutes < 1) throw new Error("indica los minutos");
+    await invoke("start_guest_session", { duration: Math.round(minutes * 60) });
+  })
+);
+
+document.getElementById("guest-end").addEventListener("click", () =>
+  withAction("terminar sesión de invitado", () => invoke("end_guest_session"))
+);
+
+document.getElementById("presentation-start").addEventListener("click", () =>
+  withAction("iniciar modo presentación", async () => {
+    const minutes = Number(document.getElementById("presentation-minutes").value);
+    if (!Number.isFinite(minutes) || minutes < 1) throw new Error("indica los minutos");
+    await invoke("set_presentation_mode", { enabled: true, duration: Math.round(minutes * 60) });
+  })
+);
+
+document.getElementById("presentation-end").addEventListener("click", () =>
+  withAction("terminar modo presentación", () =>
+    invoke("set_presentation_mode", { enabled: false, duration: null })
+  )
+);
+
 document.getElementById("trigger-micro").addEventListener("click", () =>
   withAction("forzar micro", () => invoke("trigger_break", { kind: "micro" }))
 );
@@ -585,6 +1391,15 @@ document.getElementById("trigger-rest").addEventListener("click", () =>
   withAction("forzar descanso", () => invoke("trigger_break", { kind: "rest" }))
 );
 
+document.getElementById("force-daily-reset").addEventListener("click", () =>
+  withAction("reiniciar contadores del día", async () => {
+    if (!window.confirm("El límite diario y sus aplazamientos empezarán de cero. ¿Continuar?")) {
+      return;
+    }
+    await invoke("force_daily_reset");
+  })
+);
+
 document.getElementById("strict").addEventListener("click", async () => {
   if (!state.settings) return;
   await withAction("modo estricto", async () => {
@@ -684,11 +1499,261 @@ document.getElementById("delete-profile").addEventListener("click", async () =>
   });
 });
 
+function describeChange(change) {
+  const when = new Date(change.at * 1000).toLocaleString();
+  if (change.kind === "profile_removed") {
+    return `eliminación del perfil "${change.profile_name}" (${when})`;
+  }
+  return `ajustes: ${change.changed.join(", ")} (${when})`;
+}
+
+function renderUndo() {
+  const button = document.getElementById("undo-change");
+  const last = state.changeHistory?.[0];
+  button.disabled = !last;
+  button.title = last ? `Deshacer ${describeChange(last)}` : "Nada que deshacer";
+}
+
+document.getElementById("undo-change").addEventListener("click", () =>
+  withAction("deshacer último cambio", async () => {
+    await invoke("undo_last_change");
+    state.settingsDirty = false;
+  })
+);
+
+async function checkCrashReport() {
+  if (typeof invokeRaw !== "function") return;
+  const report = await invoke("take_crash_report");
+  if (!report) return;
+
+  const location = report.location ? ` (${report.location})` : "";
+  document.getElementById("crash-summary").textContent = `${report.message}${location}`;
+  document.getElementById("crash-details").textContent = JSON.stringify(report, null, 2);
+  pushEvent("error", `recuperado tras cierre inesperado: ${report.message}`);
+  document.getElementById("crash-dialog").showModal();
+}
+
+document.getElementById("crash-dismiss").addEventListener("click", () => {
+  document.getElementById("crash-dialog").close();
+});
+
+document.getElementById("stop-dismiss").addEventListener("click", () => {
+  document.getElementById("stop-dialog").close();
+});
+
 document.getElementById("clear-events").addEventListener("click", () => {
   state.events = [];
   renderEvents();
 });
 
+document.getElementById("load-logs").addEventListener("click", async () => {
+  const node = document.getElementById("logs-output");
+  try {
+    const lines = await invoke("get_recent_logs", { limit: 200 });
+    node.textContent = lines && lines.length ? lines.join("\n") : "Sin registros";
+  } catch (err) {
+    node.textContent = `ERROR: ${errorText(err)}`;
+  }
+  node.classList.remove("hidden");
+});
+
+document.getElementById("explain-schedule").addEventListener("click", async () => {
+  const node = document.getElementById("logs-output");
+  try {
+    const entries = await invoke("explain_schedule");
+    node.textContent = entries.length
+      ? entries
+          .map((entry) => {
+            const reason = entry.deferred_by ? `${entry.reason} (${entry.deferred_by})` : entry.reason;
+            const due = entry.next_due_at ? new Date(entry.next_due_at * 1000).toLocaleTimeString() : "-";
+            return `${entry.kind}: ${entry.accumulated_seconds}/${entry.target_seconds}s, próximo ${due}, ${reason}`;
+          })
+          .join("\n")
+      : "Sin temporizadores";
+  } catch (err) {
+    node.textContent = `ERROR: ${errorText(err)}`;
+  }
+  node.classList.remove("hidden");
+});
+
+document.getElementById("export-diagnostics").addEventListener("click", () =>
+  withAction("exportar diagnóstico", async () => {
+    const path = await invoke("export_diagnostics");
+    pushEvent("info", `diagnóstico guardado en ${path}`);
+  })
+);
+
+document.getElementById("export-state").addEventListener("click", () =>
+  withAction("exportar datos", async () => {
+    const path = await invoke("export_state_json");
+    pushEvent("info", `datos guardados en ${path}`);
+  })
+);
+
+document.getElementById("create-backup").addEventListener("click", () =>
+  withAction("crear copia de seguridad", async () => {
+    const path = await invoke("create_backup");
+    document.getElementById("restore-path").value = path;
+    pushEvent("info", `copia guardada en ${path}`);
+  })
+);
+
+function describeRestore(report) {
+  const lines = [
+    `Copia del ${new Date(report.created_at * 1000).toLocaleString()} (v${report.app_version}).`,
+    `Ajustes que cambian: ${report.changed_settings.length}`,
+    `Perfiles nuevos: ${report.profiles_added.join(", ") || "ninguno"}`,
+    `Perfiles eliminados: ${report.profiles_removed.join(", ") || "ninguno"}`,
+    `Perfiles modificados: ${report.profiles_changed.join(", ") || "ninguno"}`,
+    `Días con estadísticas: ${report.daily_stats_days.current} → ${report.daily_stats_days.backup}`,
+    `Descansos en el historial: ${report.break_sessions.current} → ${report.break_sessions.backup}`,
+    `Sesiones de foco: ${report.focus_sessions.current} → ${report.focus_sessions.backup}`,
+  ];
+  return lines.join("\n");
+}
+
+document.getElementById("restore-backup").addEventListener("click", () =>
+  withAction("restaurar copia de seguridad", async () => {
+    const path = document.getElementById("restore-path").value.trim();
+    if (!path) throw new Error("indica la ruta de la copia");
+    const preview = await invoke("restore_backup", { path, dryRun: true });
+    if (!window.confirm(`${describeRestore(preview)}\n\nSe reemplazarán los datos actuales. ¿Continuar?`)) {
+      throw new Error("cancelado");
+    }
+
+    await invoke("restore_backup", { path, dryRun: false });
+    state.settingsDirty = false;
+    pushEvent("info", "copia de seguridad restaurada");
+  })
+);
+
+document.getElementById("import-external").addEventListener("click", () =>
+  withAction("importar datos", async () => {
+    const tool = document.getElementById("import-tool").value;
+    const path = document.getElementById("import-path").value.trim() || null;
+    const report = await invoke("import_external", { tool, path });
+    if (report.profile_id) {
+      pushEvent("info", `perfil ${report.profile_id} creado con ${report.settings.length} ajustes`);
+    } else {
+      pushEvent(
+        "info",
+        `${report.days_imported} días importados (${report.days_skipped} ya registrados)`
+      );
+    }
+  })
+);
+
+function renderUpdateStatus(status) {
+  const node = document.getElementById("update-status");
+  if (status.last_error) {
+    node.textContent = `No se pudo comprobar: ${status.last_error}`;
+  } else if (status.update_available) {
+    node.textContent = `Versión ${status.latest_version} disponible: ${status.release_url}`;
+  } else if (status.checked_at) {
+    node.textContent = `Lazaro ${status.current_version} está al día`;
+  } else {
+    node.textContent = `Lazaro ${status.current_version}`;
+  }
+}
+
+document.getElementById("check-updates").addEventListener("click", () =>
+  withAction("buscar actualizaciones", async () => {
+    renderUpdateStatus(await invoke("check_for_updates"));
+  })
+);
+
+const notificationBackendLabels = {
+  notify_rust: "servicio de notificaciones",
+  dbus: "D-Bus directo",
+  in_app: "solo ventana y overlay (no hay servicio de notificaciones)",
+};
+
+function renderNotificationStatus(status) {
+  const node = document.getElementById("notification-status");
+  if (!status.backend) {
+    node.textContent = "Sin comprobar todavía";
+    return;
+  }
+  const parts = [notificationBackendLabels[status.backend] || status.backend];
+  if (status.server) parts.push(status.server);
+  if (status.undelivered > 0) parts.push(`${status.undelivered} sin entregar`);
+  if (status.last_error) parts.push(`último error: ${status.last_error}`);
+  node.textContent = parts.join(" · ");
+}
+
+document.getElementById("detect-notifications").addEventListener("click", () =>
+  withAction("detectar notificaciones", async () => {
+    renderNotificationStatus(await invoke("detect_notification_backend"));
+  })
+);
+
+const resetScopeLabels = {
+  stats: "todas las estadísticas y el historial de descansos",
+  profiles: "todos los perfiles excepto el predeterminado",
+  everything: "todos los datos de Lazaro en este equipo",
+};
+
+document.getElementById("reset-data").addEventListener("click", () =>
+  withAction("borrar datos", async () => {
+    const scope = document.getElementById("reset-scope").value;
+    const challenge = await invoke("reset_all_data", { scope });
+    if (!window.confirm(`Se borrarán ${resetScopeLabels[scope]}. Esta acción no se puede deshacer.`)) {
+      throw new Error("cancelado");
+    }
+
+    const result = await invoke("reset_all_data", { scope, confirmation: challenge.token });
+    state.settingsDirty = false;
+    pushEvent("info", `datos borrados (${result.removed_files.length} archivos eliminados)`);
+  })
+);
+
+document.getElementById("apply-theme").addEventListener("click", () =>
+  withAction("aplicar tema", async () => {
+    const theme = await invoke("update_theme", {
+      theme: {
+        mode: document.getElementById("theme-mode").value,
+        accent_color: document.getElementById("theme-accent-color").value,
+        background_color: document.getElementById("theme-background-color").value,
+        text_color: document.getElementById("theme-text-color").value,
+        background_image: document.getElementById("theme-background-image").value,
+        font_scale_percent: Number(document.getElementById("theme-font-scale").value),
+      },
+    });
+    applyTheme(theme);
+  })
+);
+
+document.getElementById("preview-break").addEventListener("click", () =>
+  withAction("vista previa", async () => {
+    await invoke("preview_break", { kind: "micro", duration: 10 });
+  })
+);
+
+function askBreakFeedback(breakKind) {
+  if (!state.settings?.break_feedback_prompt || !breakKind) return;
+  state.feedbackKind = breakKind;
+  document.getElementById("break-feedback").hidden = false;
+}
+
+for (const button of document.querySelectorAll("#break-feedback [data-rating]")) {
+  button.addEventListener("click", async () => {
+    const kind = state.feedbackKind;
+    document.getElementById("break-feedback").hidden = true;
+    state.feedbackKind = null;
+    try {
+      await invoke("submit_break_feedback", { kind, rating: Number(button.dataset.rating) });
+      state.goals = await invoke("get_weekly_goal_progress");
+      renderAll();
+    } catch (err) {
+      pushEvent("warn", `valoración: ${errorText(err)}`);
+    }
+  });
+}
+
+document.getElementById("history-day").addEventListener("change", () => {
+  refresh().catch((err) => pushEvent("warn", `historial: ${errorText(err)}`));
+});
+
 document.getElementById("toggle-debug").addEventListener("click", () => {
   state.showDebug = !state.showDebug;
   renderDebug();
@@ -706,12 +1771,86 @@ if (typeof listen === "function") {
         beep();
       }
 
-      if (kind === "break_tick" || kind === "break_completed" || kind === "daily_reset") {
+      if (kind === "config_reloaded") {
+        state.settingsDirty = false;
+      }
+
+      if (kind === "break_completed") {
+        askBreakFeedback(payload.break_kind);
+      }
+
+      if (kind === "milestone_reached") {
+        showConfetti();
+      }
+
+      if (kind === "wind_down_cutoff" || kind === "wind_down_budget_reached") {
+        promptToStop(message);
+      }
+
+      if (
+        kind === "break_tick" ||
+        kind === "break_completed" ||
+        kind === "daily_reset" ||
+        kind === "config_reloaded" ||
+        kind === "runtime_crashed"
+      ) {
         await refresh();
       }
     });
   } catch (err) {
-    pushEvent("warn", `listener no disponible (${String(err)})`);
+    pushEvent("warn", `listener no disponible (${errorText(err)})`);
+  }
+
+  try {
+    listen("theme_changed", (event) => {
+      applyTheme(event.payload);
+      state.themeLoaded = false;
+    });
+  } catch (_) {
+    // update_theme applies its own result
+  }
+
+  try {
+    listen("settings_changed", async (event) => {
+      const keys = (event.payload?.changes || []).map((change) => change.key);
+      pushEvent("settings_changed", `ajustes cambiados: ${keys.join(", ")}`);
+      if (keys.some((key) => key.startsWith("theme_"))) {
+        state.themeLoaded = false;
+      }
+      // Leave a half-edited form alone; the next save or refresh catches up.
+      if (!state.settingsDirty) {
+        await refresh();
+      }
+    });
+  } catch (_) {
+    // periodic refresh still picks up the new settings
+  }
+
+  try {
+    listen("update_available", (event) => {
+      renderUpdateStatus(event.payload);
+      pushEvent("info", `nueva versión disponible: ${event.payload.latest_version}`);
+    });
+  } catch (_) {
+    // shown on the next manual check
+  }
+
+  try {
+    listen("notification_fallback", (event) => {
+      const { title, body } = event.payload || {};
+      pushEvent("warn", `notificación no entregada: ${title} — ${body}`);
+    });
+  } catch (_) {
+    // the status line still counts undelivered messages
+  }
+
+  try {
+    listen("runtime://status_tick", (event) => {
+      state.runtime = event.payload || state.runtime;
+      renderRuntime();
+    });
+  } catch (_) {
+    // periodic refresh still updates runtime status
   }
 } else {
   pushEvent("warn", "sin listener de eventos; usando refresco periódico");
@@ -719,10 +1858,16 @@ if (typeof listen === "function") {
 
 if (!state.refreshTimer) {
   state.refreshTimer = setInterval(() => {
-    refresh().catch((err) => pushEvent("warn", `refresh: ${String(err)}`));
+    refresh().catch((err) => pushEvent("warn", `refresh: ${errorText(err)}`));
   }, 2000);
 }
 
 setupUnitSelectors();
 setupSettingsDirtyTracking();
-refresh().catch((err) => pushEvent("error", `error inicial: ${String(err)}`));
+setupCustomTimers();
+setupBreakKeys();
+refresh().catch((err) => pushEvent("error", `error inicial: ${errorText(err)}`));
+invoke("get_theme")
+  .then(applyTheme)
+  .catch((err) => pushEvent("warn", `tema: ${errorText(err)}`));
+checkCrashReport().catch((err) => pushEvent("warn", `crash report: ${errorText(err)}`));
@@ -0,0 +1,30 @@
+const tauri = window.__TAURI__;
+
+function resolveListen() {
+  const candidates = [tauri?.event?.listen, tauri?.listen];
+  for (const candidate of candidates) {
+    if (typeof candidate === "function") {
+      return candidate;
+    }
+  }
+  return null;
+}
+
+const listen = resolveListen();
+
+function applyOpacity(percent) {
+  const value = Math.min(100, Math.max(0, Number(percent || 0)));
+  document.body.style.opacity = String(value / 100);
+}
+
+applyOpacity(window.__LAZARO_DIM_OPACITY__);
+
+if (typeof listen === "function") {
+  try {
+    listen("overlay://dim", (event) => {
+      applyOpacity(event.payload);
+    });
+  } catch (_) {
+    // keep initial opacity
+  }
+}
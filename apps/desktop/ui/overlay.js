@@ -40,6 +40,92 @@ async function invoke(command, args = {}) {
 
 const kindNode = document.getElementById("kind");
 const remainingNode = document.getElementById("remaining");
+const announcerNode = document.getElementById("announcer");
+const skipNode = document.getElementById("skip");
+
+let overlayConfig = window.__LAZARO_OVERLAY_CONFIG__ || null;
+let overlayPayload = window.__LAZARO_OVERLAY__ || null;
+
+function applyOverlayConfig(config) {
+  if (!config) return;
+  overlayConfig = config;
+  document.body.classList.toggle("high-contrast", Boolean(config.high_contrast));
+  document.body.classList.toggle("reduce-motion", Boolean(config.reduce_motion));
+  document.body.classList.toggle("large-countdown", Boolean(config.large_countdown));
+}
+
+function setText(id, text) {
+  const node = document.getElementById(id);
+  if (!node) return;
+  node.textContent = text || "";
+  node.classList.toggle("hidden", !text);
+}
+
+function applyTheme(theme) {
+  if (!theme) return;
+  const style = document.documentElement.style;
+  style.setProperty("--overlay-background", theme.background);
+  style.setProperty("--overlay-foreground", theme.foreground);
+  style.setProperty("--overlay-accent", theme.accent);
+  style.setProperty(
+    "--overlay-image",
+    theme.background_image ? `url("${theme.background_image}")` : "none"
+  );
+  style.fontSize = `${theme.font_scale_percent}%`;
+}
+
+// The backend sends the break, theme and localized strings; nothing here
+// should have to guess them.
+function applyOverlayPayload(payload) {
+  if (!payload) return;
+  overlayPayload = payload;
+  applyOverlayConfig(payload.config);
+
+  document.documentElement.lang = payload.language || "es";
+  applyTheme(payload.theme);
+  document.body.classList.toggle("wind-down", Boolean(payload.wind_down));
+
+  setText("heading", payload.strings.heading);
+  setText("kind", payload.strings.kind);
+  setText("activity", payload.activity);
+  setText("hint", payload.strings.hint);
+  setText("strict", payload.allow_skip ? "" : payload.strings.strict);
+  setText("preview", payload.preview ? payload.strings.preview : "");
+
+  const elapsed = Math.max(0, Math.floor(Date.now() / 1000) - payload.started_at);
+  remainingNode.textContent = formatSeconds(payload.duration_seconds - elapsed);
+  renderSkip();
+}
+
+// The skip rule comes from the backend, which refuses early ends it does not
+// allow; the button only mirrors it.
+function renderSkip() {
+  const skip = overlayPayload?.skip;
+  if (!skipNode || !skip?.allowed) {
+    skipNode?.classList.add("hidden");
+    return;
+  }
+  const wait = Math.max(0, skip.available_at - Math.floor(Date.now() / 1000));
+  skipNode.classList.remove("hidden");
+  skipNode.disabled = wait > 0;
+  skipNode.textContent =
+    wait > 0
+      ? overlayPayload.strings.skip_locked.replace("{seconds}", String(wait))
+      : overlayPayload.strings.skip;
+}
+
+skipNode?.addEventListener("click", () => {
+  invoke("end_break_early").catch(() => {
+    // not allowed yet; the button catches up on the next render
+    renderSkip();
+  });
+});
+
+function announce(message) {
+  if (!overlayConfig?.screen_reader_announcements || !announcerNode) return;
+  announcerNode.textContent = "";
+  announcerNode.textContent = message;
+}
 
 function formatSeconds(seconds) {
   const s = Math.max(0, Number(seconds || 0));
@@ -68,7 +154,7 @@ function beep() {
 }
 
 function updateFromPayload(payload) {
-  if (payload.break_kind) {
+  if (payload.break_kind && !overlayPayload) {
     kindNode.textContent = `Tipo: ${payload.break_kind}`;
   }
 
@@ -79,12 +165,22 @@ function updateFromPayload(payload) {
 
 if (typeof listen === "function") {
   try {
+    // High contrast keeps its own colors, so only follow theme changes
+    // when it is off.
+    listen("theme_changed", (event) => {
+      if (!overlayConfig?.high_contrast) applyTheme(event.payload);
+    });
     listen("runtime://event", (event) => {
       const payload = event.payload || {};
       updateFromPayload(payload);
 
       if (payload.kind === "break_started") {
         beep();
+        announce(payload.message || overlayPayload?.strings.started || "Descanso iniciado");
+      }
+
+      if (payload.kind === "break_completed") {
+        announce(payload.message || overlayPayload?.strings.completed || "Descanso completado");
       }
     });
   } catch (_) {
@@ -92,11 +188,47 @@ if (typeof listen === "function") {
   }
 }
 
+// Media and pause keys only reach the webview while the overlay has focus;
+// the backend maps them to start/snooze according to the key settings.
+document.addEventListener("keydown", (event) => {
+  if (!overlayConfig?.media_keys_enabled || event.repeat) return;
+  invoke("handle_break_key", { key: event.key })
+    .then((action) => {
+      if (action) event.preventDefault();
+    })
+    .catch(() => {
+      // runtime stopped
+    });
+});
+
+applyOverlayConfig(overlayConfig);
+applyOverlayPayload(overlayPayload);
+
 if (typeof invokeRaw === "function") {
+  invoke("get_overlay_payload")
+    .then((payload) => {
+      if (payload) {
+        applyOverlayPayload(payload);
+      } else {
+        return invoke("get_overlay_config").then(applyOverlayConfig);
+      }
+    })
+    .catch(() => {
+      // keep init config
+    });
+
   setInterval(async () => {
+    // A preview has no break in the runtime to follow.
+    if (overlayPayload?.preview) {
+      const elapsed = Math.floor(Date.now() / 1000) - overlayPayload.started_at;
+      remainingNode.textContent = formatSeconds(overlayPayload.duration_seconds - elapsed);
+      renderSkip();
+      return;
+    }
     try {
       const runtime = await invoke("get_runtime_status");
       updateFromPayload(runtime);
+      renderSkip();
     } catch (_) {
       // ignore polling issues
     }
@@ -0,0 +1,95 @@
+const tauri = window.__TAURI__;
+const internals = window.__TAURI_INTERNALS__;
+
+function resolveInvoke() {
+  const candidates = [
+    tauri?.core?.invoke,
+    tauri?.invoke,
+    internals?.invoke,
+    window.__TAURI_INVOKE__,
+  ];
+
+  for (const candidate of candidates) {
+    if (typeof candidate === "function") {
+      return candidate;
+    }
+  }
+
+  return null;
+}
+
+function resolveListen() {
+  const candidates = [tauri?.event?.listen, tauri?.listen];
+  for (const candidate of candidates) {
+    if (typeof candidate === "function") {
+      return candidate;
+    }
+  }
+  return null;
+}
+
+const invoke = resolveInvoke();
+const listen = resolveListen();
+
+const kindLabels = {
+  micro: "micro",
+  rest: "largo",
+  daily_limit: "límite diario",
+};
+
+function formatSeconds(seconds) {
+  const s = Math.max(0, Number(seconds || 0));
+  const hh = Math.floor(s / 3600);
+  const mm = String(Math.floor((s % 3600) / 60)).padStart(2, "0");
+  const ss = String(s % 60).padStart(2, "0");
+  return hh > 0 ? `${hh}:${mm}:${ss}` : `${mm}:${ss}`;
+}
+
+function kindLabel(kind) {
+  return kindLabels[kind] || "personalizado";
+}
+
+function render(status) {
+  if (!status) return;
+  const widget = document.getElementById("widget");
+  const label = document.getElementById("widget-label");
+  const count = document.getElementById("widget-count");
+  const onBreak = Boolean(status.active_break);
+  widget.classList.toggle("on-break", onBreak);
+
+  if (!status.running) {
+    label.textContent = "Runtime detenido";
+    count.textContent = "--:--";
+  } else if (onBreak) {
+    label.textContent = `Descanso ${kindLabel(status.active_break)}`;
+    count.textContent = formatSeconds(status.remaining_seconds);
+  } else if (status.pending_break) {
+    label.textContent = `Descanso ${kindLabel(status.pending_break)} pendiente`;
+    count.textContent = "00:00";
+  } else if (status.next_break_kind) {
+    label.textContent = `Próximo: ${kindLabel(status.next_break_kind)}`;
+    count.textContent = formatSeconds(status.next_break_eta_seconds);
+  } else {
+    label.textContent = "Sin descansos programados";
+    count.textContent = "--:--";
+  }
+}
+
+if (typeof listen === "function") {
+  try {
+    listen("runtime://status_tick", (event) => render(event.payload));
+  } catch (_) {
+    // polling below still updates the widget
+  }
+}
+
+if (typeof invoke === "function") {
+  const poll = () =>
+    invoke("get_runtime_status")
+      .then(render)
+      .catch(() => {
+        // runtime state unavailable
+      });
+  poll();
+  setInterval(poll, 5000);
+}
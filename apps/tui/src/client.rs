@@ -0,0 +1,114 @@
+use std::{
+    io::{Read, Write},
+    net::TcpStream,
+    time::Duration,
+};
+
+use serde::{Deserialize, de::DeserializeOwned};
+
+const TIMEOUT: Duration = Duration::from_secs(3);
+
+/// The subset of the desktop app's `/api/status` the terminal shows.
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct Status {
+    pub running: bool,
+    pub pending_break: Option<String>,
+    pub active_break: Option<String>,
+    pub remaining_seconds: Option<u64>,
+    pub next_break_kind: Option<String>,
+    pub next_break_eta_seconds: Option<u64>,
+    pub micro_progress_percent: u8,
+    pub rest_progress_percent: u8,
+    pub daily_limit_progress_percent: u8,
+    pub daily_active_seconds: u64,
+    pub strict_mode: bool,
+    pub last_event: String,
+}
+
+/// `/api/today`: the running day's totals.
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct Today {
+    pub active_seconds: u64,
+    pub micro_done: u32,
+    pub rest_done: u32,
+    pub skipped: u32,
+}
+
+#[derive(Deserialize)]
+struct ErrorBody {
+    error: String,
+}
+
+/// Talks to the remote API the desktop app serves on localhost when
+/// remote access is enabled in its settings.
+pub struct Client {
+    port: u16,
+    token: String,
+}
+
+impl Client {
+    pub fn new(port: u16, token: String) -> Self {
+        Self { port, token }
+    }
+
+    pub fn status(&self) -> Result<Status, String> {
+        self.request("GET", "/api/status")
+    }
+
+    pub fn today(&self) -> Result<Today, String> {
+        self.request("GET", "/api/today")
+    }
+
+    pub fn start_break(&self) -> Result<(), String> {
+        self.request::<serde_json::Value>("POST", "/api/breaks/start")
+            .map(|_| ())
+    }
+
+    pub fn snooze_break(&self) -> Result<(), String> {
+        self.request::<serde_json::Value>("POST", "/api/breaks/snooze")
+            .map(|_| ())
+    }
+
+    pub fn trigger_break(&self, kind: &str) -> Result<(), String> {
+        self.request::<serde_json::Value>("POST", &format!("/api/breaks/{kind}"))
+            .map(|_| ())
+    }
+
+    // One request per connection; the server always answers with
+    // `Connection: close`.
+    fn request<T: DeserializeOwned>(&self, method: &str, path: &str) -> Result<T, String> {
+        let mut stream = TcpStream::connect(("127.0.0.1", self.port))
+            .map_err(|e| format!("cannot reach Lázaro on port {}: {e}", self.port))?;
+        stream
+            .set_read_timeout(Some(TIMEOUT))
+            .map_err(|e| e.to_string())?;
+        write!(
+            stream,
+            "{method} {path} HTTP/1.1\r\nHost: 127.0.0.1\r\nAuthorization: Bearer {}\r\nContent-Length: 0\r\nConnection: close\r\n\r\n",
+            self.token
+        )
+        .map_err(|e| e.to_string())?;
+
+        let mut response = Vec::new();
+        stream
+            .read_to_end(&mut response)
+            .map_err(|e| e.to_string())?;
+        let response = String::from_utf8_lossy(&response);
+        let (head, body) = response
+            .split_once("\r\n\r\n")
+            .ok_or_else(|| "malformed response".to_string())?;
+        let code: u16 = head
+            .split_whitespace()
+            .nth(1)
+            .and_then(|code| code.parse().ok())
+            .ok_or_else(|| "malformed status line".to_string())?;
+        if code != 200 {
+            return Err(serde_json::from_str::<ErrorBody>(body)
+                .map(|body| body.error)
+                .unwrap_or_else(|_| format!("HTTP {code}")));
+        }
+        serde_json::from_str(body).map_err(|e| e.to_string())
+    }
+}
@@ -0,0 +1,184 @@
+mod client;
+mod ui;
+
+use std::time::{Duration, Instant};
+
+use ratatui::{
+    DefaultTerminal,
+    crossterm::event::{self, Event, KeyCode, KeyEventKind, KeyModifiers},
+};
+
+use crate::client::{Client, Status, Today};
+
+const DEFAULT_PORT: u16 = 7_878;
+const REFRESH_INTERVAL: Duration = Duration::from_secs(1);
+const TODAY_REFRESH_INTERVAL: Duration = Duration::from_secs(30);
+
+const USAGE: &str = "\
+Usage: lazaro-tui [--port PORT] [--token TOKEN] [--break-screen]
+
+Connects to a running Lázaro through its remote API. Enable remote access
+in Lázaro's settings and pass its token, or set LAZARO_REMOTE_TOKEN.
+
+  --break-screen  keep the terminal on the break until it ends, ignoring
+                  keys, for sessions with no overlay to block the screen";
+
+struct Options {
+    port: u16,
+    token: String,
+    break_screen: bool,
+}
+
+fn parse_args() -> Result<Options, String> {
+    let mut options = Options {
+        port: DEFAULT_PORT,
+        token: std::env::var("LAZARO_REMOTE_TOKEN").unwrap_or_default(),
+        break_screen: false,
+    };
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--port" => {
+                options.port = args
+                    .next()
+                    .and_then(|value| value.parse().ok())
+                    .ok_or("--port needs a port number")?;
+            }
+            "--token" => options.token = args.next().ok_or("--token needs a value")?,
+            "--break-screen" => options.break_screen = true,
+            "-h" | "--help" => return Err(USAGE.into()),
+            other => return Err(format!("unknown argument: {other}\n\n{USAGE}")),
+        }
+    }
+    if options.token.is_empty() {
+        return Err(format!("no remote token given\n\n{USAGE}"));
+    }
+    Ok(options)
+}
+
+pub struct App {
+    client: Client,
+    pub break_screen: bool,
+    pub status: Option<Status>,
+    pub today: Today,
+    pub error: Option<String>,
+    /// Outcome of the last key action, shown in the footer.
+    pub notice: Option<String>,
+    today_refreshed: Option<Instant>,
+}
+
+impl App {
+    fn refresh(&mut self) {
+        match self.client.status() {
+            Ok(status) => {
+                self.status = Some(status);
+                self.error = None;
+            }
+            Err(error) => {
+                self.status = None;
+                self.error = Some(error);
+                return;
+            }
+        }
+        if self
+            .today_refreshed
+            .is_none_or(|at| at.elapsed() >= TODAY_REFRESH_INTERVAL)
+            && let Ok(today) = self.client.today()
+        {
+            self.today = today;
+            self.today_refreshed = Some(Instant::now());
+        }
+    }
+
+    /// Whether the break screen holds the terminal right now.
+    pub fn blocking(&self) -> bool {
+        self.break_screen
+            && self
+                .status
+                .as_ref()
+                .is_some_and(|status| status.active_break.is_some())
+    }
+
+    fn act(&mut self, result: Result<(), String>, done: &str) {
+        self.notice = Some(result.map_or_else(|error| error, |()| done.to_string()));
+        // Counters shown for today change with each break.
+        self.today_refreshed = None;
+        self.refresh();
+    }
+
+    /// Returns false when the user asked to quit.
+    fn on_key(&mut self, code: KeyCode, modifiers: KeyModifiers) -> bool {
+        if self.blocking() {
+            return true;
+        }
+        match code {
+            KeyCode::Char('q') | KeyCode::Esc => return false,
+            KeyCode::Char('c') if modifiers.contains(KeyModifiers::CONTROL) => return false,
+            KeyCode::Char('s') | KeyCode::Enter => {
+                let result = self.client.start_break();
+                self.act(result, "break started");
+            }
+            KeyCode::Char('z') => {
+                let result = self.client.snooze_break();
+                self.act(result, "break snoozed");
+            }
+            KeyCode::Char('m') => {
+                let result = self.client.trigger_break("micro");
+                self.act(result, "micro break started");
+            }
+            KeyCode::Char('r') => {
+                let result = self.client.trigger_break("rest");
+                self.act(result, "rest break started");
+            }
+            _ => {}
+        }
+        true
+    }
+}
+
+fn run(terminal: &mut DefaultTerminal, app: &mut App) -> std::io::Result<()> {
+    let mut refreshed = Instant::now();
+    app.refresh();
+    loop {
+        terminal.draw(|frame| ui::draw(frame, app))?;
+        let timeout = REFRESH_INTERVAL.saturating_sub(refreshed.elapsed());
+        if event::poll(timeout)?
+            && let Event::Key(key) = event::read()?
+            && key.kind == KeyEventKind::Press
+            && !app.on_key(key.code, key.modifiers)
+        {
+            return Ok(());
+        }
+        if refreshed.elapsed() >= REFRESH_INTERVAL {
+            app.refresh();
+            refreshed = Instant::now();
+        }
+    }
+}
+
+fn main() {
+    let options = match parse_args() {
+        Ok(options) => options,
+        Err(message) => {
+            eprintln!("{message}");
+            std::process::exit(2);
+        }
+    };
+    let mut app = App {
+        client: Client::new(options.port, options.token),
+        break_screen: options.break_screen,
+        status: None,
+        today: Today::default(),
+        error: None,
+        notice: None,
+        today_refreshed: None,
+    };
+
+    let mut terminal = ratatui::init();
+    let result = run(&mut terminal, &mut app);
+    ratatui::restore();
+    if let Err(error) = result {
+        eprintln!("lazaro-tui: {error}");
+        std::process::exit(1);
+    }
+}
@@ -0,0 +1,144 @@
+use ratatui::{
+    Frame,
+    layout::{Alignment, Constraint, Flex, Layout, Rect},
+    style::{Color, Modifier, Style, Stylize},
+    text::Line,
+    widgets::{Block, Gauge, Paragraph},
+};
+
+use crate::{App, client::Status};
+
+pub fn draw(frame: &mut Frame, app: &App) {
+    let [body, footer] =
+        Layout::vertical([Constraint::Min(0), Constraint::Length(1)]).areas(frame.area());
+
+    match &app.status {
+        Some(status) if status.active_break.is_some() => draw_break(frame, body, status),
+        Some(status) => draw_dashboard(frame, body, app, status),
+        None => {
+            let message = app.error.as_deref().unwrap_or("connecting…");
+            frame.render_widget(
+                Paragraph::new(message)
+                    .alignment(Alignment::Center)
+                    .block(Block::bordered().title(" Lázaro ")),
+                body,
+            );
+        }
+    }
+
+    let keys = if app.blocking() {
+        "break in progress".to_string()
+    } else {
+        "s start · z snooze · m micro · r rest · q quit".to_string()
+    };
+    let line = match app.notice.as_deref().or(app.error.as_deref()) {
+        Some(notice) => format!("{keys}  —  {notice}"),
+        None => keys,
+    };
+    frame.render_widget(Paragraph::new(line).dim(), footer);
+}
+
+fn draw_dashboard(frame: &mut Frame, area: Rect, app: &App, status: &Status) {
+    let [countdown, gauges, today] = Layout::vertical([
+        Constraint::Length(4),
+        Constraint::Length(8),
+        Constraint::Min(3),
+    ])
+    .areas(area);
+
+    let state = if !status.running {
+        "paused"
+    } else if status.strict_mode {
+        "running · strict"
+    } else {
+        "running"
+    };
+    let headline = match (&status.pending_break, &status.next_break_kind) {
+        (Some(kind), _) => Line::from(format!("{kind} break due — press s to start"))
+            .style(Style::new().fg(Color::Yellow).add_modifier(Modifier::BOLD)),
+        (None, Some(kind)) => Line::from(format!(
+            "next {kind} break in {}",
+            clock(status.next_break_eta_seconds.unwrap_or(0))
+        ))
+        .bold(),
+        (None, None) => Line::from("no break scheduled"),
+    };
+    frame.render_widget(
+        Paragraph::new(vec![headline, Line::from(status.last_event.clone()).dim()])
+            .block(Block::bordered().title(format!(" Lázaro · {state} "))),
+        countdown,
+    );
+
+    let rows = Layout::vertical([Constraint::Length(2); 3])
+        .margin(1)
+        .split(gauges);
+    frame.render_widget(Block::bordered().title(" Progress "), gauges);
+    for (row, (label, percent, color)) in rows.iter().zip([
+        ("micro", status.micro_progress_percent, Color::Cyan),
+        ("rest", status.rest_progress_percent, Color::Blue),
+        (
+            "daily limit",
+            status.daily_limit_progress_percent,
+            Color::Magenta,
+        ),
+    ]) {
+        frame.render_widget(
+            Gauge::default()
+                .label(format!("{label} {percent}%"))
+                .percent(u16::from(percent.min(100)))
+                .gauge_style(Style::new().fg(color)),
+            *row,
+        );
+    }
+
+    let stats = &app.today;
+    let lines = vec![
+        Line::from(format!(
+            "active {}",
+            duration(status.daily_active_seconds.max(stats.active_seconds))
+        )),
+        Line::from(format!(
+            "micro breaks {} · rest breaks {} · skipped {}",
+            stats.micro_done, stats.rest_done, stats.skipped
+        )),
+    ];
+    frame.render_widget(
+        Paragraph::new(lines).block(Block::bordered().title(" Today ")),
+        today,
+    );
+}
+
+// Fills the terminal so nothing else is readable until the break ends.
+fn draw_break(frame: &mut Frame, area: Rect, status: &Status) {
+    let kind = status.active_break.as_deref().unwrap_or("break");
+    let remaining = status.remaining_seconds.unwrap_or(0);
+    frame.render_widget(
+        Block::new().style(Style::new().bg(Color::Black).fg(Color::White)),
+        area,
+    );
+    let [center] = Layout::vertical([Constraint::Length(5)])
+        .flex(Flex::Center)
+        .areas(area);
+    let lines = vec![
+        Line::from(format!("{kind} break")).bold(),
+        Line::from(""),
+        Line::from(clock(remaining)).bold().fg(Color::Green),
+        Line::from(""),
+        Line::from("look away from the screen and stretch").dim(),
+    ];
+    frame.render_widget(Paragraph::new(lines).alignment(Alignment::Center), center);
+}
+
+fn clock(seconds: u64) -> String {
+    format!("{:02}:{:02}", seconds / 60, seconds % 60)
+}
+
+fn duration(seconds: u64) -> String {
+    let hours = seconds / 3_600;
+    let minutes = (seconds % 3_600) / 60;
+    if hours > 0 {
+        format!("{hours}h{minutes:02}m")
+    } else {
+        format!("{minutes}m")
+    }
+}
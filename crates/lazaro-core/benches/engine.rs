@@ -0,0 +1,142 @@
+//! Hot paths the desktop runtime hits every second, plus the analytics
+//! queries behind the stats view. Run with `cargo bench -p lazaro-core`.
+
+use std::hint::black_box;
+
+use criterion::{BatchSize, Criterion, criterion_group, criterion_main};
+use lazaro_core::analytics::{BreakHistory, BreakSession, SessionOutcome};
+use lazaro_core::config::{BreakTimerSettings, CustomBreakTimer, MICRO_TIMER_ID};
+use lazaro_core::prelude::*;
+
+const START: u64 = 1_700_000_000;
+const DAY: u64 = 86_400;
+
+/// Default settings with the features that make a tick expensive switched
+/// on: custom timers competing in the queue, jitter and adaptive intervals.
+fn busy_settings() -> Settings {
+    let mut settings = Settings::default();
+    settings.adaptive.enabled = true;
+    settings.micro.jitter_percent = 20;
+    settings.custom_timers = (0..4)
+        .map(|index| CustomBreakTimer {
+            id: format!("custom-{index}"),
+            name: format!("Custom {index}"),
+            timer: BreakTimerSettings {
+                resets: vec![MICRO_TIMER_ID.into()],
+                ..BreakTimerSettings::new(300 + 60 * index, 15, 60)
+            },
+            priority: index as u8,
+        })
+        .collect();
+    settings
+}
+
+/// Drives `engine` like the runtime does for `seconds`: one active second per
+/// tick, taking every break as soon as it is offered.
+fn run_day(engine: &mut TimerEngine, from: u64, seconds: u64) {
+    for offset in 0..seconds {
+        let now = from + offset;
+        if engine.active_break_info().is_some() {
+            black_box(engine.tick_break(1));
+            continue;
+        }
+        for event in engine.on_activity(1, now) {
+            if let EngineEvent::BreakDue(kind) = event {
+                black_box(engine.start_break(kind));
+            }
+        }
+    }
+}
+
+/// Closed micro break sessions every ten minutes, as loaded from disk.
+fn long_history(count: u64) -> Vec<BreakSession> {
+    (0..count)
+        .map(|index| {
+            let at = START + index * 600;
+            BreakSession {
+                kind: BreakKind::Micro,
+                scheduled_at: at,
+                started_at: Some(at + 5),
+                ended_at: Some(at + 25),
+                outcome: Some(SessionOutcome::Completed),
+                snooze_count: 0,
+                rating: None,
+            }
+        })
+        .collect()
+}
+
+fn engine_benches(c: &mut Criterion) {
+    c.bench_function("on_activity/idle_timers", |b| {
+        let mut engine = TimerEngine::new(busy_settings(), START);
+        let mut now = START;
+        b.iter(|| {
+            now += 1;
+            black_box(engine.on_activity(black_box(1), now));
+            if engine.pending_break().is_some() {
+                engine.clear_pending();
+            }
+        });
+    });
+
+    c.bench_function("on_activity/working_hour", |b| {
+        b.iter_batched(
+            || TimerEngine::new(busy_settings(), START),
+            |mut engine| run_day(&mut engine, START, 3_600),
+            BatchSize::SmallInput,
+        );
+    });
+
+    c.bench_function("tick_break/rest", |b| {
+        b.iter_batched(
+            || {
+                let mut engine = TimerEngine::new(busy_settings(), START);
+                engine.start_break(BreakKind::Rest);
+                engine
+            },
+            |mut engine| {
+                while engine.active_break_info().is_some() {
+                    black_box(engine.tick_break(1));
+                }
+            },
+            BatchSize::SmallInput,
+        );
+    });
+
+    c.bench_function("explain", |b| {
+        let mut engine = TimerEngine::new(busy_settings(), START);
+        run_day(&mut engine, START, 1_000);
+        b.iter(|| black_box(engine.explain(START + 1_000)));
+    });
+}
+
+fn analytics_benches(c: &mut Criterion) {
+    // Ten years of history, far more than the default retention keeps.
+    let days = 3_650;
+    let mut store = AnalyticsStore::default();
+    for day in 0..days {
+        store.record_activity(day, 25_000);
+        for _ in 0..40 {
+            store.record_break(day, BreakKind::Micro, BreakOutcome::Completed);
+        }
+        store.record_break(day, BreakKind::Rest, BreakOutcome::Skipped);
+    }
+    c.bench_function("analytics/streaks", |b| {
+        b.iter(|| black_box(store.streaks(days - 1)));
+    });
+    c.bench_function("analytics/daily_average_year", |b| {
+        b.iter(|| black_box(store.daily_average(days - 365, days - 1)));
+    });
+
+    let history = BreakHistory::from_sessions(long_history(100_000));
+    let end = START + 100_000 * 600;
+    c.bench_function("history/compliance_week", |b| {
+        b.iter(|| black_box(history.compliance_since(end - 7 * DAY)));
+    });
+    c.bench_function("history/hourly", |b| {
+        b.iter(|| black_box(history.hourly(end - DAY)));
+    });
+}
+
+criterion_group!(benches, engine_benches, analytics_benches);
+criterion_main!(benches);
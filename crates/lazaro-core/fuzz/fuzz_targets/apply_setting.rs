@@ -0,0 +1,25 @@
+#![no_main]
+
+//! Any accepted value must read back as a value that applies to the same
+//! settings, the round trip the desktop settings form and config files rely on.
+
+use lazaro_core::config::{SETTING_KEYS, Settings, apply_setting, setting_value};
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|input: (u8, &str)| {
+    let (index, value) = input;
+    let key = SETTING_KEYS[usize::from(index) % SETTING_KEYS.len()];
+
+    let mut settings = Settings::default();
+    if apply_setting(&mut settings, key, value).is_err() {
+        assert_eq!(settings, Settings::default(), "{key} changed on error");
+        return;
+    }
+    let Some(stored) = setting_value(&settings, key) else {
+        return;
+    };
+    let mut again = Settings::default();
+    apply_setting(&mut again, key, &stored)
+        .unwrap_or_else(|error| panic!("{key}={stored:?} does not read back: {error:?}"));
+    assert_eq!(setting_value(&again, key).as_deref(), Some(stored.as_str()));
+});
@@ -0,0 +1,54 @@
+#![no_main]
+
+//! Restoring from a damaged snapshot and running the engine on it must never
+//! panic, whatever the persisted counters and timestamps hold.
+
+use lazaro_core::prelude::*;
+use libfuzzer_sys::fuzz_target;
+
+type Counters = (u64, u64, u64, Vec<(String, u64)>, Vec<(String, u64)>, u64);
+
+fuzz_target!(|input: (Counters, u64, i32, Vec<(u16, u32)>)| {
+    let (
+        (
+            taken_at,
+            daily_active_seconds,
+            weekly_active_seconds,
+            timer_active_seconds,
+            app_active_seconds,
+            wind_down_active_seconds,
+        ),
+        now,
+        utc_offset,
+        steps,
+    ) = input;
+    let snapshot = EngineSnapshot {
+        taken_at,
+        daily_active_seconds,
+        weekly_active_seconds,
+        timer_active_seconds,
+        app_active_seconds,
+        wind_down_active_seconds,
+    };
+    let mut engine = TimerEngine::restore_in_zone(
+        Settings::default(),
+        Some(&snapshot),
+        now,
+        i64::from(utc_offset),
+    );
+
+    let mut now = now;
+    for (active, elapsed) in steps.into_iter().take(64) {
+        now = now.saturating_add(u64::from(elapsed));
+        for event in engine.on_activity(u64::from(active), now) {
+            if let EngineEvent::BreakDue(kind) = event {
+                let _ = engine.start_break(kind);
+            }
+        }
+        let _ = engine.tick_break(u64::from(active));
+        let _ = engine.next_break_eta(now);
+        let _ = engine.forecast_daily_limit(now);
+        let _ = engine.explain(now);
+    }
+    let _ = engine.snapshot(now);
+});
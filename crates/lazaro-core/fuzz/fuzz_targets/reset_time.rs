@@ -0,0 +1,12 @@
+#![no_main]
+
+use lazaro_core::config::parse_reset_time;
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|value: &str| {
+    if let Some((hour, minute)) = parse_reset_time(value) {
+        assert!(hour <= 23 && minute <= 59);
+        let formatted = format!("{hour:02}:{minute:02}");
+        assert_eq!(parse_reset_time(&formatted), Some((hour, minute)));
+    }
+});
@@ -1,31 +1,117 @@
 use std::collections::BTreeMap;
 
-use crate::timer::{BreakKind, BreakOutcome};
+use crate::{
+    config::{AnalyticsSettings, AppCategory, WeekStart},
+    timer::{BreakKind, BreakOutcome},
+};
 
 #[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct DailyAggregate {
     pub active_seconds: u64,
     pub micro_done: u32,
     pub rest_done: u32,
+    pub custom_done: u32,
     pub daily_limit_hits: u32,
     pub skipped: u32,
+    /// Sum and count of the 1-5 break feedback ratings given that day.
+    pub rating_sum: u32,
+    pub ratings: u32,
 }
 
 #[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct WeeklySummary {
     pub total_active_seconds: u64,
     pub micro_done: u32,
     pub rest_done: u32,
+    pub custom_done: u32,
     pub daily_limit_hits: u32,
     pub skipped: u32,
+    pub rating_sum: u32,
+    pub ratings: u32,
+}
+
+const HOUR_SECONDS: u64 = 3_600;
+
+pub const MIN_BREAK_RATING: u8 = 1;
+pub const MAX_BREAK_RATING: u8 = 5;
+
+impl DailyAggregate {
+    pub fn breaks_taken(&self) -> u32 {
+        self.micro_done
+            .saturating_add(self.rest_done)
+            .saturating_add(self.custom_done)
+            .saturating_add(self.daily_limit_hits)
+    }
+}
+
+impl WeeklySummary {
+    pub fn breaks_taken(&self) -> u32 {
+        self.micro_done
+            .saturating_add(self.rest_done)
+            .saturating_add(self.custom_done)
+            .saturating_add(self.daily_limit_hits)
+    }
+
+    pub fn average_rating(&self) -> Option<f64> {
+        (self.ratings > 0).then(|| f64::from(self.rating_sum) / f64::from(self.ratings))
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct WeeklyGoalProgress {
+    pub week_start_day: i64,
+    pub active_seconds: u64,
+    pub max_active_seconds: u64,
+    pub active_percent: u8,
+    pub active_goal_met: bool,
+    pub breaks_taken: u32,
+    pub min_breaks: u32,
+    pub breaks_percent: u8,
+    pub breaks_goal_met: bool,
+}
+
+pub fn week_start_day(day_index: i64, week_start: WeekStart) -> i64 {
+    // 1970-01-01 (day 0) was a Thursday.
+    let offset = match week_start {
+        WeekStart::Monday => (day_index + 3).rem_euclid(7),
+        WeekStart::Sunday => (day_index + 4).rem_euclid(7),
+    };
+    day_index - offset
+}
+
+fn percent_of(value: u64, target: u64) -> u8 {
+    if target == 0 {
+        return 100;
+    }
+    (value.saturating_mul(100) / target).min(100) as u8
 }
 
 #[derive(Clone, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct AnalyticsStore {
     by_day: BTreeMap<i64, DailyAggregate>,
 }
 
 impl AnalyticsStore {
+    pub fn from_days(by_day: BTreeMap<i64, DailyAggregate>) -> Self {
+        Self { by_day }
+    }
+
+    pub fn days(&self) -> &BTreeMap<i64, DailyAggregate> {
+        &self.by_day
+    }
+
+    pub fn day(&self, day_index: i64) -> DailyAggregate {
+        self.by_day.get(&day_index).cloned().unwrap_or_default()
+    }
+
+    pub fn prune_before(&mut self, day_index: i64) {
+        self.by_day = self.by_day.split_off(&day_index);
+    }
+
     pub fn record_activity(&mut self, day_index: i64, seconds: u64) {
         let entry = self.by_day.entry(day_index).or_default();
         entry.active_seconds = entry.active_seconds.saturating_add(seconds);
@@ -36,26 +122,729 @@ impl AnalyticsStore {
         match (kind, outcome) {
             (BreakKind::Micro, BreakOutcome::Completed) => entry.micro_done += 1,
             (BreakKind::Rest, BreakOutcome::Completed) => entry.rest_done += 1,
+            (BreakKind::Custom(_), BreakOutcome::Completed) => entry.custom_done += 1,
             (BreakKind::DailyLimit, BreakOutcome::Completed) => entry.daily_limit_hits += 1,
             (_, BreakOutcome::Skipped) => entry.skipped += 1,
             (_, BreakOutcome::Snoozed) => {}
         }
     }
 
+    pub fn record_rating(&mut self, day_index: i64, rating: u8) {
+        let entry = self.by_day.entry(day_index).or_default();
+        entry.rating_sum = entry.rating_sum.saturating_add(u32::from(rating));
+        entry.ratings = entry.ratings.saturating_add(1);
+    }
+
     pub fn summarize_week_ending(&self, end_day_index: i64) -> WeeklySummary {
-        let start = end_day_index - 6;
+        self.summarize_range(end_day_index - 6, end_day_index)
+    }
+
+    pub fn summarize_calendar_week(&self, day_index: i64, week_start: WeekStart) -> WeeklySummary {
+        let start = week_start_day(day_index, week_start);
+        self.summarize_range(start, start + 6)
+    }
+
+    pub fn weekly_goal_progress(
+        &self,
+        day_index: i64,
+        settings: &AnalyticsSettings,
+    ) -> WeeklyGoalProgress {
+        let summary = self.summarize_calendar_week(day_index, settings.week_start);
+        let breaks_taken = summary.breaks_taken();
+        let max_active = settings.weekly_max_active_seconds;
+        let active_goal_met = max_active == 0 || summary.total_active_seconds <= max_active;
+        WeeklyGoalProgress {
+            week_start_day: week_start_day(day_index, settings.week_start),
+            active_seconds: summary.total_active_seconds,
+            max_active_seconds: max_active,
+            active_percent: percent_of(summary.total_active_seconds, max_active),
+            active_goal_met,
+            breaks_taken,
+            min_breaks: settings.weekly_min_breaks,
+            breaks_percent: percent_of(breaks_taken as u64, settings.weekly_min_breaks as u64),
+            breaks_goal_met: breaks_taken >= settings.weekly_min_breaks,
+        }
+    }
+
+    /// Per-day average over the recorded days in `start..=end`; days with no
+    /// record at all, like ones the computer stayed off, are left out.
+    pub fn daily_average(&self, start: i64, end: i64) -> DailyAggregate {
+        let days = self.by_day.range(start..=end).count() as u32;
+        if days == 0 {
+            return DailyAggregate::default();
+        }
+        let summary = self.summarize_range(start, end);
+        DailyAggregate {
+            active_seconds: summary.total_active_seconds / u64::from(days),
+            micro_done: summary.micro_done / days,
+            rest_done: summary.rest_done / days,
+            custom_done: summary.custom_done / days,
+            daily_limit_hits: summary.daily_limit_hits / days,
+            skipped: summary.skipped / days,
+            rating_sum: summary.rating_sum / days,
+            ratings: summary.ratings / days,
+        }
+    }
+
+    /// Runs of consecutive days with at least one break taken. Today only
+    /// extends the current streak once it has a break; until then the
+    /// streak ending yesterday still counts.
+    pub fn streaks(&self, today: i64) -> Streaks {
+        let mut streaks = Streaks::default();
+        let mut run = 0;
+        let mut previous = None;
+        for (day, _) in self
+            .by_day
+            .range(..=today)
+            .filter(|(_, agg)| agg.breaks_taken() > 0)
+        {
+            run = if previous == Some(day - 1) {
+                run + 1
+            } else {
+                1
+            };
+            streaks.longest_days = streaks.longest_days.max(run);
+            previous = Some(*day);
+        }
+        if previous.is_some_and(|day| day >= today - 1) {
+            streaks.current_days = run;
+        }
+        streaks
+    }
+
+    fn summarize_range(&self, start: i64, end: i64) -> WeeklySummary {
         let mut summary = WeeklySummary::default();
-        for (_day, agg) in self.by_day.range(start..=end_day_index) {
+        for (_day, agg) in self.by_day.range(start..=end) {
             summary.total_active_seconds += agg.active_seconds;
             summary.micro_done += agg.micro_done;
             summary.rest_done += agg.rest_done;
+            summary.custom_done += agg.custom_done;
             summary.daily_limit_hits += agg.daily_limit_hits;
             summary.skipped += agg.skipped;
+            summary.rating_sum += agg.rating_sum;
+            summary.ratings += agg.ratings;
         }
         summary
     }
 }
 
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Streaks {
+    pub current_days: u32,
+    pub longest_days: u32,
+}
+
+/// Completed breaks in a row worth celebrating.
+pub const BREAKS_IN_A_ROW_MILESTONES: [u32; 4] = [10, 25, 50, 100];
+/// Day streak lengths worth celebrating.
+pub const STREAK_DAYS_MILESTONES: [u32; 5] = [7, 14, 30, 100, 365];
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MilestoneKind {
+    /// Completed breaks since the last skipped one.
+    BreaksInARow,
+    /// Consecutive days with a break taken, as in [`Streaks`].
+    StreakDays,
+}
+
+impl MilestoneKind {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::BreaksInARow => "breaks_in_a_row",
+            Self::StreakDays => "streak_days",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "breaks_in_a_row" => Some(Self::BreaksInARow),
+            "streak_days" => Some(Self::StreakDays),
+            _ => None,
+        }
+    }
+
+    pub fn thresholds(self) -> &'static [u32] {
+        match self {
+            Self::BreaksInARow => &BREAKS_IN_A_ROW_MILESTONES,
+            Self::StreakDays => &STREAK_DAYS_MILESTONES,
+        }
+    }
+}
+
+/// The runs milestones are counted on, taken before and after a break
+/// completes to see which thresholds it crossed.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct MilestoneProgress {
+    pub breaks_in_a_row: u32,
+    pub streak_days: u32,
+}
+
+impl MilestoneProgress {
+    fn count(&self, kind: MilestoneKind) -> u32 {
+        match kind {
+            MilestoneKind::BreaksInARow => self.breaks_in_a_row,
+            MilestoneKind::StreakDays => self.streak_days,
+        }
+    }
+
+    /// Thresholds above `before` and reached now, smallest first.
+    pub fn reached_since(&self, before: &Self) -> Vec<(MilestoneKind, u32)> {
+        [MilestoneKind::BreaksInARow, MilestoneKind::StreakDays]
+            .into_iter()
+            .flat_map(|kind| {
+                let (from, to) = (before.count(kind), self.count(kind));
+                kind.thresholds()
+                    .iter()
+                    .filter(move |&&threshold| from < threshold && threshold <= to)
+                    .map(move |&threshold| (kind, threshold))
+            })
+            .collect()
+    }
+}
+
+/// Break outcomes per hour of one day, for sparklines.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct HourlyBreaks {
+    pub taken: [u32; 24],
+    pub skipped: [u32; 24],
+}
+
+/// How break prompts in a window were answered: breaks taken versus
+/// prompts skipped or snoozed.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Compliance {
+    pub taken: u32,
+    pub avoided: u32,
+}
+
+impl Compliance {
+    pub fn total(&self) -> u32 {
+        self.taken.saturating_add(self.avoided)
+    }
+
+    pub fn skip_rate_percent(&self) -> u8 {
+        if self.total() == 0 {
+            return 0;
+        }
+        percent_of(u64::from(self.avoided), u64::from(self.total()))
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SessionOutcome {
+    Completed,
+    Skipped,
+    Interrupted,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BreakSession {
+    pub kind: BreakKind,
+    pub scheduled_at: u64,
+    pub started_at: Option<u64>,
+    pub ended_at: Option<u64>,
+    pub outcome: Option<SessionOutcome>,
+    pub snooze_count: u32,
+    /// How the user felt afterwards, from 1 to 5, when they said.
+    pub rating: Option<u8>,
+}
+
+impl BreakSession {
+    fn new(kind: BreakKind, scheduled_at: u64) -> Self {
+        Self {
+            kind,
+            scheduled_at,
+            started_at: None,
+            ended_at: None,
+            outcome: None,
+            snooze_count: 0,
+            rating: None,
+        }
+    }
+
+    pub fn is_open(&self) -> bool {
+        self.outcome.is_none()
+    }
+
+    fn close(&mut self, at: u64, outcome: SessionOutcome) {
+        self.ended_at = Some(at);
+        self.outcome = Some(outcome);
+    }
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct BreakHistory {
+    sessions: Vec<BreakSession>,
+}
+
+impl BreakHistory {
+    pub fn from_sessions(sessions: Vec<BreakSession>) -> Self {
+        Self { sessions }
+    }
+
+    pub fn sessions(&self) -> &[BreakSession] {
+        &self.sessions
+    }
+
+    pub fn record_due(&mut self, kind: BreakKind, at: u64) {
+        for session in self.sessions.iter_mut() {
+            if session.is_open() && session.started_at.is_none() && session.kind != kind {
+                session.close(at, SessionOutcome::Skipped);
+            }
+        }
+        if self.open_session(kind).is_none() {
+            self.sessions.push(BreakSession::new(kind, at));
+        }
+    }
+
+    pub fn record_snooze(&mut self, kind: BreakKind, at: u64) {
+        if self.open_session(kind).is_none() {
+            self.sessions.push(BreakSession::new(kind, at));
+        }
+        if let Some(session) = self.open_session(kind) {
+            session.snooze_count = session.snooze_count.saturating_add(1);
+        }
+    }
+
+    pub fn record_start(&mut self, kind: BreakKind, at: u64) {
+        if self.open_session(kind).is_none() {
+            self.sessions.push(BreakSession::new(kind, at));
+        }
+        if let Some(session) = self.open_session(kind) {
+            session.started_at.get_or_insert(at);
+        }
+    }
+
+    pub fn record_end(&mut self, kind: BreakKind, at: u64, outcome: SessionOutcome) {
+        if let Some(session) = self.open_session(kind) {
+            session.close(at, outcome);
+        }
+    }
+
+    /// Rates the latest completed `kind` break that has no rating yet and
+    /// returns when it ended.
+    pub fn record_rating(&mut self, kind: BreakKind, rating: u8) -> Option<u64> {
+        let session = self.sessions.iter_mut().rev().find(|session| {
+            session.kind == kind && session.outcome == Some(SessionOutcome::Completed)
+        })?;
+        if session.rating.is_some() {
+            return None;
+        }
+        session.rating = Some(rating);
+        session.ended_at
+    }
+
+    pub fn close_open(&mut self, at: u64, outcome: SessionOutcome) {
+        for session in self.sessions.iter_mut().filter(|session| session.is_open()) {
+            session.close(at, outcome);
+        }
+    }
+
+    pub fn prune_before(&mut self, cutoff: u64) {
+        self.sessions
+            .retain(|session| session.is_open() || session.scheduled_at >= cutoff);
+    }
+
+    pub fn between(&self, start: u64, end: u64) -> Vec<&BreakSession> {
+        self.sessions
+            .iter()
+            .filter(|session| session.scheduled_at >= start && session.scheduled_at < end)
+            .collect()
+    }
+
+    /// Completed breaks since the last skipped one. Interrupted breaks
+    /// neither count nor end the run.
+    pub fn completed_in_a_row(&self) -> u32 {
+        let mut run = 0u32;
+        for session in self.sessions.iter().rev() {
+            match session.outcome {
+                Some(SessionOutcome::Completed) => run = run.saturating_add(1),
+                Some(SessionOutcome::Skipped) => break,
+                Some(SessionOutcome::Interrupted) | None => {}
+            }
+        }
+        run
+    }
+
+    pub fn compliance_since(&self, since: u64) -> Compliance {
+        self.compliance_where(since, |_| true)
+    }
+
+    /// Like [`Self::compliance_since`], for one kind of break.
+    pub fn kind_compliance_since(&self, since: u64, kind: BreakKind) -> Compliance {
+        self.compliance_where(since, |session| session.kind == kind)
+    }
+
+    fn compliance_where(&self, since: u64, keep: impl Fn(&BreakSession) -> bool) -> Compliance {
+        let mut compliance = Compliance::default();
+        for session in self
+            .sessions
+            .iter()
+            .filter(|session| session.scheduled_at >= since && keep(session))
+        {
+            compliance.avoided = compliance.avoided.saturating_add(session.snooze_count);
+            match session.outcome {
+                Some(SessionOutcome::Completed) => {
+                    compliance.taken = compliance.taken.saturating_add(1)
+                }
+                Some(SessionOutcome::Skipped) => {
+                    compliance.avoided = compliance.avoided.saturating_add(1)
+                }
+                Some(SessionOutcome::Interrupted) | None => {}
+            }
+        }
+        compliance
+    }
+
+    /// Outcomes of the breaks scheduled in the 24 hours from `day_start`.
+    pub fn hourly(&self, day_start: u64) -> HourlyBreaks {
+        let mut hourly = HourlyBreaks::default();
+        for session in self.between(day_start, day_start + 24 * HOUR_SECONDS) {
+            let hour = ((session.scheduled_at - day_start) / HOUR_SECONDS) as usize;
+            match session.outcome {
+                Some(SessionOutcome::Completed) => hourly.taken[hour] += 1,
+                Some(SessionOutcome::Skipped) => hourly.skipped[hour] += 1,
+                Some(SessionOutcome::Interrupted) | None => {}
+            }
+        }
+        hourly
+    }
+
+    fn open_session(&mut self, kind: BreakKind) -> Option<&mut BreakSession> {
+        self.sessions
+            .iter_mut()
+            .rev()
+            .find(|session| session.kind == kind && session.is_open())
+    }
+}
+
+/// A stretch of activity with no pause longer than the focus gap.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct FocusSession {
+    pub started_at: u64,
+    pub ended_at: u64,
+    pub active_seconds: u64,
+}
+
+impl FocusSession {
+    pub fn length_seconds(&self) -> u64 {
+        self.ended_at.saturating_sub(self.started_at)
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct FocusSummary {
+    pub sessions: u32,
+    pub active_seconds: u64,
+    pub longest_seconds: u64,
+    pub average_seconds: u64,
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct FocusLog {
+    sessions: Vec<FocusSession>,
+}
+
+impl FocusLog {
+    pub fn from_sessions(sessions: Vec<FocusSession>) -> Self {
+        Self { sessions }
+    }
+
+    pub fn sessions(&self) -> &[FocusSession] {
+        &self.sessions
+    }
+
+    /// Adds `seconds` of activity ending at `at`, extending the latest
+    /// session unless the pause since it exceeds `gap_seconds`.
+    pub fn record_activity(&mut self, at: u64, seconds: u64, gap_seconds: u64) {
+        match self.sessions.last_mut() {
+            Some(last)
+                if at >= last.ended_at
+                    && at.saturating_sub(seconds).saturating_sub(last.ended_at) <= gap_seconds =>
+            {
+                last.ended_at = at;
+                last.active_seconds = last.active_seconds.saturating_add(seconds);
+            }
+            _ => self.sessions.push(FocusSession {
+                started_at: at.saturating_sub(seconds),
+                ended_at: at,
+                active_seconds: seconds,
+            }),
+        }
+    }
+
+    pub fn prune_before(&mut self, cutoff: u64) {
+        self.sessions.retain(|session| session.ended_at >= cutoff);
+    }
+
+    /// Sessions that started in `[start, end)`.
+    pub fn between(&self, start: u64, end: u64) -> Vec<FocusSession> {
+        self.sessions
+            .iter()
+            .filter(|session| session.started_at >= start && session.started_at < end)
+            .copied()
+            .collect()
+    }
+
+    /// Active seconds in each of the 24 hours from `day_start`. A session
+    /// spanning several hours spreads its activity over them in proportion
+    /// to how much of its length falls in each.
+    pub fn active_by_hour(&self, day_start: u64) -> [u64; 24] {
+        let mut hours = [0; 24];
+        let day_end = day_start + 24 * HOUR_SECONDS;
+        for session in self
+            .sessions
+            .iter()
+            .filter(|session| session.ended_at >= day_start && session.started_at < day_end)
+        {
+            let length = session.length_seconds();
+            if length == 0 {
+                let hour = (session.started_at.saturating_sub(day_start) / HOUR_SECONDS) as usize;
+                hours[hour] += session.active_seconds;
+                continue;
+            }
+            for (hour, total) in hours.iter_mut().enumerate() {
+                let start = day_start + hour as u64 * HOUR_SECONDS;
+                let overlap = session
+                    .ended_at
+                    .min(start + HOUR_SECONDS)
+                    .saturating_sub(session.started_at.max(start));
+                *total += session.active_seconds * overlap / length;
+            }
+        }
+        hours
+    }
+
+    pub fn summarize(&self, start: u64, end: u64) -> FocusSummary {
+        let sessions = self.between(start, end);
+        let lengths = sessions.iter().map(FocusSession::length_seconds);
+        FocusSummary {
+            sessions: sessions.len() as u32,
+            active_seconds: sessions.iter().map(|session| session.active_seconds).sum(),
+            longest_seconds: lengths.clone().max().unwrap_or(0),
+            average_seconds: lengths
+                .sum::<u64>()
+                .checked_div(sessions.len() as u64)
+                .unwrap_or(0),
+        }
+    }
+}
+
+/// Key presses and clicks counted in one hour. Counts are all the sampler
+/// ever reports; which keys were pressed is never seen.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct IntensityBucket {
+    pub hour_start: u64,
+    pub keys: u64,
+    pub clicks: u64,
+}
+
+impl IntensityBucket {
+    pub fn events(&self) -> u64 {
+        self.keys.saturating_add(self.clicks)
+    }
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct IntensityLog {
+    buckets: Vec<IntensityBucket>,
+}
+
+impl IntensityLog {
+    pub fn from_buckets(mut buckets: Vec<IntensityBucket>) -> Self {
+        buckets.sort_by_key(|bucket| bucket.hour_start);
+        Self { buckets }
+    }
+
+    pub fn buckets(&self) -> &[IntensityBucket] {
+        &self.buckets
+    }
+
+    /// Adds counts sampled at `at` to the hour they fall in.
+    pub fn record(&mut self, at: u64, keys: u64, clicks: u64) {
+        let hour_start = at - at % HOUR_SECONDS;
+        let index = match self
+            .buckets
+            .binary_search_by_key(&hour_start, |bucket| bucket.hour_start)
+        {
+            Ok(index) => index,
+            Err(index) => {
+                self.buckets.insert(
+                    index,
+                    IntensityBucket {
+                        hour_start,
+                        ..IntensityBucket::default()
+                    },
+                );
+                index
+            }
+        };
+        let bucket = &mut self.buckets[index];
+        bucket.keys = bucket.keys.saturating_add(keys);
+        bucket.clicks = bucket.clicks.saturating_add(clicks);
+    }
+
+    pub fn prune_before(&mut self, cutoff: u64) {
+        self.buckets
+            .retain(|bucket| bucket.hour_start + HOUR_SECONDS > cutoff);
+    }
+
+    /// Key presses and clicks in each of the 24 hours from `day_start`.
+    pub fn events_by_hour(&self, day_start: u64) -> [u64; 24] {
+        let mut hours = [0; 24];
+        let day_end = day_start + 24 * HOUR_SECONDS;
+        for bucket in self
+            .buckets
+            .iter()
+            .filter(|bucket| bucket.hour_start >= day_start && bucket.hour_start < day_end)
+        {
+            hours[((bucket.hour_start - day_start) / HOUR_SECONDS) as usize] += bucket.events();
+        }
+        hours
+    }
+}
+
+/// Longest window title kept and the most titles kept per application and
+/// day; time in further titles still counts toward the application.
+pub const MAX_APP_TITLE_CHARS: usize = 120;
+pub const MAX_TITLES_PER_APP: usize = 20;
+
+/// Active time one application had in the foreground on one day. Titles
+/// are only filled in when recording them was turned on.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct AppUsage {
+    pub active_seconds: u64,
+    pub titles: BTreeMap<String, u64>,
+}
+
+/// Per-day active time by application, keyed by window class.
+#[derive(Clone, Debug, Default)]
+pub struct AppUsageStore {
+    by_day: BTreeMap<i64, BTreeMap<String, AppUsage>>,
+}
+
+impl AppUsageStore {
+    pub fn from_days(by_day: BTreeMap<i64, BTreeMap<String, AppUsage>>) -> Self {
+        Self { by_day }
+    }
+
+    pub fn days(&self) -> &BTreeMap<i64, BTreeMap<String, AppUsage>> {
+        &self.by_day
+    }
+
+    pub fn prune_before(&mut self, day_index: i64) {
+        self.by_day = self.by_day.split_off(&day_index);
+    }
+
+    pub fn record(&mut self, day_index: i64, app: &str, title: Option<&str>, seconds: u64) {
+        let app = app.trim();
+        if app.is_empty() || seconds == 0 {
+            return;
+        }
+        let usage = self
+            .by_day
+            .entry(day_index)
+            .or_default()
+            .entry(app.to_string())
+            .or_default();
+        usage.active_seconds = usage.active_seconds.saturating_add(seconds);
+        let Some(title) = title.map(str::trim).filter(|title| !title.is_empty()) else {
+            return;
+        };
+        let title: String = title.chars().take(MAX_APP_TITLE_CHARS).collect();
+        if usage.titles.len() < MAX_TITLES_PER_APP || usage.titles.contains_key(&title) {
+            let entry = usage.titles.entry(title).or_default();
+            *entry = entry.saturating_add(seconds);
+        }
+    }
+
+    /// Time on days `start..=end` by the categories in
+    /// `settings.app_categories`.
+    pub fn category_breakdown(
+        &self,
+        start: i64,
+        end: i64,
+        settings: &AnalyticsSettings,
+    ) -> CategoryBreakdown {
+        let mut breakdown = CategoryBreakdown::default();
+        for (app, usage) in self.by_day.range(start..=end).flat_map(|(_, apps)| apps) {
+            breakdown.add(settings.category_of(app), usage.active_seconds);
+        }
+        breakdown
+    }
+
+    /// Applications used on `day_index`, most used first.
+    pub fn ranked(&self, day_index: i64) -> Vec<(&str, &AppUsage)> {
+        let mut apps: Vec<_> = self
+            .by_day
+            .get(&day_index)
+            .into_iter()
+            .flatten()
+            .map(|(app, usage)| (app.as_str(), usage))
+            .collect();
+        apps.sort_by(|a, b| {
+            b.1.active_seconds
+                .cmp(&a.1.active_seconds)
+                .then(a.0.cmp(b.0))
+        });
+        apps
+    }
+}
+
+/// Application time split by category; applications without a category
+/// rule are uncategorized.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CategoryBreakdown {
+    pub work_seconds: u64,
+    pub comms_seconds: u64,
+    pub entertainment_seconds: u64,
+    pub uncategorized_seconds: u64,
+}
+
+impl CategoryBreakdown {
+    pub fn seconds(&self, category: AppCategory) -> u64 {
+        match category {
+            AppCategory::Work => self.work_seconds,
+            AppCategory::Comms => self.comms_seconds,
+            AppCategory::Entertainment => self.entertainment_seconds,
+        }
+    }
+
+    pub fn productive_seconds(&self) -> u64 {
+        AppCategory::ALL
+            .into_iter()
+            .filter(|category| category.is_productive())
+            .map(|category| self.seconds(category))
+            .sum()
+    }
+
+    pub fn leisure_seconds(&self) -> u64 {
+        AppCategory::ALL
+            .into_iter()
+            .filter(|category| !category.is_productive())
+            .map(|category| self.seconds(category))
+            .sum()
+    }
+
+    pub fn total_seconds(&self) -> u64 {
+        self.productive_seconds() + self.leisure_seconds() + self.uncategorized_seconds
+    }
+
+    fn add(&mut self, category: Option<AppCategory>, seconds: u64) {
+        let slot = match category {
+            Some(AppCategory::Work) => &mut self.work_seconds,
+            Some(AppCategory::Comms) => &mut self.comms_seconds,
+            Some(AppCategory::Entertainment) => &mut self.entertainment_seconds,
+            None => &mut self.uncategorized_seconds,
+        };
+        *slot = slot.saturating_add(seconds);
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -69,10 +858,372 @@ mod tests {
         store.record_break(11, BreakKind::Rest, BreakOutcome::Completed);
         store.record_break(11, BreakKind::Micro, BreakOutcome::Skipped);
 
+        let day = store.day(11);
+        assert_eq!(day.active_seconds, 240);
+        assert_eq!(day.breaks_taken(), 1);
+        assert_eq!(store.day(12), DailyAggregate::default());
+
         let weekly = store.summarize_week_ending(11);
         assert_eq!(weekly.total_active_seconds, 360);
         assert_eq!(weekly.micro_done, 1);
         assert_eq!(weekly.rest_done, 1);
         assert_eq!(weekly.skipped, 1);
     }
+
+    #[test]
+    fn break_history_tracks_session_lifecycle() {
+        let mut history = BreakHistory::default();
+        history.record_due(BreakKind::Micro, 100);
+        history.record_snooze(BreakKind::Micro, 105);
+        history.record_due(BreakKind::Micro, 400);
+        history.record_start(BreakKind::Micro, 410);
+        history.record_end(BreakKind::Micro, 430, SessionOutcome::Completed);
+        history.record_due(BreakKind::Rest, 500);
+        history.record_due(BreakKind::Micro, 600);
+        history.close_open(700, SessionOutcome::Interrupted);
+
+        let sessions = history.sessions();
+        assert_eq!(sessions.len(), 3);
+        assert_eq!(sessions[0].scheduled_at, 100);
+        assert_eq!(sessions[0].started_at, Some(410));
+        assert_eq!(sessions[0].snooze_count, 1);
+        assert_eq!(sessions[0].outcome, Some(SessionOutcome::Completed));
+        assert_eq!(sessions[1].outcome, Some(SessionOutcome::Skipped));
+        assert_eq!(sessions[1].ended_at, Some(600));
+        assert_eq!(sessions[2].outcome, Some(SessionOutcome::Interrupted));
+
+        history.prune_before(550);
+        assert_eq!(history.sessions().len(), 1);
+        assert_eq!(history.between(600, 601).len(), 1);
+    }
+
+    #[test]
+    fn calendar_week_respects_configured_start() {
+        // Day 19_723 is Monday 2024-01-01; day 19_729 is Sunday 2024-01-07.
+        assert_eq!(week_start_day(19_729, WeekStart::Monday), 19_723);
+        assert_eq!(week_start_day(19_729, WeekStart::Sunday), 19_729);
+        assert_eq!(week_start_day(19_728, WeekStart::Sunday), 19_722);
+
+        let mut store = AnalyticsStore::default();
+        store.record_activity(19_722, 100);
+        store.record_activity(19_723, 200);
+        store.record_activity(19_729, 400);
+
+        assert_eq!(
+            store
+                .summarize_calendar_week(19_725, WeekStart::Monday)
+                .total_active_seconds,
+            600
+        );
+        assert_eq!(
+            store
+                .summarize_calendar_week(19_725, WeekStart::Sunday)
+                .total_active_seconds,
+            300
+        );
+    }
+
+    #[test]
+    fn weekly_goal_progress_reports_both_targets() {
+        let mut store = AnalyticsStore::default();
+        store.record_activity(19_723, 3_000);
+        store.record_break(19_723, BreakKind::Micro, BreakOutcome::Completed);
+        store.record_break(19_724, BreakKind::Rest, BreakOutcome::Completed);
+
+        let settings = AnalyticsSettings {
+            week_start: WeekStart::Monday,
+            weekly_max_active_seconds: 2_000,
+            weekly_min_breaks: 4,
+            focus_gap_seconds: 120,
+            break_feedback_prompt: false,
+            milestone_celebrations: true,
+            app_usage_enabled: false,
+            app_usage_titles: false,
+            app_categories: Vec::new(),
+        };
+        let progress = store.weekly_goal_progress(19_724, &settings);
+        assert_eq!(progress.week_start_day, 19_723);
+        assert_eq!(progress.active_percent, 100);
+        assert!(!progress.active_goal_met);
+        assert_eq!(progress.breaks_taken, 2);
+        assert_eq!(progress.breaks_percent, 50);
+        assert!(!progress.breaks_goal_met);
+    }
+
+    #[test]
+    fn app_usage_aggregates_by_day_and_caps_titles() {
+        let mut store = AppUsageStore::default();
+        store.record(10, "firefox", None, 30);
+        store.record(10, "code", Some("main.rs"), 60);
+        store.record(10, "firefox", Some("Docs"), 45);
+        store.record(10, "  ", Some("ignored"), 5);
+        store.record(11, "code", None, 10);
+
+        let ranked = store.ranked(10);
+        let apps: Vec<_> = ranked
+            .iter()
+            .map(|(app, usage)| (*app, usage.active_seconds))
+            .collect();
+        assert_eq!(apps, vec![("firefox", 75), ("code", 60)]);
+        assert_eq!(ranked[0].1.titles.get("Docs"), Some(&45));
+        assert!(store.ranked(12).is_empty());
+
+        for index in 0..MAX_TITLES_PER_APP + 2 {
+            store.record(12, "term", Some(&format!("tab {index}")), 1);
+        }
+        store.record(12, "term", Some("tab 0"), 1);
+        let term = &store.ranked(12)[0].1;
+        assert_eq!(term.titles.len(), MAX_TITLES_PER_APP);
+        assert_eq!(term.titles.get("tab 0"), Some(&2));
+        assert_eq!(term.active_seconds, MAX_TITLES_PER_APP as u64 + 3);
+
+        store.prune_before(11);
+        assert_eq!(
+            store.days().keys().copied().collect::<Vec<_>>(),
+            vec![11, 12]
+        );
+    }
+
+    #[test]
+    fn category_breakdown_splits_productive_from_leisure() {
+        let mut store = AppUsageStore::default();
+        store.record(10, "code", None, 600);
+        store.record(10, "Slack", None, 120);
+        store.record(11, "mpv", None, 300);
+        store.record(11, "gimp", None, 60);
+        store.record(12, "mpv", None, 1_000);
+
+        let mut settings = crate::config::Settings::default().analytics;
+        settings.app_categories =
+            crate::config::parse_app_categories("code=work,slack=comms,MPV=entertainment").unwrap();
+        let breakdown = store.category_breakdown(10, 11, &settings);
+        assert_eq!(breakdown.seconds(AppCategory::Comms), 120);
+        assert_eq!(breakdown.productive_seconds(), 720);
+        assert_eq!(breakdown.leisure_seconds(), 300);
+        assert_eq!(breakdown.uncategorized_seconds, 60);
+        assert_eq!(breakdown.total_seconds(), 1_080);
+    }
+
+    #[test]
+    fn milestones_fire_once_when_a_run_crosses_them() {
+        let mut history = BreakHistory::default();
+        for at in 0..9 {
+            history.record_start(BreakKind::Micro, at * 100);
+            history.record_end(BreakKind::Micro, at * 100 + 20, SessionOutcome::Completed);
+        }
+        history.record_start(BreakKind::Rest, 950);
+        history.record_end(BreakKind::Rest, 960, SessionOutcome::Interrupted);
+        assert_eq!(history.completed_in_a_row(), 9);
+
+        let before = MilestoneProgress {
+            breaks_in_a_row: history.completed_in_a_row(),
+            streak_days: 6,
+        };
+        history.record_start(BreakKind::Micro, 1_000);
+        history.record_end(BreakKind::Micro, 1_020, SessionOutcome::Completed);
+        let after = MilestoneProgress {
+            breaks_in_a_row: history.completed_in_a_row(),
+            streak_days: 7,
+        };
+        assert_eq!(
+            after.reached_since(&before),
+            vec![
+                (MilestoneKind::BreaksInARow, 10),
+                (MilestoneKind::StreakDays, 7)
+            ]
+        );
+        assert!(after.reached_since(&after).is_empty());
+
+        history.record_due(BreakKind::Micro, 1_100);
+        history.record_end(BreakKind::Micro, 1_110, SessionOutcome::Skipped);
+        assert_eq!(history.completed_in_a_row(), 0);
+        for kind in [MilestoneKind::BreaksInARow, MilestoneKind::StreakDays] {
+            assert_eq!(MilestoneKind::parse(kind.as_str()), Some(kind));
+        }
+    }
+
+    #[test]
+    fn ratings_attach_to_the_latest_completed_break_once() {
+        let mut history = BreakHistory::default();
+        assert_eq!(history.record_rating(BreakKind::Rest, 4), None);
+        history.record_start(BreakKind::Rest, 100);
+        history.record_end(BreakKind::Rest, 400, SessionOutcome::Completed);
+        history.record_start(BreakKind::Rest, 900);
+
+        assert_eq!(history.record_rating(BreakKind::Rest, 4), Some(400));
+        assert_eq!(history.record_rating(BreakKind::Rest, 2), None);
+        assert_eq!(history.sessions()[0].rating, Some(4));
+        assert_eq!(history.sessions()[1].rating, None);
+
+        let mut store = AnalyticsStore::default();
+        store.record_rating(10, 4);
+        store.record_rating(11, 5);
+        store.record_rating(11, 2);
+        let summary = store.summarize_week_ending(11);
+        assert_eq!(summary.ratings, 3);
+        assert_eq!(summary.average_rating(), Some(11.0 / 3.0));
+        assert_eq!(store.summarize_week_ending(30).average_rating(), None);
+    }
+
+    #[test]
+    fn focus_sessions_split_on_pauses_longer_than_the_gap() {
+        let mut log = FocusLog::default();
+        for at in 1_001..=1_600 {
+            log.record_activity(at, 1, 120);
+        }
+        // A 20 s micro break keeps the session going; a 5 min rest ends it.
+        for at in 1_621..=2_000 {
+            log.record_activity(at, 1, 120);
+        }
+        for at in 2_301..=2_400 {
+            log.record_activity(at, 1, 120);
+        }
+
+        assert_eq!(
+            log.sessions(),
+            [
+                FocusSession {
+                    started_at: 1_000,
+                    ended_at: 2_000,
+                    active_seconds: 980,
+                },
+                FocusSession {
+                    started_at: 2_300,
+                    ended_at: 2_400,
+                    active_seconds: 100,
+                },
+            ]
+        );
+        let summary = log.summarize(0, 3_000);
+        assert_eq!(summary.sessions, 2);
+        assert_eq!(summary.active_seconds, 1_080);
+        assert_eq!(summary.longest_seconds, 1_000);
+        assert_eq!(summary.average_seconds, 550);
+        assert_eq!(log.summarize(2_000, 3_000).sessions, 1);
+
+        log.prune_before(2_100);
+        assert_eq!(log.sessions().len(), 1);
+    }
+
+    #[test]
+    fn averages_and_streaks_skip_days_without_records() {
+        let mut store = AnalyticsStore::default();
+        for day in [1, 2, 3, 6, 7] {
+            store.record_activity(day, 3_600);
+            store.record_break(day, BreakKind::Micro, BreakOutcome::Completed);
+        }
+        store.record_activity(8, 600);
+
+        let average = store.daily_average(1, 7);
+        assert_eq!(average.active_seconds, 3_600);
+        assert_eq!(average.micro_done, 1);
+        assert_eq!(store.daily_average(4, 5), DailyAggregate::default());
+
+        // Day 8 has no break yet, so the run ending on day 7 is current.
+        assert_eq!(
+            store.streaks(8),
+            Streaks {
+                current_days: 2,
+                longest_days: 3,
+            }
+        );
+        assert_eq!(store.streaks(9).current_days, 0);
+    }
+
+    #[test]
+    fn hourly_series_bucket_breaks_and_focus() {
+        let day_start = 86_400;
+        let mut history = BreakHistory::default();
+        history.record_due(BreakKind::Micro, day_start + 600);
+        history.record_end(BreakKind::Micro, day_start + 620, SessionOutcome::Completed);
+        history.record_due(BreakKind::Rest, day_start + 7_300);
+        history.record_end(BreakKind::Rest, day_start + 7_400, SessionOutcome::Skipped);
+        history.record_due(BreakKind::Micro, day_start - 10);
+
+        let hourly = history.hourly(day_start);
+        assert_eq!(hourly.taken[0], 1);
+        assert_eq!(hourly.skipped[2], 1);
+        assert_eq!(hourly.taken.iter().sum::<u32>(), 1);
+
+        let log = FocusLog::from_sessions(vec![FocusSession {
+            started_at: day_start + 3_000,
+            ended_at: day_start + 4_200,
+            active_seconds: 1_200,
+        }]);
+        let hours = log.active_by_hour(day_start);
+        assert_eq!(hours[0], 600);
+        assert_eq!(hours[1], 600);
+        assert_eq!(hours.iter().sum::<u64>(), 1_200);
+    }
+
+    #[test]
+    fn intensity_counts_are_kept_per_hour() {
+        let mut log = IntensityLog::default();
+        log.record(7_260, 120, 8);
+        log.record(3_700, 40, 2);
+        log.record(7_300, 30, 0);
+        assert_eq!(
+            log.buckets(),
+            [
+                IntensityBucket {
+                    hour_start: 3_600,
+                    keys: 40,
+                    clicks: 2,
+                },
+                IntensityBucket {
+                    hour_start: 7_200,
+                    keys: 150,
+                    clicks: 8,
+                },
+            ]
+        );
+        let hours = log.events_by_hour(0);
+        assert_eq!((hours[1], hours[2]), (42, 158));
+
+        log.prune_before(7_200);
+        assert_eq!(log.buckets().len(), 1);
+    }
+
+    /// Queries over far more history than retention keeps must stay cheap.
+    /// Run with `cargo test -p lazaro-core --release -- --ignored soak`.
+    #[test]
+    #[ignore = "soak test, run in release"]
+    fn soak_large_stores_answer_within_budget() {
+        const BUDGET: std::time::Duration = std::time::Duration::from_secs(2);
+
+        let days = 3_650;
+        let mut store = AnalyticsStore::default();
+        for day in 0..days {
+            store.record_activity(day, 25_000);
+            for _ in 0..40 {
+                store.record_break(day, BreakKind::Micro, BreakOutcome::Completed);
+            }
+        }
+        let sessions = (0..200_000u64)
+            .map(|index| BreakSession {
+                kind: BreakKind::Micro,
+                scheduled_at: index * 600,
+                started_at: Some(index * 600 + 5),
+                ended_at: Some(index * 600 + 25),
+                outcome: Some(SessionOutcome::Completed),
+                snooze_count: 0,
+                rating: None,
+            })
+            .collect();
+        let history = BreakHistory::from_sessions(sessions);
+        let end = 200_000 * 600;
+
+        let started = std::time::Instant::now();
+        for _ in 0..100 {
+            assert_eq!(store.streaks(days - 1).current_days, days as u32);
+            let _ = store.daily_average(days - 365, days - 1);
+            let _ = history.compliance_since(end - 7 * 86_400);
+            let _ = history.hourly(end - 86_400);
+        }
+        assert!(
+            started.elapsed() < BUDGET,
+            "analytics queries took {:?}",
+            started.elapsed()
+        );
+    }
 }
@@ -0,0 +1,246 @@
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BusyWindow {
+    pub start: u64,
+    pub end: u64,
+    pub summary: String,
+}
+
+impl BusyWindow {
+    pub fn contains(&self, now: u64) -> bool {
+        self.start <= now && now < self.end
+    }
+}
+
+#[derive(Default)]
+struct PendingEvent {
+    start: Option<u64>,
+    end: Option<u64>,
+    duration: Option<u64>,
+    summary: String,
+    transparent: bool,
+    cancelled: bool,
+}
+
+impl PendingEvent {
+    fn finish(self) -> Option<BusyWindow> {
+        if self.transparent || self.cancelled {
+            return None;
+        }
+        let start = self.start?;
+        let end = self
+            .end
+            .or_else(|| self.duration.map(|duration| start.saturating_add(duration)))?;
+        (end > start).then_some(BusyWindow {
+            start,
+            end,
+            summary: self.summary,
+        })
+    }
+}
+
+/// Extracts timed, opaque events from iCalendar data. Floating and `TZID`
+/// times are read as local time using `local_offset_seconds`; all-day events
+/// and recurrence rules are ignored.
+pub fn parse_ics(source: &str, local_offset_seconds: i64) -> Vec<BusyWindow> {
+    let mut windows = Vec::new();
+    let mut event: Option<PendingEvent> = None;
+
+    for line in unfold(source) {
+        let Some((head, value)) = line.split_once(':') else {
+            continue;
+        };
+        let mut parts = head.split(';');
+        let name = parts.next().unwrap_or_default().to_ascii_uppercase();
+        let all_day = parts.any(|param| param.eq_ignore_ascii_case("VALUE=DATE"));
+
+        match (name.as_str(), event.as_mut()) {
+            ("BEGIN", _) if value.eq_ignore_ascii_case("VEVENT") => {
+                event = Some(PendingEvent::default());
+            }
+            ("END", Some(_)) if value.eq_ignore_ascii_case("VEVENT") => {
+                windows.extend(event.take().and_then(PendingEvent::finish));
+            }
+            ("DTSTART", Some(pending)) if !all_day => {
+                pending.start = parse_datetime(value, local_offset_seconds);
+            }
+            ("DTEND", Some(pending)) if !all_day => {
+                pending.end = parse_datetime(value, local_offset_seconds);
+            }
+            ("DURATION", Some(pending)) => pending.duration = parse_duration(value),
+            ("SUMMARY", Some(pending)) => pending.summary = unescape(value),
+            ("TRANSP", Some(pending)) => {
+                pending.transparent = value.eq_ignore_ascii_case("TRANSPARENT");
+            }
+            ("STATUS", Some(pending)) => {
+                pending.cancelled = value.eq_ignore_ascii_case("CANCELLED");
+            }
+            _ => {}
+        }
+    }
+
+    windows.sort_by_key(|window| (window.start, window.end));
+    windows
+}
+
+pub fn busy_at(windows: &[BusyWindow], now: u64) -> Option<&BusyWindow> {
+    windows.iter().find(|window| window.contains(now))
+}
+
+/// Windows that are in progress or start within `horizon_seconds` of `now`.
+pub fn upcoming(windows: &[BusyWindow], now: u64, horizon_seconds: u64) -> Vec<BusyWindow> {
+    let until = now.saturating_add(horizon_seconds);
+    windows
+        .iter()
+        .filter(|window| window.end > now && window.start <= until)
+        .cloned()
+        .collect()
+}
+
+fn unfold(source: &str) -> Vec<String> {
+    let mut lines: Vec<String> = Vec::new();
+    for raw in source.lines() {
+        let raw = raw.trim_end_matches('\r');
+        match (raw.strip_prefix([' ', '\t']), lines.last_mut()) {
+            (Some(continuation), Some(last)) => last.push_str(continuation),
+            _ => lines.push(raw.to_string()),
+        }
+    }
+    lines
+}
+
+fn unescape(value: &str) -> String {
+    value
+        .replace("\\n", " ")
+        .replace("\\N", " ")
+        .replace("\\,", ",")
+        .replace("\\;", ";")
+        .replace("\\\\", "\\")
+}
+
+fn parse_datetime(value: &str, local_offset_seconds: i64) -> Option<u64> {
+    let value = value.trim();
+    let (value, utc) = match value.strip_suffix(['Z', 'z']) {
+        Some(stripped) => (stripped, true),
+        None => (value, false),
+    };
+    let (date, time) = value.split_once(['T', 't'])?;
+    if date.len() != 8 || time.len() != 6 {
+        return None;
+    }
+
+    let number = |digits: &str| digits.parse::<i64>().ok();
+    let days = days_from_civil(
+        number(&date[..4])?,
+        number(&date[4..6])?,
+        number(&date[6..])?,
+    );
+    let seconds = number(&time[..2])? * 3_600 + number(&time[2..4])? * 60 + number(&time[4..])?;
+    let local = days * 86_400 + seconds;
+    let unix = if utc {
+        local
+    } else {
+        local - local_offset_seconds
+    };
+    u64::try_from(unix).ok()
+}
+
+fn parse_duration(value: &str) -> Option<u64> {
+    let value = value.trim();
+    if value.starts_with('-') {
+        return None;
+    }
+    let value = value.strip_prefix('+').unwrap_or(value).strip_prefix('P')?;
+
+    let mut total = 0u64;
+    let mut digits = String::new();
+    for ch in value.chars() {
+        if ch.is_ascii_digit() {
+            digits.push(ch);
+            continue;
+        }
+        let unit = match ch {
+            'W' => 7 * 86_400,
+            'D' => 86_400,
+            'H' => 3_600,
+            'M' => 60,
+            'S' => 1,
+            'T' => continue,
+            _ => return None,
+        };
+        total = total.saturating_add(digits.parse::<u64>().ok()?.saturating_mul(unit));
+        digits.clear();
+    }
+    Some(total)
+}
+
+// Days since 1970-01-01 for a proleptic Gregorian date.
+pub(crate) fn days_from_civil(year: i64, month: i64, day: i64) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let year_of_era = year - era * 400;
+    let shifted_month = (month + 9) % 12;
+    let day_of_year = (153 * shifted_month + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    era * 146_097 + day_of_era - 719_468
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLE: &str = "BEGIN:VCALENDAR\r\n\
+BEGIN:VEVENT\r\n\
+SUMMARY:Daily\\, standup\r\n\
+DTSTART:20240102T090000Z\r\n\
+DTEND:20240102T091500Z\r\n\
+END:VEVENT\r\n\
+BEGIN:VEVENT\r\n\
+SUMMARY:Planning\r\n\
+DTSTART;TZID=Europe/Madrid:20240102T110000\r\n\
+DURATION:PT1H30M\r\n\
+END:VEVENT\r\n\
+BEGIN:VEVENT\r\n\
+SUMMARY:Holiday\r\n\
+DTSTART;VALUE=DATE:20240103\r\n\
+DTEND;VALUE=DATE:20240104\r\n\
+END:VEVENT\r\n\
+BEGIN:VEVENT\r\n\
+SUMMARY:Focus\r\n\
+TRANSP:TRANSPARENT\r\n\
+DTSTART:20240102T130000Z\r\n\
+DTEND:20240102T140000Z\r\n\
+END:VEVENT\r\n\
+END:VCALENDAR\r\n";
+
+    #[test]
+    fn parses_timed_opaque_events() {
+        let windows = parse_ics(SAMPLE, 3_600);
+        assert_eq!(
+            windows,
+            vec![
+                BusyWindow {
+                    start: 1_704_186_000,
+                    end: 1_704_186_900,
+                    summary: "Daily, standup".into(),
+                },
+                BusyWindow {
+                    start: 1_704_189_600,
+                    end: 1_704_195_000,
+                    summary: "Planning".into(),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn finds_current_and_upcoming_windows() {
+        let windows = parse_ics(SAMPLE, 3_600);
+        assert_eq!(
+            busy_at(&windows, 1_704_186_300).map(|window| window.summary.as_str()),
+            Some("Daily, standup")
+        );
+        assert!(busy_at(&windows, 1_704_186_900).is_none());
+        assert_eq!(upcoming(&windows, 1_704_186_300, 600).len(), 1);
+        assert_eq!(upcoming(&windows, 1_704_186_300, 3_600).len(), 2);
+    }
+}
@@ -0,0 +1,203 @@
+use core::{cell::Cell, time::Duration};
+
+/// Source of the current time for shells driving a `TimerEngine`.
+///
+/// The engine never reads a clock itself: every call takes the time as
+/// seconds since the Unix epoch, so it runs the same on targets without
+/// `std::time`. A `Clock` is the one place a frontend decides where those
+/// seconds come from.
+pub trait Clock {
+    fn now_unix(&self) -> u64;
+
+    /// Time since an arbitrary origin that never jumps, for measuring how
+    /// long the user was active. Defaults to the wall clock, which NTP and
+    /// manual changes can move.
+    fn monotonic(&self) -> Duration {
+        Duration::from_secs(self.now_unix())
+    }
+}
+
+impl<F: Fn() -> u64> Clock for F {
+    fn now_unix(&self) -> u64 {
+        self()
+    }
+}
+
+/// Wall-clock time from the operating system.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now_unix(&self) -> u64 {
+        std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map_or(0, |elapsed| elapsed.as_secs())
+    }
+
+    fn monotonic(&self) -> Duration {
+        static ORIGIN: std::sync::OnceLock<std::time::Instant> = std::sync::OnceLock::new();
+        ORIGIN.get_or_init(std::time::Instant::now).elapsed()
+    }
+}
+
+/// A clock moved by hand, for tests, replays and simulated days.
+#[derive(Clone, Debug, Default)]
+pub struct ManualClock {
+    now: Cell<u64>,
+    elapsed: Cell<u64>,
+}
+
+impl ManualClock {
+    pub fn new(now_unix: u64) -> Self {
+        Self {
+            now: Cell::new(now_unix),
+            elapsed: Cell::new(0),
+        }
+    }
+
+    /// Moves the wall clock only, like an NTP correction.
+    pub fn set(&self, now_unix: u64) {
+        self.now.set(now_unix);
+    }
+
+    /// Lets time pass on both clocks.
+    pub fn advance(&self, seconds: u64) -> u64 {
+        self.elapsed.set(self.elapsed.get().saturating_add(seconds));
+        let now = self.now.get().saturating_add(seconds);
+        self.now.set(now);
+        now
+    }
+}
+
+impl Clock for ManualClock {
+    fn now_unix(&self) -> u64 {
+        self.now.get()
+    }
+
+    fn monotonic(&self) -> Duration {
+        Duration::from_secs(self.elapsed.get())
+    }
+}
+
+/// Turns monotonic clock readings into whole seconds elapsed between
+/// ticks. The fraction left over is carried to the next tick, so ticks
+/// that run late or early neither lose nor invent time; a single step is
+/// capped so a process stalled for long does not credit it all at once.
+#[derive(Clone, Debug)]
+pub struct ElapsedSeconds {
+    last: Option<Duration>,
+    max_step: Duration,
+}
+
+impl ElapsedSeconds {
+    pub fn new(max_step_seconds: u64) -> Self {
+        Self {
+            last: None,
+            max_step: Duration::from_secs(max_step_seconds),
+        }
+    }
+
+    /// Whole seconds since the previous reading; the first one returns 0.
+    pub fn advance(&mut self, monotonic: Duration) -> u64 {
+        let Some(last) = self.last else {
+            self.last = Some(monotonic);
+            return 0;
+        };
+        let elapsed = monotonic.saturating_sub(last);
+        if elapsed > self.max_step {
+            self.last = Some(monotonic);
+            return self.max_step.as_secs();
+        }
+        let seconds = elapsed.as_secs();
+        self.last = Some(last + Duration::from_secs(seconds));
+        seconds
+    }
+}
+
+/// `ElapsedSeconds` at millisecond resolution, for countdowns ticked
+/// several times a second.
+#[derive(Clone, Debug)]
+pub struct ElapsedMillis {
+    last: Option<Duration>,
+    max_step: Duration,
+}
+
+impl ElapsedMillis {
+    pub fn new(max_step_millis: u64) -> Self {
+        Self {
+            last: None,
+            max_step: Duration::from_millis(max_step_millis),
+        }
+    }
+
+    /// Whole milliseconds since the previous reading; the first one returns 0.
+    pub fn advance(&mut self, monotonic: Duration) -> u64 {
+        let Some(last) = self.last else {
+            self.last = Some(monotonic);
+            return 0;
+        };
+        let elapsed = monotonic.saturating_sub(last);
+        if elapsed > self.max_step {
+            self.last = Some(monotonic);
+            return self.max_step.as_millis() as u64;
+        }
+        let millis = elapsed.as_millis() as u64;
+        self.last = Some(last + Duration::from_millis(millis));
+        millis
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prelude::*;
+
+    #[test]
+    fn manual_clock_drives_the_engine() {
+        let clock = ManualClock::new(1_700_000_000);
+        let settings = Settings::default();
+        let interval = settings.micro.interval_seconds;
+        let mut engine = TimerEngine::new(settings, clock.now_unix());
+
+        let events: Vec<EngineEvent> = (0..interval)
+            .flat_map(|_| engine.on_activity(1, clock.advance(1)))
+            .collect();
+        assert_eq!(clock.now_unix(), 1_700_000_000 + interval);
+        assert!(events.contains(&EngineEvent::BreakDue(BreakKind::Micro)));
+
+        let fixed = || 42;
+        assert_eq!(fixed.now_unix(), 42);
+    }
+
+    #[test]
+    fn elapsed_seconds_ignore_wall_clock_jumps_and_carry_fractions() {
+        let clock = ManualClock::new(10_000);
+        let mut elapsed = ElapsedSeconds::new(60);
+        assert_eq!(elapsed.advance(clock.monotonic()), 0);
+
+        clock.advance(1);
+        clock.set(10_000 - 3_600);
+        assert_eq!(elapsed.advance(clock.monotonic()), 1);
+        clock.set(10_000 + 86_400);
+        assert_eq!(elapsed.advance(clock.monotonic()), 0);
+
+        let mut elapsed = ElapsedSeconds::new(60);
+        let readings = [0, 1_400, 1_900, 3_100, 4_000, 200_000];
+        let steps: Vec<u64> = readings
+            .iter()
+            .map(|millis| elapsed.advance(Duration::from_millis(*millis)))
+            .collect();
+        assert_eq!(steps, vec![0, 1, 0, 2, 1, 60]);
+    }
+
+    #[test]
+    fn elapsed_millis_carry_fractions_and_cap_steps() {
+        let mut elapsed = ElapsedMillis::new(5_000);
+        let readings = [0, 250_600, 499_300, 1_001_000, 60_000_000];
+        let steps: Vec<u64> = readings
+            .iter()
+            .map(|micros| elapsed.advance(Duration::from_micros(*micros)))
+            .collect();
+        assert_eq!(steps, vec![0, 250, 249, 502, 5_000]);
+    }
+}
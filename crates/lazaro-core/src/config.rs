@@ -1,9 +1,17 @@
+use std::{collections::BTreeMap, fmt};
+
 #[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct BreakTimerSettings {
     pub interval_seconds: u64,
     pub duration_seconds: u64,
     pub snooze_seconds: u64,
     pub enabled: bool,
+    pub jitter_percent: u8,
+    /// `None` follows the global `Settings::block_level`.
+    pub block_level: Option<BlockLevel>,
+    /// Timer ids whose counters restart when this break completes.
+    pub resets: Vec<String>,
 }
 
 impl BreakTimerSettings {
@@ -13,17 +21,173 @@ impl BreakTimerSettings {
             duration_seconds,
             snooze_seconds,
             enabled: true,
+            jitter_percent: 0,
+            block_level: None,
+            resets: Vec::new(),
         }
     }
 }
 
+pub const MICRO_TIMER_ID: &str = "micro";
+pub const REST_TIMER_ID: &str = "rest";
+pub const MAX_CUSTOM_TIMERS: usize = 16;
+pub const MAX_JITTER_PERCENT: u8 = 50;
+/// How long a medium break shows before it can be ended early.
+pub const MEDIUM_SKIP_DELAY_SECONDS: u64 = 10;
+
+/// A user-defined interval timer that runs alongside micro and rest breaks.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CustomBreakTimer {
+    pub id: String,
+    pub name: String,
+    pub timer: BreakTimerSettings,
+    pub priority: u8,
+}
+
+/// Bounds for adaptive scheduling, as a percentage of each timer's
+/// configured interval.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct AdaptiveSettings {
+    pub enabled: bool,
+    pub min_interval_percent: u8,
+    pub max_interval_percent: u8,
+}
+
+/// Keyboard and mouse intensity sampling. Only counts per minute are ever
+/// kept, never which keys were pressed.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct IntensitySettings {
+    pub enabled: bool,
+    /// Shorten the micro interval while input is heavy.
+    pub scale_micro: bool,
+    /// Key presses and clicks per minute from which input counts as heavy.
+    pub heavy_events_per_minute: u32,
+    /// Cut to the micro interval at twice the heavy rate; less in between.
+    pub max_reduction_percent: u8,
+}
+
+/// Pressure added to a due break that is neither started nor snoozed.
+/// Either stage is skipped when its delay is 0.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct EscalationSettings {
+    pub enabled: bool,
+    pub renotify_after_seconds: u64,
+    /// Starts the break whatever its block level.
+    pub force_after_seconds: u64,
+}
+
+/// Active time allowed per week, counted from `AnalyticsSettings::week_start`
+/// and the daily reset time. Reaching it only warns.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct WeeklyLimitSettings {
+    pub enabled: bool,
+    pub limit_seconds: u64,
+    /// Percentages of `limit_seconds` that raise a warning once crossed,
+    /// kept sorted.
+    pub warning_thresholds: Vec<u8>,
+}
+
+pub const MAX_APP_LIMITS: usize = 32;
+
+/// Daily budget for one application, matched on its window class.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct AppLimit {
+    pub class: String,
+    pub limit_seconds: u64,
+}
+
+/// Daily budgets for single applications, measured on the foreground
+/// window the same way as app usage.
 #[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct AppLimitSettings {
+    pub enabled: bool,
+    pub limits: Vec<AppLimit>,
+    /// What happens once a budget is spent: `Soft` only tells, `Medium`
+    /// minimizes the application whenever it comes to the front and
+    /// `Strict` applies `blocking.app_action` to it for the rest of the day.
+    pub block_level: BlockLevel,
+}
+
+impl AppLimitSettings {
+    pub fn limit_for(&self, class: &str) -> Option<&AppLimit> {
+        self.limits
+            .iter()
+            .find(|limit| limit.class.eq_ignore_ascii_case(class))
+    }
+}
+
+/// `class=minutes` pairs, as in `firefox=60,slack=30`.
+pub fn parse_app_limits(value: &str) -> Option<Vec<AppLimit>> {
+    let mut limits: Vec<AppLimit> = Vec::new();
+    for entry in comma_list(value) {
+        let (class, minutes) = entry.split_once('=')?;
+        let class = class.trim();
+        let minutes: u64 = minutes.trim().parse().ok()?;
+        if class.is_empty()
+            || minutes == 0
+            || limits
+                .iter()
+                .any(|limit| limit.class.eq_ignore_ascii_case(class))
+        {
+            return None;
+        }
+        limits.push(AppLimit {
+            class: class.to_string(),
+            limit_seconds: minutes.saturating_mul(60),
+        });
+    }
+    (limits.len() <= MAX_APP_LIMITS).then_some(limits)
+}
+
+pub fn join_app_limits(limits: &[AppLimit]) -> String {
+    limits
+        .iter()
+        .map(|limit| format!("{}={}", limit.class, limit.limit_seconds / 60))
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+/// Whether the daily and weekly limits follow the active profile.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum LimitScope {
+    /// Switching profiles keeps the limits that were in force.
+    Global,
+    Profile,
+}
+
+/// Enforcement run once the daily limit is due and its snoozes are used up.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum DailyLimitAction {
+    None,
+    LockSession,
+    Suspend,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct DailyLimitSettings {
     pub limit_seconds: u64,
     pub snooze_seconds: u64,
     pub reset_hour_local: u8,
     pub reset_minute_local: u8,
     pub enabled: bool,
+    /// Snoozes allowed per day before `action` applies.
+    pub max_snoozes: u32,
+    pub action: DailyLimitAction,
+    /// Whether completing the daily limit break restarts every interval timer.
+    pub resets_timers: bool,
+    /// Percentages of `limit_seconds` that raise a warning once crossed,
+    /// kept sorted.
+    pub warning_thresholds: Vec<u8>,
 }
 
 impl DailyLimitSettings {
@@ -32,6 +196,7 @@ impl DailyLimitSettings {
     }
 }
 
+/// Serialized as `as_str`, and read back case-insensitively.
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum BlockLevel {
     Soft,
@@ -39,28 +204,750 @@ pub enum BlockLevel {
     Strict,
 }
 
+impl BlockLevel {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.to_ascii_lowercase().as_str() {
+            "soft" => Some(Self::Soft),
+            "medium" => Some(Self::Medium),
+            "strict" => Some(Self::Strict),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Soft => "soft",
+            Self::Medium => "medium",
+            Self::Strict => "strict",
+        }
+    }
+
+    /// Seconds into a break before it may be ended early, or `None` when it
+    /// has to run its full length.
+    pub fn skip_delay_seconds(self) -> Option<u64> {
+        match self {
+            Self::Soft => Some(0),
+            Self::Medium => Some(MEDIUM_SKIP_DELAY_SECONDS),
+            Self::Strict => None,
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for BlockLevel {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for BlockLevel {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let value = <String as serde::Deserialize>::deserialize(deserializer)?;
+        Self::parse(&value)
+            .ok_or_else(|| serde::de::Error::custom(format!("unknown block level `{value}`")))
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct DimmingSettings {
+    pub soft: bool,
+    pub medium: bool,
+    pub strict: bool,
+    pub warning_seconds: u64,
+    pub max_opacity_percent: u8,
+}
+
+impl DimmingSettings {
+    pub fn enabled_for(&self, level: BlockLevel) -> bool {
+        match level {
+            BlockLevel::Soft => self.soft,
+            BlockLevel::Medium => self.medium,
+            BlockLevel::Strict => self.strict,
+        }
+    }
+}
+
 #[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct NotificationSettings {
     pub desktop_enabled: bool,
     pub overlay_enabled: bool,
     pub sound_enabled: bool,
     pub sound_theme: String,
+    pub countdown_cue_seconds: u64,
+    pub respect_system_dnd: bool,
+    pub defer_breaks_on_dnd: bool,
+    pub daily_summary_enabled: bool,
+    /// Small always-on-top window with the next break ETA.
+    pub countdown_widget_enabled: bool,
+    /// Remaining break shown as a progress bar on the taskbar entry.
+    pub taskbar_progress_enabled: bool,
+    /// User wording for notifications and event messages, replacing the
+    /// built-in text of the same message key in every language.
+    pub message_templates: Vec<MessageTemplate>,
+}
+
+pub const MAX_MESSAGE_TEMPLATES: usize = 64;
+pub const MAX_MESSAGE_TEMPLATE_CHARS: usize = 240;
+
+/// Text for one message key, e.g. `break.due` = `Time for a {kind} break`.
+/// Placeholders are filled in by whoever renders the message.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct MessageTemplate {
+    pub key: String,
+    pub text: String,
+}
+
+/// One `key=text` pair per line; blank lines are skipped. Keys are
+/// not checked against any catalog here.
+pub fn parse_message_templates(value: &str) -> Option<Vec<MessageTemplate>> {
+    let mut templates: Vec<MessageTemplate> = Vec::new();
+    for line in value.lines().map(str::trim).filter(|line| !line.is_empty()) {
+        let (key, text) = line.split_once('=')?;
+        let (key, text) = (key.trim(), text.trim());
+        if key.is_empty()
+            || key.contains(char::is_whitespace)
+            || text.is_empty()
+            || text.chars().count() > MAX_MESSAGE_TEMPLATE_CHARS
+            || templates.iter().any(|template| template.key == key)
+        {
+            return None;
+        }
+        templates.push(MessageTemplate {
+            key: key.to_string(),
+            text: text.to_string(),
+        });
+    }
+    (templates.len() <= MAX_MESSAGE_TEMPLATES).then_some(templates)
+}
+
+pub fn join_message_templates(templates: &[MessageTemplate]) -> String {
+    templates
+        .iter()
+        .map(|template| format!("{}={}", template.key, template.text))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+impl NotificationSettings {
+    pub fn template(&self, key: &str) -> Option<&str> {
+        self.message_templates
+            .iter()
+            .find(|template| template.key == key)
+            .map(|template| template.text.as_str())
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct AccessibilitySettings {
+    pub high_contrast: bool,
+    pub reduce_motion: bool,
+    pub large_countdown: bool,
+    pub screen_reader_announcements: bool,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct InhibitSettings {
+    pub on_fullscreen: bool,
+    pub on_media: bool,
+    /// Enter presentation mode while the screen is being captured.
+    pub presentation_on_screencast: bool,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum AppBlockAction {
+    /// Minimize matching windows whenever they reappear.
+    Minimize,
+    Kill,
 }
 
+/// Enforcement during strict breaks, other than micro breaks.
 #[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct BlockingSettings {
+    pub enabled: bool,
+    /// Comma-separated window classes, also matched against process names.
+    pub app_classes: String,
+    pub app_action: AppBlockAction,
+    /// Comma-separated host names.
+    pub hosts: String,
+    /// Hosts-format file read by the local resolver (e.g. a dnsmasq
+    /// `addn-hosts` entry), filled at the start of a break and emptied after.
+    /// Only a marked block is edited; see [`crate::hosts`].
+    pub hosts_file_path: String,
+}
+
+impl BlockingSettings {
+    pub fn app_class_list(&self) -> impl Iterator<Item = &str> {
+        comma_list(&self.app_classes)
+    }
+
+    pub fn host_list(&self) -> impl Iterator<Item = &str> {
+        comma_list(&self.hosts)
+    }
+}
+
+fn join_percents(percents: &[u8]) -> String {
+    percents
+        .iter()
+        .map(u8::to_string)
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+fn comma_list(value: &str) -> impl Iterator<Item = &str> {
+    value
+        .split(',')
+        .map(str::trim)
+        .filter(|entry| !entry.is_empty())
+}
+
+pub fn is_host_name(value: &str) -> bool {
+    value.len() <= 253
+        && value.split('.').all(|label| {
+            !label.is_empty()
+                && label.len() <= 63
+                && label
+                    .bytes()
+                    .all(|byte| byte.is_ascii_alphanumeric() || byte == b'-')
+        })
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct KeyBindingSettings {
+    pub media_keys_enabled: bool,
+    /// Comma-separated key names, as reported by keyboard events or XF86 keysyms.
+    pub start_keys: String,
+    pub snooze_keys: String,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct IntegrationSettings {
+    pub slack_token: String,
+    pub teams_token: String,
+    /// Status shown while a rest break runs; supports `{kind}`, `{minutes}`
+    /// and `{until}`. Empty uses the translated default.
+    pub status_template: String,
+    pub status_emoji: String,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct RemoteSettings {
+    pub enabled: bool,
+    /// Always bound on 127.0.0.1.
+    pub port: u16,
+    pub token: String,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PrivacySettings {
+    /// Seals the persisted state with a key kept in the system keyring.
+    pub encrypt_state: bool,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum ThemeMode {
+    Dark,
+    Light,
+    /// Uses `background_color` and `text_color`.
+    Custom,
+}
+
+/// Themed sets of suggestions the break overlay rotates through.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum ActivityPack {
+    Eyes,
+    Stretch,
+    Movement,
+    Breathing,
+}
+
+impl ActivityPack {
+    pub const ALL: [Self; 4] = [Self::Eyes, Self::Stretch, Self::Movement, Self::Breathing];
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.to_ascii_lowercase().as_str() {
+            "eyes" => Some(Self::Eyes),
+            "stretch" => Some(Self::Stretch),
+            "movement" => Some(Self::Movement),
+            "breathing" => Some(Self::Breathing),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Eyes => "eyes",
+            Self::Stretch => "stretch",
+            Self::Movement => "movement",
+            Self::Breathing => "breathing",
+        }
+    }
+}
+
+/// What an application is used for, in app usage reports.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum AppCategory {
+    Work,
+    Comms,
+    Entertainment,
+}
+
+impl AppCategory {
+    pub const ALL: [Self; 3] = [Self::Work, Self::Comms, Self::Entertainment];
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.to_ascii_lowercase().as_str() {
+            "work" => Some(Self::Work),
+            "comms" => Some(Self::Comms),
+            "entertainment" => Some(Self::Entertainment),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Work => "work",
+            Self::Comms => "comms",
+            Self::Entertainment => "entertainment",
+        }
+    }
+
+    /// Whether time in it counts as productive rather than leisure.
+    pub fn is_productive(self) -> bool {
+        !matches!(self, Self::Entertainment)
+    }
+}
+
+/// Puts applications with window class `class` in `category`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct AppCategoryRule {
+    pub class: String,
+    pub category: AppCategory,
+}
+
+/// `class=category` pairs, as in `code=work,slack=comms`.
+pub fn parse_app_categories(value: &str) -> Option<Vec<AppCategoryRule>> {
+    let mut rules: Vec<AppCategoryRule> = Vec::new();
+    for entry in comma_list(value) {
+        let (class, category) = entry.split_once('=')?;
+        let class = class.trim();
+        let category = AppCategory::parse(category.trim())?;
+        if class.is_empty()
+            || rules
+                .iter()
+                .any(|rule| rule.class.eq_ignore_ascii_case(class))
+        {
+            return None;
+        }
+        rules.push(AppCategoryRule {
+            class: class.to_string(),
+            category,
+        });
+    }
+    Some(rules)
+}
+
+pub fn join_app_categories(rules: &[AppCategoryRule]) -> String {
+    rules
+        .iter()
+        .map(|rule| format!("{}={}", rule.class, rule.category.as_str()))
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+pub const MIN_FONT_SCALE_PERCENT: u16 = 75;
+pub const MAX_FONT_SCALE_PERCENT: u16 = 200;
+
+pub const MIN_BREAK_TICK_MILLIS: u64 = 50;
+pub const MAX_BREAK_TICK_MILLIS: u64 = 1000;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ThemeSettings {
+    pub mode: ThemeMode,
+    /// Colors are `#rrggbb`.
+    pub accent_color: String,
+    pub background_color: String,
+    pub text_color: String,
+    /// Image shown behind the break overlay; empty for none.
+    pub background_image_path: String,
+    pub font_scale_percent: u16,
+    /// Never empty; in the order the overlay rotates through them.
+    pub activity_packs: Vec<ActivityPack>,
+}
+
+/// Parses a local `HH:MM` reset time.
+pub fn parse_reset_time(value: &str) -> Option<(u8, u8)> {
+    let (hour, minute) = value.split_once(':')?;
+    let hour = hour.parse::<u8>().ok()?;
+    let minute = minute.parse::<u8>().ok()?;
+    (hour <= 23 && minute <= 59).then_some((hour, minute))
+}
+
+pub fn is_hex_color(value: &str) -> bool {
+    value
+        .strip_prefix('#')
+        .is_some_and(|hex| hex.len() == 6 && hex.bytes().all(|byte| byte.is_ascii_hexdigit()))
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PeerSettings {
+    pub enabled: bool,
+    pub port: u16,
+    /// Shared secret every instance on the LAN must be configured with.
+    pub secret: String,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum SyncBackend {
+    Off,
+    /// A folder shared by Syncthing, Nextcloud or similar.
+    Directory,
+    WebDav,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SyncSettings {
+    pub backend: SyncBackend,
+    pub directory: String,
+    pub webdav_url: String,
+    pub webdav_username: String,
+    pub webdav_password: String,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum MeetingPolicy {
+    Defer,
+    Silent,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CalendarSettings {
+    pub enabled: bool,
+    /// ICS file or directory of `.ics` files (khal vdirs, Evolution caches).
+    pub source_path: String,
+    pub meeting_policy: MeetingPolicy,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum WeekStart {
+    Monday,
+    Sunday,
+}
+
+/// Serialized as `as_str`, e.g. `"sat"`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Weekday {
+    Monday,
+    Tuesday,
+    Wednesday,
+    Thursday,
+    Friday,
+    Saturday,
+    Sunday,
+}
+
+impl Weekday {
+    pub const ALL: [Self; 7] = [
+        Self::Monday,
+        Self::Tuesday,
+        Self::Wednesday,
+        Self::Thursday,
+        Self::Friday,
+        Self::Saturday,
+        Self::Sunday,
+    ];
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Monday => "mon",
+            Self::Tuesday => "tue",
+            Self::Wednesday => "wed",
+            Self::Thursday => "thu",
+            Self::Friday => "fri",
+            Self::Saturday => "sat",
+            Self::Sunday => "sun",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|day| day.as_str().eq_ignore_ascii_case(value))
+    }
+
+    /// Day of the week of a day counted from the Unix epoch, a Thursday.
+    pub fn from_day_index(day_index: i64) -> Self {
+        Self::ALL[(day_index + 3).rem_euclid(7) as usize]
+    }
+}
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for Weekday {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for Weekday {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let value = <String as serde::Deserialize>::deserialize(deserializer)?;
+        Self::parse(&value)
+            .ok_or_else(|| serde::de::Error::custom(format!("unknown weekday `{value}`")))
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct AnalyticsSettings {
+    pub week_start: WeekStart,
+    pub weekly_max_active_seconds: u64,
+    pub weekly_min_breaks: u32,
+    /// Pauses longer than this split activity into separate focus sessions.
+    pub focus_gap_seconds: u64,
+    /// Ask for a 1-5 rating after each completed break.
+    pub break_feedback_prompt: bool,
+    /// Celebrate break runs and day streaks with an event and a sound.
+    pub milestone_celebrations: bool,
+    /// Record active time per application, by window class.
+    pub app_usage_enabled: bool,
+    /// Also record window titles, which can be sensitive.
+    pub app_usage_titles: bool,
+    /// Category per window class, for the productive vs leisure split.
+    pub app_categories: Vec<AppCategoryRule>,
+}
+
+impl AnalyticsSettings {
+    pub fn category_of(&self, class: &str) -> Option<AppCategory> {
+        self.app_categories
+            .iter()
+            .find(|rule| rule.class.eq_ignore_ascii_case(class))
+            .map(|rule| rule.category)
+    }
+}
+
+/// Tips drawn from usage trends; see [`crate::nudge`]. Each rule can be
+/// turned off on its own.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct NudgeSettings {
+    pub enabled: bool,
+    /// Also show nudges as desktop notifications, not only as events.
+    pub notify: bool,
+    pub skip_rate: bool,
+    pub long_focus: bool,
+    pub late_night: bool,
+    /// Least time between two nudges of any rule.
+    pub interval_seconds: u64,
+}
+
+/// An evening window with shorter intervals, ending at a hard cutoff after
+/// which the day's work should stop. Times are local and, like the daily
+/// reset, may wrap past midnight.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct WindDownSettings {
+    pub enabled: bool,
+    pub start_hour_local: u8,
+    pub start_minute_local: u8,
+    pub cutoff_hour_local: u8,
+    pub cutoff_minute_local: u8,
+    /// Share of every interval left while winding down.
+    pub interval_percent: u8,
+    /// Active time allowed inside the window, apart from the daily limit;
+    /// 0 for none.
+    pub budget_seconds: u64,
+}
+
+impl WindDownSettings {
+    pub fn start_offset_seconds(&self) -> u64 {
+        u64::from(self.start_hour_local) * 3600 + u64::from(self.start_minute_local) * 60
+    }
+
+    pub fn cutoff_offset_seconds(&self) -> u64 {
+        u64::from(self.cutoff_hour_local) * 3600 + u64::from(self.cutoff_minute_local) * 60
+    }
+}
+
+pub const MAX_DAY_OVERRIDES: usize = 32;
+
+/// Keys a day override may not change: they decide which day it is.
+const FIXED_DAY_KEYS: &[&str] = &["day_overrides", "daily_reset_time", "week_start"];
+
+/// A setting that takes another value on some days of the week, such as
+/// no daily limit on Saturdays.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct DayOverride {
+    pub days: Vec<Weekday>,
+    pub key: String,
+    pub value: String,
+}
+
+/// One `days key=value` override per line, as in
+/// `sat,sun daily_limit_enabled=false`; blank lines are skipped. Each value
+/// must be one `apply_setting` accepts.
+pub fn parse_day_overrides(value: &str) -> Option<Vec<DayOverride>> {
+    let mut overrides = Vec::new();
+    for line in value.lines().map(str::trim).filter(|line| !line.is_empty()) {
+        let (days, setting) = line.split_once(char::is_whitespace)?;
+        let (key, value) = setting.split_once('=')?;
+        let (key, value) = (key.trim(), value.trim());
+        let days = comma_list(days)
+            .map(Weekday::parse)
+            .collect::<Option<Vec<_>>>()?;
+        if days.is_empty()
+            || FIXED_DAY_KEYS.contains(&key)
+            || apply_setting(&mut Settings::default(), key, value).is_err()
+        {
+            return None;
+        }
+        overrides.push(DayOverride {
+            days,
+            key: key.to_string(),
+            value: value.to_string(),
+        });
+    }
+    (overrides.len() <= MAX_DAY_OVERRIDES).then_some(overrides)
+}
+
+pub fn join_day_overrides(overrides: &[DayOverride]) -> String {
+    overrides
+        .iter()
+        .map(|day_override| {
+            let days = day_override
+                .days
+                .iter()
+                .map(|day| day.as_str())
+                .collect::<Vec<_>>()
+                .join(",");
+            format!("{days} {}={}", day_override.key, day_override.value)
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// What the runtime gives up while the system is saving power.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum PowerSaverBehavior {
+    Ignore,
+    /// Mute sounds and emit status events less often.
+    Quiet,
+    /// As `Quiet`, and also poll DND, calendar and peers less often.
+    QuietAndSlow,
+}
+
+/// What happens to a running counter when its timer's interval is edited.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum IntervalChangePolicy {
+    /// Keep the share of the interval already done.
+    Rescale,
+    /// Keep the elapsed seconds; a shorter interval may make a break due at once.
+    Preserve,
+    Reset,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct RuntimeSettings {
+    pub tick_emit_interval_seconds: u64,
+    /// How often a running break's countdown advances. Activity is still
+    /// counted once a second.
+    pub break_tick_millis: u64,
+    pub log_level: String,
+    pub power_saver_behavior: PowerSaverBehavior,
+    pub interval_change_policy: IntervalChangePolicy,
+    /// Start the engine again from its last snapshot if it panics.
+    pub restart_on_crash: bool,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum UpdateChannel {
+    Stable,
+    /// Also offers pre-releases.
+    Beta,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct UpdateSettings {
+    pub check_enabled: bool,
+    pub channel: UpdateChannel,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct StartupSettings {
     pub xdg_autostart_enabled: bool,
     pub systemd_user_enabled: bool,
+    /// No interval break becomes due this soon after the engine starts.
+    pub warmup_grace_seconds: u64,
+    /// Time away before a restart counts as a break for every timer whose
+    /// duration it covers.
+    pub idle_counts_as_rest: bool,
 }
 
 #[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Settings {
     pub micro: BreakTimerSettings,
     pub rest: BreakTimerSettings,
+    pub custom_timers: Vec<CustomBreakTimer>,
+    pub adaptive: AdaptiveSettings,
+    pub intensity: IntensitySettings,
+    pub escalation: EscalationSettings,
     pub daily_limit: DailyLimitSettings,
+    pub weekly_limit: WeeklyLimitSettings,
+    pub app_limits: AppLimitSettings,
+    pub limit_scope: LimitScope,
     pub block_level: BlockLevel,
+    pub dimming: DimmingSettings,
     pub notifications: NotificationSettings,
+    pub inhibit: InhibitSettings,
+    pub blocking: BlockingSettings,
+    pub calendar: CalendarSettings,
+    pub keys: KeyBindingSettings,
+    pub remote: RemoteSettings,
+    pub integrations: IntegrationSettings,
+    pub sync: SyncSettings,
+    pub peers: PeerSettings,
+    pub privacy: PrivacySettings,
+    pub accessibility: AccessibilitySettings,
+    pub theme: ThemeSettings,
+    pub language: String,
+    pub analytics: AnalyticsSettings,
+    pub nudges: NudgeSettings,
+    pub wind_down: WindDownSettings,
+    /// Applied in order on top of the rest for the days they list.
+    pub day_overrides: Vec<DayOverride>,
+    pub runtime: RuntimeSettings,
     pub startup: StartupSettings,
+    pub updates: UpdateSettings,
     pub active_profile_id: String,
 }
 
@@ -68,26 +955,1347 @@ impl Default for Settings {
     fn default() -> Self {
         Self {
             micro: BreakTimerSettings::new(180, 20, 150),
-            rest: BreakTimerSettings::new(2700, 300, 180),
+            rest: BreakTimerSettings {
+                resets: vec![MICRO_TIMER_ID.to_string()],
+                ..BreakTimerSettings::new(2700, 300, 180)
+            },
+            custom_timers: Vec::new(),
+            adaptive: AdaptiveSettings {
+                enabled: false,
+                min_interval_percent: 60,
+                max_interval_percent: 100,
+            },
+            intensity: IntensitySettings {
+                enabled: false,
+                scale_micro: false,
+                heavy_events_per_minute: 200,
+                max_reduction_percent: 40,
+            },
+            escalation: EscalationSettings {
+                enabled: false,
+                renotify_after_seconds: 300,
+                force_after_seconds: 900,
+            },
             daily_limit: DailyLimitSettings {
                 limit_seconds: 14_400,
                 snooze_seconds: 1_200,
                 reset_hour_local: 4,
                 reset_minute_local: 0,
                 enabled: true,
+                max_snoozes: 2,
+                action: DailyLimitAction::None,
+                resets_timers: true,
+                warning_thresholds: vec![75, 90],
+            },
+            weekly_limit: WeeklyLimitSettings {
+                enabled: false,
+                limit_seconds: 144_000,
+                warning_thresholds: vec![75, 90],
             },
+            app_limits: AppLimitSettings {
+                enabled: false,
+                limits: Vec::new(),
+                block_level: BlockLevel::Soft,
+            },
+            limit_scope: LimitScope::Global,
             block_level: BlockLevel::Medium,
+            dimming: DimmingSettings {
+                soft: false,
+                medium: false,
+                strict: true,
+                warning_seconds: 30,
+                max_opacity_percent: 70,
+            },
             notifications: NotificationSettings {
                 desktop_enabled: true,
                 overlay_enabled: true,
                 sound_enabled: true,
                 sound_theme: "default".to_string(),
+                countdown_cue_seconds: 5,
+                respect_system_dnd: true,
+                defer_breaks_on_dnd: false,
+                daily_summary_enabled: true,
+                countdown_widget_enabled: false,
+                taskbar_progress_enabled: true,
+                message_templates: Vec::new(),
+            },
+            inhibit: InhibitSettings {
+                on_fullscreen: false,
+                on_media: false,
+                presentation_on_screencast: false,
+            },
+            blocking: BlockingSettings {
+                enabled: false,
+                app_classes: String::new(),
+                app_action: AppBlockAction::Minimize,
+                hosts: String::new(),
+                hosts_file_path: String::new(),
+            },
+            calendar: CalendarSettings {
+                enabled: false,
+                source_path: String::new(),
+                meeting_policy: MeetingPolicy::Defer,
+            },
+            keys: KeyBindingSettings {
+                media_keys_enabled: true,
+                start_keys: "MediaPlayPause,MediaPlay".to_string(),
+                snooze_keys: "Pause,MediaPause".to_string(),
+            },
+            remote: RemoteSettings {
+                enabled: false,
+                port: 7_878,
+                token: String::new(),
+            },
+            integrations: IntegrationSettings {
+                slack_token: String::new(),
+                teams_token: String::new(),
+                status_template: String::new(),
+                status_emoji: ":person_in_lotus_position:".to_string(),
+            },
+            sync: SyncSettings {
+                backend: SyncBackend::Off,
+                directory: String::new(),
+                webdav_url: String::new(),
+                webdav_username: String::new(),
+                webdav_password: String::new(),
+            },
+            peers: PeerSettings {
+                enabled: false,
+                port: 7_879,
+                secret: String::new(),
+            },
+            privacy: PrivacySettings {
+                encrypt_state: false,
+            },
+            accessibility: AccessibilitySettings {
+                high_contrast: false,
+                reduce_motion: false,
+                large_countdown: false,
+                screen_reader_announcements: true,
+            },
+            theme: ThemeSettings {
+                mode: ThemeMode::Dark,
+                accent_color: "#f4b942".to_string(),
+                background_color: "#050c14".to_string(),
+                text_color: "#f7fbff".to_string(),
+                background_image_path: String::new(),
+                font_scale_percent: 100,
+                activity_packs: ActivityPack::ALL.to_vec(),
+            },
+            language: "system".to_string(),
+            analytics: AnalyticsSettings {
+                week_start: WeekStart::Monday,
+                weekly_max_active_seconds: 0,
+                weekly_min_breaks: 0,
+                focus_gap_seconds: 120,
+                break_feedback_prompt: false,
+                milestone_celebrations: true,
+                app_usage_enabled: false,
+                app_usage_titles: false,
+                app_categories: Vec::new(),
+            },
+            nudges: NudgeSettings {
+                enabled: true,
+                notify: false,
+                skip_rate: true,
+                long_focus: true,
+                late_night: true,
+                interval_seconds: 3_600,
+            },
+            wind_down: WindDownSettings {
+                enabled: false,
+                start_hour_local: 21,
+                start_minute_local: 0,
+                cutoff_hour_local: 23,
+                cutoff_minute_local: 0,
+                interval_percent: 75,
+                budget_seconds: 0,
+            },
+            day_overrides: Vec::new(),
+            runtime: RuntimeSettings {
+                tick_emit_interval_seconds: 1,
+                break_tick_millis: 250,
+                log_level: "info".into(),
+                power_saver_behavior: PowerSaverBehavior::Quiet,
+                interval_change_policy: IntervalChangePolicy::Rescale,
+                restart_on_crash: true,
             },
             startup: StartupSettings {
                 xdg_autostart_enabled: true,
                 systemd_user_enabled: false,
+                warmup_grace_seconds: 0,
+                idle_counts_as_rest: true,
+            },
+            updates: UpdateSettings {
+                check_enabled: true,
+                channel: UpdateChannel::Stable,
             },
             active_profile_id: "default".to_string(),
         }
     }
 }
+
+pub const ENV_PREFIX: &str = "LAZARO_";
+
+pub const SETTING_KEYS: &[&str] = &[
+    "micro_interval_seconds",
+    "micro_duration_seconds",
+    "micro_snooze_seconds",
+    "micro_enabled",
+    "micro_jitter_percent",
+    "micro_block_level",
+    "micro_resets",
+    "rest_interval_seconds",
+    "rest_duration_seconds",
+    "rest_snooze_seconds",
+    "rest_enabled",
+    "rest_jitter_percent",
+    "rest_block_level",
+    "rest_resets",
+    "adaptive_enabled",
+    "adaptive_min_interval_percent",
+    "adaptive_max_interval_percent",
+    "intensity_enabled",
+    "intensity_scale_micro",
+    "intensity_heavy_events_per_minute",
+    "intensity_max_reduction_percent",
+    "escalation_enabled",
+    "escalation_renotify_seconds",
+    "escalation_force_seconds",
+    "daily_limit_seconds",
+    "daily_limit_snooze_seconds",
+    "daily_limit_enabled",
+    "daily_reset_time",
+    "daily_limit_max_snoozes",
+    "daily_limit_action",
+    "daily_limit_resets_timers",
+    "daily_limit_warning_thresholds",
+    "weekly_limit_enabled",
+    "weekly_limit_seconds",
+    "weekly_limit_warning_thresholds",
+    "app_limits_enabled",
+    "app_limits",
+    "app_limit_block_level",
+    "limit_scope",
+    "block_level",
+    "fade_on_soft",
+    "fade_on_medium",
+    "fade_on_strict",
+    "fade_warning_seconds",
+    "fade_max_opacity_percent",
+    "desktop_notifications",
+    "overlay_notifications",
+    "sound_notifications",
+    "sound_theme",
+    "countdown_cue_seconds",
+    "respect_system_dnd",
+    "defer_breaks_on_dnd",
+    "daily_summary_notifications",
+    "countdown_widget",
+    "taskbar_progress",
+    "message_templates",
+    "inhibit_on_fullscreen",
+    "inhibit_on_media",
+    "presentation_on_screencast",
+    "blocking_enabled",
+    "blocking_app_classes",
+    "blocking_app_action",
+    "blocking_hosts",
+    "blocking_hosts_file",
+    "calendar_enabled",
+    "calendar_source_path",
+    "calendar_meeting_policy",
+    "media_keys_enabled",
+    "media_start_keys",
+    "media_snooze_keys",
+    "remote_enabled",
+    "remote_port",
+    "remote_token",
+    "slack_token",
+    "teams_token",
+    "break_status_template",
+    "break_status_emoji",
+    "sync_backend",
+    "sync_directory",
+    "sync_webdav_url",
+    "sync_webdav_username",
+    "sync_webdav_password",
+    "peer_sharing_enabled",
+    "peer_port",
+    "peer_secret",
+    "encrypt_state",
+    "high_contrast_overlay",
+    "reduce_motion",
+    "large_countdown",
+    "screen_reader_announcements",
+    "theme_mode",
+    "theme_accent_color",
+    "theme_background_color",
+    "theme_text_color",
+    "theme_background_image",
+    "theme_font_scale_percent",
+    "activity_packs",
+    "language",
+    "week_start",
+    "weekly_max_active_seconds",
+    "weekly_min_breaks",
+    "focus_gap_seconds",
+    "break_feedback_prompt",
+    "milestone_celebrations",
+    "app_usage_enabled",
+    "app_usage_titles",
+    "app_categories",
+    "nudges_enabled",
+    "nudge_notifications",
+    "nudge_skip_rate",
+    "nudge_long_focus",
+    "nudge_late_night",
+    "nudge_interval_seconds",
+    "wind_down_enabled",
+    "wind_down_start",
+    "wind_down_cutoff",
+    "wind_down_interval_percent",
+    "wind_down_budget_seconds",
+    "day_overrides",
+    "tick_emit_interval_seconds",
+    "break_tick_millis",
+    "log_level",
+    "power_saver_behavior",
+    "interval_change_policy",
+    "restart_runtime_on_crash",
+    "warmup_grace_seconds",
+    "idle_counts_as_rest",
+    "update_check_enabled",
+    "update_channel",
+];
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ConfigError {
+    UnknownKey(String),
+    InvalidValue { key: String, value: String },
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConfigError::UnknownKey(key) => write!(f, "unknown setting: {key}"),
+            ConfigError::InvalidValue { key, value } => {
+                write!(f, "invalid value for {key}: {value}")
+            }
+        }
+    }
+}
+
+impl std::error::Error for ConfigError {}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum ConfigSource {
+    File,
+    Env,
+    Cli,
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct LayeredConfig {
+    entries: Vec<(ConfigSource, String, String)>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ResolvedSettings {
+    pub settings: Settings,
+    pub sources: BTreeMap<String, ConfigSource>,
+    pub errors: Vec<ConfigError>,
+}
+
+impl LayeredConfig {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_file<I, K, V>(mut self, entries: I) -> Self
+    where
+        I: IntoIterator<Item = (K, V)>,
+        K: Into<String>,
+        V: Into<String>,
+    {
+        for (key, value) in entries {
+            self.entries
+                .push((ConfigSource::File, key.into(), value.into()));
+        }
+        self
+    }
+
+    pub fn with_env<I>(mut self, vars: I) -> Self
+    where
+        I: IntoIterator<Item = (String, String)>,
+    {
+        for (name, value) in vars {
+            let Some(key) = name.strip_prefix(ENV_PREFIX) else {
+                continue;
+            };
+            let key = key.to_ascii_lowercase();
+            if SETTING_KEYS.contains(&key.as_str()) {
+                self.entries.push((ConfigSource::Env, key, value));
+            }
+        }
+        self
+    }
+
+    pub fn with_args<I>(mut self, args: I) -> Self
+    where
+        I: IntoIterator<Item = String>,
+    {
+        let mut args = args.into_iter().peekable();
+        while let Some(arg) = args.next() {
+            let Some(flag) = arg.strip_prefix("--") else {
+                continue;
+            };
+            let (name, inline_value) = match flag.split_once('=') {
+                Some((name, value)) => (name, Some(value.to_string())),
+                None => (flag, None),
+            };
+            let key = name.replace('-', "_");
+            if !SETTING_KEYS.contains(&key.as_str()) {
+                continue;
+            }
+
+            let value = match inline_value {
+                Some(value) => Some(value),
+                None => args.next_if(|next| !next.starts_with("--")),
+            };
+            if let Some(value) = value {
+                self.entries.push((ConfigSource::Cli, key, value));
+            }
+        }
+        self
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    pub fn resolve(&self, base: Settings) -> ResolvedSettings {
+        let mut entries: Vec<&(ConfigSource, String, String)> = self.entries.iter().collect();
+        entries.sort_by_key(|(source, _, _)| *source);
+
+        let mut resolved = ResolvedSettings {
+            settings: base,
+            sources: BTreeMap::new(),
+            errors: Vec::new(),
+        };
+        for (source, key, value) in entries {
+            match apply_setting(&mut resolved.settings, key, value) {
+                Ok(()) => {
+                    resolved.sources.insert(key.clone(), *source);
+                }
+                Err(error) => resolved.errors.push(error),
+            }
+        }
+        resolved
+    }
+}
+
+pub fn apply_setting(settings: &mut Settings, key: &str, value: &str) -> Result<(), ConfigError> {
+    let value = value.trim();
+    let invalid = || ConfigError::InvalidValue {
+        key: key.to_string(),
+        value: value.to_string(),
+    };
+    let seconds = || value.parse::<u64>().map_err(|_| invalid());
+    let jitter = || match value.parse::<u8>() {
+        Ok(percent) if percent <= MAX_JITTER_PERCENT => Ok(percent),
+        _ => Err(invalid()),
+    };
+    let timer_block_level = || {
+        if value.eq_ignore_ascii_case("default") {
+            Ok(None)
+        } else {
+            BlockLevel::parse(value).map(Some).ok_or_else(invalid)
+        }
+    };
+    let timer_ids = || comma_list(value).map(str::to_string).collect::<Vec<_>>();
+    let color = || {
+        if is_hex_color(value) {
+            Ok(value.to_ascii_lowercase())
+        } else {
+            Err(invalid())
+        }
+    };
+    let percents = || {
+        let mut percents = comma_list(value)
+            .map(|entry| match entry.parse::<u8>() {
+                Ok(percent @ 1..=100) => Ok(percent),
+                _ => Err(invalid()),
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        percents.sort_unstable();
+        percents.dedup();
+        Ok(percents)
+    };
+    let flag = || match value.to_ascii_lowercase().as_str() {
+        "true" | "1" | "yes" | "on" => Ok(true),
+        "false" | "0" | "no" | "off" => Ok(false),
+        _ => Err(invalid()),
+    };
+
+    match key {
+        "micro_interval_seconds" => settings.micro.interval_seconds = seconds()?,
+        "micro_duration_seconds" => settings.micro.duration_seconds = seconds()?,
+        "micro_snooze_seconds" => settings.micro.snooze_seconds = seconds()?,
+        "micro_enabled" => settings.micro.enabled = flag()?,
+        "micro_jitter_percent" => settings.micro.jitter_percent = jitter()?,
+        "rest_interval_seconds" => settings.rest.interval_seconds = seconds()?,
+        "rest_duration_seconds" => settings.rest.duration_seconds = seconds()?,
+        "rest_snooze_seconds" => settings.rest.snooze_seconds = seconds()?,
+        "rest_enabled" => settings.rest.enabled = flag()?,
+        "rest_jitter_percent" => settings.rest.jitter_percent = jitter()?,
+        "adaptive_enabled" => settings.adaptive.enabled = flag()?,
+        "adaptive_min_interval_percent" => {
+            settings.adaptive.min_interval_percent = value.parse().map_err(|_| invalid())?
+        }
+        "adaptive_max_interval_percent" => {
+            settings.adaptive.max_interval_percent = value.parse().map_err(|_| invalid())?
+        }
+        "intensity_enabled" => settings.intensity.enabled = flag()?,
+        "intensity_scale_micro" => settings.intensity.scale_micro = flag()?,
+        "intensity_heavy_events_per_minute" => {
+            settings.intensity.heavy_events_per_minute = value.parse().map_err(|_| invalid())?
+        }
+        "intensity_max_reduction_percent" => {
+            settings.intensity.max_reduction_percent = value.parse().map_err(|_| invalid())?
+        }
+        "escalation_enabled" => settings.escalation.enabled = flag()?,
+        "escalation_renotify_seconds" => settings.escalation.renotify_after_seconds = seconds()?,
+        "escalation_force_seconds" => settings.escalation.force_after_seconds = seconds()?,
+        "daily_limit_seconds" => settings.daily_limit.limit_seconds = seconds()?,
+        "daily_limit_snooze_seconds" => settings.daily_limit.snooze_seconds = seconds()?,
+        "daily_limit_enabled" => settings.daily_limit.enabled = flag()?,
+        "daily_limit_max_snoozes" => {
+            settings.daily_limit.max_snoozes = value.parse().map_err(|_| invalid())?
+        }
+        "daily_limit_action" => {
+            settings.daily_limit.action = match value.to_ascii_lowercase().as_str() {
+                "none" => DailyLimitAction::None,
+                "lock" => DailyLimitAction::LockSession,
+                "suspend" => DailyLimitAction::Suspend,
+                _ => return Err(invalid()),
+            }
+        }
+        "daily_reset_time" => {
+            let (hour, minute) = parse_reset_time(value).ok_or_else(invalid)?;
+            settings.daily_limit.reset_hour_local = hour;
+            settings.daily_limit.reset_minute_local = minute;
+        }
+        "block_level" => settings.block_level = BlockLevel::parse(value).ok_or_else(invalid)?,
+        "micro_block_level" => settings.micro.block_level = timer_block_level()?,
+        "rest_block_level" => settings.rest.block_level = timer_block_level()?,
+        "micro_resets" => settings.micro.resets = timer_ids(),
+        "rest_resets" => settings.rest.resets = timer_ids(),
+        "daily_limit_resets_timers" => settings.daily_limit.resets_timers = flag()?,
+        "daily_limit_warning_thresholds" => settings.daily_limit.warning_thresholds = percents()?,
+        "weekly_limit_enabled" => settings.weekly_limit.enabled = flag()?,
+        "weekly_limit_seconds" => settings.weekly_limit.limit_seconds = seconds()?,
+        "weekly_limit_warning_thresholds" => settings.weekly_limit.warning_thresholds = percents()?,
+        "app_limits_enabled" => settings.app_limits.enabled = flag()?,
+        "app_limits" => settings.app_limits.limits = parse_app_limits(value).ok_or_else(invalid)?,
+        "app_limit_block_level" => {
+            settings.app_limits.block_level = BlockLevel::parse(value).ok_or_else(invalid)?
+        }
+        "fade_on_soft" => settings.dimming.soft = flag()?,
+        "fade_on_medium" => settings.dimming.medium = flag()?,
+        "fade_on_strict" => settings.dimming.strict = flag()?,
+        "fade_warning_seconds" => settings.dimming.warning_seconds = seconds()?,
+        "fade_max_opacity_percent" => {
+            let percent = value.parse::<u8>().map_err(|_| invalid())?;
+            if percent > 100 {
+                return Err(invalid());
+            }
+            settings.dimming.max_opacity_percent = percent;
+        }
+        "desktop_notifications" => settings.notifications.desktop_enabled = flag()?,
+        "overlay_notifications" => settings.notifications.overlay_enabled = flag()?,
+        "sound_notifications" => settings.notifications.sound_enabled = flag()?,
+        "sound_theme" => settings.notifications.sound_theme = value.to_string(),
+        "countdown_cue_seconds" => settings.notifications.countdown_cue_seconds = seconds()?,
+        "respect_system_dnd" => settings.notifications.respect_system_dnd = flag()?,
+        "defer_breaks_on_dnd" => settings.notifications.defer_breaks_on_dnd = flag()?,
+        "daily_summary_notifications" => settings.notifications.daily_summary_enabled = flag()?,
+        "countdown_widget" => settings.notifications.countdown_widget_enabled = flag()?,
+        "taskbar_progress" => settings.notifications.taskbar_progress_enabled = flag()?,
+        "message_templates" => {
+            settings.notifications.message_templates =
+                parse_message_templates(value).ok_or_else(invalid)?
+        }
+        "inhibit_on_fullscreen" => settings.inhibit.on_fullscreen = flag()?,
+        "inhibit_on_media" => settings.inhibit.on_media = flag()?,
+        "presentation_on_screencast" => settings.inhibit.presentation_on_screencast = flag()?,
+        "blocking_enabled" => settings.blocking.enabled = flag()?,
+        "blocking_app_classes" => {
+            settings.blocking.app_classes = comma_list(value).collect::<Vec<_>>().join(",")
+        }
+        "blocking_app_action" => {
+            settings.blocking.app_action = match value.to_ascii_lowercase().as_str() {
+                "minimize" => AppBlockAction::Minimize,
+                "kill" => AppBlockAction::Kill,
+                _ => return Err(invalid()),
+            }
+        }
+        "blocking_hosts" => {
+            let hosts = comma_list(value)
+                .map(str::to_ascii_lowercase)
+                .collect::<Vec<_>>();
+            if !hosts.iter().all(|host| is_host_name(host)) {
+                return Err(invalid());
+            }
+            settings.blocking.hosts = hosts.join(",");
+        }
+        "blocking_hosts_file" => settings.blocking.hosts_file_path = value.to_string(),
+        "calendar_enabled" => settings.calendar.enabled = flag()?,
+        "calendar_source_path" => settings.calendar.source_path = value.to_string(),
+        "media_keys_enabled" => settings.keys.media_keys_enabled = flag()?,
+        "media_start_keys" => settings.keys.start_keys = value.to_string(),
+        "media_snooze_keys" => settings.keys.snooze_keys = value.to_string(),
+        "remote_enabled" => settings.remote.enabled = flag()?,
+        "remote_port" => match value.parse::<u16>() {
+            Ok(port) if port > 0 => settings.remote.port = port,
+            _ => return Err(invalid()),
+        },
+        "remote_token" => settings.remote.token = value.to_string(),
+        "slack_token" => settings.integrations.slack_token = value.to_string(),
+        "teams_token" => settings.integrations.teams_token = value.to_string(),
+        "break_status_template" => settings.integrations.status_template = value.to_string(),
+        "break_status_emoji" => settings.integrations.status_emoji = value.to_string(),
+        "sync_backend" => {
+            settings.sync.backend = match value.to_ascii_lowercase().as_str() {
+                "off" => SyncBackend::Off,
+                "directory" => SyncBackend::Directory,
+                "webdav" => SyncBackend::WebDav,
+                _ => return Err(invalid()),
+            }
+        }
+        "sync_directory" => settings.sync.directory = value.to_string(),
+        "sync_webdav_url" => settings.sync.webdav_url = value.to_string(),
+        "sync_webdav_username" => settings.sync.webdav_username = value.to_string(),
+        "sync_webdav_password" => settings.sync.webdav_password = value.to_string(),
+        "peer_sharing_enabled" => settings.peers.enabled = flag()?,
+        "peer_port" => match value.parse::<u16>() {
+            Ok(port) if port > 0 => settings.peers.port = port,
+            _ => return Err(invalid()),
+        },
+        "peer_secret" => settings.peers.secret = value.to_string(),
+        "encrypt_state" => settings.privacy.encrypt_state = flag()?,
+        "calendar_meeting_policy" => {
+            settings.calendar.meeting_policy = match value.to_ascii_lowercase().as_str() {
+                "defer" => MeetingPolicy::Defer,
+                "silent" => MeetingPolicy::Silent,
+                _ => return Err(invalid()),
+            }
+        }
+        "high_contrast_overlay" => settings.accessibility.high_contrast = flag()?,
+        "reduce_motion" => settings.accessibility.reduce_motion = flag()?,
+        "large_countdown" => settings.accessibility.large_countdown = flag()?,
+        "screen_reader_announcements" => {
+            settings.accessibility.screen_reader_announcements = flag()?
+        }
+        "theme_mode" => {
+            settings.theme.mode = match value.to_ascii_lowercase().as_str() {
+                "dark" => ThemeMode::Dark,
+                "light" => ThemeMode::Light,
+                "custom" => ThemeMode::Custom,
+                _ => return Err(invalid()),
+            }
+        }
+        "theme_accent_color" => settings.theme.accent_color = color()?,
+        "theme_background_color" => settings.theme.background_color = color()?,
+        "theme_text_color" => settings.theme.text_color = color()?,
+        "theme_background_image" => settings.theme.background_image_path = value.to_string(),
+        "theme_font_scale_percent" => match value.parse::<u16>() {
+            Ok(percent) if (MIN_FONT_SCALE_PERCENT..=MAX_FONT_SCALE_PERCENT).contains(&percent) => {
+                settings.theme.font_scale_percent = percent
+            }
+            _ => return Err(invalid()),
+        },
+        "activity_packs" => {
+            let mut packs = Vec::new();
+            for entry in comma_list(value) {
+                let pack = ActivityPack::parse(entry).ok_or_else(invalid)?;
+                if !packs.contains(&pack) {
+                    packs.push(pack);
+                }
+            }
+            if packs.is_empty() {
+                return Err(invalid());
+            }
+            settings.theme.activity_packs = packs;
+        }
+        "language" => settings.language = value.to_string(),
+        "week_start" => {
+            settings.analytics.week_start = match value.to_ascii_lowercase().as_str() {
+                "monday" => WeekStart::Monday,
+                "sunday" => WeekStart::Sunday,
+                _ => return Err(invalid()),
+            }
+        }
+        "weekly_max_active_seconds" => settings.analytics.weekly_max_active_seconds = seconds()?,
+        "weekly_min_breaks" => {
+            settings.analytics.weekly_min_breaks = value.parse().map_err(|_| invalid())?
+        }
+        "focus_gap_seconds" => settings.analytics.focus_gap_seconds = seconds()?,
+        "break_feedback_prompt" => settings.analytics.break_feedback_prompt = flag()?,
+        "milestone_celebrations" => settings.analytics.milestone_celebrations = flag()?,
+        "app_usage_enabled" => settings.analytics.app_usage_enabled = flag()?,
+        "app_usage_titles" => settings.analytics.app_usage_titles = flag()?,
+        "app_categories" => {
+            settings.analytics.app_categories = parse_app_categories(value).ok_or_else(invalid)?
+        }
+        "nudges_enabled" => settings.nudges.enabled = flag()?,
+        "nudge_notifications" => settings.nudges.notify = flag()?,
+        "nudge_skip_rate" => settings.nudges.skip_rate = flag()?,
+        "nudge_long_focus" => settings.nudges.long_focus = flag()?,
+        "nudge_late_night" => settings.nudges.late_night = flag()?,
+        "nudge_interval_seconds" => settings.nudges.interval_seconds = seconds()?.max(60),
+        "wind_down_enabled" => settings.wind_down.enabled = flag()?,
+        "wind_down_start" => {
+            let (hour, minute) = parse_reset_time(value).ok_or_else(invalid)?;
+            settings.wind_down.start_hour_local = hour;
+            settings.wind_down.start_minute_local = minute;
+        }
+        "wind_down_cutoff" => {
+            let (hour, minute) = parse_reset_time(value).ok_or_else(invalid)?;
+            settings.wind_down.cutoff_hour_local = hour;
+            settings.wind_down.cutoff_minute_local = minute;
+        }
+        "wind_down_interval_percent" => {
+            settings.wind_down.interval_percent = match value.parse::<u8>() {
+                Ok(percent @ 10..=100) => percent,
+                _ => return Err(invalid()),
+            }
+        }
+        "wind_down_budget_seconds" => settings.wind_down.budget_seconds = seconds()?,
+        "day_overrides" => {
+            settings.day_overrides = parse_day_overrides(value).ok_or_else(invalid)?
+        }
+        "tick_emit_interval_seconds" => {
+            settings.runtime.tick_emit_interval_seconds = seconds()?.max(1)
+        }
+        "break_tick_millis" => match value.parse::<u64>() {
+            Ok(millis) if (MIN_BREAK_TICK_MILLIS..=MAX_BREAK_TICK_MILLIS).contains(&millis) => {
+                settings.runtime.break_tick_millis = millis
+            }
+            _ => return Err(invalid()),
+        },
+        "log_level" => settings.runtime.log_level = value.to_ascii_lowercase(),
+        "restart_runtime_on_crash" => settings.runtime.restart_on_crash = flag()?,
+        "warmup_grace_seconds" => settings.startup.warmup_grace_seconds = seconds()?,
+        "idle_counts_as_rest" => settings.startup.idle_counts_as_rest = flag()?,
+        "update_check_enabled" => settings.updates.check_enabled = flag()?,
+        "update_channel" => {
+            settings.updates.channel = match value.to_ascii_lowercase().as_str() {
+                "stable" => UpdateChannel::Stable,
+                "beta" => UpdateChannel::Beta,
+                _ => return Err(invalid()),
+            }
+        }
+        "power_saver_behavior" => {
+            settings.runtime.power_saver_behavior = match value.to_ascii_lowercase().as_str() {
+                "ignore" => PowerSaverBehavior::Ignore,
+                "quiet" => PowerSaverBehavior::Quiet,
+                "quiet_and_slow" => PowerSaverBehavior::QuietAndSlow,
+                _ => return Err(invalid()),
+            }
+        }
+        "limit_scope" => {
+            settings.limit_scope = match value.to_ascii_lowercase().as_str() {
+                "global" => LimitScope::Global,
+                "profile" => LimitScope::Profile,
+                _ => return Err(invalid()),
+            }
+        }
+        "interval_change_policy" => {
+            settings.runtime.interval_change_policy = match value.to_ascii_lowercase().as_str() {
+                "rescale" => IntervalChangePolicy::Rescale,
+                "preserve" => IntervalChangePolicy::Preserve,
+                "reset" => IntervalChangePolicy::Reset,
+                _ => return Err(invalid()),
+            }
+        }
+        _ => return Err(ConfigError::UnknownKey(key.to_string())),
+    }
+    Ok(())
+}
+
+pub fn numeric_setting(settings: &Settings, key: &str) -> Option<u64> {
+    let value = match key {
+        "micro_interval_seconds" => settings.micro.interval_seconds,
+        "micro_duration_seconds" => settings.micro.duration_seconds,
+        "micro_snooze_seconds" => settings.micro.snooze_seconds,
+        "rest_interval_seconds" => settings.rest.interval_seconds,
+        "rest_duration_seconds" => settings.rest.duration_seconds,
+        "rest_snooze_seconds" => settings.rest.snooze_seconds,
+        "micro_jitter_percent" => settings.micro.jitter_percent as u64,
+        "rest_jitter_percent" => settings.rest.jitter_percent as u64,
+        "adaptive_min_interval_percent" => settings.adaptive.min_interval_percent as u64,
+        "adaptive_max_interval_percent" => settings.adaptive.max_interval_percent as u64,
+        "intensity_heavy_events_per_minute" => settings.intensity.heavy_events_per_minute as u64,
+        "intensity_max_reduction_percent" => settings.intensity.max_reduction_percent as u64,
+        "escalation_renotify_seconds" => settings.escalation.renotify_after_seconds,
+        "escalation_force_seconds" => settings.escalation.force_after_seconds,
+        "daily_limit_seconds" => settings.daily_limit.limit_seconds,
+        "daily_limit_snooze_seconds" => settings.daily_limit.snooze_seconds,
+        "daily_limit_max_snoozes" => settings.daily_limit.max_snoozes as u64,
+        "fade_warning_seconds" => settings.dimming.warning_seconds,
+        "fade_max_opacity_percent" => settings.dimming.max_opacity_percent as u64,
+        "countdown_cue_seconds" => settings.notifications.countdown_cue_seconds,
+        "tick_emit_interval_seconds" => settings.runtime.tick_emit_interval_seconds,
+        "break_tick_millis" => settings.runtime.break_tick_millis,
+        "nudge_interval_seconds" => settings.nudges.interval_seconds,
+        "wind_down_interval_percent" => settings.wind_down.interval_percent as u64,
+        "wind_down_budget_seconds" => settings.wind_down.budget_seconds,
+        "warmup_grace_seconds" => settings.startup.warmup_grace_seconds,
+        "weekly_max_active_seconds" => settings.analytics.weekly_max_active_seconds,
+        "weekly_limit_seconds" => settings.weekly_limit.limit_seconds,
+        "weekly_min_breaks" => settings.analytics.weekly_min_breaks as u64,
+        "focus_gap_seconds" => settings.analytics.focus_gap_seconds,
+        "remote_port" => settings.remote.port as u64,
+        "peer_port" => settings.peers.port as u64,
+        "theme_font_scale_percent" => settings.theme.font_scale_percent as u64,
+        _ => return None,
+    };
+    Some(value)
+}
+
+/// Keys whose values `Settings::diff` leaves out.
+pub const SECRET_SETTING_KEYS: &[&str] = &[
+    "remote_token",
+    "slack_token",
+    "teams_token",
+    "sync_webdav_password",
+    "peer_secret",
+];
+
+/// The value of `key` in the form `apply_setting` accepts.
+pub fn setting_value(settings: &Settings, key: &str) -> Option<String> {
+    if let Some(value) = numeric_setting(settings, key) {
+        return Some(value.to_string());
+    }
+    let timer_block_level =
+        |level: Option<BlockLevel>| level.map_or("default", BlockLevel::as_str).to_string();
+    let value = match key {
+        "micro_enabled" => settings.micro.enabled.to_string(),
+        "micro_block_level" => timer_block_level(settings.micro.block_level),
+        "rest_enabled" => settings.rest.enabled.to_string(),
+        "rest_block_level" => timer_block_level(settings.rest.block_level),
+        "micro_resets" => settings.micro.resets.join(","),
+        "rest_resets" => settings.rest.resets.join(","),
+        "daily_limit_resets_timers" => settings.daily_limit.resets_timers.to_string(),
+        "daily_limit_warning_thresholds" => join_percents(&settings.daily_limit.warning_thresholds),
+        "weekly_limit_enabled" => settings.weekly_limit.enabled.to_string(),
+        "app_limits_enabled" => settings.app_limits.enabled.to_string(),
+        "app_limits" => join_app_limits(&settings.app_limits.limits),
+        "app_limit_block_level" => settings.app_limits.block_level.as_str().to_string(),
+        "weekly_limit_warning_thresholds" => {
+            join_percents(&settings.weekly_limit.warning_thresholds)
+        }
+        "adaptive_enabled" => settings.adaptive.enabled.to_string(),
+        "intensity_enabled" => settings.intensity.enabled.to_string(),
+        "intensity_scale_micro" => settings.intensity.scale_micro.to_string(),
+        "escalation_enabled" => settings.escalation.enabled.to_string(),
+        "daily_limit_enabled" => settings.daily_limit.enabled.to_string(),
+        "daily_reset_time" => format!(
+            "{:02}:{:02}",
+            settings.daily_limit.reset_hour_local, settings.daily_limit.reset_minute_local
+        ),
+        "daily_limit_action" => match settings.daily_limit.action {
+            DailyLimitAction::None => "none",
+            DailyLimitAction::LockSession => "lock",
+            DailyLimitAction::Suspend => "suspend",
+        }
+        .to_string(),
+        "block_level" => settings.block_level.as_str().to_string(),
+        "fade_on_soft" => settings.dimming.soft.to_string(),
+        "fade_on_medium" => settings.dimming.medium.to_string(),
+        "fade_on_strict" => settings.dimming.strict.to_string(),
+        "desktop_notifications" => settings.notifications.desktop_enabled.to_string(),
+        "overlay_notifications" => settings.notifications.overlay_enabled.to_string(),
+        "sound_notifications" => settings.notifications.sound_enabled.to_string(),
+        "sound_theme" => settings.notifications.sound_theme.clone(),
+        "respect_system_dnd" => settings.notifications.respect_system_dnd.to_string(),
+        "defer_breaks_on_dnd" => settings.notifications.defer_breaks_on_dnd.to_string(),
+        "daily_summary_notifications" => settings.notifications.daily_summary_enabled.to_string(),
+        "countdown_widget" => settings.notifications.countdown_widget_enabled.to_string(),
+        "taskbar_progress" => settings.notifications.taskbar_progress_enabled.to_string(),
+        "message_templates" => join_message_templates(&settings.notifications.message_templates),
+        "inhibit_on_fullscreen" => settings.inhibit.on_fullscreen.to_string(),
+        "inhibit_on_media" => settings.inhibit.on_media.to_string(),
+        "presentation_on_screencast" => settings.inhibit.presentation_on_screencast.to_string(),
+        "blocking_enabled" => settings.blocking.enabled.to_string(),
+        "blocking_app_classes" => settings.blocking.app_classes.clone(),
+        "blocking_app_action" => match settings.blocking.app_action {
+            AppBlockAction::Minimize => "minimize",
+            AppBlockAction::Kill => "kill",
+        }
+        .to_string(),
+        "blocking_hosts" => settings.blocking.hosts.clone(),
+        "blocking_hosts_file" => settings.blocking.hosts_file_path.clone(),
+        "calendar_enabled" => settings.calendar.enabled.to_string(),
+        "calendar_source_path" => settings.calendar.source_path.clone(),
+        "calendar_meeting_policy" => match settings.calendar.meeting_policy {
+            MeetingPolicy::Defer => "defer",
+            MeetingPolicy::Silent => "silent",
+        }
+        .to_string(),
+        "media_keys_enabled" => settings.keys.media_keys_enabled.to_string(),
+        "media_start_keys" => settings.keys.start_keys.clone(),
+        "media_snooze_keys" => settings.keys.snooze_keys.clone(),
+        "remote_enabled" => settings.remote.enabled.to_string(),
+        "remote_token" => settings.remote.token.clone(),
+        "slack_token" => settings.integrations.slack_token.clone(),
+        "teams_token" => settings.integrations.teams_token.clone(),
+        "break_status_template" => settings.integrations.status_template.clone(),
+        "break_status_emoji" => settings.integrations.status_emoji.clone(),
+        "sync_backend" => match settings.sync.backend {
+            SyncBackend::Off => "off",
+            SyncBackend::Directory => "directory",
+            SyncBackend::WebDav => "webdav",
+        }
+        .to_string(),
+        "sync_directory" => settings.sync.directory.clone(),
+        "sync_webdav_url" => settings.sync.webdav_url.clone(),
+        "sync_webdav_username" => settings.sync.webdav_username.clone(),
+        "sync_webdav_password" => settings.sync.webdav_password.clone(),
+        "peer_sharing_enabled" => settings.peers.enabled.to_string(),
+        "peer_secret" => settings.peers.secret.clone(),
+        "encrypt_state" => settings.privacy.encrypt_state.to_string(),
+        "high_contrast_overlay" => settings.accessibility.high_contrast.to_string(),
+        "reduce_motion" => settings.accessibility.reduce_motion.to_string(),
+        "large_countdown" => settings.accessibility.large_countdown.to_string(),
+        "screen_reader_announcements" => settings
+            .accessibility
+            .screen_reader_announcements
+            .to_string(),
+        "theme_mode" => match settings.theme.mode {
+            ThemeMode::Dark => "dark",
+            ThemeMode::Light => "light",
+            ThemeMode::Custom => "custom",
+        }
+        .to_string(),
+        "theme_accent_color" => settings.theme.accent_color.clone(),
+        "theme_background_color" => settings.theme.background_color.clone(),
+        "theme_text_color" => settings.theme.text_color.clone(),
+        "theme_background_image" => settings.theme.background_image_path.clone(),
+        "activity_packs" => settings
+            .theme
+            .activity_packs
+            .iter()
+            .map(|pack| pack.as_str())
+            .collect::<Vec<_>>()
+            .join(","),
+        "language" => settings.language.clone(),
+        "week_start" => match settings.analytics.week_start {
+            WeekStart::Monday => "monday",
+            WeekStart::Sunday => "sunday",
+        }
+        .to_string(),
+        "log_level" => settings.runtime.log_level.clone(),
+        "power_saver_behavior" => match settings.runtime.power_saver_behavior {
+            PowerSaverBehavior::Ignore => "ignore",
+            PowerSaverBehavior::Quiet => "quiet",
+            PowerSaverBehavior::QuietAndSlow => "quiet_and_slow",
+        }
+        .to_string(),
+        "limit_scope" => match settings.limit_scope {
+            LimitScope::Global => "global",
+            LimitScope::Profile => "profile",
+        }
+        .to_string(),
+        "interval_change_policy" => match settings.runtime.interval_change_policy {
+            IntervalChangePolicy::Rescale => "rescale",
+            IntervalChangePolicy::Preserve => "preserve",
+            IntervalChangePolicy::Reset => "reset",
+        }
+        .to_string(),
+        "restart_runtime_on_crash" => settings.runtime.restart_on_crash.to_string(),
+        "idle_counts_as_rest" => settings.startup.idle_counts_as_rest.to_string(),
+        "break_feedback_prompt" => settings.analytics.break_feedback_prompt.to_string(),
+        "milestone_celebrations" => settings.analytics.milestone_celebrations.to_string(),
+        "app_usage_enabled" => settings.analytics.app_usage_enabled.to_string(),
+        "app_usage_titles" => settings.analytics.app_usage_titles.to_string(),
+        "app_categories" => join_app_categories(&settings.analytics.app_categories),
+        "nudges_enabled" => settings.nudges.enabled.to_string(),
+        "nudge_notifications" => settings.nudges.notify.to_string(),
+        "nudge_skip_rate" => settings.nudges.skip_rate.to_string(),
+        "nudge_long_focus" => settings.nudges.long_focus.to_string(),
+        "nudge_late_night" => settings.nudges.late_night.to_string(),
+        "wind_down_enabled" => settings.wind_down.enabled.to_string(),
+        "day_overrides" => join_day_overrides(&settings.day_overrides),
+        "wind_down_start" => format!(
+            "{:02}:{:02}",
+            settings.wind_down.start_hour_local, settings.wind_down.start_minute_local
+        ),
+        "wind_down_cutoff" => format!(
+            "{:02}:{:02}",
+            settings.wind_down.cutoff_hour_local, settings.wind_down.cutoff_minute_local
+        ),
+        "update_check_enabled" => settings.updates.check_enabled.to_string(),
+        "update_channel" => match settings.updates.channel {
+            UpdateChannel::Stable => "stable",
+            UpdateChannel::Beta => "beta",
+        }
+        .to_string(),
+        _ => return None,
+    };
+    Some(value)
+}
+
+/// One entry of `Settings::diff`. Values are left out for secrets and for
+/// `custom_timers`, which has no single-value form.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SettingChange {
+    pub key: &'static str,
+    pub previous: Option<String>,
+    pub current: Option<String>,
+}
+
+impl Settings {
+    /// These settings as they apply on `day`, with its overrides on top.
+    pub fn for_weekday(&self, day: Weekday) -> Settings {
+        let mut settings = self.clone();
+        for day_override in self
+            .day_overrides
+            .iter()
+            .filter(|candidate| candidate.days.contains(&day))
+        {
+            // Checked when parsed, so this only fails for hand-edited state.
+            let _ = apply_setting(&mut settings, &day_override.key, &day_override.value);
+        }
+        settings
+    }
+
+    /// Keys from `SETTING_KEYS` whose value differs in `other`, in that
+    /// order, then `custom_timers` and `active_profile_id`.
+    pub fn diff(&self, other: &Settings) -> Vec<SettingChange> {
+        let mut changes = SETTING_KEYS
+            .iter()
+            .filter_map(|key| {
+                let previous = setting_value(self, key);
+                let current = setting_value(other, key);
+                if previous == current {
+                    return None;
+                }
+                let secret = SECRET_SETTING_KEYS.contains(key);
+                Some(SettingChange {
+                    key,
+                    previous: previous.filter(|_| !secret),
+                    current: current.filter(|_| !secret),
+                })
+            })
+            .collect::<Vec<_>>();
+        if self.custom_timers != other.custom_timers {
+            changes.push(SettingChange {
+                key: "custom_timers",
+                previous: None,
+                current: None,
+            });
+        }
+        if self.active_profile_id != other.active_profile_id {
+            changes.push(SettingChange {
+                key: "active_profile_id",
+                previous: Some(self.active_profile_id.clone()),
+                current: Some(other.active_profile_id.clone()),
+            });
+        }
+        changes
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn stricter_levels_delay_or_forbid_skipping() {
+        assert_eq!(BlockLevel::Soft.skip_delay_seconds(), Some(0));
+        assert_eq!(
+            BlockLevel::Medium.skip_delay_seconds(),
+            Some(MEDIUM_SKIP_DELAY_SECONDS)
+        );
+        assert_eq!(BlockLevel::Strict.skip_delay_seconds(), None);
+    }
+
+    #[test]
+    fn later_layers_take_precedence() {
+        let resolved = LayeredConfig::new()
+            .with_file([("micro_interval_seconds", "600"), ("block_level", "soft")])
+            .with_env([(
+                "LAZARO_MICRO_INTERVAL_SECONDS".to_string(),
+                "900".to_string(),
+            )])
+            .with_args(["--block-level".to_string(), "strict".to_string()])
+            .resolve(Settings::default());
+
+        assert_eq!(resolved.settings.micro.interval_seconds, 900);
+        assert_eq!(resolved.settings.block_level, BlockLevel::Strict);
+        assert_eq!(
+            resolved.sources.get("micro_interval_seconds"),
+            Some(&ConfigSource::Env)
+        );
+        assert_eq!(
+            resolved.sources.get("block_level"),
+            Some(&ConfigSource::Cli)
+        );
+        assert!(resolved.errors.is_empty());
+    }
+
+    #[test]
+    fn invalid_entries_are_reported_and_skipped() {
+        let resolved = LayeredConfig::new()
+            .with_file([("daily_reset_time", "25:00"), ("volume", "3")])
+            .with_env([("LAZARO_DEBUG".to_string(), "1".to_string())])
+            .with_args([
+                "--minimized".to_string(),
+                "--rest-enabled=false".to_string(),
+            ])
+            .resolve(Settings::default());
+
+        assert_eq!(resolved.settings.daily_limit.reset_hour_local, 4);
+        assert!(!resolved.settings.rest.enabled);
+        assert_eq!(resolved.errors.len(), 2);
+        assert!(
+            resolved
+                .errors
+                .contains(&ConfigError::UnknownKey("volume".into()))
+        );
+    }
+
+    #[test]
+    fn theme_values_are_validated() {
+        let mut settings = Settings::default();
+        apply_setting(&mut settings, "theme_accent_color", "#A0B1C2").unwrap();
+        apply_setting(&mut settings, "theme_mode", "custom").unwrap();
+        assert_eq!(settings.theme.accent_color, "#a0b1c2");
+        assert_eq!(settings.theme.mode, ThemeMode::Custom);
+
+        for (key, value) in [
+            ("theme_accent_color", "red"),
+            ("theme_text_color", "#12345"),
+            ("theme_font_scale_percent", "300"),
+            ("theme_mode", "sepia"),
+        ] {
+            assert!(apply_setting(&mut settings, key, value).is_err(), "{key}");
+        }
+        assert_eq!(settings.theme.font_scale_percent, 100);
+    }
+
+    #[test]
+    fn activity_packs_keep_their_order_and_reject_unknown_names() {
+        let mut settings = Settings::default();
+        apply_setting(&mut settings, "activity_packs", "Breathing, eyes,breathing").unwrap();
+        assert_eq!(
+            settings.theme.activity_packs,
+            vec![ActivityPack::Breathing, ActivityPack::Eyes]
+        );
+        assert_eq!(
+            setting_value(&settings, "activity_packs").as_deref(),
+            Some("breathing,eyes")
+        );
+
+        for value in ["", " , ", "eyes,yoga"] {
+            assert!(apply_setting(&mut settings, "activity_packs", value).is_err());
+        }
+        assert_eq!(settings.theme.activity_packs.len(), 2);
+    }
+
+    #[test]
+    fn blocking_lists_are_normalized() {
+        let mut settings = Settings::default();
+        apply_setting(
+            &mut settings,
+            "blocking_hosts",
+            " News.example.com, ,reddit.com ",
+        )
+        .unwrap();
+        apply_setting(&mut settings, "blocking_app_classes", "slack , discord").unwrap();
+        assert_eq!(
+            settings.blocking.host_list().collect::<Vec<_>>(),
+            ["news.example.com", "reddit.com"]
+        );
+        assert_eq!(settings.blocking.app_classes, "slack,discord");
+
+        assert!(apply_setting(&mut settings, "blocking_hosts", "bad host.com").is_err());
+        assert!(apply_setting(&mut settings, "blocking_hosts", "a..b").is_err());
+        assert!(apply_setting(&mut settings, "blocking_app_action", "hide").is_err());
+        assert_eq!(settings.blocking.hosts, "news.example.com,reddit.com");
+    }
+
+    #[test]
+    fn app_limits_parse_minutes_per_class() {
+        let mut settings = Settings::default();
+        apply_setting(&mut settings, "app_limits", " firefox=60, Slack = 15 ,").unwrap();
+        assert_eq!(
+            settings.app_limits.limit_for("slack"),
+            Some(&AppLimit {
+                class: "Slack".into(),
+                limit_seconds: 900,
+            })
+        );
+        assert_eq!(
+            setting_value(&settings, "app_limits").as_deref(),
+            Some("firefox=60,Slack=15")
+        );
+
+        for value in ["firefox", "firefox=0", "=10", "a=1,A=2", "a=x"] {
+            assert!(apply_setting(&mut settings, "app_limits", value).is_err());
+        }
+        assert_eq!(settings.app_limits.limits.len(), 2);
+        apply_setting(&mut settings, "app_limits", "").unwrap();
+        assert!(settings.app_limits.limits.is_empty());
+    }
+
+    #[test]
+    fn message_templates_are_one_key_per_line() {
+        let mut settings = Settings::default();
+        apply_setting(
+            &mut settings,
+            "message_templates",
+            "break.due = Time for a {kind} break\n\n  app.title=Lazaro: a=b  \n",
+        )
+        .unwrap();
+        let notifications = &settings.notifications;
+        assert_eq!(
+            notifications.template("break.due"),
+            Some("Time for a {kind} break")
+        );
+        assert_eq!(notifications.template("app.title"), Some("Lazaro: a=b"));
+        assert_eq!(notifications.template("break.completed"), None);
+        assert_eq!(
+            setting_value(&settings, "message_templates").as_deref(),
+            Some("break.due=Time for a {kind} break\napp.title=Lazaro: a=b")
+        );
+
+        let long = format!("a={}", "x".repeat(MAX_MESSAGE_TEMPLATE_CHARS + 1));
+        for value in [
+            "break.due",
+            "break.due=",
+            "=text",
+            "a b=text",
+            "a=1\na=2",
+            &long,
+        ] {
+            assert!(apply_setting(&mut settings, "message_templates", value).is_err());
+        }
+        assert_eq!(settings.notifications.message_templates.len(), 2);
+    }
+
+    #[test]
+    fn day_overrides_apply_only_on_their_days() {
+        let mut settings = Settings::default();
+        apply_setting(
+            &mut settings,
+            "day_overrides",
+            "SAT,sun daily_limit_enabled=false\n\nsat micro_interval_seconds = 600",
+        )
+        .unwrap();
+        assert_eq!(
+            setting_value(&settings, "day_overrides").as_deref(),
+            Some("sat,sun daily_limit_enabled=false\nsat micro_interval_seconds=600")
+        );
+
+        let saturday = settings.for_weekday(Weekday::Saturday);
+        assert!(!saturday.daily_limit.enabled);
+        assert_eq!(saturday.micro.interval_seconds, 600);
+        let sunday = settings.for_weekday(Weekday::Sunday);
+        assert!(!sunday.daily_limit.enabled);
+        assert_eq!(sunday.micro.interval_seconds, 180);
+        assert_eq!(settings.for_weekday(Weekday::Monday), settings);
+        assert_eq!(Weekday::from_day_index(0), Weekday::Thursday);
+        assert_eq!(Weekday::from_day_index(-1), Weekday::Wednesday);
+
+        for value in [
+            "sat",
+            "sat daily_limit_enabled",
+            "someday daily_limit_enabled=false",
+            "sat daily_limit_enabled=maybe",
+            "sat daily_reset_time=05:00",
+            "sat day_overrides=",
+        ] {
+            assert!(apply_setting(&mut settings, "day_overrides", value).is_err());
+        }
+        assert_eq!(settings.day_overrides.len(), 2);
+    }
+
+    #[test]
+    fn warning_thresholds_are_sorted_and_bounded() {
+        let mut settings = Settings::default();
+        apply_setting(
+            &mut settings,
+            "daily_limit_warning_thresholds",
+            "90, 50,75,50",
+        )
+        .unwrap();
+        assert_eq!(settings.daily_limit.warning_thresholds, [50, 75, 90]);
+
+        assert!(apply_setting(&mut settings, "daily_limit_warning_thresholds", "0").is_err());
+        assert!(apply_setting(&mut settings, "daily_limit_warning_thresholds", "120").is_err());
+        assert_eq!(settings.daily_limit.warning_thresholds, [50, 75, 90]);
+
+        apply_setting(&mut settings, "daily_limit_warning_thresholds", "").unwrap();
+        assert!(settings.daily_limit.warning_thresholds.is_empty());
+    }
+
+    #[test]
+    fn power_saver_behavior_parses_known_values() {
+        let mut settings = Settings::default();
+        assert_eq!(
+            settings.runtime.power_saver_behavior,
+            PowerSaverBehavior::Quiet
+        );
+        apply_setting(&mut settings, "power_saver_behavior", "Quiet_And_Slow").unwrap();
+        assert_eq!(
+            settings.runtime.power_saver_behavior,
+            PowerSaverBehavior::QuietAndSlow
+        );
+        assert!(apply_setting(&mut settings, "power_saver_behavior", "off").is_err());
+    }
+
+    #[test]
+    fn every_key_has_a_value_that_round_trips() {
+        let settings = Settings::default();
+        for key in SETTING_KEYS {
+            let value = setting_value(&settings, key).unwrap_or_else(|| panic!("{key}"));
+            let mut copy = settings.clone();
+            apply_setting(&mut copy, key, &value).unwrap_or_else(|error| panic!("{error}"));
+            assert_eq!(copy, settings, "{key}");
+        }
+    }
+
+    #[test]
+    fn diff_lists_changed_keys_and_hides_secrets() {
+        let previous = Settings::default();
+        let mut current = previous.clone();
+        apply_setting(&mut current, "micro_interval_seconds", "600").unwrap();
+        apply_setting(&mut current, "rest_block_level", "strict").unwrap();
+        apply_setting(&mut current, "remote_token", "hunter2").unwrap();
+
+        assert_eq!(
+            previous.diff(&current),
+            [
+                SettingChange {
+                    key: "micro_interval_seconds",
+                    previous: Some("180".into()),
+                    current: Some("600".into()),
+                },
+                SettingChange {
+                    key: "rest_block_level",
+                    previous: Some("default".into()),
+                    current: Some("strict".into()),
+                },
+                SettingChange {
+                    key: "remote_token",
+                    previous: None,
+                    current: None,
+                },
+            ]
+        );
+        assert!(current.diff(&current).is_empty());
+    }
+}
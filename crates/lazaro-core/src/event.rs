@@ -0,0 +1,318 @@
+//! Machine-readable runtime events for consumers outside the desktop UI:
+//! the terminal client, the web dashboard and hooks.
+//!
+//! With the `serde` feature an event serializes as an object tagged by
+//! `type`, e.g. `{"type":"break_due","break_kind":"micro"}`. Break kinds,
+//! block levels, reasons and milestones use the ids from `BreakKind::id`,
+//! `BlockLevel::as_str`, `DeferReason::as_str`, `AdaptReason::as_str` and
+//! `MilestoneKind::as_str`. Localized text never goes in here; frontends
+//! send it alongside.
+//!
+//! Within one [`EVENT_SCHEMA_VERSION`] types and fields are only ever added,
+//! never renamed, retyped or removed, so consumers should ignore what they
+//! do not know. Anything else bumps the version.
+
+use crate::{
+    analytics::MilestoneKind,
+    config::BlockLevel,
+    nudge::Nudge,
+    timer::{AdaptReason, BreakKind, DeferReason},
+};
+
+pub const EVENT_SCHEMA_VERSION: u32 = 1;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(tag = "type", rename_all = "snake_case")
+)]
+pub enum RuntimeEvent {
+    BreakDue {
+        break_kind: BreakKind,
+    },
+    BreakDeferred {
+        break_kind: BreakKind,
+        #[cfg_attr(feature = "serde", serde(with = "id"))]
+        reason: DeferReason,
+    },
+    BreakStarted {
+        break_kind: BreakKind,
+        duration_seconds: u64,
+        strict: bool,
+    },
+    BreakTick {
+        break_kind: BreakKind,
+        remaining_seconds: u64,
+    },
+    BreakCompleted {
+        break_kind: BreakKind,
+    },
+    /// `until` is unset when the snooze lasts until the break is due again.
+    BreakSnoozed {
+        break_kind: BreakKind,
+        until: Option<u64>,
+    },
+    BreakEscalated {
+        break_kind: BreakKind,
+    },
+    BreakForced {
+        break_kind: BreakKind,
+    },
+    /// Ended from the app before its time; `completed` when it had run long
+    /// enough to count.
+    BreakEndedEarly {
+        break_kind: BreakKind,
+        completed: bool,
+    },
+    /// The break screen was closed by the user or the compositor.
+    OverlayClosed {
+        break_kind: BreakKind,
+        completed: bool,
+    },
+    OverlayFailed {
+        break_kind: BreakKind,
+    },
+    DailyLimitWarning {
+        percent: u8,
+        used_seconds: u64,
+        limit_seconds: u64,
+    },
+    /// The configured lock or suspend runs in `grace_seconds` unless the
+    /// break starts first.
+    DailyLimitActionPending {
+        grace_seconds: u64,
+    },
+    DailyLimitActionCancelled,
+    DailyReset {
+        forced: bool,
+    },
+    DailySummary {
+        day: String,
+    },
+    IntervalsAdjusted {
+        scale_percent: u8,
+        #[cfg_attr(feature = "serde", serde(with = "id"))]
+        reason: AdaptReason,
+    },
+    WeeklyLimitWarning {
+        percent: u8,
+        used_seconds: u64,
+        limit_seconds: u64,
+    },
+    WeeklyLimitReached {
+        limit_seconds: u64,
+    },
+    /// `app` is the window class from `app_limits`; `block_level` says how
+    /// it is kept out for the rest of the day.
+    AppLimitReached {
+        app: String,
+        limit_seconds: u64,
+        block_level: BlockLevel,
+    },
+    /// A completed break pushed a run to `count`, one of the thresholds
+    /// of `milestone`.
+    MilestoneReached {
+        #[cfg_attr(feature = "serde", serde(with = "id"))]
+        milestone: MilestoneKind,
+        count: u32,
+    },
+    /// A tip from the nudge rules; `nudge` is tagged by its `rule`.
+    Nudge {
+        nudge: Nudge,
+    },
+    /// The evening wind-down window began; intervals run at
+    /// `interval_percent` of their length until the daily reset.
+    WindDownStarted {
+        interval_percent: u8,
+    },
+    /// The wind-down cutoff passed.
+    WindDownCutoff,
+    /// Active time inside the wind-down window spent its budget.
+    WindDownBudgetReached {
+        budget_seconds: u64,
+    },
+    VacationStarted {
+        until: u64,
+    },
+    VacationEnded,
+    GuestStarted {
+        until: u64,
+    },
+    GuestEnded,
+    /// `until` is unset while a screencast holds it.
+    PresentationStarted {
+        until: Option<u64>,
+        screencast: bool,
+    },
+    PresentationEnded {
+        screencast: bool,
+    },
+    ConfigReloaded,
+    ConfigError,
+    /// The engine loop panicked; `restarted` when it was brought back from
+    /// its last snapshot.
+    RuntimeCrashed {
+        restarted: bool,
+    },
+}
+
+impl RuntimeEvent {
+    /// The `type` tag, stable within a schema version.
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Self::BreakDue { .. } => "break_due",
+            Self::BreakDeferred { .. } => "break_deferred",
+            Self::BreakStarted { .. } => "break_started",
+            Self::BreakTick { .. } => "break_tick",
+            Self::BreakCompleted { .. } => "break_completed",
+            Self::BreakSnoozed { .. } => "break_snoozed",
+            Self::BreakEscalated { .. } => "break_escalated",
+            Self::BreakForced { .. } => "break_forced",
+            Self::BreakEndedEarly { .. } => "break_ended_early",
+            Self::OverlayClosed { .. } => "overlay_closed",
+            Self::OverlayFailed { .. } => "overlay_failed",
+            Self::DailyLimitWarning { .. } => "daily_limit_warning",
+            Self::DailyLimitActionPending { .. } => "daily_limit_action_pending",
+            Self::DailyLimitActionCancelled => "daily_limit_action_cancelled",
+            Self::DailyReset { .. } => "daily_reset",
+            Self::DailySummary { .. } => "daily_summary",
+            Self::IntervalsAdjusted { .. } => "intervals_adjusted",
+            Self::WeeklyLimitWarning { .. } => "weekly_limit_warning",
+            Self::WeeklyLimitReached { .. } => "weekly_limit_reached",
+            Self::AppLimitReached { .. } => "app_limit_reached",
+            Self::MilestoneReached { .. } => "milestone_reached",
+            Self::Nudge { .. } => "nudge",
+            Self::WindDownStarted { .. } => "wind_down_started",
+            Self::WindDownCutoff => "wind_down_cutoff",
+            Self::WindDownBudgetReached { .. } => "wind_down_budget_reached",
+            Self::VacationStarted { .. } => "vacation_started",
+            Self::VacationEnded => "vacation_ended",
+            Self::GuestStarted { .. } => "guest_started",
+            Self::GuestEnded => "guest_ended",
+            Self::PresentationStarted { .. } => "presentation_started",
+            Self::PresentationEnded { .. } => "presentation_ended",
+            Self::ConfigReloaded => "config_reloaded",
+            Self::ConfigError => "config_error",
+            Self::RuntimeCrashed { .. } => "runtime_crashed",
+        }
+    }
+
+    pub fn break_kind(&self) -> Option<BreakKind> {
+        match self {
+            Self::BreakDue { break_kind }
+            | Self::BreakDeferred { break_kind, .. }
+            | Self::BreakStarted { break_kind, .. }
+            | Self::BreakTick { break_kind, .. }
+            | Self::BreakCompleted { break_kind }
+            | Self::BreakSnoozed { break_kind, .. }
+            | Self::BreakEscalated { break_kind }
+            | Self::BreakForced { break_kind }
+            | Self::BreakEndedEarly { break_kind, .. }
+            | Self::OverlayClosed { break_kind, .. }
+            | Self::OverlayFailed { break_kind } => Some(*break_kind),
+            Self::DailyLimitWarning { .. }
+            | Self::DailyLimitActionPending { .. }
+            | Self::DailyLimitActionCancelled => Some(BreakKind::DailyLimit),
+            _ => None,
+        }
+    }
+}
+
+/// Serializes reasons through their string ids rather than variant names,
+/// so the schema does not follow Rust renames. `BreakKind` does this itself.
+#[cfg(feature = "serde")]
+mod id {
+    use serde::{Deserialize, Deserializer, Serializer, de::Error};
+
+    use crate::{
+        analytics::MilestoneKind,
+        timer::{AdaptReason, DeferReason},
+    };
+
+    pub trait Id: Sized {
+        fn to_id(&self) -> String;
+        fn from_id(value: &str) -> Option<Self>;
+    }
+
+    impl Id for DeferReason {
+        fn to_id(&self) -> String {
+            self.as_str().into()
+        }
+
+        fn from_id(value: &str) -> Option<Self> {
+            Self::parse(value)
+        }
+    }
+
+    impl Id for AdaptReason {
+        fn to_id(&self) -> String {
+            self.as_str().into()
+        }
+
+        fn from_id(value: &str) -> Option<Self> {
+            Self::parse(value)
+        }
+    }
+
+    impl Id for MilestoneKind {
+        fn to_id(&self) -> String {
+            self.as_str().into()
+        }
+
+        fn from_id(value: &str) -> Option<Self> {
+            Self::parse(value)
+        }
+    }
+
+    pub fn serialize<T: Id, S: Serializer>(value: &T, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&value.to_id())
+    }
+
+    pub fn deserialize<'de, T: Id, D: Deserializer<'de>>(deserializer: D) -> Result<T, D::Error> {
+        let value = String::deserialize(deserializer)?;
+        T::from_id(&value).ok_or_else(|| D::Error::custom(format!("unknown id `{value}`")))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ids_round_trip() {
+        for kind in [
+            BreakKind::Micro,
+            BreakKind::Rest,
+            BreakKind::DailyLimit,
+            BreakKind::Custom(3),
+        ] {
+            assert_eq!(BreakKind::parse(&kind.id()), Some(kind));
+        }
+        assert_eq!(BreakKind::parse("custom-x"), None);
+        for reason in [
+            DeferReason::DoNotDisturb,
+            DeferReason::Fullscreen,
+            DeferReason::MediaPlaying,
+            DeferReason::Meeting,
+        ] {
+            assert_eq!(DeferReason::parse(reason.as_str()), Some(reason));
+        }
+        for reason in [
+            AdaptReason::FrequentSkips,
+            AdaptReason::LongActiveStreak,
+            AdaptReason::GoodCompliance,
+            AdaptReason::Disabled,
+        ] {
+            assert_eq!(AdaptReason::parse(reason.as_str()), Some(reason));
+        }
+    }
+
+    #[test]
+    fn limit_events_belong_to_the_daily_limit() {
+        let event = RuntimeEvent::DailyLimitActionPending { grace_seconds: 60 };
+        assert_eq!(event.kind(), "daily_limit_action_pending");
+        assert_eq!(event.break_kind(), Some(BreakKind::DailyLimit));
+        assert_eq!(RuntimeEvent::GuestEnded.break_kind(), None);
+    }
+}
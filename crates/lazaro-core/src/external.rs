@@ -0,0 +1,264 @@
+use std::collections::BTreeMap;
+
+use crate::{analytics::DailyAggregate, calendar::days_from_civil};
+
+/// Other break reminders whose data can be imported.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ExternalTool {
+    Workrave,
+    Stretchly,
+    SafeEyes,
+}
+
+impl ExternalTool {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "workrave" => Some(Self::Workrave),
+            "stretchly" => Some(Self::Stretchly),
+            "safe_eyes" => Some(Self::SafeEyes),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Workrave => "workrave",
+            Self::Stretchly => "stretchly",
+            Self::SafeEyes => "safe_eyes",
+        }
+    }
+
+    pub fn display_name(self) -> &'static str {
+        match self {
+            Self::Workrave => "Workrave",
+            Self::Stretchly => "Stretchly",
+            Self::SafeEyes => "Safe Eyes",
+        }
+    }
+}
+
+// Workrave break ids and the per-break and misc value slots it writes.
+const WORKRAVE_MICRO: usize = 0;
+const WORKRAVE_REST: usize = 1;
+const WORKRAVE_DAILY_LIMIT: usize = 2;
+const WORKRAVE_PROMPTED: usize = 0;
+const WORKRAVE_TAKEN: usize = 1;
+const WORKRAVE_NATURAL_TAKEN: usize = 2;
+const WORKRAVE_SKIPPED: usize = 3;
+const WORKRAVE_ACTIVE_TIME: usize = 0;
+
+/// Reads Workrave's `historystats` into per-day totals, keyed by days since
+/// 1970-01-01. Each `D` record opens a day; its `B` lines carry break counts
+/// and its `m` line the active time. Malformed records are skipped.
+pub fn parse_workrave_history(source: &str) -> BTreeMap<i64, DailyAggregate> {
+    let mut days: BTreeMap<i64, DailyAggregate> = BTreeMap::new();
+    let mut current: Option<i64> = None;
+
+    for line in source.lines() {
+        let mut fields = line.split_whitespace();
+        let tag = fields.next();
+        let numbers: Vec<u64> = fields.filter_map(|field| field.parse().ok()).collect();
+        match tag {
+            // Day of month, zero-based month, years since 1900.
+            Some("D") => {
+                current = match numbers.as_slice() {
+                    [day, month, year, ..] if (1..=31).contains(day) && *month < 12 => Some(
+                        days_from_civil(1900 + *year as i64, *month as i64 + 1, *day as i64),
+                    ),
+                    _ => None,
+                };
+            }
+            Some("B") => {
+                let (Some(day), [id, count, values @ ..]) = (current, numbers.as_slice()) else {
+                    continue;
+                };
+                let values = &values[..(*count as usize).min(values.len())];
+                let value = |slot: usize| values.get(slot).map_or(0, |value| *value as u32);
+                let taken = value(WORKRAVE_TAKEN).saturating_add(value(WORKRAVE_NATURAL_TAKEN));
+                let aggregate = days.entry(day).or_default();
+                match *id as usize {
+                    WORKRAVE_MICRO => {
+                        aggregate.micro_done = aggregate.micro_done.saturating_add(taken)
+                    }
+                    WORKRAVE_REST => {
+                        aggregate.rest_done = aggregate.rest_done.saturating_add(taken)
+                    }
+                    WORKRAVE_DAILY_LIMIT => {
+                        aggregate.daily_limit_hits = aggregate
+                            .daily_limit_hits
+                            .saturating_add(value(WORKRAVE_PROMPTED).min(1))
+                    }
+                    _ => continue,
+                }
+                aggregate.skipped = aggregate.skipped.saturating_add(value(WORKRAVE_SKIPPED));
+            }
+            Some("m") => {
+                let (Some(day), [count, values @ ..]) = (current, numbers.as_slice()) else {
+                    continue;
+                };
+                if *count as usize > WORKRAVE_ACTIVE_TIME
+                    && let Some(active) = values.get(WORKRAVE_ACTIVE_TIME)
+                {
+                    let aggregate = days.entry(day).or_default();
+                    aggregate.active_seconds = aggregate.active_seconds.saturating_add(*active);
+                }
+            }
+            _ => {}
+        }
+    }
+    days
+}
+
+/// Maps Stretchly's `config.json`, flattened to top-level key/value pairs,
+/// to Lazaro setting changes. Stretchly counts in milliseconds and spaces
+/// long breaks as a number of mini breaks.
+pub fn stretchly_settings(values: &BTreeMap<String, String>) -> Vec<(&'static str, String)> {
+    let millis = |key: &str| number(values, key).map(|value| value / 1000);
+    let micro_interval = millis("microbreakInterval");
+    let rest_interval = micro_interval.and_then(|interval| {
+        let mini_breaks = number(values, "breakInterval")?;
+        Some(interval.saturating_mul(mini_breaks.saturating_add(1)))
+    });
+
+    let mut changes = Vec::new();
+    push(&mut changes, "micro_interval_seconds", micro_interval);
+    push(
+        &mut changes,
+        "micro_duration_seconds",
+        millis("microbreakDuration"),
+    );
+    push(&mut changes, "micro_enabled", flag(values, "microbreak"));
+    push(&mut changes, "rest_interval_seconds", rest_interval);
+    push(
+        &mut changes,
+        "rest_duration_seconds",
+        millis("breakDuration"),
+    );
+    push(&mut changes, "rest_enabled", flag(values, "break"));
+    changes
+}
+
+/// Maps Safe Eyes' `safeeyes.json`, flattened to top-level key/value pairs,
+/// to Lazaro setting changes. Intervals are in minutes, durations in
+/// seconds; older versions space long breaks as a number of short ones.
+pub fn safe_eyes_settings(values: &BTreeMap<String, String>) -> Vec<(&'static str, String)> {
+    let minutes = |key: &str| number(values, key).map(|value| value.saturating_mul(60));
+    let micro_interval = minutes("short_break_interval");
+    let rest_interval = minutes("long_break_interval").or_else(|| {
+        let short_breaks = number(values, "no_of_short_breaks_per_long_break")?;
+        Some(micro_interval?.saturating_mul(short_breaks.saturating_add(1)))
+    });
+
+    let mut changes = Vec::new();
+    push(&mut changes, "micro_interval_seconds", micro_interval);
+    push(
+        &mut changes,
+        "micro_duration_seconds",
+        number(values, "short_break_duration"),
+    );
+    push(&mut changes, "rest_interval_seconds", rest_interval);
+    push(
+        &mut changes,
+        "rest_duration_seconds",
+        number(values, "long_break_duration"),
+    );
+    changes
+}
+
+fn number(values: &BTreeMap<String, String>, key: &str) -> Option<u64> {
+    let value: f64 = values.get(key)?.trim().parse().ok()?;
+    (value.is_finite() && value >= 0.0).then_some(value.round() as u64)
+}
+
+fn flag(values: &BTreeMap<String, String>, key: &str) -> Option<bool> {
+    values.get(key)?.trim().parse().ok()
+}
+
+fn push<T: ToString>(
+    changes: &mut Vec<(&'static str, String)>,
+    key: &'static str,
+    value: Option<T>,
+) {
+    if let Some(value) = value {
+        changes.push((key, value.to_string()));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn values(pairs: &[(&str, &str)]) -> BTreeMap<String, String> {
+        pairs
+            .iter()
+            .map(|(key, value)| (key.to_string(), value.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn workrave_history_becomes_daily_totals() {
+        let source = "WorkRaveStats 4\n\
+D 2 0 124 9 0 2 0 124 18 30\n\
+B 0 7 30 25 3 2 1 30 0\n\
+B 1 7 4 2 1 1 0 4 0\n\
+B 2 7 1 0 0 0 0 1 0\n\
+m 7 21600 0 0 0 0 0 0\n\
+D 40 0 124 9 0 2 0 124 18 30\n\
+B 0 7 5 5 0 0 0 5 0\n\
+D 3 0 124 9 0 3 0 124 18 30\n\
+m 1 3600\n";
+        let days = parse_workrave_history(source);
+        let jan_2 = days_from_civil(2024, 1, 2);
+
+        assert_eq!(days.len(), 2);
+        assert_eq!(
+            days[&jan_2],
+            DailyAggregate {
+                active_seconds: 21_600,
+                micro_done: 28,
+                rest_done: 3,
+                daily_limit_hits: 1,
+                skipped: 3,
+                ..DailyAggregate::default()
+            }
+        );
+        assert_eq!(days[&(jan_2 + 1)].active_seconds, 3_600);
+    }
+
+    #[test]
+    fn stretchly_and_safe_eyes_map_to_timer_settings() {
+        let stretchly = stretchly_settings(&values(&[
+            ("microbreakInterval", "600000"),
+            ("microbreakDuration", "20000"),
+            ("breakInterval", "2"),
+            ("breakDuration", "300000"),
+            ("break", "false"),
+        ]));
+        assert_eq!(
+            stretchly,
+            vec![
+                ("micro_interval_seconds", "600".to_string()),
+                ("micro_duration_seconds", "20".to_string()),
+                ("rest_interval_seconds", "1800".to_string()),
+                ("rest_duration_seconds", "300".to_string()),
+                ("rest_enabled", "false".to_string()),
+            ]
+        );
+
+        let safe_eyes = safe_eyes_settings(&values(&[
+            ("short_break_interval", "15"),
+            ("short_break_duration", "15"),
+            ("no_of_short_breaks_per_long_break", "4"),
+            ("long_break_duration", "60.0"),
+        ]));
+        assert_eq!(
+            safe_eyes,
+            vec![
+                ("micro_interval_seconds", "900".to_string()),
+                ("micro_duration_seconds", "15".to_string()),
+                ("rest_interval_seconds", "4500".to_string()),
+                ("rest_duration_seconds", "60".to_string()),
+            ]
+        );
+    }
+}
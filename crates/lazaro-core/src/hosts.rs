@@ -0,0 +1,135 @@
+//! The blocklist Lázaro keeps in a hosts-format file. Only the lines between
+//! its markers are Lázaro's; everything else in the file is left untouched.
+
+use std::fmt;
+
+const BLOCK_BEGIN: &str = "# BEGIN Lázaro: filled during strict breaks only.";
+const BLOCK_END: &str = "# END Lázaro";
+// Whole-file format used before the block; such a file is entirely ours.
+const LEGACY_HEADER: &str = "# Managed by Lázaro: filled during strict breaks only.\n";
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum HostsError {
+    /// A begin marker without its end; editing could drop lines that are
+    /// not ours.
+    UnterminatedBlock,
+}
+
+impl fmt::Display for HostsError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            HostsError::UnterminatedBlock => write!(f, "Lázaro block has no end marker"),
+        }
+    }
+}
+
+impl std::error::Error for HostsError {}
+
+/// `contents` with the Lázaro block listing `hosts`, or without one when
+/// `hosts` is empty.
+pub fn with_block(contents: &str, hosts: &[&str]) -> Result<String, HostsError> {
+    let mut updated = if contents.starts_with(LEGACY_HEADER) {
+        String::new()
+    } else {
+        without_block(contents)?
+    };
+    if hosts.is_empty() {
+        return Ok(updated);
+    }
+
+    if !updated.is_empty() && !updated.ends_with('\n') {
+        updated.push('\n');
+    }
+    updated.push_str(BLOCK_BEGIN);
+    updated.push('\n');
+    for host in hosts {
+        updated.push_str(&format!("0.0.0.0 {host}\n:: {host}\n"));
+    }
+    updated.push_str(BLOCK_END);
+    updated.push('\n');
+    Ok(updated)
+}
+
+/// Whether `contents` still lists hosts of ours, e.g. after a crash.
+pub fn has_block(contents: &str) -> bool {
+    if contents.starts_with(LEGACY_HEADER) {
+        return contents != LEGACY_HEADER;
+    }
+    contents.lines().any(|line| line.trim_end() == BLOCK_BEGIN)
+}
+
+fn without_block(contents: &str) -> Result<String, HostsError> {
+    let mut kept = String::with_capacity(contents.len());
+    let mut inside = false;
+    for line in contents.split_inclusive('\n') {
+        let marker = line.trim_end();
+        if inside {
+            inside = marker != BLOCK_END;
+        } else if marker == BLOCK_BEGIN {
+            inside = true;
+        } else {
+            kept.push_str(line);
+        }
+    }
+    if inside {
+        return Err(HostsError::UnterminatedBlock);
+    }
+    Ok(kept)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SYSTEM_HOSTS: &str = "127.0.0.1 localhost\n::1 localhost\n";
+
+    #[test]
+    fn foreign_file_keeps_its_lines() {
+        let blocked = with_block(SYSTEM_HOSTS, &["example.com"]).unwrap();
+
+        assert!(blocked.starts_with(SYSTEM_HOSTS));
+        assert!(blocked.contains("0.0.0.0 example.com\n:: example.com\n"));
+        assert!(has_block(&blocked));
+        assert!(!has_block(SYSTEM_HOSTS));
+
+        let cleared = with_block(&blocked, &[]).unwrap();
+        assert_eq!(cleared, SYSTEM_HOSTS);
+    }
+
+    #[test]
+    fn foreign_file_without_trailing_newline() {
+        let blocked = with_block("127.0.0.1 localhost", &["example.com"]).unwrap();
+
+        assert!(blocked.starts_with(&format!("127.0.0.1 localhost\n{BLOCK_BEGIN}\n")));
+        assert_eq!(with_block(&blocked, &[]).unwrap(), "127.0.0.1 localhost\n");
+    }
+
+    #[test]
+    fn block_is_replaced_not_repeated() {
+        let first = with_block(SYSTEM_HOSTS, &["a.example"]).unwrap();
+        let second = with_block(&first, &["b.example"]).unwrap();
+
+        assert_eq!(second.matches(BLOCK_BEGIN).count(), 1);
+        assert!(!second.contains("a.example"));
+        assert!(second.contains("0.0.0.0 b.example\n"));
+    }
+
+    #[test]
+    fn legacy_owned_file_is_replaced() {
+        let legacy = format!("{LEGACY_HEADER}0.0.0.0 example.com\n:: example.com\n");
+        assert!(has_block(&legacy));
+        assert!(!has_block(LEGACY_HEADER));
+
+        assert_eq!(with_block(&legacy, &[]).unwrap(), "");
+        let blocked = with_block(&legacy, &["b.example"]).unwrap();
+        assert!(blocked.starts_with(BLOCK_BEGIN));
+        assert!(!blocked.contains("example.com"));
+    }
+
+    #[test]
+    fn unterminated_block_is_left_alone() {
+        let broken = format!("{SYSTEM_HOSTS}{BLOCK_BEGIN}\n0.0.0.0 example.com\n");
+
+        assert_eq!(with_block(&broken, &[]), Err(HostsError::UnterminatedBlock));
+    }
+}
@@ -1,4 +1,46 @@
+//! Break-reminder engine behind Lázaro, usable from any shell: the Tauri
+//! desktop app, a terminal UI, or a Qt frontend.
+//!
+//! Start from [`prelude`]. Its items follow semver: while the crate is at
+//! `0.x`, a breaking change to any of them bumps the minor version. Other
+//! modules are public for the desktop app and may change in any release.
+//!
+//! Features:
+//! - `serde`: `Serialize`/`Deserialize` for settings, engine snapshots,
+//!   analytics aggregates and the versioned [`event::RuntimeEvent`] schema.
+//! - `tracing`: debug events from the engine under the `lazaro_core` target.
+//!
+//! The engine takes time as seconds since the Unix epoch on every call and
+//! never reads a clock itself; see [`clock::Clock`].
+
+#[cfg(feature = "tracing")]
+macro_rules! trace_event {
+    ($($arg:tt)*) => {
+        tracing::debug!(target: "lazaro_core", $($arg)*)
+    };
+}
+
+#[cfg(not(feature = "tracing"))]
+macro_rules! trace_event {
+    ($($arg:tt)*) => {
+        if false {
+            let _ = format_args!($($arg)*);
+        }
+    };
+}
+
 pub mod analytics;
+pub mod calendar;
+pub mod clock;
 pub mod config;
+pub mod crypto;
+pub mod event;
+pub mod external;
+pub mod hosts;
+pub mod nudge;
+pub mod policy;
+pub mod prelude;
 pub mod profile;
+pub mod sync;
 pub mod timer;
+pub mod update;
@@ -0,0 +1,245 @@
+//! Rule-based tips drawn from how the day is going: breaks mostly skipped,
+//! a very long stretch without one, or work late at night.
+//!
+//! The frontend gathers a [`NudgeInput`] now and then and asks a
+//! [`NudgeTracker`] whether to say anything. The tracker spaces nudges by
+//! `NudgeSettings::interval_seconds` and repeats a rule at most once per
+//! [`RULE_COOLDOWN_SECONDS`], so a bad day does not turn into nagging.
+
+use std::collections::BTreeMap;
+
+use crate::{analytics::Compliance, config::NudgeSettings, timer::BreakKind};
+
+/// Skip rate, counting snoozes, from which a break kind is worth a nudge.
+pub const SKIP_RATE_PERCENT: u8 = 60;
+/// Prompts of a kind needed before its skip rate means anything.
+pub const MIN_SKIP_RATE_PROMPTS: u32 = 4;
+/// Activity without a break that counts as a very long focus session.
+pub const LONG_FOCUS_SECONDS: u64 = 90 * 60;
+/// Local hours, `[start, end)` across midnight, that count as late night.
+pub const LATE_NIGHT_START_HOUR: u8 = 23;
+pub const LATE_NIGHT_END_HOUR: u8 = 5;
+/// Least time before the same rule fires again.
+pub const RULE_COOLDOWN_SECONDS: u64 = 6 * 3_600;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum NudgeRule {
+    SkipRate,
+    LongFocus,
+    LateNight,
+}
+
+impl NudgeRule {
+    pub const ALL: [Self; 3] = [Self::SkipRate, Self::LongFocus, Self::LateNight];
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::SkipRate => "skip_rate",
+            Self::LongFocus => "long_focus",
+            Self::LateNight => "late_night",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|rule| rule.as_str() == value)
+    }
+
+    fn enabled(self, settings: &NudgeSettings) -> bool {
+        match self {
+            Self::SkipRate => settings.skip_rate,
+            Self::LongFocus => settings.long_focus,
+            Self::LateNight => settings.late_night,
+        }
+    }
+}
+
+/// One suggestion with the numbers behind it. With the `serde` feature it
+/// serializes tagged by `rule`, e.g. `{"rule":"long_focus","seconds":6000}`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(tag = "rule", rename_all = "snake_case")
+)]
+pub enum Nudge {
+    /// `percent` of today's `break_kind` prompts were skipped or snoozed.
+    SkipRate { break_kind: BreakKind, percent: u8 },
+    /// Active for `seconds` without a break.
+    LongFocus { seconds: u64 },
+    /// Still active at local `hour`.
+    LateNight { hour: u8 },
+}
+
+impl Nudge {
+    pub fn rule(&self) -> NudgeRule {
+        match self {
+            Self::SkipRate { .. } => NudgeRule::SkipRate,
+            Self::LongFocus { .. } => NudgeRule::LongFocus,
+            Self::LateNight { .. } => NudgeRule::LateNight,
+        }
+    }
+}
+
+/// What the rules look at, gathered by the frontend.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct NudgeInput {
+    /// Today's prompts per break kind.
+    pub compliance: Vec<(BreakKind, Compliance)>,
+    /// Activity since the last break.
+    pub focus_seconds: u64,
+    /// Local hour of day, 0-23.
+    pub local_hour: u8,
+    /// Whether the user is at the computer right now.
+    pub active: bool,
+}
+
+pub fn is_late_night(hour: u8) -> bool {
+    !(LATE_NIGHT_END_HOUR..LATE_NIGHT_START_HOUR).contains(&hour)
+}
+
+/// Remembers when each rule last fired. Kept in memory only: after a
+/// restart a rule may fire once more.
+#[derive(Clone, Debug, Default)]
+pub struct NudgeTracker {
+    last_any: Option<u64>,
+    last_by_rule: BTreeMap<NudgeRule, u64>,
+}
+
+impl NudgeTracker {
+    /// The first rule, in [`NudgeRule::ALL`] order, that holds and is not
+    /// held back by spacing; it is then marked as fired at `now`.
+    pub fn evaluate(
+        &mut self,
+        settings: &NudgeSettings,
+        input: &NudgeInput,
+        now: u64,
+    ) -> Option<Nudge> {
+        if !settings.enabled
+            || self
+                .last_any
+                .is_some_and(|last| now.saturating_sub(last) < settings.interval_seconds)
+        {
+            return None;
+        }
+        let nudge = NudgeRule::ALL
+            .into_iter()
+            .filter(|rule| rule.enabled(settings))
+            .filter(|rule| {
+                self.last_by_rule
+                    .get(rule)
+                    .is_none_or(|last| now.saturating_sub(*last) >= RULE_COOLDOWN_SECONDS)
+            })
+            .find_map(|rule| check(rule, input))?;
+        self.last_any = Some(now);
+        self.last_by_rule.insert(nudge.rule(), now);
+        Some(nudge)
+    }
+}
+
+fn check(rule: NudgeRule, input: &NudgeInput) -> Option<Nudge> {
+    match rule {
+        NudgeRule::SkipRate => input
+            .compliance
+            .iter()
+            .filter(|(_, compliance)| compliance.total() >= MIN_SKIP_RATE_PROMPTS)
+            .map(|(kind, compliance)| (*kind, compliance.skip_rate_percent()))
+            .filter(|(_, percent)| *percent >= SKIP_RATE_PERCENT)
+            .max_by_key(|(_, percent)| *percent)
+            .map(|(break_kind, percent)| Nudge::SkipRate {
+                break_kind,
+                percent,
+            }),
+        NudgeRule::LongFocus => {
+            (input.focus_seconds >= LONG_FOCUS_SECONDS).then_some(Nudge::LongFocus {
+                seconds: input.focus_seconds,
+            })
+        }
+        NudgeRule::LateNight => {
+            (input.active && is_late_night(input.local_hour)).then_some(Nudge::LateNight {
+                hour: input.local_hour,
+            })
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Settings;
+
+    #[test]
+    fn nudges_are_spaced_and_rules_cool_down() {
+        let settings = Settings::default().nudges;
+        let mut tracker = NudgeTracker::default();
+        let mut input = NudgeInput {
+            compliance: vec![
+                (
+                    BreakKind::Micro,
+                    Compliance {
+                        taken: 3,
+                        avoided: 7,
+                    },
+                ),
+                (
+                    BreakKind::Rest,
+                    Compliance {
+                        taken: 0,
+                        avoided: 2,
+                    },
+                ),
+            ],
+            focus_seconds: LONG_FOCUS_SECONDS,
+            local_hour: 23,
+            active: true,
+        };
+
+        assert_eq!(
+            tracker.evaluate(&settings, &input, 1_000),
+            Some(Nudge::SkipRate {
+                break_kind: BreakKind::Micro,
+                percent: 70,
+            })
+        );
+        assert_eq!(tracker.evaluate(&settings, &input, 1_001), None);
+        let later = 1_000 + settings.interval_seconds;
+        assert_eq!(
+            tracker.evaluate(&settings, &input, later),
+            Some(Nudge::LongFocus {
+                seconds: LONG_FOCUS_SECONDS,
+            })
+        );
+        let later = later + settings.interval_seconds;
+        assert_eq!(
+            tracker.evaluate(&settings, &input, later),
+            Some(Nudge::LateNight { hour: 23 })
+        );
+        // Everything is cooling down now.
+        let later = later + settings.interval_seconds;
+        assert_eq!(tracker.evaluate(&settings, &input, later), None);
+
+        input.active = false;
+        input.focus_seconds = 0;
+        let next_day = 1_000 + RULE_COOLDOWN_SECONDS;
+        assert!(matches!(
+            tracker.evaluate(&settings, &input, next_day),
+            Some(Nudge::SkipRate { .. })
+        ));
+
+        let mut quiet = settings.clone();
+        quiet.skip_rate = false;
+        let mut tracker = NudgeTracker::default();
+        assert_eq!(tracker.evaluate(&quiet, &input, next_day), None);
+        for rule in NudgeRule::ALL {
+            assert_eq!(NudgeRule::parse(rule.as_str()), Some(rule));
+        }
+    }
+
+    #[test]
+    fn late_night_wraps_midnight() {
+        assert!(is_late_night(23));
+        assert!(is_late_night(0));
+        assert!(is_late_night(4));
+        assert!(!is_late_night(5));
+        assert!(!is_late_night(22));
+    }
+}
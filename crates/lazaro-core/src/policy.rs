@@ -0,0 +1,120 @@
+use std::collections::BTreeMap;
+
+use crate::config::{ConfigError, Settings, apply_setting, numeric_setting};
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Policy {
+    locked: BTreeMap<String, String>,
+    minimums: BTreeMap<String, u64>,
+}
+
+impl Policy {
+    pub fn lock(&mut self, key: &str, value: &str) -> Result<(), ConfigError> {
+        apply_setting(&mut Settings::default(), key, value)?;
+        self.locked.insert(key.to_string(), value.to_string());
+        Ok(())
+    }
+
+    pub fn require_minimum(&mut self, key: &str, minimum: u64) -> Result<(), ConfigError> {
+        if numeric_setting(&Settings::default(), key).is_none() {
+            return Err(ConfigError::InvalidValue {
+                key: key.to_string(),
+                value: minimum.to_string(),
+            });
+        }
+        self.minimums.insert(key.to_string(), minimum);
+        Ok(())
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.locked.is_empty() && self.minimums.is_empty()
+    }
+
+    pub fn locked_fields(&self) -> impl Iterator<Item = &str> {
+        self.locked.keys().map(String::as_str)
+    }
+
+    pub fn minimums(&self) -> impl Iterator<Item = (&str, u64)> {
+        self.minimums
+            .iter()
+            .map(|(key, minimum)| (key.as_str(), *minimum))
+    }
+
+    pub fn enforce(&self, settings: &mut Settings) {
+        for (key, value) in &self.locked {
+            let _ = apply_setting(settings, key, value);
+        }
+        for (key, minimum) in &self.minimums {
+            if numeric_setting(settings, key).is_some_and(|current| current < *minimum) {
+                let _ = apply_setting(settings, key, &minimum.to_string());
+            }
+        }
+    }
+
+    pub fn violations(&self, requested: &Settings) -> Vec<String> {
+        let mut fields = Vec::new();
+        for (key, value) in &self.locked {
+            let mut pinned = requested.clone();
+            if apply_setting(&mut pinned, key, value).is_ok() && pinned != *requested {
+                fields.push(key.clone());
+            }
+        }
+        for (key, minimum) in &self.minimums {
+            if numeric_setting(requested, key).is_some_and(|current| current < *minimum) {
+                fields.push(key.clone());
+            }
+        }
+        fields
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::BlockLevel;
+
+    fn sample_policy() -> Policy {
+        let mut policy = Policy::default();
+        policy.lock("block_level", "strict").unwrap();
+        policy
+            .require_minimum("micro_duration_seconds", 30)
+            .unwrap();
+        policy
+    }
+
+    #[test]
+    fn enforce_pins_locked_values_and_raises_minimums() {
+        let mut settings = Settings::default();
+        sample_policy().enforce(&mut settings);
+
+        assert_eq!(settings.block_level, BlockLevel::Strict);
+        assert_eq!(settings.micro.duration_seconds, 30);
+    }
+
+    #[test]
+    fn violations_list_only_fields_that_break_the_policy() {
+        let policy = sample_policy();
+        let mut settings = Settings::default();
+        policy.enforce(&mut settings);
+        assert!(policy.violations(&settings).is_empty());
+
+        settings.block_level = BlockLevel::Soft;
+        settings.micro.duration_seconds = 10;
+        settings.rest.duration_seconds = 60;
+        assert_eq!(
+            policy.violations(&settings),
+            vec![
+                "block_level".to_string(),
+                "micro_duration_seconds".to_string()
+            ]
+        );
+    }
+
+    #[test]
+    fn rejects_unknown_or_non_numeric_keys() {
+        let mut policy = Policy::default();
+        assert!(policy.lock("volume", "3").is_err());
+        assert!(policy.require_minimum("block_level", 1).is_err());
+        assert!(policy.is_empty());
+    }
+}
@@ -0,0 +1,26 @@
+//! The types a frontend needs to embed the break engine:
+//!
+//! ```
+//! use lazaro_core::prelude::*;
+//!
+//! let clock = ManualClock::new(1_700_000_000);
+//! let mut engine = TimerEngine::new(Settings::default(), clock.now_unix());
+//! for event in engine.on_activity(60, clock.advance(60)) {
+//!     if let EngineEvent::BreakDue(kind) = event {
+//!         engine.start_break(kind);
+//!     }
+//! }
+//! let snapshot: EngineSnapshot = engine.snapshot(clock.now_unix());
+//! let engine = TimerEngine::restore(Settings::default(), Some(&snapshot), clock.now_unix());
+//! # let _ = engine;
+//! ```
+
+pub use crate::analytics::{AnalyticsStore, DailyAggregate, WeeklySummary};
+pub use crate::clock::{Clock, ElapsedMillis, ElapsedSeconds, ManualClock, SystemClock};
+pub use crate::config::{ConfigError, Settings};
+pub use crate::event::{EVENT_SCHEMA_VERSION, RuntimeEvent};
+pub use crate::profile::{Profile, ProfileStore};
+pub use crate::timer::{
+    BreakKind, BreakOutcome, BreakProgress, DailyLimitForecast, DailyStatus, DeferReason,
+    EngineEvent, EngineProgress, EngineSnapshot, ScheduleReason, TimerEngine, TimerExplanation,
+};
@@ -3,6 +3,7 @@ use std::collections::BTreeMap;
 use crate::config::Settings;
 
 #[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Profile {
     pub id: String,
     pub name: String,
@@ -0,0 +1,103 @@
+use std::collections::BTreeMap;
+
+use crate::analytics::DailyAggregate;
+
+/// A synced section together with the device that last wrote it.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Stamped<T> {
+    pub device_id: String,
+    pub updated_at: u64,
+    pub value: T,
+}
+
+/// Picks the most recently written copy of a section. Equal timestamps are
+/// broken by device id so every device settles on the same winner.
+pub fn last_writer_wins<T>(candidates: impl IntoIterator<Item = Stamped<T>>) -> Option<Stamped<T>> {
+    candidates
+        .into_iter()
+        .max_by(|a, b| (a.updated_at, &a.device_id).cmp(&(b.updated_at, &b.device_id)))
+}
+
+impl DailyAggregate {
+    pub fn merge(&mut self, other: &DailyAggregate) {
+        self.active_seconds = self.active_seconds.saturating_add(other.active_seconds);
+        self.micro_done = self.micro_done.saturating_add(other.micro_done);
+        self.rest_done = self.rest_done.saturating_add(other.rest_done);
+        self.custom_done = self.custom_done.saturating_add(other.custom_done);
+        self.daily_limit_hits = self.daily_limit_hits.saturating_add(other.daily_limit_hits);
+        self.skipped = self.skipped.saturating_add(other.skipped);
+    }
+}
+
+/// Sums per-day analytics across devices. Each device only records its own
+/// activity, so adding them never double counts.
+pub fn merge_days<'a>(
+    devices: impl IntoIterator<Item = &'a BTreeMap<i64, DailyAggregate>>,
+) -> BTreeMap<i64, DailyAggregate> {
+    let mut merged: BTreeMap<i64, DailyAggregate> = BTreeMap::new();
+    for days in devices {
+        for (day, aggregate) in days {
+            merged.entry(*day).or_default().merge(aggregate);
+        }
+    }
+    merged
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn stamped(device_id: &str, updated_at: u64, value: u32) -> Stamped<u32> {
+        Stamped {
+            device_id: device_id.into(),
+            updated_at,
+            value,
+        }
+    }
+
+    #[test]
+    fn newest_section_wins_with_device_tiebreak() {
+        let winner = last_writer_wins([
+            stamped("laptop", 100, 1),
+            stamped("desktop", 200, 2),
+            stamped("aaa", 200, 3),
+        ])
+        .expect("winner");
+        assert_eq!(winner.value, 2);
+        assert_eq!(last_writer_wins(Vec::<Stamped<u32>>::new()), None);
+    }
+
+    #[test]
+    fn merge_days_sums_each_device() {
+        let laptop = BTreeMap::from([(
+            10,
+            DailyAggregate {
+                active_seconds: 3_600,
+                micro_done: 2,
+                ..Default::default()
+            },
+        )]);
+        let desktop = BTreeMap::from([
+            (
+                10,
+                DailyAggregate {
+                    active_seconds: 1_800,
+                    rest_done: 1,
+                    ..Default::default()
+                },
+            ),
+            (
+                11,
+                DailyAggregate {
+                    active_seconds: 60,
+                    ..Default::default()
+                },
+            ),
+        ]);
+
+        let merged = merge_days([&laptop, &desktop]);
+        assert_eq!(merged[&10].active_seconds, 5_400);
+        assert_eq!(merged[&10].breaks_taken(), 3);
+        assert_eq!(merged[&11].active_seconds, 60);
+    }
+}
@@ -1,295 +1,1784 @@
-use crate::config::{BlockLevel, Settings};
+use std::collections::{BTreeMap, BTreeSet, VecDeque};
 
+use crate::analytics::{Compliance, week_start_day};
+use crate::config::{
+    BlockLevel, BreakTimerSettings, IntervalChangePolicy, MAX_CUSTOM_TIMERS, MAX_JITTER_PERCENT,
+    MICRO_TIMER_ID, REST_TIMER_ID, Settings, Weekday,
+};
+
+const ACTIVITY_WINDOW_SECONDS: u64 = 1_800;
+const MIN_FORECAST_SPAN_SECONDS: u64 = 60;
+const DAILY_LIMIT_PRIORITY: u8 = u8::MAX;
+const ADAPT_MIN_SAMPLES: u32 = 3;
+const ADAPT_STEP_PERCENT: u8 = 10;
+const ADAPT_SCALE_BOUNDS: (u8, u8) = (10, 200);
+const FREQUENT_SKIP_PERCENT: u8 = 50;
+const PARTIAL_BREAK_PERCENT: u64 = 50;
+const GOOD_COMPLIANCE_PERCENT: u8 = 10;
+const MAX_INTENSITY_REDUCTION_PERCENT: u8 = 90;
+
+/// Serialized as its `id`, e.g. `"micro"` or `"custom-2"`.
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum BreakKind {
     Micro,
     Rest,
     DailyLimit,
+    Custom(u8),
+}
+
+impl BreakKind {
+    /// Stable id, `custom-<index>` for custom timers.
+    pub fn id(self) -> String {
+        match self {
+            Self::Micro => "micro".into(),
+            Self::Rest => "rest".into(),
+            Self::DailyLimit => "daily_limit".into(),
+            Self::Custom(index) => format!("custom-{index}"),
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "micro" => Some(Self::Micro),
+            "rest" => Some(Self::Rest),
+            "daily_limit" => Some(Self::DailyLimit),
+            _ => value
+                .strip_prefix("custom-")
+                .and_then(|index| index.parse().ok())
+                .map(Self::Custom),
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for BreakKind {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.id())
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for BreakKind {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let value = <String as serde::Deserialize>::deserialize(deserializer)?;
+        Self::parse(&value)
+            .ok_or_else(|| serde::de::Error::custom(format!("unknown break kind `{value}`")))
+    }
 }
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum BreakOutcome {
     Completed,
     Snoozed,
     Skipped,
 }
 
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum DeferReason {
+    DoNotDisturb,
+    Fullscreen,
+    MediaPlaying,
+    Meeting,
+}
+
+impl DeferReason {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "do_not_disturb" => Some(Self::DoNotDisturb),
+            "fullscreen" => Some(Self::Fullscreen),
+            "media_playing" => Some(Self::MediaPlaying),
+            "meeting" => Some(Self::Meeting),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::DoNotDisturb => "do_not_disturb",
+            Self::Fullscreen => "fullscreen",
+            Self::MediaPlaying => "media_playing",
+            Self::Meeting => "meeting",
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum AdaptReason {
+    FrequentSkips,
+    LongActiveStreak,
+    GoodCompliance,
+    Disabled,
+}
+
+impl AdaptReason {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "frequent_skips" => Some(Self::FrequentSkips),
+            "long_active_streak" => Some(Self::LongActiveStreak),
+            "good_compliance" => Some(Self::GoodCompliance),
+            "disabled" => Some(Self::Disabled),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::FrequentSkips => "frequent_skips",
+            Self::LongActiveStreak => "long_active_streak",
+            Self::GoodCompliance => "good_compliance",
+            Self::Disabled => "disabled",
+        }
+    }
+}
+
 #[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum EngineEvent {
     BreakDue(BreakKind),
+    BreakDeferred(BreakKind, DeferReason),
     BreakStarted(BreakKind),
     BreakCompleted(BreakKind),
     BreakSnoozed(BreakKind, u64),
+    DailyLimitWarning(u8),
+    WeeklyLimitWarning(u8),
+    WeeklyLimitReached,
+    /// The application with this window class spent its daily budget.
+    AppLimitReached(String),
+    IntervalsAdjusted(u8, AdaptReason),
+    BreakEscalated(BreakKind, EscalationStage),
     DailyReset,
+    /// The evening window of `WindDownSettings` began.
+    WindDownStarted,
+    /// The wind-down cutoff passed; work should stop for the day.
+    WindDownCutoff,
+    /// Active time inside the wind-down window used up its budget.
+    WindDownBudgetReached,
+}
+
+/// Where the local day stands against the wind-down window, in the order
+/// the phases come after the daily reset.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum WindDownPhase {
+    Off,
+    Winding,
+    PastCutoff,
+}
+
+impl WindDownPhase {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Off => "off",
+            Self::Winding => "winding",
+            Self::PastCutoff => "past_cutoff",
+        }
+    }
+}
+
+/// Ordered by severity; see `EscalationSettings`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum EscalationStage {
+    Renotify,
+    ForceStart,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct BreakProgress {
+    pub elapsed_seconds: u64,
+    pub target_seconds: u64,
+}
+
+impl BreakProgress {
+    pub fn percent(&self) -> u8 {
+        if self.target_seconds == 0 {
+            return 0;
+        }
+        (self.elapsed_seconds.saturating_mul(100) / self.target_seconds).min(100) as u8
+    }
+}
+
+/// Counters carried across restarts by `TimerEngine::snapshot` and
+/// `TimerEngine::restore`.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct EngineSnapshot {
+    pub taken_at: u64,
+    pub daily_active_seconds: u64,
+    pub weekly_active_seconds: u64,
+    /// Active seconds keyed by timer id, so reordered custom timers keep theirs.
+    pub timer_active_seconds: Vec<(String, u64)>,
+    /// Today's foreground seconds of applications with a daily budget.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub app_active_seconds: Vec<(String, u64)>,
+    /// Active seconds inside today's wind-down window.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub wind_down_active_seconds: u64,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct EngineProgress {
+    pub micro: BreakProgress,
+    pub rest: BreakProgress,
+    pub daily_limit: BreakProgress,
+    pub weekly_limit: BreakProgress,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct DailyLimitForecast {
+    pub used_seconds: u64,
+    pub limit_seconds: u64,
+    pub percent: u8,
+    pub activity_density_percent: u8,
+    pub seconds_until_limit: Option<u64>,
+    pub reached_at: Option<u64>,
+    pub before_reset: bool,
+}
+
+/// Where the current day stands against the daily limit.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct DailyStatus {
+    pub used_seconds: u64,
+    pub limit_seconds: u64,
+    pub snoozes_used: u32,
+    pub max_snoozes: u32,
+    pub snoozed_until: Option<u64>,
+    pub seconds_until_reset: u64,
+}
+
+/// The constraint that decides when a timer fires next, most binding first.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum ScheduleReason {
+    Disabled,
+    /// Another break is running; no timer advances until it ends.
+    BreakActive,
+    /// The startup grace period has not run out yet.
+    Warmup,
+    Snoozed,
+    /// Still counting active time towards its interval.
+    Interval,
+    /// The daily reset comes before the limit can be reached.
+    AfterReset,
+    /// Due, and offered next.
+    Due,
+    /// Due, but waiting behind a higher-priority break.
+    Queued,
+    /// Due, but covered by another due break that restarts it.
+    Absorbed,
+    /// Due, but held back while the inhibitor is active.
+    Deferred(DeferReason),
+}
+
+/// Why a timer stands where it does, as returned by `TimerEngine::explain`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TimerExplanation {
+    pub kind: BreakKind,
+    pub accumulated_seconds: u64,
+    pub target_seconds: u64,
+    pub snoozed_until: Option<u64>,
+    /// Earliest time it can fire, assuming continuous activity from now on.
+    pub next_due_at: Option<u64>,
+    pub reason: ScheduleReason,
+}
+
+/// One interval timer as the engine sees it: the built-in micro and rest
+/// timers first, followed by the user's custom timers in settings order.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TimerSpec {
+    pub kind: BreakKind,
+    pub timer: BreakTimerSettings,
+    pub priority: u8,
+    pub resets: Vec<BreakKind>,
+}
+
+pub fn timer_specs(settings: &Settings) -> Vec<TimerSpec> {
+    let custom_timers =
+        &settings.custom_timers[..settings.custom_timers.len().min(MAX_CUSTOM_TIMERS)];
+    let kind_for = |id: &str| match id {
+        MICRO_TIMER_ID => Some(BreakKind::Micro),
+        REST_TIMER_ID => Some(BreakKind::Rest),
+        _ => custom_timers
+            .iter()
+            .position(|custom| custom.id == id)
+            .map(|index| BreakKind::Custom(index as u8)),
+    };
+
+    let mut specs = vec![
+        (BreakKind::Micro, &settings.micro, 0),
+        (BreakKind::Rest, &settings.rest, 1),
+    ];
+    specs.extend(custom_timers.iter().enumerate().map(|(index, custom)| {
+        (
+            BreakKind::Custom(index as u8),
+            &custom.timer,
+            custom.priority,
+        )
+    }));
+    specs
+        .into_iter()
+        .map(|(kind, timer, priority)| TimerSpec {
+            kind,
+            timer: timer.clone(),
+            priority,
+            resets: timer
+                .resets
+                .iter()
+                .filter_map(|id| kind_for(id))
+                .filter(|reset| *reset != kind)
+                .collect(),
+        })
+        .collect()
+}
+
+/// `TimerEngine::block_level` for callers without an engine.
+pub fn block_level(settings: &Settings, kind: BreakKind) -> BlockLevel {
+    timer_specs(settings)
+        .into_iter()
+        .find(|spec| spec.kind == kind)
+        .and_then(|spec| spec.timer.block_level)
+        .unwrap_or(settings.block_level)
 }
 
 #[derive(Clone, Debug)]
 struct OngoingBreak {
     kind: BreakKind,
-    remaining_seconds: u64,
+    /// Kept in milliseconds so a countdown ticked more often than once a
+    /// second does not lose the fractions.
+    remaining_millis: u64,
+}
+
+impl OngoingBreak {
+    fn remaining_seconds(&self) -> u64 {
+        self.remaining_millis.div_ceil(1000)
+    }
+}
+
+/// A break that is due but has been neither started nor snoozed.
+#[derive(Clone, Copy, Debug)]
+struct DueBreak {
+    kind: BreakKind,
+    /// Active time since it became due; time away does not count as ignoring it.
+    ignored_seconds: u64,
+    stage: Option<EscalationStage>,
+}
+
+#[derive(Clone, Copy, Debug, Default)]
+struct TimerState {
+    active_seconds: u64,
+    snooze_until: Option<u64>,
+    cycle: u64,
+}
+
+impl TimerState {
+    fn restart(&mut self) {
+        self.active_seconds = 0;
+        self.cycle = self.cycle.wrapping_add(1);
+    }
+}
+
+fn splitmix64(value: u64) -> u64 {
+    let mut z = value.wrapping_add(0x9E37_79B9_7F4A_7C15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    z ^ (z >> 31)
+}
+
+/// Highest threshold `percent` has reached that was not warned about yet.
+fn crossed_threshold(thresholds: &[u8], percent: u8, warned: u8) -> Option<u8> {
+    thresholds
+        .iter()
+        .copied()
+        .filter(|threshold| percent >= *threshold && *threshold > warned)
+        .max()
 }
 
 #[derive(Clone, Debug)]
 pub struct TimerEngine {
+    /// `base_settings` with today's day overrides applied.
     settings: Settings,
-    micro_active: u64,
-    rest_active: u64,
+    /// Settings as given, resolved again for each day at the daily reset.
+    base_settings: Settings,
+    timers: Vec<TimerState>,
     daily_active: u64,
-    micro_snooze_until: Option<u64>,
-    rest_snooze_until: Option<u64>,
+    shared_daily_active: u64,
+    shared_daily_baseline: u64,
     daily_snooze_until: Option<u64>,
+    daily_snoozes: u32,
     active_break: Option<OngoingBreak>,
     last_reset_bucket: i64,
+    inhibitor: Option<DeferReason>,
+    deferred: Option<BreakKind>,
+    started_at: u64,
+    /// Latest time seen by `on_activity`, used to re-anchor the reset
+    /// buckets when settings change.
+    last_seen_at: u64,
+    recent_activity: VecDeque<(u64, u64)>,
+    daily_warning_percent: u8,
+    weekly_active: u64,
+    last_week_bucket: i64,
+    weekly_warning_percent: u8,
+    weekly_limit_reached: bool,
+    /// Foreground seconds today of applications in `app_limits`, keyed by
+    /// lowercased window class.
+    app_active: BTreeMap<String, u64>,
+    /// Applications whose `AppLimitReached` went out today.
+    app_limits_announced: BTreeSet<String>,
+    /// Phase as of the latest `on_activity`; each rise is announced once.
+    wind_down: WindDownPhase,
+    wind_down_active: u64,
+    wind_down_budget_announced: bool,
+    jitter_seed: u64,
+    interval_scale_percent: u8,
+    active_streak: u64,
+    warmup_until: u64,
+    due: Option<DueBreak>,
+    /// Key presses and clicks in the last sampled minute.
+    input_events_per_minute: u32,
+    /// Breaks due but not yet started or snoozed, the one to offer first.
+    pending: Vec<BreakKind>,
+    /// Seconds local time is ahead of UTC, for the daily and weekly resets.
+    utc_offset_seconds: i64,
 }
 
 impl TimerEngine {
-    pub fn new(settings: Settings, now_local_unix: u64) -> Self {
-        let bucket =
-            Self::daily_bucket(now_local_unix, settings.daily_limit.reset_offset_seconds());
-        Self {
-            settings,
-            micro_active: 0,
-            rest_active: 0,
+    pub fn new(settings: Settings, now_unix: u64) -> Self {
+        Self::new_in_zone(settings, now_unix, 0)
+    }
+
+    /// Like `new`, for a clock `utc_offset_seconds` ahead of UTC; daily and
+    /// weekly resets follow local wall-clock time.
+    pub fn new_in_zone(settings: Settings, now_unix: u64, utc_offset_seconds: i64) -> Self {
+        let mut engine = Self {
+            settings: settings.clone(),
+            base_settings: settings,
+            timers: Vec::new(),
             daily_active: 0,
-            micro_snooze_until: None,
-            rest_snooze_until: None,
+            shared_daily_active: 0,
+            shared_daily_baseline: 0,
             daily_snooze_until: None,
+            daily_snoozes: 0,
             active_break: None,
-            last_reset_bucket: bucket,
+            last_reset_bucket: 0,
+            inhibitor: None,
+            deferred: None,
+            started_at: now_unix,
+            last_seen_at: now_unix,
+            recent_activity: VecDeque::new(),
+            daily_warning_percent: 0,
+            weekly_active: 0,
+            last_week_bucket: 0,
+            weekly_warning_percent: 0,
+            weekly_limit_reached: false,
+            app_active: BTreeMap::new(),
+            app_limits_announced: BTreeSet::new(),
+            wind_down: WindDownPhase::Off,
+            wind_down_active: 0,
+            wind_down_budget_announced: false,
+            jitter_seed: now_unix,
+            interval_scale_percent: 100,
+            active_streak: 0,
+            warmup_until: 0,
+            due: None,
+            input_events_per_minute: 0,
+            pending: Vec::new(),
+            utc_offset_seconds,
+        };
+        engine.last_reset_bucket = engine.daily_bucket(now_unix);
+        engine.last_week_bucket = engine.week_bucket(now_unix);
+        engine.settings = engine.settings_for_day(engine.last_reset_bucket);
+        engine.sync_timers();
+        engine
+    }
+
+    /// Builds an engine from the counters saved by a previous run. The
+    /// warm-up grace only applies after a real absence, not a quick restart.
+    pub fn restore(settings: Settings, snapshot: Option<&EngineSnapshot>, now_unix: u64) -> Self {
+        Self::restore_in_zone(settings, snapshot, now_unix, 0)
+    }
+
+    /// `restore` with the local UTC offset of `new_in_zone`.
+    pub fn restore_in_zone(
+        settings: Settings,
+        snapshot: Option<&EngineSnapshot>,
+        now_unix: u64,
+        utc_offset_seconds: i64,
+    ) -> Self {
+        let mut engine = Self::new_in_zone(settings, now_unix, utc_offset_seconds);
+        let grace = engine.settings.startup.warmup_grace_seconds;
+        let idle = snapshot.map_or(u64::MAX, |snapshot| {
+            now_unix.saturating_sub(snapshot.taken_at)
+        });
+        if idle >= grace {
+            engine.warmup_until = now_unix.saturating_add(grace);
+        }
+        let Some(snapshot) = snapshot else {
+            return engine;
+        };
+
+        if engine.daily_bucket(snapshot.taken_at) == engine.last_reset_bucket {
+            engine.daily_active = snapshot.daily_active_seconds;
+            // Warnings already shown before the restart stay quiet.
+            let _ = engine.crossed_daily_warning();
+            engine.app_active = snapshot.app_active_seconds.iter().cloned().collect();
+            engine.app_limits_announced = engine.exhausted_apps().into_iter().collect();
+            engine.wind_down_active = snapshot.wind_down_active_seconds;
+            engine.wind_down_budget_announced = engine.wind_down_budget_spent();
+        }
+        if engine.week_bucket(snapshot.taken_at) == engine.last_week_bucket {
+            engine.weekly_active = snapshot.weekly_active_seconds;
+            let _ = engine.weekly_limit_events();
+        }
+
+        let idle_counts_as_rest = engine.settings.startup.idle_counts_as_rest;
+        for spec in engine.timer_specs() {
+            if idle_counts_as_rest && idle >= spec.timer.duration_seconds {
+                continue;
+            }
+            let saved = engine.timer_id(spec.kind).and_then(|id| {
+                snapshot
+                    .timer_active_seconds
+                    .iter()
+                    .find(|(saved_id, _)| saved_id == id)
+                    .map(|(_, seconds)| *seconds)
+            });
+            if let (Some(seconds), Some(state)) = (
+                saved,
+                Self::slot(spec.kind).and_then(|slot| engine.timers.get_mut(slot)),
+            ) {
+                state.active_seconds = seconds;
+            }
+        }
+        engine
+    }
+
+    pub fn snapshot(&self, now_unix: u64) -> EngineSnapshot {
+        EngineSnapshot {
+            taken_at: now_unix,
+            daily_active_seconds: self.daily_active,
+            weekly_active_seconds: self.weekly_active,
+            timer_active_seconds: self
+                .timer_specs()
+                .into_iter()
+                .filter_map(|spec| {
+                    let id = self.timer_id(spec.kind)?;
+                    Some((id.to_string(), self.timer_state(spec.kind).active_seconds))
+                })
+                .collect(),
+            app_active_seconds: self
+                .app_active
+                .iter()
+                .map(|(app, seconds)| (app.clone(), *seconds))
+                .collect(),
+            wind_down_active_seconds: self.wind_down_active,
+        }
+    }
+
+    fn timer_id(&self, kind: BreakKind) -> Option<&str> {
+        match kind {
+            BreakKind::Micro => Some(MICRO_TIMER_ID),
+            BreakKind::Rest => Some(REST_TIMER_ID),
+            BreakKind::Custom(index) => self
+                .settings
+                .custom_timers
+                .get(usize::from(index))
+                .map(|custom| custom.id.as_str()),
+            BreakKind::DailyLimit => None,
         }
     }
 
+    /// Settings in effect today, day overrides included.
     pub fn settings(&self) -> &Settings {
         &self.settings
     }
 
-    pub fn settings_mut(&mut self) -> &mut Settings {
-        &mut self.settings
+    /// Settings as last given, before any day override.
+    pub fn base_settings(&self) -> &Settings {
+        &self.base_settings
+    }
+
+    /// Edits the base settings in place and resolves today's from them
+    /// again, so the edit survives the next daily reset.
+    pub fn edit_settings(&mut self, edit: impl FnOnce(&mut Settings)) {
+        edit(&mut self.base_settings);
+        let policy = self.base_settings.runtime.interval_change_policy;
+        self.apply_settings(self.base_settings.clone(), policy);
+    }
+
+    pub fn update_settings(&mut self, settings: Settings) {
+        self.apply_settings(settings, IntervalChangePolicy::Preserve);
+    }
+
+    /// Swaps in new base settings and resolves today's from them; see
+    /// `resolve_settings`.
+    pub fn apply_settings(&mut self, settings: Settings, policy: IntervalChangePolicy) {
+        self.base_settings = settings;
+        // Re-anchored rather than compared, so moving the reset time (as a
+        // profile switch may) never wipes the usage counted so far.
+        self.last_reset_bucket = self.daily_bucket(self.last_seen_at);
+        self.last_week_bucket = self.week_bucket(self.last_seen_at);
+        self.resolve_settings(policy);
+    }
+
+    /// Swaps in today's resolution of `base_settings`, leaving the reset
+    /// buckets alone. Counters are matched to timers by id so reordered
+    /// custom timers keep theirs, and `policy` applies to every timer whose
+    /// interval changed.
+    fn resolve_settings(&mut self, policy: IntervalChangePolicy) {
+        let previous = self
+            .timer_specs()
+            .into_iter()
+            .filter_map(|spec| {
+                let id = self.timer_id(spec.kind)?.to_string();
+                Some((id, spec.timer.interval_seconds, self.timer_state(spec.kind)))
+            })
+            .collect::<Vec<_>>();
+        self.settings = self.settings_for_day(self.last_reset_bucket);
+        self.timers.clear();
+        self.sync_timers();
+        // A raised or removed budget may go out again once spent.
+        let exhausted = self.exhausted_apps();
+        self.app_limits_announced
+            .retain(|app| exhausted.contains(app));
+        self.wind_down_budget_announced = self.wind_down_budget_spent();
+
+        for spec in self.timer_specs() {
+            let Some((_, old_interval, mut state)) = self
+                .timer_id(spec.kind)
+                .and_then(|id| previous.iter().find(|(old_id, _, _)| old_id == id).cloned())
+            else {
+                continue;
+            };
+            let interval = spec.timer.interval_seconds;
+            if interval != old_interval {
+                match policy {
+                    IntervalChangePolicy::Rescale => {
+                        state.active_seconds = state
+                            .active_seconds
+                            .saturating_mul(interval)
+                            .checked_div(old_interval)
+                            .unwrap_or(0);
+                    }
+                    IntervalChangePolicy::Preserve => {}
+                    IntervalChangePolicy::Reset => state.restart(),
+                }
+            }
+            if let Some(slot) = Self::slot(spec.kind).and_then(|slot| self.timers.get_mut(slot)) {
+                *slot = state;
+            }
+        }
+    }
+
+    pub fn timer_specs(&self) -> Vec<TimerSpec> {
+        timer_specs(&self.settings)
+    }
+
+    /// Seeds the per-cycle interval jitter. The same seed and activity always
+    /// produce the same due times.
+    pub fn set_jitter_seed(&mut self, seed: u64) {
+        self.jitter_seed = seed;
+    }
+
+    /// Interval of the current cycle of `kind`, shifted by up to
+    /// `jitter_percent` in either direction and scaled by adaptive scheduling.
+    pub fn effective_interval(&self, kind: BreakKind, timer: &BreakTimerSettings) -> u64 {
+        let base = timer.interval_seconds;
+        let span =
+            base.saturating_mul(u64::from(timer.jitter_percent.min(MAX_JITTER_PERCENT))) / 100;
+        let jittered = if span == 0 {
+            base
+        } else {
+            let slot = Self::slot(kind).unwrap_or_default() as u64;
+            let cycle = self.timer_state(kind).cycle;
+            let roll = splitmix64(self.jitter_seed ^ splitmix64((slot << 48) | cycle));
+            (base - span).saturating_add(roll % (2 * span + 1)).max(1)
+        };
+
+        let mut scale = u64::from(self.interval_scale_percent());
+        if kind == BreakKind::Micro {
+            scale = scale * u64::from(self.intensity_scale_percent()) / 100;
+        }
+        scale = scale * u64::from(self.wind_down_scale_percent()) / 100;
+        if scale == 100 {
+            return jittered;
+        }
+        (jittered.saturating_mul(scale) / 100).max(1)
+    }
+
+    /// Latest input rate from the intensity sampler.
+    pub fn set_input_intensity(&mut self, events_per_minute: u32) {
+        self.input_events_per_minute = events_per_minute;
+    }
+
+    /// Share of the micro interval left under the current input rate: 100
+    /// below the heavy rate, falling linearly to the maximum reduction at
+    /// twice that rate.
+    pub fn intensity_scale_percent(&self) -> u8 {
+        let intensity = &self.settings.intensity;
+        let heavy = intensity.heavy_events_per_minute;
+        if !intensity.enabled || !intensity.scale_micro || heavy == 0 {
+            return 100;
+        }
+        let over = self
+            .input_events_per_minute
+            .saturating_sub(heavy)
+            .min(heavy);
+        let max = u32::from(
+            intensity
+                .max_reduction_percent
+                .min(MAX_INTENSITY_REDUCTION_PERCENT),
+        );
+        (100 - max * over / heavy) as u8
+    }
+
+    pub fn interval_scale_percent(&self) -> u8 {
+        if self.settings.adaptive.enabled {
+            self.interval_scale_percent
+        } else {
+            100
+        }
+    }
+
+    pub fn active_streak_seconds(&self) -> u64 {
+        self.active_streak
+    }
+
+    /// Tightens intervals while breaks keep being skipped or activity runs
+    /// far past the longest interval, and relaxes them again once breaks are
+    /// taken, staying within the adaptive bounds.
+    pub fn adapt(&mut self, compliance: &Compliance) -> Option<EngineEvent> {
+        let adaptive = &self.settings.adaptive;
+        let (next, reason) = if adaptive.enabled {
+            let (floor, ceiling) = ADAPT_SCALE_BOUNDS;
+            let min = adaptive.min_interval_percent.clamp(floor, 100);
+            let max = adaptive.max_interval_percent.clamp(100, ceiling);
+            let sampled = compliance.total() >= ADAPT_MIN_SAMPLES;
+            let skip_rate = compliance.skip_rate_percent();
+            let long_streak = self
+                .longest_enabled_interval()
+                .is_some_and(|longest| self.active_streak >= longest.saturating_mul(3) / 2);
+
+            let (target, reason) = if sampled && skip_rate >= FREQUENT_SKIP_PERCENT {
+                (
+                    self.interval_scale_percent
+                        .saturating_sub(ADAPT_STEP_PERCENT),
+                    AdaptReason::FrequentSkips,
+                )
+            } else if long_streak {
+                (
+                    self.interval_scale_percent
+                        .saturating_sub(ADAPT_STEP_PERCENT),
+                    AdaptReason::LongActiveStreak,
+                )
+            } else if sampled && skip_rate <= GOOD_COMPLIANCE_PERCENT {
+                (
+                    self.interval_scale_percent
+                        .saturating_add(ADAPT_STEP_PERCENT),
+                    AdaptReason::GoodCompliance,
+                )
+            } else {
+                return None;
+            };
+            (target.clamp(min, max), reason)
+        } else {
+            (100, AdaptReason::Disabled)
+        };
+
+        if next == self.interval_scale_percent {
+            return None;
+        }
+        self.interval_scale_percent = next;
+        trace_event!("intervals scaled to {next}% ({reason:?})");
+        Some(EngineEvent::IntervalsAdjusted(next, reason))
+    }
+
+    /// Reports today's active time recorded by other synced devices so it
+    /// counts toward the shared daily limit.
+    pub fn set_shared_daily_active(&mut self, seconds: u64) {
+        self.shared_daily_active = seconds;
+        self.shared_daily_baseline = self.shared_daily_baseline.min(seconds);
+    }
+
+    fn daily_used(&self) -> u64 {
+        self.daily_active.saturating_add(
+            self.shared_daily_active
+                .saturating_sub(self.shared_daily_baseline),
+        )
+    }
+
+    /// Follows a time zone or DST change. Counters carry over and the next
+    /// reset happens at the reset time of the new offset, but a day whose
+    /// reset already ran is not reset again, so flying west or turning the
+    /// clocks back makes that day longer instead.
+    pub fn set_utc_offset(&mut self, utc_offset_seconds: i64) {
+        if utc_offset_seconds != self.utc_offset_seconds {
+            trace_event!("utc offset changed to {utc_offset_seconds}");
+            self.utc_offset_seconds = utc_offset_seconds;
+        }
+    }
+
+    pub fn utc_offset(&self) -> i64 {
+        self.utc_offset_seconds
+    }
+
+    pub fn set_inhibitor(&mut self, reason: Option<DeferReason>) {
+        self.inhibitor = reason;
+    }
+
+    pub fn inhibitor(&self) -> Option<DeferReason> {
+        self.inhibitor
     }
 
     pub fn active_break_info(&self) -> Option<(BreakKind, u64)> {
         self.active_break
             .as_ref()
-            .map(|active| (active.kind, active.remaining_seconds))
+            .map(|active| (active.kind, active.remaining_seconds()))
     }
 
-    pub fn next_break_eta(&self, now_local_unix: u64) -> Option<(BreakKind, u64)> {
-        if self.active_break.is_some() {
-            return None;
-        }
+    /// Like `active_break_info`, without rounding the time left up to whole
+    /// seconds.
+    pub fn active_break_remaining_millis(&self) -> Option<u64> {
+        self.active_break
+            .as_ref()
+            .map(|active| active.remaining_millis)
+    }
 
-        let mut candidates: Vec<(BreakKind, u64)> = Vec::new();
+    /// Share of the active break still to go, rounded up so it only reads 0
+    /// once the break is over.
+    pub fn active_break_remaining_percent(&self) -> Option<u8> {
+        let active = self.active_break.as_ref()?;
+        let duration = self.break_duration(active.kind).unwrap_or(0).max(1);
+        let remaining = active.remaining_seconds().min(duration);
+        Some((remaining * 100).div_ceil(duration) as u8)
+    }
 
-        if self.settings.micro.enabled {
-            let countdown = self
-                .settings
-                .micro
-                .interval_seconds
-                .saturating_sub(self.micro_active)
-                .max(self.snooze_remaining(self.micro_snooze_until, now_local_unix));
-            candidates.push((BreakKind::Micro, countdown));
+    pub fn progress(&self) -> EngineProgress {
+        EngineProgress {
+            micro: BreakProgress {
+                elapsed_seconds: self.timer_state(BreakKind::Micro).active_seconds,
+                target_seconds: self.effective_interval(BreakKind::Micro, &self.settings.micro),
+            },
+            rest: BreakProgress {
+                elapsed_seconds: self.timer_state(BreakKind::Rest).active_seconds,
+                target_seconds: self.effective_interval(BreakKind::Rest, &self.settings.rest),
+            },
+            daily_limit: BreakProgress {
+                elapsed_seconds: self.daily_used(),
+                target_seconds: self.settings.daily_limit.limit_seconds,
+            },
+            weekly_limit: BreakProgress {
+                elapsed_seconds: self.weekly_active,
+                target_seconds: self.settings.weekly_limit.limit_seconds,
+            },
         }
+    }
 
-        if self.settings.rest.enabled {
-            let countdown = self
-                .settings
-                .rest
-                .interval_seconds
-                .saturating_sub(self.rest_active)
-                .max(self.snooze_remaining(self.rest_snooze_until, now_local_unix));
-            candidates.push((BreakKind::Rest, countdown));
+    pub fn timer_progress(&self) -> Vec<(BreakKind, BreakProgress)> {
+        self.timer_specs()
+            .into_iter()
+            .map(|spec| {
+                (
+                    spec.kind,
+                    BreakProgress {
+                        elapsed_seconds: self.timer_state(spec.kind).active_seconds,
+                        target_seconds: self.effective_interval(spec.kind, &spec.timer),
+                    },
+                )
+            })
+            .collect()
+    }
+
+    pub fn next_break_eta(&self, now_unix: u64) -> Option<(BreakKind, u64)> {
+        if self.active_break.is_some() {
+            return None;
         }
 
+        let mut candidates: Vec<(BreakKind, u64, u8)> = self
+            .timer_specs()
+            .into_iter()
+            .filter(|spec| spec.timer.enabled)
+            .map(|spec| {
+                let state = self.timer_state(spec.kind);
+                let countdown = self
+                    .effective_interval(spec.kind, &spec.timer)
+                    .saturating_sub(state.active_seconds)
+                    .max(self.snooze_remaining(state.snooze_until, now_unix))
+                    .max(self.warmup_until.saturating_sub(now_unix));
+                (spec.kind, countdown, spec.priority)
+            })
+            .collect();
+
         if self.settings.daily_limit.enabled {
             let countdown = self
                 .settings
                 .daily_limit
                 .limit_seconds
-                .saturating_sub(self.daily_active)
-                .max(self.snooze_remaining(self.daily_snooze_until, now_local_unix));
+                .saturating_sub(self.daily_used())
+                .max(self.snooze_remaining(self.daily_snooze_until, now_unix));
 
-            let until_reset = self.seconds_until_next_reset(
-                now_local_unix,
-                self.settings.daily_limit.reset_offset_seconds(),
-            );
+            let until_reset = self.seconds_until_next_reset(now_unix);
 
             if countdown < until_reset {
-                candidates.push((BreakKind::DailyLimit, countdown));
+                candidates.push((BreakKind::DailyLimit, countdown, DAILY_LIMIT_PRIORITY));
             }
         }
 
         candidates
             .into_iter()
-            .min_by_key(|(kind, countdown)| (*countdown, Self::kind_priority(*kind)))
+            .min_by_key(|(_, countdown, priority)| (*countdown, *priority))
+            .map(|(kind, countdown, _)| (kind, countdown))
+    }
+
+    /// Projects when the daily limit will be hit if activity keeps the density
+    /// observed over the last half hour.
+    pub fn forecast_daily_limit(&self, now_unix: u64) -> Option<DailyLimitForecast> {
+        if !self.settings.daily_limit.enabled {
+            return None;
+        }
+
+        let limit_seconds = self.settings.daily_limit.limit_seconds;
+        let used_seconds = self.daily_used().min(limit_seconds);
+        let window_start = now_unix.saturating_sub(ACTIVITY_WINDOW_SECONDS);
+        let recent_seconds: u64 = self
+            .recent_activity
+            .iter()
+            .filter(|(at, _)| *at > window_start)
+            .map(|(_, seconds)| *seconds)
+            .sum();
+        let span = now_unix
+            .saturating_sub(self.started_at)
+            .min(ACTIVITY_WINDOW_SECONDS);
+        let density_known = span >= MIN_FORECAST_SPAN_SECONDS;
+        let recent_seconds = recent_seconds.min(span);
+
+        let seconds_until_limit = if used_seconds >= limit_seconds {
+            Some(0)
+        } else if density_known && recent_seconds > 0 {
+            let remaining = limit_seconds - used_seconds;
+            Some(remaining.saturating_mul(span).div_ceil(recent_seconds))
+        } else {
+            None
+        };
+        let until_reset = self.seconds_until_next_reset(now_unix);
+
+        Some(DailyLimitForecast {
+            used_seconds,
+            limit_seconds,
+            percent: BreakProgress {
+                elapsed_seconds: used_seconds,
+                target_seconds: limit_seconds,
+            }
+            .percent(),
+            activity_density_percent: if density_known {
+                (recent_seconds.saturating_mul(100) / span) as u8
+            } else {
+                0
+            },
+            seconds_until_limit,
+            reached_at: seconds_until_limit.map(|eta| now_unix.saturating_add(eta)),
+            before_reset: seconds_until_limit.is_some_and(|eta| eta <= until_reset),
+        })
+    }
+
+    /// Dry run of the scheduler: where each enabled or disabled timer stands
+    /// and which constraint decides when it fires. Changes nothing.
+    pub fn explain(&self, now_unix: u64) -> Vec<TimerExplanation> {
+        let queue = self.due_queue(now_unix);
+        let warmup = self.warmup_until.saturating_sub(now_unix);
+        let until_reset = self.seconds_until_next_reset(now_unix);
+
+        let mut entries: Vec<(BreakKind, u64, u64, Option<u64>, bool)> = self
+            .timer_specs()
+            .into_iter()
+            .map(|spec| {
+                let state = self.timer_state(spec.kind);
+                (
+                    spec.kind,
+                    state.active_seconds,
+                    self.effective_interval(spec.kind, &spec.timer),
+                    state.snooze_until,
+                    spec.timer.enabled,
+                )
+            })
+            .collect();
+        entries.push((
+            BreakKind::DailyLimit,
+            self.daily_used(),
+            self.settings.daily_limit.limit_seconds,
+            self.daily_snooze_until,
+            self.settings.daily_limit.enabled,
+        ));
+
+        entries
+            .into_iter()
+            .map(
+                |(kind, accumulated_seconds, target_seconds, snooze_until, enabled)| {
+                    let snoozed_until = snooze_until.filter(|until| now_unix < *until);
+                    let remaining = target_seconds.saturating_sub(accumulated_seconds);
+                    let snooze = self.snooze_remaining(snoozed_until, now_unix);
+                    // The daily limit is not held back by the warmup grace.
+                    let warmup = if kind == BreakKind::DailyLimit {
+                        0
+                    } else {
+                        warmup
+                    };
+                    let countdown = remaining.max(snooze).max(warmup);
+
+                    let reason = if !enabled {
+                        ScheduleReason::Disabled
+                    } else if self.active_break.is_some() {
+                        ScheduleReason::BreakActive
+                    } else if kind == BreakKind::DailyLimit && countdown >= until_reset {
+                        ScheduleReason::AfterReset
+                    } else if countdown > 0 {
+                        if snooze == countdown {
+                            ScheduleReason::Snoozed
+                        } else if warmup == countdown {
+                            ScheduleReason::Warmup
+                        } else {
+                            ScheduleReason::Interval
+                        }
+                    } else {
+                        match (
+                            queue.iter().position(|queued| *queued == kind),
+                            self.inhibitor,
+                        ) {
+                            (None, _) => ScheduleReason::Absorbed,
+                            (Some(_), Some(inhibitor)) => ScheduleReason::Deferred(inhibitor),
+                            (Some(0), None) => ScheduleReason::Due,
+                            (Some(_), None) => ScheduleReason::Queued,
+                        }
+                    };
+                    let next_due_at = match reason {
+                        ScheduleReason::Disabled
+                        | ScheduleReason::BreakActive
+                        | ScheduleReason::AfterReset => None,
+                        _ => Some(now_unix.saturating_add(countdown)),
+                    };
+
+                    TimerExplanation {
+                        kind,
+                        accumulated_seconds,
+                        target_seconds,
+                        snoozed_until,
+                        next_due_at,
+                        reason,
+                    }
+                },
+            )
+            .collect()
     }
 
-    pub fn on_activity(&mut self, active_seconds: u64, now_local_unix: u64) -> Vec<EngineEvent> {
+    pub fn on_activity(&mut self, active_seconds: u64, now_unix: u64) -> Vec<EngineEvent> {
+        self.sync_timers();
+        self.last_seen_at = self.last_seen_at.max(now_unix);
         let mut events = Vec::new();
-        if self.maybe_daily_reset(now_local_unix) {
+        if self.maybe_daily_reset(now_unix) {
+            trace_event!("daily reset applied at {now_unix}");
             events.push(EngineEvent::DailyReset);
         }
+        self.maybe_weekly_reset(now_unix);
+        events.extend(self.wind_down_event(now_unix));
 
         if active_seconds == 0 || self.active_break.is_some() {
             return events;
         }
 
-        self.micro_active = self.micro_active.saturating_add(active_seconds);
-        self.rest_active = self.rest_active.saturating_add(active_seconds);
+        let specs = self.timer_specs();
+        for (state, spec) in self.timers.iter_mut().zip(specs) {
+            if spec.timer.enabled {
+                state.active_seconds = state.active_seconds.saturating_add(active_seconds);
+            }
+        }
         self.daily_active = self.daily_active.saturating_add(active_seconds);
+        self.active_streak = self.active_streak.saturating_add(active_seconds);
+        self.record_recent_activity(active_seconds, now_unix);
+        if let Some(threshold) = self.crossed_daily_warning() {
+            trace_event!("daily limit warning at {threshold}%");
+            events.push(EngineEvent::DailyLimitWarning(threshold));
+        }
+        self.weekly_active = self.weekly_active.saturating_add(active_seconds);
+        events.extend(self.weekly_limit_events());
+        if self.wind_down != WindDownPhase::Off {
+            self.wind_down_active = self.wind_down_active.saturating_add(active_seconds);
+            if self.wind_down_budget_spent() && !self.wind_down_budget_announced {
+                trace_event!("wind-down budget reached");
+                self.wind_down_budget_announced = true;
+                events.push(EngineEvent::WindDownBudgetReached);
+            }
+        }
 
-        if let Some(kind) = self.next_due(now_local_unix) {
-            events.push(EngineEvent::BreakDue(kind));
-            if matches!(self.settings.block_level, BlockLevel::Strict) {
-                events.extend(self.start_break(kind));
+        self.pending = self.due_queue(now_unix);
+        let Some(kind) = self.pending.first().copied() else {
+            self.due = None;
+            return events;
+        };
+        if let Some(reason) = self.inhibitor {
+            self.due = None;
+            if self.deferred != Some(kind) {
+                trace_event!("{kind:?} break deferred by {reason:?}");
+                self.deferred = Some(kind);
+                events.push(EngineEvent::BreakDeferred(kind, reason));
             }
+            return events;
+        }
+
+        self.deferred = None;
+        trace_event!("{kind:?} break due");
+        events.push(EngineEvent::BreakDue(kind));
+        if matches!(self.block_level(kind), BlockLevel::Strict) {
+            events.extend(self.start_break(kind));
+        } else {
+            events.extend(self.escalate(kind, active_seconds));
+        }
+        events
+    }
+
+    /// Breaks due but neither started nor snoozed, in the order they will be
+    /// offered. Each is announced with `BreakDue` once it reaches the front.
+    pub fn pending_breaks(&self) -> &[BreakKind] {
+        &self.pending
+    }
+
+    /// The break currently offered to the user.
+    pub fn pending_break(&self) -> Option<BreakKind> {
+        self.pending.first().copied()
+    }
+
+    /// Forgets the pending breaks without snoozing them, e.g. while tracking
+    /// is paused; they come back once due again.
+    pub fn clear_pending(&mut self) {
+        self.pending.clear();
+        self.due = None;
+    }
+
+    /// Active seconds `kind` has been due without being started or snoozed.
+    pub fn due_age(&self, kind: BreakKind) -> Option<u64> {
+        self.due
+            .filter(|due| due.kind == kind)
+            .map(|due| due.ignored_seconds)
+    }
+
+    fn escalate(&mut self, kind: BreakKind, active_seconds: u64) -> Vec<EngineEvent> {
+        let due = match self.due {
+            // The first tick it is due on does not count as ignoring it.
+            Some(due) if due.kind == kind => DueBreak {
+                ignored_seconds: due.ignored_seconds.saturating_add(active_seconds),
+                ..due
+            },
+            _ => DueBreak {
+                kind,
+                ignored_seconds: 0,
+                stage: None,
+            },
+        };
+        self.due = Some(due);
+
+        let escalation = &self.settings.escalation;
+        if !escalation.enabled {
+            return Vec::new();
+        }
+        let reached = |after: u64| after > 0 && due.ignored_seconds >= after;
+        let stage = if reached(escalation.force_after_seconds) {
+            EscalationStage::ForceStart
+        } else if reached(escalation.renotify_after_seconds) {
+            EscalationStage::Renotify
+        } else {
+            return Vec::new();
+        };
+        if due.stage >= Some(stage) {
+            return Vec::new();
         }
 
+        trace_event!("{kind:?} break escalated to {stage:?}");
+        self.due = Some(DueBreak {
+            stage: Some(stage),
+            ..due
+        });
+        let mut events = vec![EngineEvent::BreakEscalated(kind, stage)];
+        if stage == EscalationStage::ForceStart {
+            events.extend(self.start_break(kind));
+        }
         events
     }
 
+    /// The timer's own level, falling back to the global one.
+    pub fn block_level(&self, kind: BreakKind) -> BlockLevel {
+        self.timer_spec(kind)
+            .and_then(|spec| spec.timer.block_level)
+            .unwrap_or(self.settings.block_level)
+    }
+
     pub fn start_break(&mut self, kind: BreakKind) -> Vec<EngineEvent> {
         if self.active_break.is_some() {
             return Vec::new();
         }
-        let duration = match kind {
-            BreakKind::Micro => self.settings.micro.duration_seconds,
-            BreakKind::Rest => self.settings.rest.duration_seconds,
-            BreakKind::DailyLimit => 60,
+        let Some(duration) = self.break_duration(kind) else {
+            return Vec::new();
         };
+        trace_event!("{kind:?} break started for {duration}s");
+        self.due = None;
+        self.pending.retain(|queued| *queued != kind);
         self.active_break = Some(OngoingBreak {
             kind,
-            remaining_seconds: duration,
+            remaining_millis: duration.saturating_mul(1000),
         });
         vec![EngineEvent::BreakStarted(kind)]
     }
 
     pub fn tick_break(&mut self, elapsed_seconds: u64) -> Vec<EngineEvent> {
+        self.tick_break_millis(elapsed_seconds.saturating_mul(1000))
+    }
+
+    /// Counts the active break down by `elapsed_millis`, for shells that
+    /// redraw the countdown more often than once a second.
+    pub fn tick_break_millis(&mut self, elapsed_millis: u64) -> Vec<EngineEvent> {
         let mut events = Vec::new();
         let Some(active) = self.active_break.as_mut() else {
             return events;
         };
 
-        if elapsed_seconds >= active.remaining_seconds {
+        if elapsed_millis >= active.remaining_millis {
             let kind = active.kind;
             self.active_break = None;
             self.complete_break(kind);
+            trace_event!("{kind:?} break completed");
             events.push(EngineEvent::BreakCompleted(kind));
         } else {
-            active.remaining_seconds -= elapsed_seconds;
+            active.remaining_millis -= elapsed_millis;
         }
 
         events
     }
 
-    pub fn snooze(&mut self, kind: BreakKind, now_local_unix: u64) -> Option<EngineEvent> {
-        let until = match kind {
-            BreakKind::Micro => now_local_unix.saturating_add(self.settings.micro.snooze_seconds),
-            BreakKind::Rest => now_local_unix.saturating_add(self.settings.rest.snooze_seconds),
-            BreakKind::DailyLimit => {
-                now_local_unix.saturating_add(self.settings.daily_limit.snooze_seconds)
-            }
+    /// Snoozes `kind` for its configured `snooze_seconds`.
+    pub fn snooze(&mut self, kind: BreakKind, now_unix: u64) -> Option<EngineEvent> {
+        let seconds = match kind {
+            BreakKind::DailyLimit => self.settings.daily_limit.snooze_seconds,
+            _ => self.timer_spec(kind)?.timer.snooze_seconds,
         };
+        self.snooze_for(kind, now_unix, seconds)
+    }
 
-        match kind {
-            BreakKind::Micro => self.micro_snooze_until = Some(until),
-            BreakKind::Rest => self.rest_snooze_until = Some(until),
-            BreakKind::DailyLimit => self.daily_snooze_until = Some(until),
+    /// Snoozes `kind` for exactly `seconds`, as when the user picks how much
+    /// longer they need. Counts against the daily limit's snoozes like
+    /// `snooze`.
+    pub fn snooze_for(
+        &mut self,
+        kind: BreakKind,
+        now_unix: u64,
+        seconds: u64,
+    ) -> Option<EngineEvent> {
+        self.sync_timers();
+        let until = now_unix.saturating_add(seconds);
+        if kind == BreakKind::DailyLimit {
+            self.daily_snooze_until = Some(until);
+            self.daily_snoozes = self.daily_snoozes.saturating_add(1);
+        } else {
+            self.timers.get_mut(Self::slot(kind)?)?.snooze_until = Some(until);
         }
 
+        trace_event!("{kind:?} break snoozed until {until}");
+        self.due = None;
+        self.pending.retain(|queued| *queued != kind);
         Some(EngineEvent::BreakSnoozed(kind, until))
     }
 
-    fn next_due(&self, now_local_unix: u64) -> Option<BreakKind> {
-        if self.settings.micro.enabled
-            && self.micro_active >= self.settings.micro.interval_seconds
-            && !Self::is_snoozed(self.micro_snooze_until, now_local_unix)
-        {
-            return Some(BreakKind::Micro);
+    pub fn daily_status(&self, now_unix: u64) -> DailyStatus {
+        DailyStatus {
+            used_seconds: self.daily_used(),
+            limit_seconds: self.settings.daily_limit.limit_seconds,
+            snoozes_used: self.daily_snoozes,
+            max_snoozes: self.settings.daily_limit.max_snoozes,
+            snoozed_until: self
+                .daily_snooze_until
+                .filter(|until| Self::is_snoozed(Some(*until), now_unix)),
+            seconds_until_reset: self.seconds_until_next_reset(now_unix),
         }
+    }
 
-        if self.settings.rest.enabled
-            && self.rest_active >= self.settings.rest.interval_seconds
-            && !Self::is_snoozed(self.rest_snooze_until, now_local_unix)
+    /// Starts a new day now, as the scheduled reset would: the daily counters,
+    /// snoozes and warnings are cleared and a pending limit break is dropped.
+    /// The day is re-anchored on the current time, so a reset skipped after
+    /// flying west is not waited for. Interval timers and the weekly total
+    /// are left alone, and a limit break already running goes on.
+    pub fn force_reset(&mut self, now_unix: u64) {
+        trace_event!("daily reset forced at {now_unix}");
+        self.last_reset_bucket = self.daily_bucket(now_unix);
+        self.daily_active = 0;
+        self.shared_daily_baseline = self.shared_daily_active;
+        self.daily_snooze_until = None;
+        self.daily_snoozes = 0;
+        self.daily_warning_percent = 0;
+        self.pending.retain(|kind| *kind != BreakKind::DailyLimit);
+        if self
+            .due
+            .is_some_and(|due| due.kind == BreakKind::DailyLimit)
         {
-            return Some(BreakKind::Rest);
+            self.due = None;
         }
+    }
+
+    /// Whether the daily limit has been snoozed as often as the settings allow.
+    pub fn daily_snoozes_exhausted(&self) -> bool {
+        self.daily_snoozes >= self.settings.daily_limit.max_snoozes
+    }
+
+    /// Ends the running break early and snoozes its timer instead.
+    pub fn snooze_active(&mut self, now_unix: u64) -> Option<EngineEvent> {
+        let kind = self.active_break.take()?.kind;
+        self.snooze(kind, now_unix)
+    }
+
+    /// Ends the running break before its time is up, as when its overlay is
+    /// closed. Taking at least half of it still counts as completed; anything
+    /// shorter snoozes the timer like `snooze_active`.
+    pub fn abandon_break(&mut self, now_unix: u64) -> Option<EngineEvent> {
+        let active = self.active_break.as_ref()?;
+        let kind = active.kind;
+        let duration = self.break_duration(kind).unwrap_or(0);
+        let taken = duration.saturating_sub(active.remaining_seconds());
+        if taken.saturating_mul(100) >= duration.saturating_mul(PARTIAL_BREAK_PERCENT) {
+            self.active_break = None;
+            self.complete_break(kind);
+            trace_event!("{kind:?} break ended early after {taken}s");
+            return Some(EngineEvent::BreakCompleted(kind));
+        }
+        self.snooze_active(now_unix)
+    }
+
+    /// Whether completing a `kind` break also counts as taking `other`,
+    /// because it restarts `other`'s timer. Such a break swallows `other`
+    /// while both are due.
+    pub fn absorbs(&self, kind: BreakKind, other: BreakKind) -> bool {
+        match kind {
+            BreakKind::DailyLimit => other != kind && self.settings.daily_limit.resets_timers,
+            _ => self
+                .timer_spec(kind)
+                .is_some_and(|spec| spec.resets.contains(&other)),
+        }
+    }
+
+    fn break_duration(&self, kind: BreakKind) -> Option<u64> {
+        match kind {
+            BreakKind::DailyLimit => Some(60),
+            _ => Some(self.timer_spec(kind)?.timer.duration_seconds),
+        }
+    }
+
+    fn sync_timers(&mut self) {
+        let count = 2 + self.settings.custom_timers.len().min(MAX_CUSTOM_TIMERS);
+        self.timers.resize(count, TimerState::default());
+    }
+
+    fn slot(kind: BreakKind) -> Option<usize> {
+        match kind {
+            BreakKind::Micro => Some(0),
+            BreakKind::Rest => Some(1),
+            BreakKind::Custom(index) => Some(2 + index as usize),
+            BreakKind::DailyLimit => None,
+        }
+    }
+
+    fn timer_state(&self, kind: BreakKind) -> TimerState {
+        Self::slot(kind)
+            .and_then(|slot| self.timers.get(slot))
+            .copied()
+            .unwrap_or_default()
+    }
+
+    fn timer_spec(&self, kind: BreakKind) -> Option<TimerSpec> {
+        self.timer_specs()
+            .into_iter()
+            .find(|spec| spec.kind == kind)
+    }
+
+    /// Due breaks in the order they should be offered: by priority, and by
+    /// how long they have been waiting among equals.
+    fn due_queue(&self, now_unix: u64) -> Vec<BreakKind> {
+        let mut due: Vec<(BreakKind, u8)> = self
+            .timer_specs()
+            .into_iter()
+            .filter(|spec| {
+                let state = self.timer_state(spec.kind);
+                spec.timer.enabled
+                    && state.active_seconds >= self.effective_interval(spec.kind, &spec.timer)
+                    && !Self::is_snoozed(state.snooze_until, now_unix)
+                    && now_unix >= self.warmup_until
+            })
+            .map(|spec| (spec.kind, spec.priority))
+            .collect();
 
         if self.settings.daily_limit.enabled
-            && self.daily_active >= self.settings.daily_limit.limit_seconds
-            && !Self::is_snoozed(self.daily_snooze_until, now_local_unix)
+            && self.daily_used() >= self.settings.daily_limit.limit_seconds
+            && !Self::is_snoozed(self.daily_snooze_until, now_unix)
+        {
+            due.push((BreakKind::DailyLimit, DAILY_LIMIT_PRIORITY));
+        }
+
+        // Coalesce before ranking: a break that is covered by another due
+        // one is never prompted on its own, so taking the larger break does
+        // not leave the smaller one to pop up right after it.
+        let kinds: Vec<BreakKind> = due.iter().map(|(kind, _)| *kind).collect();
+        due.retain(|(kind, _)| {
+            !kinds.iter().any(|other| {
+                other != kind && self.absorbs(*other, *kind) && !self.absorbs(*kind, *other)
+            })
+        });
+
+        let waiting = |kind: &BreakKind| {
+            self.pending
+                .iter()
+                .position(|queued| queued == kind)
+                .unwrap_or(usize::MAX)
+        };
+        due.sort_by_key(|(kind, priority)| (*priority, waiting(kind)));
+        due.into_iter().map(|(kind, _)| kind).collect()
+    }
+
+    fn record_recent_activity(&mut self, active_seconds: u64, now_unix: u64) {
+        self.recent_activity.push_back((now_unix, active_seconds));
+        let window_start = now_unix.saturating_sub(ACTIVITY_WINDOW_SECONDS);
+        while self
+            .recent_activity
+            .front()
+            .is_some_and(|(at, _)| *at <= window_start)
         {
-            return Some(BreakKind::DailyLimit);
+            self.recent_activity.pop_front();
+        }
+    }
+
+    fn crossed_daily_warning(&mut self) -> Option<u8> {
+        if !self.settings.daily_limit.enabled {
+            return None;
         }
 
-        None
+        let percent = BreakProgress {
+            elapsed_seconds: self.daily_used(),
+            target_seconds: self.settings.daily_limit.limit_seconds,
+        }
+        .percent();
+        let threshold = crossed_threshold(
+            &self.settings.daily_limit.warning_thresholds,
+            percent,
+            self.daily_warning_percent,
+        )?;
+        self.daily_warning_percent = threshold;
+        Some(threshold)
     }
 
-    fn kind_priority(kind: BreakKind) -> u8 {
-        match kind {
-            BreakKind::Micro => 0,
-            BreakKind::Rest => 1,
-            BreakKind::DailyLimit => 2,
+    fn weekly_limit_events(&mut self) -> Vec<EngineEvent> {
+        let limit = &self.settings.weekly_limit;
+        if !limit.enabled {
+            return Vec::new();
+        }
+        let mut events = Vec::new();
+        let percent = BreakProgress {
+            elapsed_seconds: self.weekly_active,
+            target_seconds: limit.limit_seconds,
+        }
+        .percent();
+        if let Some(threshold) = crossed_threshold(
+            &limit.warning_thresholds,
+            percent,
+            self.weekly_warning_percent,
+        ) {
+            trace_event!("weekly limit warning at {threshold}%");
+            self.weekly_warning_percent = threshold;
+            events.push(EngineEvent::WeeklyLimitWarning(threshold));
+        }
+        if !self.weekly_limit_reached && self.weekly_active >= limit.limit_seconds {
+            trace_event!("weekly limit reached");
+            self.weekly_limit_reached = true;
+            events.push(EngineEvent::WeeklyLimitReached);
+        }
+        events
+    }
+
+    /// Credits `seconds` in the foreground to the application with window
+    /// class `app`, returning `AppLimitReached` the first time today that
+    /// its budget runs out. Applications without a budget are not counted.
+    pub fn on_app_activity(&mut self, app: &str, seconds: u64) -> Vec<EngineEvent> {
+        let limits = &self.settings.app_limits;
+        if !limits.enabled || seconds == 0 || self.active_break.is_some() {
+            return Vec::new();
+        }
+        let Some(limit) = limits.limit_for(app) else {
+            return Vec::new();
+        };
+        let key = limit.class.to_ascii_lowercase();
+        let active = self.app_active.entry(key.clone()).or_default();
+        *active = active.saturating_add(seconds);
+        if *active < limit.limit_seconds || !self.app_limits_announced.insert(key) {
+            return Vec::new();
+        }
+        trace_event!("app limit reached for {app}");
+        vec![EngineEvent::AppLimitReached(limit.class.clone())]
+    }
+
+    /// Whether the application with window class `app` has spent today's
+    /// budget.
+    pub fn app_limit_reached(&self, app: &str) -> bool {
+        let limits = &self.settings.app_limits;
+        limits.enabled
+            && limits
+                .limit_for(app)
+                .is_some_and(|limit| self.app_progress(&limit.class).percent() >= 100)
+    }
+
+    /// Lowercased window classes of the applications out of budget today.
+    pub fn exhausted_apps(&self) -> Vec<String> {
+        if !self.settings.app_limits.enabled {
+            return Vec::new();
         }
+        self.settings
+            .app_limits
+            .limits
+            .iter()
+            .filter(|limit| self.app_progress(&limit.class).percent() >= 100)
+            .map(|limit| limit.class.to_ascii_lowercase())
+            .collect()
     }
 
-    fn snooze_remaining(&self, until: Option<u64>, now_local_unix: u64) -> u64 {
+    /// Today's use of each budgeted application against its budget.
+    pub fn app_limit_progress(&self) -> Vec<(String, BreakProgress)> {
+        self.settings
+            .app_limits
+            .limits
+            .iter()
+            .map(|limit| (limit.class.clone(), self.app_progress(&limit.class)))
+            .collect()
+    }
+
+    fn app_progress(&self, class: &str) -> BreakProgress {
+        BreakProgress {
+            elapsed_seconds: self
+                .app_active
+                .get(&class.to_ascii_lowercase())
+                .copied()
+                .unwrap_or(0),
+            target_seconds: self
+                .settings
+                .app_limits
+                .limit_for(class)
+                .map_or(0, |limit| limit.limit_seconds),
+        }
+    }
+
+    fn snooze_remaining(&self, until: Option<u64>, now_unix: u64) -> u64 {
         until
-            .map(|value| value.saturating_sub(now_local_unix))
+            .map(|value| value.saturating_sub(now_unix))
             .unwrap_or(0)
     }
 
+    fn longest_enabled_interval(&self) -> Option<u64> {
+        self.timer_specs()
+            .into_iter()
+            .filter(|spec| spec.timer.enabled)
+            .map(|spec| spec.timer.interval_seconds)
+            .max()
+    }
+
     fn complete_break(&mut self, kind: BreakKind) {
-        match kind {
-            BreakKind::Micro => self.micro_active = 0,
-            BreakKind::Rest => {
-                self.rest_active = 0;
-                self.micro_active = 0;
+        self.active_streak = 0;
+        if kind == BreakKind::DailyLimit {
+            self.daily_active = 0;
+            self.shared_daily_baseline = self.shared_daily_active;
+            self.daily_warning_percent = 0;
+            self.daily_snoozes = 0;
+            if self.settings.daily_limit.resets_timers {
+                for state in &mut self.timers {
+                    state.restart();
+                }
             }
-            BreakKind::DailyLimit => {
-                self.daily_active = 0;
-                self.rest_active = 0;
-                self.micro_active = 0;
+            return;
+        }
+
+        let resets = self
+            .timer_spec(kind)
+            .map(|spec| spec.resets)
+            .unwrap_or_default();
+        for reset in std::iter::once(kind).chain(resets) {
+            if let Some(state) = Self::slot(reset).and_then(|slot| self.timers.get_mut(slot)) {
+                state.restart();
             }
         }
     }
 
-    fn is_snoozed(until: Option<u64>, now_local_unix: u64) -> bool {
-        until.is_some_and(|value| now_local_unix < value)
+    fn is_snoozed(until: Option<u64>, now_unix: u64) -> bool {
+        until.is_some_and(|value| now_unix < value)
     }
 
-    fn maybe_daily_reset(&mut self, now_local_unix: u64) -> bool {
-        let bucket = Self::daily_bucket(
-            now_local_unix,
-            self.settings.daily_limit.reset_offset_seconds(),
-        );
-        if bucket != self.last_reset_bucket {
+    fn maybe_daily_reset(&mut self, now_unix: u64) -> bool {
+        // Only forward: when the zone or clock moves back, as when DST ends
+        // just after the reset, the day already started is not started again.
+        let bucket = self.daily_bucket(now_unix);
+        if bucket > self.last_reset_bucket {
             self.last_reset_bucket = bucket;
             self.daily_active = 0;
+            self.shared_daily_active = 0;
+            self.shared_daily_baseline = 0;
             self.daily_snooze_until = None;
+            self.daily_snoozes = 0;
+            self.daily_warning_percent = 0;
+            self.app_active.clear();
+            self.app_limits_announced.clear();
+            self.wind_down_active = 0;
+            self.wind_down_budget_announced = false;
+            if self.settings_for_day(bucket) != self.settings {
+                trace_event!("day overrides changed at {now_unix}");
+                self.resolve_settings(self.base_settings.runtime.interval_change_policy);
+            }
             return true;
         }
         false
     }
 
-    fn daily_bucket(now_local_unix: u64, reset_offset_seconds: u64) -> i64 {
-        ((now_local_unix as i64 - reset_offset_seconds as i64) / 86_400) as i64
+    /// Phase of the wind-down window at the latest `on_activity`.
+    pub fn wind_down_phase(&self) -> WindDownPhase {
+        self.wind_down
+    }
+
+    /// Active time inside today's wind-down window against its budget.
+    pub fn wind_down_progress(&self) -> BreakProgress {
+        BreakProgress {
+            elapsed_seconds: self.wind_down_active,
+            target_seconds: self.settings.wind_down.budget_seconds,
+        }
+    }
+
+    fn wind_down_scale_percent(&self) -> u8 {
+        if self.settings.wind_down.enabled && self.wind_down != WindDownPhase::Off {
+            self.settings.wind_down.interval_percent.clamp(1, 100)
+        } else {
+            100
+        }
+    }
+
+    fn wind_down_budget_spent(&self) -> bool {
+        let budget = self.settings.wind_down.budget_seconds;
+        self.settings.wind_down.enabled && budget > 0 && self.wind_down_active >= budget
     }
 
-    fn seconds_until_next_reset(&self, now_local_unix: u64, reset_offset_seconds: u64) -> u64 {
-        let current_bucket = Self::daily_bucket(now_local_unix, reset_offset_seconds);
-        let next_reset = (current_bucket + 1) * 86_400 + reset_offset_seconds as i64;
-        if next_reset <= now_local_unix as i64 {
-            0
+    // Falls back to `Off` at the daily reset, so each phase is announced
+    // once a day. Jumping straight past the cutoff skips `WindDownStarted`.
+    fn wind_down_event(&mut self, now_unix: u64) -> Option<EngineEvent> {
+        let phase = self.wind_down_phase_at(now_unix);
+        let previous = std::mem::replace(&mut self.wind_down, phase);
+        if phase <= previous {
+            return None;
+        }
+        trace_event!("wind-down phase {phase:?} at {now_unix}");
+        match phase {
+            WindDownPhase::Off => None,
+            WindDownPhase::Winding => Some(EngineEvent::WindDownStarted),
+            WindDownPhase::PastCutoff => Some(EngineEvent::WindDownCutoff),
+        }
+    }
+
+    // Measured from the daily reset, so a window running past midnight
+    // stays in the day it started. A cutoff before the start in that order
+    // leaves no winding time at all.
+    fn wind_down_phase_at(&self, now_unix: u64) -> WindDownPhase {
+        let wind_down = &self.settings.wind_down;
+        if !wind_down.enabled {
+            return WindDownPhase::Off;
+        }
+        let reset = self.settings.daily_limit.reset_offset_seconds() as i64;
+        let since_reset = |offset: u64| (offset as i64 - reset).rem_euclid(86_400);
+        let now = self
+            .local_seconds(now_unix)
+            .saturating_sub(reset)
+            .rem_euclid(86_400);
+        if now >= since_reset(wind_down.cutoff_offset_seconds()) {
+            WindDownPhase::PastCutoff
+        } else if now >= since_reset(wind_down.start_offset_seconds()) {
+            WindDownPhase::Winding
         } else {
-            (next_reset as u64).saturating_sub(now_local_unix)
+            WindDownPhase::Off
+        }
+    }
+
+    // Overrides never move the reset time or the week start, which is why
+    // the buckets are taken from the base settings.
+    fn settings_for_day(&self, bucket: i64) -> Settings {
+        if self.base_settings.day_overrides.is_empty() {
+            return self.base_settings.clone();
+        }
+        self.base_settings
+            .for_weekday(Weekday::from_day_index(bucket))
+    }
+
+    fn maybe_weekly_reset(&mut self, now_unix: u64) {
+        let bucket = self.week_bucket(now_unix);
+        if bucket > self.last_week_bucket {
+            trace_event!("weekly reset applied at {now_unix}");
+            self.last_week_bucket = bucket;
+            self.weekly_active = 0;
+            self.weekly_warning_percent = 0;
+            self.weekly_limit_reached = false;
         }
     }
+
+    // Weeks roll over at the daily reset time of their first day.
+    fn week_bucket(&self, now_unix: u64) -> i64 {
+        week_start_day(
+            self.daily_bucket(now_unix),
+            self.base_settings.analytics.week_start,
+        )
+    }
+
+    // Days are counted on the local wall clock, starting at the reset time.
+    fn daily_bucket(&self, now_unix: u64) -> i64 {
+        self.local_seconds(now_unix)
+            .saturating_sub(self.base_settings.daily_limit.reset_offset_seconds() as i64)
+            .div_euclid(86_400)
+    }
+
+    // Saturates rather than wrapping for timestamps read back from damaged
+    // state, which can be anything.
+    fn local_seconds(&self, now_unix: u64) -> i64 {
+        i64::try_from(now_unix)
+            .unwrap_or(i64::MAX)
+            .saturating_add(self.utc_offset_seconds)
+    }
+
+    // Assumes the offset holds until then; a DST change in between moves
+    // the actual reset by the size of the shift.
+    fn seconds_until_next_reset(&self, now_unix: u64) -> u64 {
+        let next_reset = (self.daily_bucket(now_unix) + 1)
+            .saturating_mul(86_400)
+            .saturating_add(self.base_settings.daily_limit.reset_offset_seconds() as i64);
+        next_reset
+            .saturating_sub(self.local_seconds(now_unix))
+            .max(0) as u64
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::config::Settings;
+    use crate::config::{CustomBreakTimer, Settings, apply_setting};
+
+    fn eye_timer(priority: u8) -> CustomBreakTimer {
+        CustomBreakTimer {
+            id: "eyes".into(),
+            name: "Eyes".into(),
+            timer: BreakTimerSettings {
+                resets: vec![MICRO_TIMER_ID.into(), "unknown".into()],
+                ..BreakTimerSettings::new(100, 10, 30)
+            },
+            priority,
+        }
+    }
 
     #[test]
     fn micro_break_becomes_due_after_interval() {
@@ -302,8 +1791,10 @@ mod tests {
 
     #[test]
     fn strict_mode_autostarts_break() {
-        let mut settings = Settings::default();
-        settings.block_level = BlockLevel::Strict;
+        let settings = Settings {
+            block_level: BlockLevel::Strict,
+            ..Settings::default()
+        };
         let mut engine = TimerEngine::new(settings, 0);
 
         let events = engine.on_activity(180, 180);
@@ -331,6 +1822,129 @@ mod tests {
         assert_eq!(events, vec![EngineEvent::BreakDue(BreakKind::Micro)]);
     }
 
+    #[test]
+    fn explain_names_the_constraint_holding_each_timer() {
+        let mut engine = TimerEngine::new(Settings::default(), 0);
+        let _ = engine.on_activity(100, 100);
+
+        let reason_of = |engine: &TimerEngine, kind: BreakKind, now: u64| {
+            engine
+                .explain(now)
+                .into_iter()
+                .find(|entry| entry.kind == kind)
+                .unwrap()
+        };
+        let micro = reason_of(&engine, BreakKind::Micro, 100);
+        assert_eq!(micro.reason, ScheduleReason::Interval);
+        assert_eq!(micro.accumulated_seconds, 100);
+        assert_eq!(micro.next_due_at, Some(180));
+        // The 4:00 reset comes before four hours of activity can add up.
+        let daily = reason_of(&engine, BreakKind::DailyLimit, 100);
+        assert_eq!(daily.reason, ScheduleReason::AfterReset);
+        assert_eq!(daily.next_due_at, None);
+
+        let _ = engine.on_activity(80, 180);
+        assert_eq!(
+            reason_of(&engine, BreakKind::Micro, 180).reason,
+            ScheduleReason::Due
+        );
+
+        engine.set_inhibitor(Some(DeferReason::Meeting));
+        assert_eq!(
+            reason_of(&engine, BreakKind::Micro, 180).reason,
+            ScheduleReason::Deferred(DeferReason::Meeting)
+        );
+
+        engine.set_inhibitor(None);
+        engine.snooze(BreakKind::Micro, 180);
+        let micro = reason_of(&engine, BreakKind::Micro, 200);
+        assert_eq!(micro.reason, ScheduleReason::Snoozed);
+        assert_eq!(micro.snoozed_until, Some(330));
+        assert_eq!(micro.next_due_at, Some(330));
+    }
+
+    #[test]
+    fn snoozing_active_break_ends_it() {
+        let mut engine = TimerEngine::new(Settings::default(), 0);
+
+        let _ = engine.on_activity(180, 180);
+        let _ = engine.start_break(BreakKind::Micro);
+        assert_eq!(
+            engine.snooze_active(185),
+            Some(EngineEvent::BreakSnoozed(BreakKind::Micro, 335))
+        );
+        assert!(engine.active_break_info().is_none());
+        assert_eq!(engine.snooze_active(186), None);
+
+        let events = engine.on_activity(1, 400);
+        assert_eq!(events, vec![EngineEvent::BreakDue(BreakKind::Micro)]);
+    }
+
+    #[test]
+    fn abandoning_a_break_completes_it_only_past_half() {
+        let mut settings = Settings::default();
+        settings.micro.duration_seconds = 20;
+        let mut engine = TimerEngine::new(settings, 0);
+
+        let _ = engine.on_activity(180, 180);
+        let _ = engine.start_break(BreakKind::Micro);
+        let _ = engine.tick_break(9);
+        assert_eq!(
+            engine.abandon_break(189),
+            Some(EngineEvent::BreakSnoozed(BreakKind::Micro, 339))
+        );
+        assert!(engine.active_break_info().is_none());
+
+        let _ = engine.on_activity(1, 400);
+        let _ = engine.start_break(BreakKind::Micro);
+        let _ = engine.tick_break(10);
+        assert_eq!(
+            engine.abandon_break(410),
+            Some(EngineEvent::BreakCompleted(BreakKind::Micro))
+        );
+        assert!(engine.on_activity(1, 411).is_empty());
+        assert_eq!(engine.abandon_break(412), None);
+    }
+
+    #[test]
+    fn remaining_percent_follows_the_active_break() {
+        let mut settings = Settings::default();
+        settings.micro.duration_seconds = 30;
+        let mut engine = TimerEngine::new(settings, 0);
+        assert_eq!(engine.active_break_remaining_percent(), None);
+
+        let _ = engine.on_activity(180, 180);
+        let _ = engine.start_break(BreakKind::Micro);
+        assert_eq!(engine.active_break_remaining_percent(), Some(100));
+        let _ = engine.tick_break(10);
+        assert_eq!(engine.active_break_remaining_percent(), Some(67));
+        let _ = engine.tick_break(19);
+        assert_eq!(engine.active_break_remaining_percent(), Some(4));
+    }
+
+    #[test]
+    fn millisecond_ticks_keep_the_fractions() {
+        let mut settings = Settings::default();
+        settings.micro.duration_seconds = 2;
+        let mut engine = TimerEngine::new(settings, 0);
+        let _ = engine.on_activity(180, 180);
+        let _ = engine.start_break(BreakKind::Micro);
+
+        let _ = engine.tick_break_millis(250);
+        assert_eq!(engine.active_break_info(), Some((BreakKind::Micro, 2)));
+        assert_eq!(engine.active_break_remaining_millis(), Some(1_750));
+        for _ in 0..3 {
+            assert!(engine.tick_break_millis(250).is_empty());
+        }
+        assert_eq!(engine.active_break_info(), Some((BreakKind::Micro, 1)));
+        let _ = engine.tick_break_millis(999);
+        assert_eq!(engine.active_break_info(), Some((BreakKind::Micro, 1)));
+        assert_eq!(
+            engine.tick_break_millis(1),
+            vec![EngineEvent::BreakCompleted(BreakKind::Micro)]
+        );
+    }
+
     #[test]
     fn daily_reset_resets_limit_counter() {
         let settings = Settings::default();
@@ -343,6 +1957,36 @@ mod tests {
         assert!(!events.contains(&EngineEvent::BreakDue(BreakKind::DailyLimit)));
     }
 
+    #[test]
+    fn forced_reset_clears_the_day_without_touching_interval_timers() {
+        let mut settings = Settings::default();
+        settings.daily_limit.limit_seconds = 600;
+        let mut engine = TimerEngine::new(settings, 0);
+        engine.set_shared_daily_active(300);
+
+        let _ = engine.on_activity(600, 1_000);
+        assert!(engine.snooze(BreakKind::DailyLimit, 1_000).is_some());
+        let until = engine.daily_status(1_000).snoozed_until.unwrap();
+        let _ = engine.on_activity(1, until);
+        assert!(engine.pending_breaks().contains(&BreakKind::DailyLimit));
+        assert_eq!(engine.daily_status(until).used_seconds, 901);
+        let micro_before = engine.progress().micro;
+
+        engine.force_reset(until);
+
+        let status = engine.daily_status(until);
+        assert_eq!(status.used_seconds, 0);
+        assert_eq!(status.snoozes_used, 0);
+        assert_eq!(status.snoozed_until, None);
+        assert!(!engine.pending_breaks().contains(&BreakKind::DailyLimit));
+        assert_eq!(engine.progress().micro, micro_before);
+        assert!(
+            !engine
+                .on_activity(1, until + 1)
+                .contains(&EngineEvent::DailyReset)
+        );
+    }
+
     #[test]
     fn next_break_eta_prefers_earliest_kind() {
         let settings = Settings::default();
@@ -366,4 +2010,982 @@ mod tests {
         assert_eq!(kind, BreakKind::Micro);
         assert_eq!(eta, 130);
     }
+
+    #[test]
+    fn progress_reports_elapsed_against_targets() {
+        let settings = Settings::default();
+        let mut engine = TimerEngine::new(settings, 0);
+
+        let _ = engine.on_activity(90, 90);
+        let progress = engine.progress();
+        assert_eq!(progress.micro.elapsed_seconds, 90);
+        assert_eq!(progress.micro.percent(), 50);
+        assert_eq!(progress.rest.percent(), 3);
+        assert_eq!(progress.daily_limit.target_seconds, 14_400);
+
+        let _ = engine.on_activity(300, 390);
+        assert_eq!(engine.progress().micro.percent(), 100);
+    }
+
+    #[test]
+    fn daily_limit_warns_once_per_threshold() {
+        let settings = Settings::default();
+        let mut engine = TimerEngine::new(settings, 0);
+
+        let events = engine.on_activity(10_799, 10_799);
+        assert!(!events.contains(&EngineEvent::DailyLimitWarning(75)));
+        let events = engine.on_activity(1, 10_800);
+        assert!(events.contains(&EngineEvent::DailyLimitWarning(75)));
+        let events = engine.on_activity(1, 10_801);
+        assert!(!events.contains(&EngineEvent::DailyLimitWarning(75)));
+
+        let events = engine.on_activity(2_160, 12_961);
+        assert!(events.contains(&EngineEvent::DailyLimitWarning(90)));
+    }
+
+    #[test]
+    fn daily_limit_warnings_follow_configured_thresholds() {
+        let mut settings = Settings::default();
+        settings.micro.enabled = false;
+        settings.rest.enabled = false;
+        settings.daily_limit.warning_thresholds = vec![50];
+        let mut engine = TimerEngine::new(settings, 0);
+
+        let events = engine.on_activity(7_200, 7_200);
+        assert!(events.contains(&EngineEvent::DailyLimitWarning(50)));
+        let events = engine.on_activity(3_600, 10_800);
+        assert!(!events.contains(&EngineEvent::DailyLimitWarning(75)));
+
+        let mut settings = engine.settings().clone();
+        settings.daily_limit.warning_thresholds.clear();
+        let mut engine = TimerEngine::new(settings, 0);
+        assert!(
+            !engine
+                .on_activity(14_000, 14_000)
+                .iter()
+                .any(|event| matches!(event, EngineEvent::DailyLimitWarning(_)))
+        );
+    }
+
+    #[test]
+    fn weekly_limit_accumulates_across_days_until_the_week_rolls_over() {
+        let mut settings = Settings::default();
+        settings.micro.enabled = false;
+        settings.rest.enabled = false;
+        settings.daily_limit.enabled = false;
+        settings.weekly_limit.enabled = true;
+        settings.weekly_limit.limit_seconds = 10_000;
+        settings.weekly_limit.warning_thresholds = vec![50];
+        // Day 0 was a Thursday; weeks start on Monday at the 04:00 reset.
+        let mut engine = TimerEngine::new(settings.clone(), 14_400);
+
+        let events = engine.on_activity(5_000, 20_000);
+        assert!(events.contains(&EngineEvent::WeeklyLimitWarning(50)));
+        let events = engine.on_activity(5_000, 106_400);
+        assert!(events.contains(&EngineEvent::WeeklyLimitReached));
+        assert_eq!(
+            engine.on_activity(1, 356_400),
+            vec![EngineEvent::DailyReset]
+        );
+
+        let restored = TimerEngine::restore(settings, Some(&engine.snapshot(356_400)), 356_400);
+        assert_eq!(restored.progress().weekly_limit.elapsed_seconds, 10_001);
+
+        let events = engine.on_activity(5_000, 365_000);
+        assert!(events.contains(&EngineEvent::WeeklyLimitWarning(50)));
+        assert_eq!(engine.progress().weekly_limit.elapsed_seconds, 5_000);
+    }
+
+    #[test]
+    fn app_limits_fire_once_a_day_and_survive_restarts() {
+        let mut settings = Settings::default();
+        settings.app_limits.enabled = true;
+        settings.app_limits.limits = vec![crate::config::AppLimit {
+            class: "Firefox".into(),
+            limit_seconds: 60,
+        }];
+        let mut engine = TimerEngine::new(settings.clone(), 14_400);
+
+        assert!(engine.on_app_activity("code", 600).is_empty());
+        assert!(engine.on_app_activity("firefox", 50).is_empty());
+        assert!(!engine.app_limit_reached("firefox"));
+        assert_eq!(
+            engine.on_app_activity("firefox", 10),
+            vec![EngineEvent::AppLimitReached("Firefox".into())]
+        );
+        assert!(engine.on_app_activity("FIREFOX", 10).is_empty());
+        assert!(engine.app_limit_reached("firefox"));
+        assert_eq!(engine.exhausted_apps(), vec!["firefox".to_string()]);
+
+        let restored =
+            TimerEngine::restore(settings.clone(), Some(&engine.snapshot(20_000)), 20_000);
+        assert!(restored.app_limit_reached("Firefox"));
+        assert_eq!(restored.app_limit_progress()[0].1.elapsed_seconds, 70);
+
+        let mut raised = settings;
+        raised.app_limits.limits[0].limit_seconds = 120;
+        engine.update_settings(raised);
+        assert!(!engine.app_limit_reached("firefox"));
+        assert_eq!(
+            engine.on_app_activity("firefox", 50),
+            vec![EngineEvent::AppLimitReached("Firefox".into())]
+        );
+
+        assert_eq!(
+            engine.on_activity(1, 14_400 + 86_400),
+            vec![EngineEvent::DailyReset]
+        );
+        assert!(engine.exhausted_apps().is_empty());
+    }
+
+    #[test]
+    fn day_overrides_switch_at_the_daily_reset() {
+        let mut settings = Settings::default();
+        apply_setting(
+            &mut settings,
+            "day_overrides",
+            "sat daily_limit_enabled=false",
+        )
+        .unwrap();
+        // Friday 1970-01-02, 10:00 UTC.
+        let friday = 86_400 + 10 * 3_600;
+        let mut engine = TimerEngine::new(settings, friday);
+        assert!(engine.settings().daily_limit.enabled);
+
+        let saturday = 2 * 86_400 + 4 * 3_600;
+        let events = engine.on_activity(1, saturday);
+        assert!(events.contains(&EngineEvent::DailyReset));
+        assert!(!engine.settings().daily_limit.enabled);
+        assert!(engine.base_settings().daily_limit.enabled);
+
+        let restored = TimerEngine::restore(
+            engine.base_settings().clone(),
+            Some(&engine.snapshot(saturday)),
+            saturday + 60,
+        );
+        assert!(!restored.settings().daily_limit.enabled);
+
+        let _ = engine.on_activity(1, saturday + 86_400);
+        assert!(engine.settings().daily_limit.enabled);
+    }
+
+    #[test]
+    fn day_overrides_keep_the_weekly_reset() {
+        let mut settings = Settings::default();
+        apply_setting(
+            &mut settings,
+            "day_overrides",
+            "sat,sun micro_interval_seconds=600",
+        )
+        .unwrap();
+        // Sunday 1970-01-04 and the Monday after, both past the 04:00 reset.
+        let sunday = 3 * 86_400 + 10 * 3_600;
+        let monday = 4 * 86_400 + 4 * 3_600;
+        let mut engine = TimerEngine::new(settings, sunday);
+        let _ = engine.on_activity(5_000, sunday);
+        assert_eq!(engine.progress().weekly_limit.elapsed_seconds, 5_000);
+
+        let _ = engine.on_activity(1, monday);
+        assert_eq!(engine.settings().micro.interval_seconds, 180);
+        assert_eq!(engine.progress().weekly_limit.elapsed_seconds, 1);
+    }
+
+    #[test]
+    fn wind_down_shortens_intervals_and_announces_each_phase_once() {
+        let mut settings = Settings::default();
+        settings.micro.enabled = false;
+        settings.rest.enabled = false;
+        settings.wind_down.enabled = true;
+        settings.wind_down.budget_seconds = 600;
+        let evening = 86_400 + 20 * 3_600;
+        let mut engine = TimerEngine::new(settings.clone(), evening);
+        let micro = settings.micro.clone();
+
+        assert!(engine.on_activity(1, evening).is_empty());
+        assert_eq!(engine.effective_interval(BreakKind::Micro, &micro), 180);
+
+        let start = evening + 3_600;
+        assert_eq!(
+            engine.on_activity(1, start),
+            vec![EngineEvent::WindDownStarted]
+        );
+        assert_eq!(engine.wind_down_phase(), WindDownPhase::Winding);
+        assert_eq!(engine.effective_interval(BreakKind::Micro, &micro), 135);
+        assert!(engine.on_activity(1, start + 1).is_empty());
+        assert_eq!(
+            engine.on_activity(598, start + 600),
+            vec![EngineEvent::WindDownBudgetReached]
+        );
+        assert!(engine.on_activity(10, start + 610).is_empty());
+
+        let restored =
+            TimerEngine::restore(settings, Some(&engine.snapshot(start + 610)), start + 610);
+        assert_eq!(restored.wind_down_progress().elapsed_seconds, 610);
+
+        let cutoff = start + 2 * 3_600;
+        assert_eq!(
+            engine.on_activity(1, cutoff),
+            vec![EngineEvent::WindDownCutoff]
+        );
+        assert!(engine.on_activity(1, cutoff + 2 * 3_600).is_empty());
+        assert_eq!(engine.wind_down_phase(), WindDownPhase::PastCutoff);
+
+        let reset = 2 * 86_400 + 4 * 3_600;
+        assert_eq!(engine.on_activity(1, reset), vec![EngineEvent::DailyReset]);
+        assert_eq!(engine.wind_down_phase(), WindDownPhase::Off);
+        assert_eq!(engine.wind_down_progress().elapsed_seconds, 0);
+    }
+
+    #[test]
+    fn shared_activity_counts_toward_daily_limit() {
+        let mut settings = Settings::default();
+        settings.micro.enabled = false;
+        settings.rest.enabled = false;
+        settings.daily_limit.limit_seconds = 3_600;
+        let mut engine = TimerEngine::new(settings, 0);
+
+        engine.set_shared_daily_active(3_000);
+        assert_eq!(engine.progress().daily_limit.elapsed_seconds, 3_000);
+        let events = engine.on_activity(600, 600);
+        assert!(events.contains(&EngineEvent::BreakDue(BreakKind::DailyLimit)));
+
+        engine.start_break(BreakKind::DailyLimit);
+        engine.tick_break(u64::MAX);
+        assert_eq!(engine.progress().daily_limit.elapsed_seconds, 0);
+        engine.set_shared_daily_active(3_100);
+        assert_eq!(engine.progress().daily_limit.elapsed_seconds, 100);
+    }
+
+    #[test]
+    fn daily_snoozes_run_out_until_the_limit_break_is_taken() {
+        let mut settings = Settings::default();
+        settings.daily_limit.max_snoozes = 2;
+        let mut engine = TimerEngine::new(settings, 0);
+
+        engine.snooze(BreakKind::DailyLimit, 10);
+        assert!(!engine.daily_snoozes_exhausted());
+        engine.snooze(BreakKind::DailyLimit, 20);
+        assert!(engine.daily_snoozes_exhausted());
+
+        engine.start_break(BreakKind::DailyLimit);
+        engine.tick_break(u64::MAX);
+        assert!(!engine.daily_snoozes_exhausted());
+    }
+
+    #[test]
+    fn warmup_grace_holds_back_first_break() {
+        let mut settings = Settings::default();
+        settings.startup.warmup_grace_seconds = 600;
+        let mut engine = TimerEngine::restore(settings, None, 0);
+
+        assert_eq!(engine.next_break_eta(0), Some((BreakKind::Micro, 600)));
+        assert!(engine.on_activity(180, 180).is_empty());
+        assert_eq!(
+            engine.on_activity(420, 600),
+            vec![EngineEvent::BreakDue(BreakKind::Micro)]
+        );
+    }
+
+    #[test]
+    fn restore_counts_long_idle_as_rest() {
+        let mut settings = Settings::default();
+        settings.micro.interval_seconds = 1_000;
+        let mut engine = TimerEngine::new(settings.clone(), 0);
+        engine.on_activity(900, 900);
+        let snapshot = engine.snapshot(900);
+
+        // Away longer than the micro break but shorter than the rest break.
+        let restored = TimerEngine::restore(settings.clone(), Some(&snapshot), 960);
+        assert_eq!(restored.progress().micro.elapsed_seconds, 0);
+        assert_eq!(restored.progress().rest.elapsed_seconds, 900);
+        assert_eq!(restored.progress().daily_limit.elapsed_seconds, 900);
+
+        settings.startup.idle_counts_as_rest = false;
+        let restored = TimerEngine::restore(settings, Some(&snapshot), 960);
+        assert_eq!(restored.progress().micro.elapsed_seconds, 900);
+    }
+
+    #[test]
+    fn forecast_projects_limit_from_recent_density() {
+        let mut settings = Settings::default();
+        settings.daily_limit.limit_seconds = 3_600;
+        let mut engine = TimerEngine::new(settings, 0);
+
+        assert_eq!(
+            engine.forecast_daily_limit(30).unwrap().seconds_until_limit,
+            None
+        );
+
+        for minute in 1..=20 {
+            engine.on_activity(30, minute * 60);
+        }
+        let forecast = engine.forecast_daily_limit(1_200).expect("forecast");
+        assert_eq!(forecast.used_seconds, 600);
+        assert_eq!(forecast.activity_density_percent, 50);
+        assert_eq!(forecast.seconds_until_limit, Some(6_000));
+        assert_eq!(forecast.reached_at, Some(7_200));
+        assert!(forecast.before_reset);
+    }
+
+    #[test]
+    fn custom_timer_becomes_due_and_resets_listed_timers() {
+        let mut settings = Settings::default();
+        settings.custom_timers.push(eye_timer(0));
+        let mut engine = TimerEngine::new(settings, 0);
+
+        let events = engine.on_activity(100, 100);
+        assert_eq!(events, vec![EngineEvent::BreakDue(BreakKind::Custom(0))]);
+        assert_eq!(
+            engine.timer_specs()[2].resets,
+            vec![BreakKind::Micro],
+            "unknown ids are ignored"
+        );
+
+        engine.start_break(BreakKind::Custom(0));
+        let events = engine.tick_break(10);
+        assert_eq!(
+            events,
+            vec![EngineEvent::BreakCompleted(BreakKind::Custom(0))]
+        );
+        let progress = engine.timer_progress();
+        assert_eq!(progress[0].1.elapsed_seconds, 0);
+        assert_eq!(progress[1].1.elapsed_seconds, 100);
+        assert_eq!(progress[2].1.elapsed_seconds, 0);
+    }
+
+    #[test]
+    fn timer_priority_breaks_ties_between_due_timers() {
+        let mut settings = Settings::default();
+        settings.micro.enabled = false;
+        settings.rest.interval_seconds = 100;
+        settings.custom_timers.push(eye_timer(5));
+        let mut engine = TimerEngine::new(settings.clone(), 0);
+        assert_eq!(
+            engine.on_activity(100, 100),
+            vec![EngineEvent::BreakDue(BreakKind::Rest)]
+        );
+
+        settings.custom_timers[0].priority = 0;
+        engine.update_settings(settings);
+        assert_eq!(
+            engine.on_activity(1, 101),
+            vec![EngineEvent::BreakDue(BreakKind::Custom(0))]
+        );
+    }
+
+    #[test]
+    fn breaks_restarted_by_a_due_break_are_absorbed() {
+        let mut settings = Settings::default();
+        settings.micro.interval_seconds = 100;
+        settings.rest.interval_seconds = 90;
+        let mut engine = TimerEngine::new(settings, 0);
+        assert_eq!(
+            engine.on_activity(90, 90),
+            vec![EngineEvent::BreakDue(BreakKind::Rest)]
+        );
+        // Micro comes due while rest is still pending: rest keeps the prompt.
+        assert_eq!(
+            engine.on_activity(10, 100),
+            vec![EngineEvent::BreakDue(BreakKind::Rest)]
+        );
+
+        let _ = engine.start_break(BreakKind::Rest);
+        assert_eq!(
+            engine.tick_break(300),
+            vec![EngineEvent::BreakCompleted(BreakKind::Rest)]
+        );
+        assert!(engine.on_activity(1, 401).is_empty());
+
+        // Both due on the same tick: the rest is offered, not the micro.
+        let mut engine = TimerEngine::new(Settings::default(), 0);
+        assert_eq!(
+            engine.on_activity(2_700, 2_700),
+            vec![EngineEvent::BreakDue(BreakKind::Rest)]
+        );
+    }
+
+    #[test]
+    fn breaks_that_do_not_cover_each_other_keep_priority_order() {
+        let mut settings = Settings::default();
+        settings.micro.interval_seconds = 100;
+        settings.rest.interval_seconds = 100;
+        settings.rest.resets.clear();
+        let mut engine = TimerEngine::new(settings.clone(), 0);
+        assert!(!engine.absorbs(BreakKind::Rest, BreakKind::Micro));
+        assert_eq!(
+            engine.on_activity(100, 100),
+            vec![EngineEvent::BreakDue(BreakKind::Micro)]
+        );
+
+        settings.micro.resets = vec![REST_TIMER_ID.into()];
+        settings.rest.resets = vec![MICRO_TIMER_ID.into()];
+        engine.update_settings(settings);
+        assert_eq!(
+            engine.on_activity(1, 101),
+            vec![EngineEvent::BreakDue(BreakKind::Micro)]
+        );
+    }
+
+    #[test]
+    fn daily_limit_that_restarts_timers_swallows_a_pending_break() {
+        for resets_timers in [true, false] {
+            let mut settings = Settings::default();
+            settings.micro.enabled = false;
+            settings.rest.interval_seconds = 90;
+            settings.daily_limit.limit_seconds = 100;
+            settings.daily_limit.resets_timers = resets_timers;
+            let mut engine = TimerEngine::new(settings, 0);
+            assert!(
+                engine
+                    .on_activity(90, 90)
+                    .contains(&EngineEvent::BreakDue(BreakKind::Rest))
+            );
+
+            let expected = if resets_timers {
+                BreakKind::DailyLimit
+            } else {
+                BreakKind::Rest
+            };
+            assert!(
+                engine
+                    .on_activity(10, 100)
+                    .contains(&EngineEvent::BreakDue(expected))
+            );
+        }
+    }
+
+    #[test]
+    fn breaks_due_together_queue_up_instead_of_replacing_each_other() {
+        let mut settings = Settings::default();
+        settings.micro.enabled = false;
+        settings.rest.interval_seconds = 90;
+        settings.daily_limit.limit_seconds = 100;
+        settings.daily_limit.resets_timers = false;
+        let mut engine = TimerEngine::new(settings, 0);
+        let _ = engine.on_activity(90, 90);
+        assert_eq!(engine.pending_breaks(), [BreakKind::Rest]);
+
+        let events = engine.on_activity(10, 100);
+        assert!(events.contains(&EngineEvent::BreakDue(BreakKind::Rest)));
+        assert!(!events.contains(&EngineEvent::BreakDue(BreakKind::DailyLimit)));
+        assert_eq!(
+            engine.pending_breaks(),
+            [BreakKind::Rest, BreakKind::DailyLimit]
+        );
+
+        let _ = engine.snooze(BreakKind::Rest, 100);
+        assert_eq!(engine.pending_break(), Some(BreakKind::DailyLimit));
+        assert!(
+            engine
+                .on_activity(1, 101)
+                .contains(&EngineEvent::BreakDue(BreakKind::DailyLimit))
+        );
+        let _ = engine.start_break(BreakKind::DailyLimit);
+        assert!(engine.pending_breaks().is_empty());
+    }
+
+    #[test]
+    fn snoozing_for_a_chosen_time_overrides_the_configured_one() {
+        let mut settings = Settings::default();
+        settings.micro.interval_seconds = 100;
+        settings.rest.enabled = false;
+        let mut engine = TimerEngine::new(settings, 0);
+        let _ = engine.on_activity(100, 100);
+
+        assert_eq!(
+            engine.snooze_for(BreakKind::Micro, 100, 600),
+            Some(EngineEvent::BreakSnoozed(BreakKind::Micro, 700))
+        );
+        assert!(engine.pending_breaks().is_empty());
+        assert!(engine.on_activity(599, 699).is_empty());
+        assert_eq!(
+            engine.on_activity(1, 700),
+            vec![EngineEvent::BreakDue(BreakKind::Micro)]
+        );
+        assert_eq!(engine.snooze_for(BreakKind::Custom(3), 700, 60), None);
+    }
+
+    #[test]
+    fn disabled_timers_neither_accumulate_nor_report_eta() {
+        let mut settings = Settings::default();
+        settings.micro.enabled = false;
+        settings.daily_limit.enabled = false;
+        let mut engine = TimerEngine::new(settings.clone(), 0);
+
+        let events = engine.on_activity(180, 180);
+        assert!(events.is_empty());
+        assert_eq!(engine.progress().micro.elapsed_seconds, 0);
+        let (kind, _) = engine.next_break_eta(180).expect("rest eta");
+        assert_eq!(kind, BreakKind::Rest);
+        assert!(engine.forecast_daily_limit(180).is_none());
+
+        settings.rest.enabled = false;
+        engine.update_settings(settings);
+        assert_eq!(engine.next_break_eta(181), None);
+    }
+
+    #[test]
+    fn heavy_input_shortens_only_the_micro_interval() {
+        let mut settings = Settings::default();
+        settings.intensity.enabled = true;
+        settings.intensity.heavy_events_per_minute = 100;
+        settings.intensity.max_reduction_percent = 40;
+        let mut engine = TimerEngine::new(settings.clone(), 0);
+        let interval = |engine: &TimerEngine, kind| {
+            let spec = engine.timer_spec(kind).expect("timer");
+            engine.effective_interval(kind, &spec.timer)
+        };
+
+        engine.set_input_intensity(400);
+        assert_eq!(
+            interval(&engine, BreakKind::Micro),
+            180,
+            "scaling is opt-in"
+        );
+
+        settings.intensity.scale_micro = true;
+        engine.update_settings(settings);
+        engine.set_input_intensity(99);
+        assert_eq!(engine.intensity_scale_percent(), 100);
+        engine.set_input_intensity(150);
+        assert_eq!(engine.intensity_scale_percent(), 80);
+        assert_eq!(interval(&engine, BreakKind::Micro), 144);
+        engine.set_input_intensity(400);
+        assert_eq!(engine.intensity_scale_percent(), 60);
+        assert_eq!(interval(&engine, BreakKind::Micro), 108);
+        assert_eq!(interval(&engine, BreakKind::Rest), 2_700);
+    }
+
+    #[test]
+    fn jitter_varies_intervals_deterministically_within_bounds() {
+        let mut settings = Settings::default();
+        settings.micro.interval_seconds = 1_000;
+        settings.micro.jitter_percent = 20;
+        settings.rest.enabled = false;
+        settings.daily_limit.enabled = false;
+
+        let run = |seed: u64| {
+            let mut engine = TimerEngine::new(settings.clone(), 0);
+            engine.set_jitter_seed(seed);
+            let mut intervals = Vec::new();
+            for _ in 0..8 {
+                let interval = engine.progress().micro.target_seconds;
+                intervals.push(interval);
+                engine.start_break(BreakKind::Micro);
+                engine.tick_break(20);
+            }
+            intervals
+        };
+
+        let intervals = run(7);
+        assert_eq!(intervals, run(7));
+        assert!(intervals.iter().all(|value| (800..=1_200).contains(value)));
+        assert!(intervals.windows(2).any(|pair| pair[0] != pair[1]));
+
+        let mut engine = TimerEngine::new(settings, 0);
+        engine.set_jitter_seed(7);
+        let due_at = intervals[0];
+        assert!(engine.on_activity(due_at - 1, due_at - 1).is_empty());
+        assert_eq!(
+            engine.on_activity(1, due_at),
+            vec![EngineEvent::BreakDue(BreakKind::Micro)]
+        );
+    }
+
+    #[test]
+    fn adaptive_mode_tightens_on_skips_and_relaxes_on_compliance() {
+        let mut settings = Settings::default();
+        settings.adaptive.enabled = true;
+        settings.adaptive.min_interval_percent = 80;
+        let mut engine = TimerEngine::new(settings, 0);
+        let skipping = Compliance {
+            taken: 1,
+            avoided: 3,
+        };
+
+        assert_eq!(
+            engine.adapt(&skipping),
+            Some(EngineEvent::IntervalsAdjusted(
+                90,
+                AdaptReason::FrequentSkips
+            ))
+        );
+        assert_eq!(engine.progress().micro.target_seconds, 162);
+        assert!(engine.adapt(&skipping).is_some());
+        assert_eq!(engine.adapt(&skipping), None, "clamped to the minimum");
+        assert_eq!(engine.interval_scale_percent(), 80);
+
+        let compliant = Compliance {
+            taken: 5,
+            avoided: 0,
+        };
+        assert_eq!(
+            engine.adapt(&compliant),
+            Some(EngineEvent::IntervalsAdjusted(
+                90,
+                AdaptReason::GoodCompliance
+            ))
+        );
+
+        engine.edit_settings(|settings| settings.adaptive.enabled = false);
+        assert_eq!(
+            engine.adapt(&compliant),
+            Some(EngineEvent::IntervalsAdjusted(100, AdaptReason::Disabled))
+        );
+        assert_eq!(engine.progress().micro.target_seconds, 180);
+    }
+
+    #[test]
+    fn per_timer_block_level_overrides_global() {
+        let mut settings = Settings {
+            block_level: BlockLevel::Strict,
+            ..Settings::default()
+        };
+        settings.micro.block_level = Some(BlockLevel::Soft);
+        let engine = TimerEngine::new(settings, 0);
+
+        assert_eq!(engine.block_level(BreakKind::Micro), BlockLevel::Soft);
+        assert_eq!(engine.block_level(BreakKind::Rest), BlockLevel::Strict);
+        assert_eq!(
+            engine.block_level(BreakKind::DailyLimit),
+            BlockLevel::Strict
+        );
+
+        let mut settings = Settings::default();
+        settings.micro.block_level = Some(BlockLevel::Strict);
+        let mut engine = TimerEngine::new(settings, 0);
+        assert!(
+            engine
+                .on_activity(180, 180)
+                .contains(&EngineEvent::BreakStarted(BreakKind::Micro))
+        );
+    }
+
+    #[test]
+    fn inhibitor_defers_due_break_until_cleared() {
+        let settings = Settings {
+            block_level: BlockLevel::Strict,
+            ..Settings::default()
+        };
+        let mut engine = TimerEngine::new(settings, 0);
+        engine.set_inhibitor(Some(DeferReason::Fullscreen));
+
+        let events = engine.on_activity(180, 180);
+        assert_eq!(
+            events,
+            vec![EngineEvent::BreakDeferred(
+                BreakKind::Micro,
+                DeferReason::Fullscreen
+            )]
+        );
+
+        let events = engine.on_activity(1, 181);
+        assert!(events.is_empty());
+        assert!(engine.active_break_info().is_none());
+
+        engine.set_inhibitor(None);
+        let events = engine.on_activity(1, 182);
+        assert_eq!(
+            events,
+            vec![
+                EngineEvent::BreakDue(BreakKind::Micro),
+                EngineEvent::BreakStarted(BreakKind::Micro)
+            ]
+        );
+    }
+
+    #[test]
+    fn interval_changes_follow_the_policy() {
+        let mut settings = Settings::default();
+        settings.micro.interval_seconds = 600;
+        settings.rest.enabled = false;
+        settings.daily_limit.enabled = false;
+        let mut shorter = settings.clone();
+        shorter.micro.interval_seconds = 300;
+
+        let run = |policy| {
+            let mut engine = TimerEngine::new(settings.clone(), 0);
+            assert!(engine.on_activity(400, 400).is_empty());
+            engine.apply_settings(shorter.clone(), policy);
+            (
+                engine.progress().micro.elapsed_seconds,
+                engine.on_activity(1, 401),
+            )
+        };
+
+        assert_eq!(run(IntervalChangePolicy::Rescale), (200, Vec::new()));
+        assert_eq!(run(IntervalChangePolicy::Reset), (0, Vec::new()));
+        assert_eq!(
+            run(IntervalChangePolicy::Preserve),
+            (400, vec![EngineEvent::BreakDue(BreakKind::Micro)])
+        );
+    }
+
+    #[test]
+    fn reordered_custom_timers_keep_their_counters() {
+        let mut settings = Settings::default();
+        let mut stretch = eye_timer(5);
+        stretch.id = "stretch".into();
+        stretch.timer.interval_seconds = 1_000;
+        settings.custom_timers = vec![eye_timer(5), stretch];
+        let mut engine = TimerEngine::new(settings.clone(), 0);
+        engine.on_activity(50, 50);
+        engine.start_break(BreakKind::Custom(0));
+        engine.tick_break(10);
+        engine.on_activity(20, 80);
+
+        settings.custom_timers.reverse();
+        engine.apply_settings(settings, IntervalChangePolicy::Rescale);
+        let progress = engine.timer_progress();
+        assert_eq!(progress[2].1.elapsed_seconds, 70);
+        assert_eq!(progress[3].1.elapsed_seconds, 20);
+    }
+
+    #[test]
+    fn moving_the_reset_time_keeps_the_daily_usage() {
+        let mut settings = Settings::default();
+        settings.micro.enabled = false;
+        settings.rest.enabled = false;
+        let mut engine = TimerEngine::new(settings.clone(), 0);
+        // 04:30 on day 1, just after the default 04:00 reset.
+        let now = 86_400 + 16_200;
+        let _ = engine.on_activity(600, now);
+
+        settings.daily_limit.reset_hour_local = 5;
+        settings.daily_limit.limit_seconds = 28_800;
+        engine.apply_settings(settings, IntervalChangePolicy::Rescale);
+        assert!(
+            !engine
+                .on_activity(60, now + 60)
+                .contains(&EngineEvent::DailyReset)
+        );
+        assert_eq!(engine.progress().daily_limit.elapsed_seconds, 660);
+
+        let events = engine.on_activity(60, 2 * 86_400 + 18_000);
+        assert!(events.contains(&EngineEvent::DailyReset));
+    }
+
+    fn resets_between(engine: &mut TimerEngine, from: u64, to: u64) -> usize {
+        (from..to)
+            .step_by(60)
+            .filter(|now| {
+                engine
+                    .on_activity(1, *now)
+                    .contains(&EngineEvent::DailyReset)
+            })
+            .count()
+    }
+
+    fn dst_settings() -> Settings {
+        let mut settings = Settings::default();
+        settings.micro.enabled = false;
+        settings.rest.enabled = false;
+        settings.daily_limit.enabled = false;
+        settings.daily_limit.reset_hour_local = 2;
+        settings.daily_limit.reset_minute_local = 30;
+        settings
+    }
+
+    #[test]
+    fn dst_ending_after_the_reset_does_not_fire_it_twice() {
+        // 2024-10-27 in Madrid: 03:00 CEST (+2h) becomes 02:00 CET (+1h) at
+        // 01:00 UTC, so 02:30 local happens twice.
+        let switch = 1_729_990_800;
+        let mut engine = TimerEngine::new_in_zone(dst_settings(), switch - 7_200, 7_200);
+
+        assert_eq!(resets_between(&mut engine, switch - 7_200, switch), 1);
+        engine.set_utc_offset(3_600);
+        assert_eq!(resets_between(&mut engine, switch, switch + 7_200), 0);
+        assert_eq!(
+            resets_between(&mut engine, switch + 7_200, switch + 93_600),
+            1
+        );
+    }
+
+    #[test]
+    fn dst_starting_over_the_reset_fires_it_once() {
+        // 2024-03-31 in Madrid: 02:00 CET (+1h) becomes 03:00 CEST (+2h) at
+        // 01:00 UTC, so 02:30 local never happens.
+        let switch = 1_711_846_800;
+        let mut engine = TimerEngine::new_in_zone(dst_settings(), switch - 3_600, 3_600);
+
+        assert_eq!(resets_between(&mut engine, switch - 3_600, switch), 0);
+        engine.set_utc_offset(7_200);
+        assert_eq!(resets_between(&mut engine, switch, switch + 7_200), 1);
+        assert_eq!(
+            engine.seconds_until_next_reset(switch + 7_200),
+            86_400 - 7_200 - 1_800
+        );
+    }
+
+    #[test]
+    fn resets_follow_local_time_after_travel() {
+        // 22:00 UTC is 23:00 in Madrid but already 07:00 in Tokyo.
+        let now = 19_723 * 86_400 + 79_200;
+        let mut settings = dst_settings();
+        settings.daily_limit.enabled = true;
+        let mut engine = TimerEngine::new_in_zone(settings.clone(), now, 3_600);
+        let _ = engine.on_activity(600, now);
+
+        engine.set_utc_offset(9 * 3_600);
+        assert!(
+            engine
+                .on_activity(60, now + 60)
+                .contains(&EngineEvent::DailyReset)
+        );
+        assert_eq!(engine.progress().daily_limit.elapsed_seconds, 60);
+
+        let snapshot = engine.snapshot(now + 120);
+        let restored =
+            TimerEngine::restore_in_zone(settings, Some(&snapshot), now + 180, 9 * 3_600);
+        assert_eq!(restored.progress().daily_limit.elapsed_seconds, 60);
+    }
+
+    #[test]
+    fn completed_breaks_reset_the_timers_they_list() {
+        let run = |micro_resets: &[&str], rest_resets: &[&str], completed: BreakKind| {
+            let mut settings = Settings::default();
+            settings.micro.interval_seconds = 1_000;
+            settings.rest.interval_seconds = 1_000;
+            settings.micro.resets = micro_resets.iter().map(|id| id.to_string()).collect();
+            settings.rest.resets = rest_resets.iter().map(|id| id.to_string()).collect();
+            let mut engine = TimerEngine::new(settings, 0);
+            assert!(engine.on_activity(100, 100).is_empty());
+            engine.start_break(completed);
+            assert_eq!(
+                engine.tick_break(1_000),
+                vec![EngineEvent::BreakCompleted(completed)]
+            );
+            let progress = engine.progress();
+            (
+                progress.micro.elapsed_seconds,
+                progress.rest.elapsed_seconds,
+            )
+        };
+
+        assert_eq!(run(&[], &[MICRO_TIMER_ID], BreakKind::Rest), (0, 0));
+        assert_eq!(run(&[], &[], BreakKind::Rest), (100, 0));
+        assert_eq!(run(&[REST_TIMER_ID], &[], BreakKind::Micro), (0, 0));
+        assert_eq!(run(&[], &[], BreakKind::Micro), (0, 100));
+        assert_eq!(
+            run(&[MICRO_TIMER_ID], &[REST_TIMER_ID], BreakKind::Micro),
+            (0, 100),
+            "a timer does not list itself"
+        );
+    }
+
+    #[test]
+    fn daily_limit_break_restarts_timers_only_when_configured() {
+        for resets_timers in [true, false] {
+            let mut settings = Settings::default();
+            settings.micro.interval_seconds = 1_000;
+            settings.daily_limit.resets_timers = resets_timers;
+            let mut engine = TimerEngine::new(settings, 0);
+            assert!(engine.on_activity(100, 100).is_empty());
+            engine.start_break(BreakKind::DailyLimit);
+            engine.tick_break(60);
+
+            let expected = if resets_timers { 0 } else { 100 };
+            assert_eq!(engine.progress().micro.elapsed_seconds, expected);
+            assert_eq!(engine.progress().daily_limit.elapsed_seconds, 0);
+        }
+    }
+
+    #[test]
+    fn ignored_breaks_are_renotified_then_forced() {
+        let mut settings = Settings::default();
+        settings.micro.interval_seconds = 100;
+        settings.rest.enabled = false;
+        settings.escalation.enabled = true;
+        settings.escalation.renotify_after_seconds = 60;
+        settings.escalation.force_after_seconds = 120;
+        let mut engine = TimerEngine::new(settings, 0);
+
+        let due = vec![EngineEvent::BreakDue(BreakKind::Micro)];
+        assert_eq!(engine.on_activity(100, 100), due);
+        assert_eq!(engine.on_activity(59, 159), due);
+        assert_eq!(engine.due_age(BreakKind::Micro), Some(59));
+        let renotify = engine.on_activity(1, 160);
+        assert_eq!(
+            renotify[1],
+            EngineEvent::BreakEscalated(BreakKind::Micro, EscalationStage::Renotify)
+        );
+        assert_eq!(engine.on_activity(30, 190), due, "each stage fires once");
+        assert_eq!(
+            engine.on_activity(30, 220),
+            vec![
+                EngineEvent::BreakDue(BreakKind::Micro),
+                EngineEvent::BreakEscalated(BreakKind::Micro, EscalationStage::ForceStart),
+                EngineEvent::BreakStarted(BreakKind::Micro),
+            ]
+        );
+        assert_eq!(engine.due_age(BreakKind::Micro), None);
+    }
+
+    /// A million simulated seconds of work through the tick path, checking
+    /// the queue stays bounded and the whole run fits a time budget. Run
+    /// with `cargo test -p lazaro-core --release -- --ignored soak`.
+    #[test]
+    #[ignore = "soak test, run in release"]
+    fn soak_tick_path_stays_bounded_and_within_budget() {
+        const SECONDS: u64 = 1_000_000;
+        const BUDGET: std::time::Duration = std::time::Duration::from_secs(5);
+
+        let mut settings = Settings {
+            custom_timers: vec![eye_timer(0)],
+            ..Settings::default()
+        };
+        settings.adaptive.enabled = true;
+        settings.micro.jitter_percent = 20;
+        let mut engine = TimerEngine::new(settings, 0);
+
+        let started = std::time::Instant::now();
+        let mut breaks = 0u64;
+        for now in 1..=SECONDS {
+            if engine.active_break_info().is_some() {
+                let _ = engine.tick_break(1);
+                continue;
+            }
+            // Every third offered break is snoozed instead of taken.
+            for event in engine.on_activity(1, now) {
+                if let EngineEvent::BreakDue(kind) = event {
+                    breaks += 1;
+                    if breaks.is_multiple_of(3) {
+                        let _ = engine.snooze(kind, now);
+                    } else {
+                        let _ = engine.start_break(kind);
+                    }
+                }
+            }
+            assert!(engine.pending_breaks().len() <= engine.timer_specs().len() + 1);
+        }
+
+        assert!(breaks > 1_000, "only {breaks} breaks offered");
+        assert!(
+            started.elapsed() < BUDGET,
+            "tick path took {:?} for {SECONDS} seconds",
+            started.elapsed()
+        );
+    }
+
+    #[test]
+    fn snoozing_or_disabling_escalation_stops_it() {
+        let mut settings = Settings::default();
+        settings.micro.interval_seconds = 100;
+        settings.rest.enabled = false;
+        settings.escalation.enabled = true;
+        settings.escalation.renotify_after_seconds = 0;
+        settings.escalation.force_after_seconds = 60;
+        let mut engine = TimerEngine::new(settings.clone(), 0);
+        engine.on_activity(100, 100);
+        engine.on_activity(50, 150);
+        engine.snooze(BreakKind::Micro, 150);
+        assert_eq!(engine.due_age(BreakKind::Micro), None);
+
+        settings.escalation.enabled = false;
+        engine.update_settings(settings);
+        let due = vec![EngineEvent::BreakDue(BreakKind::Micro)];
+        assert_eq!(engine.on_activity(1_000, 1_150), due);
+        assert_eq!(engine.on_activity(1_000, 2_150), due);
+    }
 }
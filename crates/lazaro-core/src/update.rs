@@ -0,0 +1,157 @@
+use std::cmp::Ordering;
+
+use crate::config::UpdateChannel;
+
+/// A `major.minor.patch[-pre.release]` version; a leading `v` is accepted
+/// so release tags parse as is.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Version {
+    pub major: u64,
+    pub minor: u64,
+    pub patch: u64,
+    pub pre: Vec<String>,
+}
+
+impl Version {
+    pub fn parse(value: &str) -> Option<Self> {
+        let value = value.trim().trim_start_matches('v');
+        let value = value.split_once('+').map_or(value, |(version, _)| version);
+        let (core, pre) = match value.split_once('-') {
+            Some((core, pre)) => (core, pre.split('.').map(str::to_string).collect()),
+            None => (value, Vec::new()),
+        };
+        let mut parts = core.split('.').map(|part| part.parse::<u64>().ok());
+        let version = Self {
+            major: parts.next()??,
+            minor: parts.next().unwrap_or(Some(0))?,
+            patch: parts.next().unwrap_or(Some(0))?,
+            pre,
+        };
+        parts.next().is_none().then_some(version)
+    }
+
+    pub fn is_prerelease(&self) -> bool {
+        !self.pre.is_empty()
+    }
+}
+
+impl Ord for Version {
+    fn cmp(&self, other: &Self) -> Ordering {
+        (self.major, self.minor, self.patch)
+            .cmp(&(other.major, other.minor, other.patch))
+            .then_with(|| match (self.pre.is_empty(), other.pre.is_empty()) {
+                (true, true) => Ordering::Equal,
+                (true, false) => Ordering::Greater,
+                (false, true) => Ordering::Less,
+                (false, false) => compare_pre(&self.pre, &other.pre),
+            })
+    }
+}
+
+impl PartialOrd for Version {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+// Numeric identifiers compare as numbers and sort before text ones.
+fn compare_pre(left: &[String], right: &[String]) -> Ordering {
+    for (left, right) in left.iter().zip(right) {
+        let ordering = match (left.parse::<u64>(), right.parse::<u64>()) {
+            (Ok(left), Ok(right)) => left.cmp(&right),
+            (Ok(_), Err(_)) => Ordering::Less,
+            (Err(_), Ok(_)) => Ordering::Greater,
+            (Err(_), Err(_)) => left.cmp(right),
+        };
+        if ordering != Ordering::Equal {
+            return ordering;
+        }
+    }
+    left.len().cmp(&right.len())
+}
+
+/// One entry of the release feed.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Release {
+    pub tag: String,
+    pub prerelease: bool,
+    pub url: String,
+}
+
+/// The newest release on `channel` that is newer than `current`. Stable
+/// skips releases flagged or versioned as pre-releases; tags that are not
+/// versions, like rolling dev builds, are ignored.
+pub fn newest_release<'a>(
+    current: &str,
+    releases: &'a [Release],
+    channel: UpdateChannel,
+) -> Option<&'a Release> {
+    let current = Version::parse(current)?;
+    releases
+        .iter()
+        .filter_map(|release| Some((Version::parse(&release.tag)?, release)))
+        .filter(|(version, release)| {
+            channel == UpdateChannel::Beta || !(release.prerelease || version.is_prerelease())
+        })
+        .filter(|(version, _)| *version > current)
+        .max_by(|(left, _), (right, _)| left.cmp(right))
+        .map(|(_, release)| release)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn release(tag: &str, prerelease: bool) -> Release {
+        Release {
+            tag: tag.into(),
+            prerelease,
+            url: format!("https://example.invalid/{tag}"),
+        }
+    }
+
+    #[test]
+    fn versions_order_like_semver() {
+        let ordered = [
+            "0.9.0",
+            "v1.0.0-alpha",
+            "1.0.0-alpha.1",
+            "1.0.0-alpha.beta",
+            "1.0.0-beta.2",
+            "1.0.0-beta.11",
+            "1.0.0-rc.1",
+            "1.0.0",
+            "1.0.1+build.5",
+            "1.2",
+        ];
+        let versions: Vec<Version> = ordered
+            .iter()
+            .map(|value| Version::parse(value).unwrap())
+            .collect();
+        assert!(versions.windows(2).all(|pair| pair[0] < pair[1]));
+        assert_eq!(Version::parse("dev-latest"), None);
+        assert_eq!(Version::parse("1.2.3.4"), None);
+    }
+
+    #[test]
+    fn channel_decides_which_releases_are_offered() {
+        let releases = [
+            release("dev-latest", true),
+            release("v0.1.7", false),
+            release("v0.1.9", false),
+            release("v0.2.0-beta.1", true),
+            release("v0.2.0-rc.1", false),
+        ];
+        let stable = newest_release("0.1.8", &releases, UpdateChannel::Stable);
+        assert_eq!(stable.map(|release| release.tag.as_str()), Some("v0.1.9"));
+        let beta = newest_release("0.1.8", &releases, UpdateChannel::Beta);
+        assert_eq!(
+            beta.map(|release| release.tag.as_str()),
+            Some("v0.2.0-rc.1")
+        );
+        assert_eq!(
+            newest_release("0.1.9", &releases, UpdateChannel::Stable),
+            None
+        );
+    }
+}